@@ -0,0 +1,168 @@
+//! PNG color metadata test example for galaxy_image
+//!
+//! Tests that ICC profiles, gAMA and sRGB chunks are read into the image metadata,
+//! written back when saving, and converted to sRGB on request.
+
+use galaxy_image::{ComponentType, GalaxyImage, Image, ImageFormat, ImageMetadata, LoadOptions, PixelFormat, ResizeFilter};
+
+/// Display P3 colorants adapted to D50, as in the Apple Display P3 profile
+const DISPLAY_P3: [[f64; 3]; 3] = [[0.5151, 0.2412, -0.0011], [0.2919, 0.6922, 0.0419], [0.1571, 0.0666, 0.7841]];
+
+/// sRGB colorants adapted to D50
+const SRGB: [[f64; 3]; 3] = [[0.4361, 0.2225, 0.0139], [0.3851, 0.7169, 0.0971], [0.1431, 0.0606, 0.7141]];
+
+/// Tone curve of a test profile
+enum Trc {
+    /// `curv` tag with a single gamma
+    Gamma(f64),
+    /// `para` tag with the sRGB transfer function
+    Srgb,
+}
+
+fn fixed16(value: f64) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+/// Build a matrix/TRC ICC profile, RGB with colorants or gray without
+fn icc_profile(colorants: Option<[[f64; 3]; 3]>, trc: Trc) -> Vec<u8> {
+    let curve = match trc {
+        Trc::Gamma(gamma) => [&b"curv\0\0\0\0\0\0\0\x01"[..], &((gamma * 256.0) as u16).to_be_bytes(), &[0, 0]].concat(),
+        Trc::Srgb => {
+            let parameters = [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045];
+            [&b"para\0\0\0\0\0\x03\0\0"[..], &parameters.iter().flat_map(|&p| fixed16(p)).collect::<Vec<u8>>()].concat()
+        }
+    };
+    let xyz = |v: [f64; 3]| [&b"XYZ \0\0\0\0"[..], &fixed16(v[0]), &fixed16(v[1]), &fixed16(v[2])].concat();
+
+    let mut tags: Vec<(&[u8; 4], Vec<u8>)> = Vec::new();
+    match colorants {
+        Some(c) => {
+            tags.push((b"rXYZ", xyz(c[0])));
+            tags.push((b"gXYZ", xyz(c[1])));
+            tags.push((b"bXYZ", xyz(c[2])));
+            tags.extend([b"rTRC", b"gTRC", b"bTRC"].map(|signature| (signature, curve.clone())));
+        }
+        None => tags.push((b"kTRC", curve)),
+    }
+
+    let mut header = vec![0u8; 128];
+    header[8..12].copy_from_slice(&[2, 0x10, 0, 0]);
+    header[12..16].copy_from_slice(b"mntr");
+    header[16..20].copy_from_slice(if colorants.is_some() { b"RGB " } else { b"GRAY" });
+    header[20..24].copy_from_slice(b"XYZ ");
+    header[36..40].copy_from_slice(b"acsp");
+    header[68..80].copy_from_slice(&[fixed16(0.9642), fixed16(1.0), fixed16(0.8249)].concat());
+
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    let data_offset = 128 + 4 + tags.len() * 12;
+    for (signature, tag) in &tags {
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&((data_offset + data.len()) as u32).to_be_bytes());
+        table.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+        data.extend_from_slice(tag);
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let mut profile = [header, table, data].concat();
+    let size = (profile.len() as u32).to_be_bytes();
+    profile[0..4].copy_from_slice(&size);
+    profile
+}
+
+/// Save an image with the given metadata and reload it, converted to sRGB or not
+fn round_trip(image: &Image, metadata: ImageMetadata, convert_to_srgb: bool) -> Result<Image, Box<dyn std::error::Error>> {
+    let mut tagged = image.clone();
+    tagged.set_metadata(metadata);
+    let png = GalaxyImage::save_to_bytes(&tagged, ImageFormat::Png, 90)?;
+    let options = LoadOptions { convert_to_srgb, ..Default::default() };
+    Ok(GalaxyImage::load_from_bytes_with_options(&png, ImageFormat::Png, &options)?)
+}
+
+fn assert_close(actual: &[u8], expected: &[u8], what: &str) {
+    let close = actual.len() == expected.len() && actual.iter().zip(expected).all(|(&a, &e)| a.abs_diff(e) <= 1);
+    assert!(close, "{}: got {:?}, expected {:?}", what, actual, expected);
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("galaxy_image - PNG Color Metadata Test");
+    println!("======================================\n");
+
+    let gray = Image::from_raw(vec![0, 128, 255], 3, 1, PixelFormat::R, ComponentType::U8);
+    let colors = Image::from_raw(vec![128, 128, 128, 255, 0, 0, 255, 128, 128], 3, 1, PixelFormat::RGB, ComponentType::U8);
+
+    // 1. Metadata survives a PNG round trip
+    println!("Round-tripping metadata through PNG...");
+    let profile = icc_profile(Some(DISPLAY_P3), Trc::Srgb);
+    let loaded = round_trip(&colors, ImageMetadata { icc_profile: Some(profile.clone()), ..Default::default() }, false)?;
+    assert_eq!(loaded.metadata().icc_profile.as_deref(), Some(&profile[..]));
+    assert_eq!(loaded.data(), colors.data(), "pixels changed without conversion");
+    let loaded = round_trip(&gray, ImageMetadata { gamma: Some(1.0), ..Default::default() }, false)?;
+    assert_eq!(loaded.metadata().gamma, Some(1.0));
+    let loaded = round_trip(&colors, ImageMetadata { srgb: true, ..Default::default() }, false)?;
+    assert!(loaded.metadata().srgb);
+    let plain = GalaxyImage::load_from_bytes(&GalaxyImage::save_to_bytes(&colors, ImageFormat::Png, 90)?, ImageFormat::Png)?;
+    assert!(plain.metadata().is_empty());
+    println!("  ✓ ICC profile, gAMA and sRGB chunks preserved");
+
+    // 2. gAMA of 1.0 (linear values) is encoded to sRGB
+    println!("\nConverting gamma 1.0 to sRGB...");
+    let converted = round_trip(&gray, ImageMetadata { gamma: Some(1.0), ..Default::default() }, true)?;
+    assert_eq!(converted.data(), &[0, 188, 255]);
+    assert_eq!(converted.metadata(), &ImageMetadata { srgb: true, ..Default::default() });
+    let unchanged = round_trip(&gray, ImageMetadata { gamma: Some(1.0 / 2.2), ..Default::default() }, true)?;
+    assert_eq!(unchanged.data(), gray.data(), "gamma 1/2.2 should be treated as sRGB");
+    println!("  ✓ Linear 128 -> sRGB 188, gamma 1/2.2 kept as is");
+
+    // 3. Gray and RGB ICC profiles with linear curves
+    println!("\nConverting linear ICC profiles to sRGB...");
+    let converted = round_trip(&gray, ImageMetadata { icc_profile: Some(icc_profile(None, Trc::Gamma(1.0))), ..Default::default() }, true)?;
+    assert_eq!(converted.data(), &[0, 188, 255]);
+    let converted = round_trip(&colors, ImageMetadata { icc_profile: Some(icc_profile(Some(SRGB), Trc::Gamma(1.0))), ..Default::default() }, true)?;
+    assert_close(&converted.data()[..6], &[188, 188, 188, 255, 0, 0], "linear sRGB profile");
+    println!("  ✓ Gray and RGB matrix/TRC profiles");
+
+    // 4. Display P3 colors are remapped to sRGB, neutral colors stay neutral
+    println!("\nConverting Display P3 to sRGB...");
+    let converted = round_trip(&colors, ImageMetadata { icc_profile: Some(profile.clone()), ..Default::default() }, true)?;
+    assert_close(converted.data(), &[128, 128, 128, 255, 0, 0, 255, 119, 124], "Display P3 profile");
+    assert!(converted.metadata().srgb && converted.metadata().icc_profile.is_none());
+    println!("  P3 (255, 128, 128) -> sRGB {:?}", &converted.data()[6..9]);
+
+    // 5. Unsupported profiles leave the image untouched
+    println!("\nLoading with unsupported profiles...");
+    let mut cmyk = icc_profile(Some(SRGB), Trc::Gamma(1.0));
+    cmyk[16..20].copy_from_slice(b"CMYK");
+    for profile in [cmyk, icc_profile(None, Trc::Gamma(1.0)), b"not a profile".to_vec()] {
+        let loaded = round_trip(&colors, ImageMetadata { icc_profile: Some(profile.clone()), ..Default::default() }, true)?;
+        assert_eq!(loaded.data(), colors.data());
+        assert_eq!(loaded.metadata().icc_profile.as_deref(), Some(&profile[..]));
+        let mut image = loaded.clone();
+        assert!(!image.convert_to_srgb());
+        assert_eq!(image, loaded);
+    }
+    println!("  ✓ CMYK, mismatched gray and malformed profiles ignored");
+
+    // 6. Resizes, crops, mips and conversions keep the metadata
+    println!("\nEditing images with metadata...");
+    let metadata = ImageMetadata { icc_profile: Some(profile.clone()), gamma: Some(0.5), ..Default::default() };
+    let mut tagged = colors.clone();
+    tagged.set_metadata(metadata.clone());
+    let edits = [
+        tagged.resize(6, 2, ResizeFilter::Bilinear),
+        tagged.crop(1, 0, 2, 1)?,
+        tagged.rotate_90(),
+        tagged.generate_mipmaps(ResizeFilter::Area)[1].clone(),
+        tagged.convert(PixelFormat::RGBA, ComponentType::U16)?,
+    ];
+    for edited in edits {
+        let png = GalaxyImage::save_to_bytes(&edited, ImageFormat::Png, 90)?;
+        assert_eq!(GalaxyImage::load_from_bytes(&png, ImageFormat::Png)?.metadata(), &metadata);
+    }
+    let gray_copy = tagged.convert(PixelFormat::R, ComponentType::U8)?;
+    assert_eq!(gray_copy.metadata(), &ImageMetadata { icc_profile: None, ..metadata });
+    println!("  ✓ Kept through resize, crop, rotation, mips and conversion; RGB profile dropped for gray");
+
+    println!("\nAll color metadata tests passed!");
+    Ok(())
+}
@@ -1,8 +1,9 @@
-/// EXR format test example for galaxy_image
-///
-/// Tests EXR creation, save, reload, and format detection.
+//! EXR format test example for galaxy_image
+//!
+//! Tests EXR creation, save, reload, format detection, and decoding of
+//! DWAA/DWAB/PIZ files written by OpenEXR.
 
-use galaxy_image::{GalaxyImage, Image, ImageFormat, PixelFormat, ComponentType};
+use galaxy_image::{GalaxyImage, Image, ImageFormat, Pixel, PixelFormat, ComponentType};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("galaxy_image - EXR Format Test");
@@ -49,8 +50,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(reloaded.component_type(), ComponentType::F32);
 
     // Verify HDR value preserved
-    let data = reloaded.data();
-    let b_value = f32::from_le_bytes([data[8], data[9], data[10], data[11]]);
+    let Pixel::F32(pixel) = reloaded.get_pixel(0, 0) else { unreachable!() };
+    let b_value = pixel.b;
     assert!((b_value - 2.5).abs() < 0.001, "HDR value not preserved: {}", b_value);
     println!("  HDR value preserved: B = {}", b_value);
 
@@ -81,19 +82,47 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(reloaded_f16.component_type(), ComponentType::F16);
     println!("  F16 RGBA round-trip OK: {}x{}", reloaded_f16.width(), reloaded_f16.height());
 
-    // 6. Try loading real EXR textures (may fail if DWAA compressed)
-    println!("\nTrying real EXR textures (DWAA compression)...");
-    let exr_files = [
-        "F:/dev/rust/Galaxy/Games/galaxy3d_scene_demo/assets/mesh/lion_head/textures/lion_head_metal_4k.exr",
-        "F:/dev/rust/Galaxy/Games/galaxy3d_scene_demo/assets/mesh/lion_head/textures/lion_head_nor_gl_4k.exr",
-        "F:/dev/rust/Galaxy/Games/galaxy3d_scene_demo/assets/mesh/lion_head/textures/lion_head_rough_4k.exr",
+    // 6. Decode files written by the OpenEXR library
+    //
+    // 64x48 images of the pattern below, written by OpenEXR 3.0 (DWA level 45).
+    // The checksums are those of OpenEXR's own decode of each file.
+    println!("\nDecoding OpenEXR compressed files...");
+    let pattern = |x: u32, y: u32| {
+        let fx = x as f32 / 63.0;
+        let fy = y as f32 / 47.0;
+        [fx * 4.0, fy, 0.25 + 0.5 * fx * fy, if x < 32 { 1.0 } else { 0.5 }]
+    };
+    let fixtures = [
+        ("rgba_f16_dwaa.exr", &include_bytes!("data/rgba_f16_dwaa.exr")[..], PixelFormat::RGBA, ComponentType::F16, 0.05, 0x5bbb_ab00_de7f_4e30),
+        ("rgba_f16_dwab.exr", &include_bytes!("data/rgba_f16_dwab.exr")[..], PixelFormat::RGBA, ComponentType::F16, 0.05, 0x5bbb_ab00_de7f_4e30),
+        ("rgba_f16_piz.exr", &include_bytes!("data/rgba_f16_piz.exr")[..], PixelFormat::RGBA, ComponentType::F16, 0.002, 0x6c63_3bc6_2855_5e50),
+        ("rgb_f32_piz.exr", &include_bytes!("data/rgb_f32_piz.exr")[..], PixelFormat::RGB, ComponentType::F32, 0.0, 0xfb68_c084_d43b_128b),
+        ("rgb_f32_dwaa.exr", &include_bytes!("data/rgb_f32_dwaa.exr")[..], PixelFormat::RGB, ComponentType::F32, 0.05, 0x9773_3d96_5038_1403),
     ];
-    for path in &exr_files {
-        let filename = path.rsplit('/').next().unwrap_or(path);
-        match GalaxyImage::load_from_file(path) {
-            Ok(img) => println!("  {} -> {}x{} {:?} {:?}", filename, img.width(), img.height(), img.pixel_format(), img.component_type()),
-            Err(e) => println!("  {} -> {}", filename, e),
+    for (name, bytes, pixel_format, component_type, tolerance, checksum) in fixtures {
+        let decoded = GalaxyImage::load_from_bytes(bytes, ImageFormat::Exr)?;
+        assert_eq!((decoded.width(), decoded.height()), (64, 48));
+        assert_eq!(decoded.pixel_format(), pixel_format);
+        assert_eq!(decoded.component_type(), component_type);
+
+        let channels = pixel_format.channel_count();
+        let mut max_error = 0.0f32;
+        for y in 0..48 {
+            for x in 0..64 {
+                let pixel = decoded.read_pixel_f32(x, y);
+                let expected = pattern(x, y);
+                for c in 0..channels {
+                    max_error = max_error.max((pixel[c] - expected[c]).abs());
+                }
+            }
         }
+        assert!(max_error <= tolerance, "{}: max error {}", name, max_error);
+        assert_eq!(decoded.checksum(), checksum, "{}: decode differs from OpenEXR", name);
+
+        // The streaming path must decode the same pixels
+        let streamed = GalaxyImage::load_from_reader(std::io::Cursor::new(bytes))?;
+        assert_eq!(streamed, decoded, "{}: streaming decode differs", name);
+        println!("  {} -> {:?} {:?}, max error {:.4}", name, pixel_format, component_type, max_error);
     }
 
     // Cleanup
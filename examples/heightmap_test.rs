@@ -0,0 +1,128 @@
+//! 16-bit heightmap precision test example for galaxy_image
+//!
+//! Tests that downsampling U16 and F32 images keeps their precision
+//! (no intermediate 8-bit quantization), and that U16 heightmaps round-trip
+//! through normalized F32.
+
+use galaxy_image::{ColorSpace, ComponentType, GalaxyImage, GpuRequirements, Image, ImageFormat, Pixel, PixelFormat};
+
+/// Height of a terrain sample, using the full 16-bit range with fine steps
+fn height_at(x: u32, y: u32) -> u16 {
+    (x * 97 + y * 31 + (x * y) % 13) as u16
+}
+
+fn read_u16(image: &Image, x: u32, y: u32) -> u16 {
+    match image.get_pixel(x, y) {
+        Pixel::U16(pixel) => pixel.r,
+        pixel => panic!("expected a U16 image, got {:?}", pixel.component_type()),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("galaxy_image - Heightmap Precision Test");
+    println!("=======================================\n");
+
+    // 1. Create a 512x512 U16 heightmap
+    println!("Creating 512x512 U16 heightmap...");
+    let size = 512u32;
+    let mut heightmap = Image::new(size, size, PixelFormat::R, ComponentType::U16);
+    {
+        let data = heightmap.data_mut();
+        for y in 0..size {
+            for x in 0..size {
+                let offset = ((y * size + x) * 2) as usize;
+                data[offset..offset + 2].copy_from_slice(&height_at(x, y).to_le_bytes());
+            }
+        }
+    }
+
+    // 2. Downsample via PNG round-trip + thumbnail (4x4 box filter)
+    println!("\nDownsampling through PNG thumbnail to 128x128...");
+    let png = GalaxyImage::save_to_bytes(&heightmap, ImageFormat::Png, 90)?;
+    let thumbnail = GalaxyImage::thumbnail_from_bytes(&png, ImageFormat::Png, 128)?;
+    assert_eq!(thumbnail.component_type(), ComponentType::U16);
+    assert_eq!((thumbnail.width(), thumbnail.height()), (128, 128));
+
+    let mut max_error = 0i32;
+    let mut off_8bit_grid = 0;
+    for ty in 0..128 {
+        for tx in 0..128 {
+            let mut sum = 0u64;
+            for y in ty * 4..ty * 4 + 4 {
+                for x in tx * 4..tx * 4 + 4 {
+                    sum += height_at(x, y) as u64;
+                }
+            }
+            let expected = (sum as f64 / 16.0).round() as i32;
+            let actual = read_u16(&thumbnail, tx, ty) as i32;
+            max_error = max_error.max((actual - expected).abs());
+            if actual % 257 != 0 {
+                off_8bit_grid += 1;
+            }
+        }
+    }
+    println!("  Max error vs exact average: {} LSB", max_error);
+    assert!(max_error <= 1, "U16 downsampling lost precision: {} LSB", max_error);
+    assert!(off_8bit_grid > 128 * 128 / 2, "U16 values look 8-bit quantized");
+
+    // 3. Mip chain of the heightmap keeps exact 2x2 averages
+    println!("\nGenerating linear mips...");
+    let upload = heightmap.prepare_for_gpu(&GpuRequirements {
+        generate_mips: true,
+        ..Default::default()
+    });
+    assert_eq!(upload.desc.color_space, ColorSpace::Linear);
+    let level = upload.desc.levels[1];
+    let mip = &upload.data[level.offset..level.offset + level.size];
+    let mut mip_error = 0i32;
+    for y in 0..level.height {
+        for x in 0..level.width {
+            let sum: u32 = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                .iter()
+                .map(|&(dx, dy)| height_at(x * 2 + dx, y * 2 + dy) as u32)
+                .sum();
+            let expected = (sum as f64 / 4.0).round() as i32;
+            let offset = y as usize * level.row_pitch + x as usize * 2;
+            let actual = u16::from_le_bytes([mip[offset], mip[offset + 1]]) as i32;
+            mip_error = mip_error.max((actual - expected).abs());
+        }
+    }
+    println!("  Mip 1: {}x{}, max error {} LSB", level.width, level.height, mip_error);
+    assert!(mip_error <= 1, "U16 mip lost precision: {} LSB", mip_error);
+
+    // 4. F32 heights above 1.0 survive downsampling
+    println!("\nDownsampling F32 heights (meters)...");
+    let mut meters = Image::new(64, 64, PixelFormat::R, ComponentType::F32);
+    for (i, sample) in meters.data_mut().chunks_exact_mut(4).enumerate() {
+        let height = 1200.0 + (i % 64) as f32 * 0.001;
+        sample.copy_from_slice(&height.to_le_bytes());
+    }
+    let exr = GalaxyImage::save_to_bytes(&meters, ImageFormat::Exr, 90)?;
+    let small = GalaxyImage::thumbnail_from_bytes(&exr, ImageFormat::Exr, 16)?;
+    let first = small.read_pixel_f32(0, 0)[0];
+    println!("  First sample: {} m", first);
+    assert!((first - 1200.0015).abs() < 1e-3, "F32 height not preserved: {}", first);
+
+    // 5. U16 PNG -> normalized F32 -> U16 PNG round trip is lossless
+    println!("\nRound-tripping the heightmap through normalized F32...");
+    let loaded = GalaxyImage::load_from_bytes(&png, ImageFormat::Png)?;
+    let normalized = loaded.to_f32_normalized()?;
+    assert_eq!((normalized.pixel_format(), normalized.component_type()), (PixelFormat::R, ComponentType::F32));
+    let expected = height_at(300, 200) as f32 / 65535.0;
+    assert_eq!(normalized.read_pixel_f32(300, 200)[0], expected);
+    let requantized = normalized.to_u16_normalized()?;
+    let png_again = GalaxyImage::save_to_bytes(&requantized, ImageFormat::Png, 90)?;
+    let reloaded = GalaxyImage::load_from_bytes(&png_again, ImageFormat::Png)?;
+    assert_eq!(reloaded.data(), heightmap.data(), "U16 -> F32 -> U16 round trip changed heights");
+    println!("  {} samples preserved exactly", size * size);
+
+    // 6. Out-of-range F32 values are clamped when quantizing
+    let mut edited = normalized.clone();
+    edited.write_pixel_f32(0, 0, [-0.5, 0.0, 0.0, 1.0]);
+    edited.write_pixel_f32(1, 0, [1.5, 0.0, 0.0, 1.0]);
+    let clamped = edited.to_u16_normalized()?;
+    assert_eq!((read_u16(&clamped, 0, 0), read_u16(&clamped, 1, 0)), (0, 65535));
+
+    println!("\nAll heightmap tests passed!");
+    Ok(())
+}
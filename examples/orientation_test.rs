@@ -0,0 +1,135 @@
+//! JPEG EXIF orientation test example for galaxy_image
+//!
+//! Tests that the EXIF orientation of JPEG files is read into the image
+//! metadata, written back when saving, and applied on request.
+
+use galaxy_image::{ComponentType, GalaxyImage, Image, ImageFormat, ImageMetadata, JpegSubsampling, LoadOptions, Orientation, PixelFormat, SaveOptions};
+
+/// Side of the flat blocks, one JPEG block so they survive compression
+const BLOCK: u32 = 8;
+
+/// Gray level of each block of the 3x2 test grid
+const LEVELS: [[u8; 3]; 2] = [[0, 50, 100], [150, 200, 250]];
+
+fn test_image() -> Image {
+    let (width, height) = (3 * BLOCK, 2 * BLOCK);
+    let mut data = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            data.push(LEVELS[(y / BLOCK) as usize][(x / BLOCK) as usize]);
+        }
+    }
+    Image::from_raw(data, width, height, PixelFormat::R, ComponentType::U8)
+}
+
+/// Block of the stored grid shown at block (x, y) of the upright grid, after the EXIF definitions
+fn stored_block(orientation: Orientation, x: usize, y: usize) -> (usize, usize) {
+    let (w, h) = (3, 2);
+    match orientation {
+        Orientation::Normal => (x, y),
+        Orientation::FlipHorizontal => (w - 1 - x, y),
+        Orientation::Rotate180 => (w - 1 - x, h - 1 - y),
+        Orientation::FlipVertical => (x, h - 1 - y),
+        Orientation::Transpose => (y, x),
+        Orientation::Rotate90 => (y, h - 1 - x),
+        Orientation::Transverse => (w - 1 - y, h - 1 - x),
+        Orientation::Rotate270 => (w - 1 - y, x),
+    }
+}
+
+/// Gray levels at the block centers of an image
+fn block_levels(image: &Image) -> Vec<Vec<u8>> {
+    (0..image.height() / BLOCK)
+        .map(|by| (0..image.width() / BLOCK).map(|bx| (image.read_pixel_f32(bx * BLOCK + 4, by * BLOCK + 4)[0] * 255.0).round() as u8).collect())
+        .collect()
+}
+
+fn assert_levels(actual: &[Vec<u8>], expected: &[Vec<u8>], what: &str) {
+    let close = actual.len() == expected.len()
+        && actual.iter().zip(expected).all(|(a, e)| a.len() == e.len() && a.iter().zip(e).all(|(&a, &e)| a.abs_diff(e) <= 3));
+    assert!(close, "{}: got {:?}, expected {:?}", what, actual, expected);
+}
+
+/// Insert an APP1 EXIF segment right after the SOI marker of a JPEG file
+fn with_exif(jpeg: &[u8], tiff: &[u8]) -> Vec<u8> {
+    let payload = [&b"Exif\0\0"[..], tiff].concat();
+    let length = (payload.len() as u16 + 2).to_be_bytes();
+    [&jpeg[..2], &[0xFF, 0xE1], &length, &payload, &jpeg[2..]].concat()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("galaxy_image - JPEG EXIF Orientation Test");
+    println!("=========================================\n");
+
+    let image = test_image();
+    let options = SaveOptions::new().with_jpeg_quality(100).with_jpeg_subsampling(JpegSubsampling::Yuv444);
+    let auto_orient = LoadOptions { auto_orient: true, ..Default::default() };
+
+    // 1. Every orientation round-trips through the EXIF tag and is applied correctly
+    println!("Saving and loading the 8 EXIF orientations...");
+    for value in 1..=8 {
+        let orientation = Orientation::from_exif(value).unwrap();
+        assert_eq!(orientation.to_exif(), value);
+        let mut tagged = image.clone();
+        tagged.set_metadata(ImageMetadata { orientation, ..Default::default() });
+        let jpeg = GalaxyImage::save_to_bytes_with_options(&tagged, ImageFormat::Jpeg, &options)?;
+
+        let stored = GalaxyImage::load_from_bytes(&jpeg, ImageFormat::Jpeg)?;
+        assert_eq!(stored.metadata().orientation, orientation);
+        assert_eq!((stored.width(), stored.height()), (image.width(), image.height()));
+        assert_levels(&block_levels(&stored), &block_levels(&image), "stored pixels");
+
+        let upright = GalaxyImage::load_from_bytes_with_options(&jpeg, ImageFormat::Jpeg, &auto_orient)?;
+        assert_eq!(upright.metadata().orientation, Orientation::Normal);
+        let (blocks_x, blocks_y) = if orientation.swaps_dimensions() { (2, 3) } else { (3, 2) };
+        assert_eq!((upright.width(), upright.height()), (blocks_x * BLOCK, blocks_y * BLOCK));
+        let expected: Vec<Vec<u8>> = (0..blocks_y as usize)
+            .map(|y| {
+                (0..blocks_x as usize)
+                    .map(|x| {
+                        let (sx, sy) = stored_block(orientation, x, y);
+                        LEVELS[sy][sx]
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_levels(&block_levels(&upright), &expected, &format!("{:?}", orientation));
+        assert_eq!(stored.apply_orientation().data(), upright.data());
+        println!("  ✓ EXIF {} ({:?}): {:?}", value, orientation, block_levels(&upright));
+    }
+
+    // 2. flip_y applies after the orientation
+    println!("\nCombining auto_orient with flip_y...");
+    let mut tagged = image.clone();
+    tagged.set_metadata(ImageMetadata { orientation: Orientation::Rotate90, ..Default::default() });
+    let jpeg = GalaxyImage::save_to_bytes_with_options(&tagged, ImageFormat::Jpeg, &options)?;
+    let upright = GalaxyImage::load_from_bytes_with_options(&jpeg, ImageFormat::Jpeg, &auto_orient)?;
+    let flipped = GalaxyImage::load_from_bytes_with_options(&jpeg, ImageFormat::Jpeg, &LoadOptions { flip_y: true, ..auto_orient.clone() })?;
+    assert_eq!(flipped.data(), upright.flip_vertical().data());
+    println!("  ✓ Rows flipped after rotation");
+
+    // 3. Big-endian EXIF from a camera, with other tags before the orientation
+    println!("\nReading big-endian camera EXIF...");
+    let plain = GalaxyImage::save_to_bytes_with_options(&image, ImageFormat::Jpeg, &options)?;
+    let mut tiff = b"MM\0\x2A\0\0\0\x08\0\x02".to_vec();
+    tiff.extend_from_slice(&[0x01, 0x0F, 0, 2, 0, 0, 0, 4, b'C', b'a', b'm', 0]); // Make, ASCII "Cam"
+    tiff.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 8, 0, 0]); // Orientation 8, SHORT
+    tiff.extend_from_slice(&[0, 0, 0, 0]);
+    let camera = GalaxyImage::load_from_bytes(&with_exif(&plain, &tiff), ImageFormat::Jpeg)?;
+    assert_eq!(camera.metadata().orientation, Orientation::Rotate270);
+    println!("  ✓ Orientation 8 read after the Make tag");
+
+    // 4. Missing, invalid and malformed tags leave the image upright
+    println!("\nLoading files without a valid orientation...");
+    let mut invalid = tiff.clone();
+    invalid[31] = 9;
+    for jpeg in [plain.clone(), with_exif(&plain, &invalid), with_exif(&plain, b"MM\0\x2A\xFF\xFF\xFF\xFF")] {
+        let loaded = GalaxyImage::load_from_bytes_with_options(&jpeg, ImageFormat::Jpeg, &auto_orient)?;
+        assert_eq!(loaded.metadata().orientation, Orientation::Normal);
+        assert_eq!((loaded.width(), loaded.height()), (image.width(), image.height()));
+    }
+    println!("  ✓ Untagged, out-of-range and truncated EXIF ignored");
+
+    println!("\nAll orientation tests passed!");
+    Ok(())
+}
@@ -1,6 +1,6 @@
-/// Simple test example for galaxy_image
-///
-/// This example creates a simple gradient image and saves it in multiple formats.
+//! Simple test example for galaxy_image
+//!
+//! This example creates a simple gradient image and saves it in multiple formats.
 
 use galaxy_image::{GalaxyImage, Image, PixelFormat, ComponentType, ImageFormat};
 
@@ -0,0 +1,49 @@
+//! WebP lossy decoder test example for galaxy_image
+//!
+//! Tests that a lossy (VP8) WebP file decodes to the same pixels as libwebp,
+//! and that corrupt files return an error instead of panicking.
+
+use galaxy_image::{GalaxyImage, ImageFormat, PixelFormat};
+
+/// 3x5 smooth gradient encoded by libwebp at quality 50
+const LOSSY: [u8; 62] = [
+    82, 73, 70, 70, 54, 0, 0, 0, 87, 69, 66, 80, 86, 80, 56, 32, 42, 0, 0, 0, 208, 1, 0, 157, 1, 42, 3, 0, 5, 0, 2,
+    192, 76, 37, 168, 2, 116, 1, 14, 254, 3, 142, 0, 0, 254, 240, 111, 211, 237, 55, 5, 125, 8, 222, 206, 63, 13, 214,
+    68, 144, 0, 0,
+];
+
+/// RGB output of libwebp for [`LOSSY`]
+const LOSSY_RGB: [u8; 45] = [
+    149, 237, 159, 153, 241, 164, 160, 248, 171, 146, 231, 156, 150, 235, 161, 157, 242, 168, 143, 222, 155, 148, 227,
+    159, 154, 233, 165, 142, 213, 155, 147, 218, 160, 153, 223, 165, 143, 206, 157, 148, 211, 162, 154, 216, 168,
+];
+
+/// Fuzzed 1x1 file whose coefficients overflow the inverse DCT, rejected by libwebp
+const OVERFLOWING: [u8; 60] = [
+    82, 73, 70, 70, 52, 0, 0, 0, 87, 69, 66, 80, 86, 80, 56, 32, 40, 0, 0, 0, 144, 1, 0, 157, 1, 42, 1, 0, 1, 0, 7,
+    64, 150, 37, 160, 2, 116, 186, 0, 3, 152, 0, 255, 229, 10, 248, 39, 169, 114, 143, 254, 55, 143, 2, 188, 151, 99,
+    160, 0, 0,
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("galaxy_image - WebP Lossy Decoder Test");
+    println!("======================================\n");
+
+    // 1. Lossy pixels match libwebp
+    println!("Decoding a lossy WebP file...");
+    let image = GalaxyImage::load_from_bytes(&LOSSY, ImageFormat::WebP)?;
+    assert_eq!((image.width(), image.height(), image.pixel_format()), (3, 5, PixelFormat::RGB));
+    assert_eq!(image.data(), &LOSSY_RGB);
+    println!("  ✓ 3x5 RGB, identical to libwebp");
+
+    // 2. Corrupt files are rejected without panicking (overflow checks are on in debug builds)
+    println!("\nDecoding corrupt WebP files...");
+    assert!(GalaxyImage::load_from_bytes(&OVERFLOWING, ImageFormat::WebP).is_err());
+    for length in 20..LOSSY.len() {
+        assert!(GalaxyImage::load_from_bytes(&LOSSY[..length], ImageFormat::WebP).is_err());
+    }
+    println!("  ✓ Overflowing coefficients and truncated files return errors");
+
+    println!("\nAll WebP tests passed!");
+    Ok(())
+}
@@ -0,0 +1,294 @@
+/// Per-pixel color adjustments
+use crate::color::{blackbody_linear_srgb, linear_to_srgb, srgb_to_linear};
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, PixelFormat};
+
+/// Reference white of the white balance correction (D65 correlated color temperature)
+const NEUTRAL_TEMPERATURE: f32 = 6504.0;
+
+/// Apply a function to the normalized samples of every row of an image
+///
+/// Integer images are decoded from sRGB to linear light before the call and
+/// encoded back afterwards when `linear_light` is set; float images are
+/// assumed to be linear already. The function receives one row in storage order.
+fn map_rows(image: &mut Image, linear_light: bool, mut f: impl FnMut(&mut [f32])) {
+    let component_type = image.component_type();
+    let channel_count = image.pixel_format().channel_count();
+    let color_channels = if image.pixel_format().has_alpha() { channel_count - 1 } else { channel_count };
+    let decode = linear_light && matches!(component_type, ComponentType::U8 | ComponentType::U16);
+
+    let row_bytes = image.width() as usize * image.bytes_per_pixel();
+    if row_bytes == 0 {
+        return;
+    }
+    let mut values = vec![0f32; image.width() as usize * channel_count];
+    for row in image.data_mut().chunks_exact_mut(row_bytes) {
+        read_normalized_slice(row, component_type, &mut values);
+        if decode {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                pixel[..color_channels].iter_mut().for_each(|v| *v = srgb_to_linear(*v));
+            }
+        }
+
+        f(&mut values);
+
+        if decode {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                pixel[..color_channels].iter_mut().for_each(|v| *v = linear_to_srgb(v.max(0.0)));
+            }
+        }
+        write_normalized_slice(row, component_type, &values);
+    }
+}
+
+/// Values at a percentile from each end of a sample set, ignoring non-finite values
+///
+/// Returns `None` when there are no finite values or they are all equal.
+fn percentile_range(values: &mut Vec<f32>, clip_percent: f32) -> Option<(f32, f32)> {
+    values.retain(|value| value.is_finite());
+    let last = values.len().checked_sub(1)?;
+    let clipped = ((last as f32 * clip_percent.clamp(0.0, 50.0) / 100.0).round() as usize).min(last / 2);
+    let low = *values.select_nth_unstable_by(clipped, f32::total_cmp).1;
+    let high = *values.select_nth_unstable_by(last - clipped, f32::total_cmp).1;
+    (high > low).then_some((low, high))
+}
+
+/// Map `low..=high` linearly to `floor..=1.0`, clipping values outside
+fn stretch(value: f32, low: f32, high: f32, floor: f32) -> f32 {
+    (floor + (value - low) / (high - low) * (1.0 - floor)).clamp(floor, 1.0)
+}
+
+impl Image {
+    /// Correct the white balance of a photo taken under a colored illuminant
+    ///
+    /// Scales the color channels in linear light so that a neutral surface lit
+    /// by a blackbody of `temperature` Kelvin becomes neutral under D65, keeping
+    /// its luminance. 8 and 16-bit images are treated as sRGB encoded and float
+    /// images as linear. Grayscale images are left unchanged.
+    ///
+    /// # Arguments
+    /// * `temperature` - Color temperature of the capture illuminant in Kelvin
+    ///   (e.g. 3200 for tungsten, 6504 leaves colors unchanged)
+    /// * `tint` - Green cast of the illuminant (`-1.0..=1.0`): positive values
+    ///   remove a green cast, negative values remove a magenta cast
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut albedo = GalaxyImage::load_from_file("scan_tungsten.png")?;
+    /// albedo.adjust_white_balance(3200.0, 0.05);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn adjust_white_balance(&mut self, temperature: f32, tint: f32) {
+        if !matches!(
+            self.pixel_format(),
+            PixelFormat::RGB | PixelFormat::RGBA | PixelFormat::BGR | PixelFormat::BGRA
+        ) {
+            return;
+        }
+
+        // Von Kries scaling from the illuminant white to the reference white
+        let source = blackbody_linear_srgb(temperature);
+        let target = blackbody_linear_srgb(NEUTRAL_TEMPERATURE);
+        let mut gains = [0f32; 3];
+        for channel in 0..3 {
+            gains[channel] = target[channel] / source[channel].max(1e-6);
+        }
+        gains[1] *= 1.0 - tint.clamp(-1.0, 1.0) * 0.5;
+
+        // Keep the luminance of white unchanged
+        let luminance = 0.2126 * gains[0] + 0.7152 * gains[1] + 0.0722 * gains[2];
+        gains.iter_mut().for_each(|gain| *gain /= luminance);
+        if matches!(self.pixel_format(), PixelFormat::BGR | PixelFormat::BGRA) {
+            gains.swap(0, 2);
+        }
+
+        let channel_count = self.pixel_format().channel_count();
+        map_rows(self, true, |values| {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                for (value, gain) in pixel.iter_mut().zip(gains) {
+                    *value *= gain;
+                }
+            }
+        });
+    }
+
+    /// Build a binary mask of the pixels at or above a threshold
+    ///
+    /// Compares the Rec.709 luminance of the stored (normalized) values, or the
+    /// value itself for grayscale images; alpha is ignored. Returns an R/U8 image
+    /// with 255 for pixels at or above `value` and 0 elsewhere, ready for
+    /// morphology or distance field steps.
+    ///
+    /// # Arguments
+    /// * `value` - Threshold in normalized units (`0.5` is mid-gray for integer images)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let splat = GalaxyImage::load_from_file("splat.png")?;
+    /// let mask = splat.threshold(0.5);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn threshold(&self, value: f32) -> Image {
+        let channel_count = self.pixel_format().channel_count();
+        let weights: &[f32] = match self.pixel_format() {
+            PixelFormat::R | PixelFormat::RG => &[1.0],
+            PixelFormat::RGB | PixelFormat::RGBA => &[0.2126, 0.7152, 0.0722],
+            PixelFormat::BGR | PixelFormat::BGRA => &[0.0722, 0.7152, 0.2126],
+        };
+
+        let width = self.width() as usize;
+        let mut mask = Image::new(self.width(), self.height(), PixelFormat::R, ComponentType::U8);
+        let row_bytes = width * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return mask;
+        }
+
+        let mut values = vec![0f32; width * channel_count];
+        let mask_data = mask.data_mut();
+        for (row, mask_row) in self.data().chunks_exact(row_bytes).zip(mask_data.chunks_exact_mut(width)) {
+            read_normalized_slice(row, self.component_type(), &mut values);
+            for (pixel, out) in values.chunks_exact(channel_count).zip(mask_row.iter_mut()) {
+                let luminance: f32 = pixel.iter().zip(weights).map(|(v, w)| v * w).sum();
+                *out = if luminance >= value { u8::MAX } else { 0 };
+            }
+        }
+        mask
+    }
+
+    /// Reduce every color channel to a number of evenly spaced levels
+    ///
+    /// Values are quantized in normalized units (`0.0..=1.0` for integer images);
+    /// alpha is unchanged. `levels` below 2 are treated as 2.
+    ///
+    /// # Arguments
+    /// * `levels` - Number of levels per channel (e.g. 4 for a stylized look)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut image = GalaxyImage::load_from_file("concept.png")?;
+    /// image.posterize(4);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn posterize(&mut self, levels: u32) {
+        let steps = (levels.max(2) - 1) as f32;
+        let channel_count = self.pixel_format().channel_count();
+        let color_channels = if self.pixel_format().has_alpha() { channel_count - 1 } else { channel_count };
+
+        map_rows(self, false, |values| {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                for value in &mut pixel[..color_channels] {
+                    *value = (*value * steps).round() / steps;
+                }
+            }
+        });
+    }
+
+    /// Stretch every channel to the full range, clipping histogram tails
+    ///
+    /// Each color channel is remapped linearly so that the value at
+    /// `percentile_clip` percent from the bottom becomes the minimum and the
+    /// value at `percentile_clip` percent from the top the maximum; values
+    /// beyond are clipped. The full range is `0.0..=1.0` for unsigned and float
+    /// images and `-1.0..=1.0` for signed-normalized ones. Alpha and constant
+    /// channels are unchanged. Stretching channels independently also removes
+    /// color casts; use [`Image::auto_normalize_luminance`] to keep hues.
+    ///
+    /// # Arguments
+    /// * `percentile_clip` - Percentage of samples clipped at each end
+    ///   (`0.0..=50.0`, e.g. 0.5; 0 maps the minimum and maximum exactly)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut height = GalaxyImage::load_from_file("scanned_height.png")?;
+    /// height.auto_normalize(0.5);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn auto_normalize(&mut self, percentile_clip: f32) {
+        let channel_count = self.pixel_format().channel_count();
+        let color_channels = if self.pixel_format().has_alpha() { channel_count - 1 } else { channel_count };
+        let ranges: Vec<_> = (0..color_channels)
+            .map(|channel| percentile_range(&mut self.channel_samples(|pixel| pixel[channel]), percentile_clip))
+            .collect();
+        let target_low = self.normalized_floor();
+
+        map_rows(self, false, |values| {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                for (value, range) in pixel.iter_mut().zip(&ranges) {
+                    if let Some((low, high)) = *range {
+                        *value = stretch(*value, low, high, target_low);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Stretch the luminance to the full range, clipping histogram tails
+    ///
+    /// Like [`Image::auto_normalize`], but the percentiles are taken on the
+    /// Rec.709 luminance of the stored values and the same mapping is applied
+    /// to every color channel, so hues and saturation are kept. Grayscale
+    /// images are handled as their single channel.
+    ///
+    /// # Arguments
+    /// * `percentile_clip` - Percentage of pixels clipped at each end
+    ///   (`0.0..=50.0`, e.g. 0.5)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut albedo = GalaxyImage::load_from_file("faded_scan.png")?;
+    /// albedo.auto_normalize_luminance(1.0);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn auto_normalize_luminance(&mut self, percentile_clip: f32) {
+        let channel_count = self.pixel_format().channel_count();
+        let (weights, color_channels): (&[f32], usize) = match self.pixel_format() {
+            PixelFormat::R | PixelFormat::RG => (&[1.0], 1),
+            PixelFormat::RGB | PixelFormat::RGBA => (&[0.2126, 0.7152, 0.0722], 3),
+            PixelFormat::BGR | PixelFormat::BGRA => (&[0.0722, 0.7152, 0.2126], 3),
+        };
+        let luminance = |pixel: &[f32]| pixel.iter().zip(weights).map(|(v, w)| v * w).sum::<f32>();
+        let Some((low, high)) = percentile_range(&mut self.channel_samples(luminance), percentile_clip) else {
+            return;
+        };
+        let target_low = self.normalized_floor();
+
+        map_rows(self, false, |values| {
+            for pixel in values.chunks_exact_mut(channel_count) {
+                for value in &mut pixel[..color_channels] {
+                    *value = stretch(*value, low, high, target_low);
+                }
+            }
+        });
+    }
+
+    /// Collect one normalized value per pixel
+    fn channel_samples(&self, sample: impl Fn(&[f32]) -> f32) -> Vec<f32> {
+        let channel_count = self.pixel_format().channel_count();
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return Vec::new();
+        }
+        let mut samples = Vec::with_capacity(self.width() as usize * self.height() as usize);
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data().chunks_exact(row_bytes) {
+            read_normalized_slice(row, self.component_type(), &mut values);
+            samples.extend(values.chunks_exact(channel_count).map(&sample));
+        }
+        samples
+    }
+
+    /// Lowest normalized value of the component type (-1 for signed, 0 otherwise)
+    fn normalized_floor(&self) -> f32 {
+        if self.component_type().is_signed() { -1.0 } else { 0.0 }
+    }
+}
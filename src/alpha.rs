@@ -0,0 +1,128 @@
+/// Alpha premultiplication
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image};
+
+/// Relation between the color channels and alpha of an image
+///
+/// Loaders return straight alpha, and new images start straight. Resizing,
+/// mip generation and conversions keep the tag of their source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AlphaMode {
+    /// Color channels are independent of alpha (how files usually store them)
+    #[default]
+    Straight,
+
+    /// Color channels are multiplied by alpha
+    ///
+    /// Blends correctly with `src + dst * (1 - src_alpha)` and filters without
+    /// dark fringes around transparent texels.
+    Premultiplied,
+}
+
+impl Image {
+    /// Multiply the color channels by alpha
+    ///
+    /// Works for every pixel format with alpha (RG, RGBA, BGRA) and component
+    /// type; U8 and U16 samples are rounded exactly. Tags the image
+    /// [`AlphaMode::Premultiplied`]. Images already premultiplied, or without
+    /// alpha, are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{AlphaMode, ComponentType, Image, PixelFormat};
+    ///
+    /// let mut image = Image::from_raw(vec![255, 128, 0, 128], 1, 1, PixelFormat::RGBA, ComponentType::U8);
+    /// image.premultiply_alpha();
+    /// assert_eq!(image.data(), &[128, 64, 0, 128]);
+    /// assert_eq!(image.alpha_mode(), AlphaMode::Premultiplied);
+    /// ```
+    pub fn premultiply_alpha(&mut self) {
+        if self.alpha_mode() == AlphaMode::Premultiplied || !self.pixel_format().has_alpha() {
+            return;
+        }
+        let channel_count = self.pixel_format().channel_count();
+        match self.component_type() {
+            ComponentType::U8 => {
+                for pixel in self.data_mut().chunks_exact_mut(channel_count) {
+                    let alpha = pixel[channel_count - 1] as u32;
+                    for value in &mut pixel[..channel_count - 1] {
+                        *value = ((*value as u32 * alpha + 127) / 255) as u8;
+                    }
+                }
+            }
+            ComponentType::U16 => {
+                for pixel in self.data_mut().chunks_exact_mut(channel_count * 2) {
+                    let alpha = u16::from_le_bytes([pixel[channel_count * 2 - 2], pixel[channel_count * 2 - 1]]) as u64;
+                    for sample in pixel[..(channel_count - 1) * 2].chunks_exact_mut(2) {
+                        let value = u16::from_le_bytes([sample[0], sample[1]]) as u64;
+                        sample.copy_from_slice(&(((value * alpha + 32767) / 65535) as u16).to_le_bytes());
+                    }
+                }
+            }
+            _ => self.map_with_alpha(|value, alpha| value * alpha),
+        }
+        self.set_alpha_mode(AlphaMode::Premultiplied);
+    }
+
+    /// Divide the color channels by alpha
+    ///
+    /// The inverse of [`Image::premultiply_alpha`]: fully transparent pixels
+    /// get zero color, and integer samples are rounded and clamped to their
+    /// range. Tags the image [`AlphaMode::Straight`]. Images already straight,
+    /// or without alpha, are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{AlphaMode, ComponentType, Image, PixelFormat};
+    ///
+    /// let mut image = Image::from_raw(vec![128, 64, 0, 128], 1, 1, PixelFormat::RGBA, ComponentType::U8);
+    /// image.set_alpha_mode(AlphaMode::Premultiplied);
+    /// image.unpremultiply_alpha();
+    /// assert_eq!(image.data(), &[255, 128, 0, 128]);
+    /// ```
+    pub fn unpremultiply_alpha(&mut self) {
+        if self.alpha_mode() == AlphaMode::Straight || !self.pixel_format().has_alpha() {
+            return;
+        }
+        let channel_count = self.pixel_format().channel_count();
+        match self.component_type() {
+            ComponentType::U8 => {
+                for pixel in self.data_mut().chunks_exact_mut(channel_count) {
+                    let alpha = pixel[channel_count - 1] as u32;
+                    for value in &mut pixel[..channel_count - 1] {
+                        *value = (*value as u32 * 255 + alpha / 2).checked_div(alpha).map_or(0, |straight| straight.min(255) as u8);
+                    }
+                }
+            }
+            ComponentType::U16 => {
+                for pixel in self.data_mut().chunks_exact_mut(channel_count * 2) {
+                    let alpha = u16::from_le_bytes([pixel[channel_count * 2 - 2], pixel[channel_count * 2 - 1]]) as u64;
+                    for sample in pixel[..(channel_count - 1) * 2].chunks_exact_mut(2) {
+                        let value = u16::from_le_bytes([sample[0], sample[1]]) as u64;
+                        let straight = (value * 65535 + alpha / 2).checked_div(alpha).map_or(0, |straight| straight.min(65535));
+                        sample.copy_from_slice(&(straight as u16).to_le_bytes());
+                    }
+                }
+            }
+            _ => self.map_with_alpha(|value, alpha| if alpha == 0.0 { 0.0 } else { value / alpha }),
+        }
+        self.set_alpha_mode(AlphaMode::Straight);
+    }
+
+    /// Replace every color sample with `f(value, alpha)` on normalized values
+    fn map_with_alpha(&mut self, f: impl Fn(f32, f32) -> f32) {
+        let channel_count = self.pixel_format().channel_count();
+        let component_type = self.component_type();
+        let mut values = vec![0f32; self.size_bytes() / component_type.size_bytes()];
+        read_normalized_slice(self.data(), component_type, &mut values);
+        for pixel in values.chunks_exact_mut(channel_count) {
+            let alpha = pixel[channel_count - 1];
+            for value in &mut pixel[..channel_count - 1] {
+                *value = f(*value, alpha);
+            }
+        }
+        write_normalized_slice(self.data_mut(), component_type, &values);
+    }
+}
@@ -0,0 +1,116 @@
+/// Animated images decoded into frame sequences
+use crate::{Image, Rect};
+
+/// What happens to a frame's area before the next frame is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FrameDisposal {
+    /// Leave the frame in place; the next frame draws over it
+    #[default]
+    Keep,
+
+    /// Clear the frame's area to transparent
+    Background,
+
+    /// Restore the area to what it was before the frame was drawn
+    Previous,
+}
+
+/// One frame of an animation
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    /// Full canvas as displayed while the frame is shown
+    ///
+    /// Earlier frames and the disposal of the previous frame are already
+    /// applied, so frames can be shown as is.
+    pub image: Image,
+
+    /// Display duration in milliseconds
+    ///
+    /// Stored as in the file; browsers show frames with a delay below 20 ms for 100 ms.
+    pub delay_ms: u32,
+
+    /// Disposal applied to `rect` before the next frame
+    pub disposal: FrameDisposal,
+
+    /// Canvas area updated by this frame, clipped to the canvas
+    pub rect: Rect,
+}
+
+/// Frame sequence of an animated image (e.g. a GIF sprite animation)
+#[derive(Debug, Clone)]
+pub struct AnimatedImage {
+    /// Canvas width in pixels
+    pub width: u32,
+
+    /// Canvas height in pixels
+    pub height: u32,
+
+    /// Frames in display order
+    pub frames: Vec<AnimationFrame>,
+
+    /// Number of times the animation plays, 0 to loop forever
+    pub loop_count: u32,
+}
+
+impl AnimatedImage {
+    /// Create a one-frame animation from a still image
+    pub fn from_image(image: Image) -> Self {
+        Self {
+            width: image.width(),
+            height: image.height(),
+            frames: vec![AnimationFrame {
+                rect: Rect::of_image(&image),
+                image,
+                delay_ms: 0,
+                disposal: FrameDisposal::Keep,
+            }],
+            loop_count: 1,
+        }
+    }
+
+    /// Total duration of one loop in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.frames.iter().map(|frame| frame.delay_ms as u64).sum()
+    }
+
+    /// Get the frame shown `time_ms` milliseconds after the start, honoring `loop_count`
+    ///
+    /// Returns the last frame once the animation has finished, and the first
+    /// frame when no frame has a delay.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{AnimatedImage, ComponentType, Image, PixelFormat};
+    ///
+    /// let mut animation = AnimatedImage::from_image(Image::new(4, 4, PixelFormat::RGBA, ComponentType::U8));
+    /// animation.frames[0].delay_ms = 100;
+    /// animation.frames.push(animation.frames[0].clone());
+    /// animation.loop_count = 0;
+    /// assert_eq!(animation.frame_index_at(250), 0);
+    /// assert_eq!(animation.frame_index_at(150), 1);
+    /// ```
+    pub fn frame_index_at(&self, time_ms: u64) -> usize {
+        let duration = self.duration_ms();
+        if duration == 0 || self.frames.is_empty() {
+            return 0;
+        }
+        if self.loop_count != 0 && time_ms >= duration * self.loop_count as u64 {
+            return self.frames.len() - 1;
+        }
+
+        let mut remaining = time_ms % duration;
+        for (index, frame) in self.frames.iter().enumerate() {
+            if remaining < frame.delay_ms as u64 {
+                return index;
+            }
+            remaining -= frame.delay_ms as u64;
+        }
+        self.frames.len() - 1
+    }
+
+    /// Take the frame images, dropping timing information
+    pub fn into_images(self) -> Vec<Image> {
+        self.frames.into_iter().map(|frame| frame.image).collect()
+    }
+}
@@ -0,0 +1,131 @@
+/// Async load and save on the tokio runtime (feature `async`)
+use crate::galaxy_image::detect_file_format;
+use crate::{atomic_write, GalaxyImage, Image, ImageError, ImageFormat, ImageResult, LoadOptions, SaveOptions};
+use std::future::Future;
+use std::path::Path;
+
+/// Run a decode or encode on the blocking pool of the runtime
+async fn run_blocking<T, F>(work: F) -> ImageResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> ImageResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|error| ImageError::Other(format!("Blocking image task failed: {}", error)))?
+}
+
+impl GalaxyImage {
+    /// Load an image from a file path without blocking the async runtime
+    ///
+    /// The file is read with `tokio::fs` and decoded on the blocking thread
+    /// pool of the runtime; the format is detected like
+    /// [`GalaxyImage::load_from_file`]. Must be awaited inside a tokio
+    /// runtime. Requires the `async` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// # async fn load() -> galaxy_image::ImageResult<()> {
+    /// let image = GalaxyImage::load_from_file_async("texture.png").await?;
+    /// println!("Loaded {}x{} image", image.width(), image.height());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_from_file_async<P: AsRef<Path>>(path: P) -> impl Future<Output = ImageResult<Image>> + Send + 'static {
+        Self::load_from_file_with_options_async(path, &LoadOptions::default())
+    }
+
+    /// Load an image from a file path with load options, without blocking the async runtime
+    ///
+    /// See [`GalaxyImage::load_from_file_async`]. Requires the `async` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `options` - Decoding options (e.g. bottom-up row order)
+    pub fn load_from_file_with_options_async<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> impl Future<Output = ImageResult<Image>> + Send + 'static {
+        let path = path.as_ref().to_path_buf();
+        let options = options.clone();
+        async move {
+            let bytes = tokio::fs::read(&path).await?;
+            run_blocking(move || {
+                let format = detect_file_format(&path, &bytes);
+                Self::load_from_bytes_with_options(&bytes, format, &options)
+            })
+            .await
+        }
+    }
+
+    /// Save an image to a file without blocking the async runtime
+    ///
+    /// See [`GalaxyImage::save_to_file_with_options_async`]. Requires the
+    /// `async` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path` - Output file path
+    /// * `format` - Output format
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    ///
+    /// # async fn save(image: galaxy_image::Image) -> galaxy_image::ImageResult<()> {
+    /// GalaxyImage::save_to_file_async(&image, "screenshot.png", ImageFormat::Png).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_to_file_async<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        format: ImageFormat,
+    ) -> impl Future<Output = ImageResult<()>> + Send + 'static {
+        Self::save_to_file_with_options_async(image, path, format, &SaveOptions::default())
+    }
+
+    /// Save an image to a file with encoding options, without blocking the async runtime
+    ///
+    /// The image is encoded on the blocking thread pool of the runtime and
+    /// written with `tokio::fs`. Atomic writes (the default, see
+    /// [`SaveOptions::atomic`]) sync the file to disk, so they also run on the
+    /// blocking pool. The pixel buffer is shared with `image`, not copied.
+    /// Must be awaited inside a tokio runtime. Requires the `async` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path` - Output file path
+    /// * `format` - Output format
+    /// * `options` - Encoding options
+    pub fn save_to_file_with_options_async<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        format: ImageFormat,
+        options: &SaveOptions,
+    ) -> impl Future<Output = ImageResult<()>> + Send + 'static {
+        let image = image.clone();
+        let path = path.as_ref().to_path_buf();
+        let options = options.clone();
+        async move {
+            let atomic = options.atomic;
+            let bytes = run_blocking(move || Self::save_to_bytes_with_options(&image, format, &options)).await?;
+            if atomic {
+                run_blocking(move || Ok(atomic_write::write_file(&path, &bytes, true)?)).await
+            } else {
+                Ok(tokio::fs::write(&path, &bytes).await?)
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+/// Texture atlas packing
+use crate::roi::Rect;
+use crate::{Image, ImageError, ImageResult};
+
+/// Packing options of [`GalaxyImage::pack_atlas`](crate::GalaxyImage::pack_atlas)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtlasOptions {
+    /// Maximum width and height of a page in pixels (default: 2048)
+    pub max_page_size: u32,
+
+    /// Gutter around every sprite in pixels (default: 2)
+    ///
+    /// Neighboring sprites are `2 * padding` pixels apart, which keeps
+    /// bilinear filtering and mip levels from sampling the next sprite.
+    pub padding: u32,
+
+    /// Fill the gutter with the edge pixels of the sprite (default: true)
+    ///
+    /// Without bleeding the gutter stays transparent black.
+    pub bleed: bool,
+
+    /// Round page sizes up to powers of two (default: false)
+    pub power_of_two: bool,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        Self {
+            max_page_size: 2048,
+            padding: 2,
+            bleed: true,
+            power_of_two: false,
+        }
+    }
+}
+
+/// Placement of one sprite in a [`TextureAtlas`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasSprite {
+    /// Index of the page holding the sprite
+    pub page: usize,
+
+    /// Pixel rectangle of the sprite on its page, padding excluded
+    pub rect: Rect,
+
+    /// Normalized texture coordinates of the rectangle, `[u0, v0, u1, v1]`
+    ///
+    /// `(u0, v0)` is the top-left corner and `(u1, v1)` the bottom-right one,
+    /// with `v` growing downwards like the rows of the page.
+    pub uv: [f32; 4],
+}
+
+/// Sprites packed into one or more atlas pages
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    /// Page images, in the pixel format and component type of the first sprite
+    pub pages: Vec<Image>,
+
+    /// Placement of every sprite, in input order
+    pub sprites: Vec<AtlasSprite>,
+}
+
+/// Free space of one page, tracked as maximal free rectangles
+struct MaxRects {
+    free: Vec<Rect>,
+    used_width: u32,
+    used_height: u32,
+}
+
+impl MaxRects {
+    fn new(size: u32) -> Self {
+        Self {
+            free: vec![Rect::new(0, 0, size, size)],
+            used_width: 0,
+            used_height: 0,
+        }
+    }
+
+    /// Place a rectangle with the best short side fit heuristic
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (x, y) = self
+            .free
+            .iter()
+            .filter(|free| width <= free.width && height <= free.height)
+            .min_by_key(|free| {
+                let (dw, dh) = (free.width - width, free.height - height);
+                (dw.min(dh), dw.max(dh), free.y, free.x)
+            })
+            .map(|free| (free.x, free.y))?;
+
+        let placed = Rect::new(x, y, width, height);
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+        for rect in self.free.drain(..) {
+            if rect.intersect(&placed).is_none() {
+                free.push(rect);
+                continue;
+            }
+            // Keep the parts of the free rectangle on each side of the placed one
+            let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+            let (placed_right, placed_bottom) = (x + width, y + height);
+            if x > rect.x {
+                free.push(Rect::new(rect.x, rect.y, x - rect.x, rect.height));
+            }
+            if placed_right < right {
+                free.push(Rect::new(placed_right, rect.y, right - placed_right, rect.height));
+            }
+            if y > rect.y {
+                free.push(Rect::new(rect.x, rect.y, rect.width, y - rect.y));
+            }
+            if placed_bottom < bottom {
+                free.push(Rect::new(rect.x, placed_bottom, rect.width, bottom - placed_bottom));
+            }
+        }
+
+        // Drop rectangles contained in another one
+        let contains = |outer: &Rect, inner: &Rect| {
+            inner.x >= outer.x
+                && inner.y >= outer.y
+                && inner.x + inner.width <= outer.x + outer.width
+                && inner.y + inner.height <= outer.y + outer.height
+        };
+        let mut index = 0;
+        while index < free.len() {
+            let redundant = free
+                .iter()
+                .enumerate()
+                .any(|(other, rect)| other != index && contains(rect, &free[index]) && (free[index] != *rect || other < index));
+            if redundant {
+                free.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        self.free = free;
+
+        self.used_width = self.used_width.max(x + width);
+        self.used_height = self.used_height.max(y + height);
+        Some((x, y))
+    }
+}
+
+/// Replicate the edge pixels of `rect` into the `padding` pixels around it
+fn bleed_edges(page: &mut Image, rect: Rect, padding: u32) {
+    let bytes_per_pixel = page.bytes_per_pixel();
+    let page_width = page.width() as usize;
+    let offset = |x: u32, y: u32| (y as usize * page_width + x as usize) * bytes_per_pixel;
+    let data = page.data_mut();
+
+    for y in rect.y - padding..rect.y + rect.height + padding {
+        let source_y = y.clamp(rect.y, rect.y + rect.height - 1);
+        for x in rect.x - padding..rect.x + rect.width + padding {
+            let source_x = x.clamp(rect.x, rect.x + rect.width - 1);
+            if (source_x, source_y) != (x, y) {
+                let source = offset(source_x, source_y);
+                data.copy_within(source..source + bytes_per_pixel, offset(x, y));
+            }
+        }
+    }
+}
+
+/// Pack images into pages
+pub(crate) fn pack(images: &[&Image], options: &AtlasOptions) -> ImageResult<TextureAtlas> {
+    let padding = options.padding;
+    let padded = |image: &Image| {
+        let size = |side: u32| side.checked_add(padding.saturating_mul(2)).filter(|&side| side <= options.max_page_size);
+        size(image.width()).zip(size(image.height()))
+    };
+    for image in images {
+        if image.width() == 0 || image.height() == 0 || padded(image).is_none() {
+            return Err(ImageError::InvalidDimensions {
+                width: image.width(),
+                height: image.height(),
+            });
+        }
+    }
+
+    // Largest sprites first leave the fewest unusable gaps
+    let mut order: Vec<usize> = (0..images.len()).collect();
+    order.sort_by_key(|&index| {
+        let image = images[index];
+        std::cmp::Reverse((image.width().max(image.height()), image.width().min(image.height())))
+    });
+
+    let mut bins: Vec<MaxRects> = Vec::new();
+    let mut placements = vec![(0, 0, 0); images.len()];
+    for index in order {
+        let (width, height) = padded(images[index]).expect("sprite sizes are checked above");
+        let placed = bins
+            .iter_mut()
+            .enumerate()
+            .find_map(|(page, bin)| bin.insert(width, height).map(|(x, y)| (page, x, y)));
+        placements[index] = match placed {
+            Some(placement) => placement,
+            None => {
+                let mut bin = MaxRects::new(options.max_page_size);
+                let (x, y) = bin.insert(width, height).expect("a sprite always fits an empty page");
+                bins.push(bin);
+                (bins.len() - 1, x, y)
+            }
+        };
+    }
+
+    let (pixel_format, component_type) = match images.first() {
+        Some(image) => (image.pixel_format(), image.component_type()),
+        None => return Ok(TextureAtlas { pages: Vec::new(), sprites: Vec::new() }),
+    };
+    let page_size = |used: u32| if options.power_of_two { used.next_power_of_two() } else { used };
+    let mut pages: Vec<Image> = bins
+        .iter()
+        .map(|bin| Image::new(page_size(bin.used_width), page_size(bin.used_height), pixel_format, component_type))
+        .collect();
+
+    let sprites = images
+        .iter()
+        .zip(placements)
+        .map(|(image, (page, x, y))| {
+            let rect = Rect::new(x + padding, y + padding, image.width(), image.height());
+            let target = &mut pages[page];
+            target.blit(image, rect.x as i32, rect.y as i32);
+            if options.bleed && padding > 0 {
+                bleed_edges(target, rect, padding);
+            }
+
+            let (page_width, page_height) = (target.width() as f32, target.height() as f32);
+            let uv = [
+                rect.x as f32 / page_width,
+                rect.y as f32 / page_height,
+                (rect.x + rect.width) as f32 / page_width,
+                (rect.y + rect.height) as f32 / page_height,
+            ];
+            AtlasSprite { page, rect, uv }
+        })
+        .collect();
+
+    Ok(TextureAtlas { pages, sprites })
+}
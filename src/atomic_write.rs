@@ -0,0 +1,75 @@
+/// Crash-safe file writes: temporary file, fsync, rename
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes temporary files of concurrent writes from the same process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Hidden temporary path next to the destination, e.g. `dir/.albedo.png.1234.0.tmp`
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), counter))
+}
+
+/// Write a temporary file in the destination directory, flush it to disk and
+/// rename it over the destination
+///
+/// Readers see either the previous file or the complete new one, never a
+/// partial write. The temporary file is removed if any step fails. An existing
+/// destination keeps its permissions.
+fn write_atomic<E, F>(path: &Path, write: F) -> Result<(), E>
+where
+    E: From<io::Error>,
+    F: FnOnce(&mut File) -> Result<(), E>,
+{
+    let temp = temp_path(path);
+    let result = (|| {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&temp)?;
+        write(&mut file)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        drop(file);
+        Ok(fs::rename(&temp, path)?)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+
+    // Persist the rename itself; directories cannot be opened for syncing on
+    // every platform, so this is best effort
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+        if let Ok(directory) = fs::File::open(parent) {
+            let _ = directory.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Write a file, atomically or in place
+pub(crate) fn write_file(path: &Path, bytes: &[u8], atomic: bool) -> io::Result<()> {
+    write_file_with(path, atomic, |file| file.write_all(bytes))
+}
+
+/// Write a file through a callback, atomically or in place
+///
+/// For files streamed in parts; the callback gets the open file, which is
+/// created empty.
+pub(crate) fn write_file_with<E, F>(path: &Path, atomic: bool, write: F) -> Result<(), E>
+where
+    E: From<io::Error>,
+    F: FnOnce(&mut File) -> Result<(), E>,
+{
+    if atomic {
+        write_atomic(path, write)
+    } else {
+        write(&mut File::create(path)?)
+    }
+}
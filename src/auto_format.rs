@@ -0,0 +1,112 @@
+/// Automatic output format selection
+use crate::{ComponentType, GalaxyImage, Image, ImageError, ImageFormat, ImageResult, SaveOptions};
+use std::collections::HashSet;
+
+/// Distinct colors up to which an opaque 8-bit image is treated as a graphic
+const GRAPHIC_MAX_COLORS: usize = 256;
+
+/// Distinct gray levels up to which a grayscale 8-bit image is treated as a graphic
+const GRAPHIC_MAX_GRAY_LEVELS: usize = 64;
+
+/// JPEG qualities tried in turn when the encoded size is limited
+const FALLBACK_JPEG_QUALITIES: [u8; 5] = [80, 70, 60, 50, 40];
+
+/// Constraints on the format chosen by [`GalaxyImage::save_auto`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SaveConstraints {
+    /// Only choose lossless formats (default: false)
+    pub lossless: bool,
+
+    /// Maximum encoded size in bytes (default: None)
+    ///
+    /// JPEG quality is lowered step by step until the file fits.
+    pub max_size_bytes: Option<usize>,
+
+    /// Encoder options (JPEG quality is the starting quality)
+    pub options: SaveOptions,
+}
+
+/// Check if an 8-bit image has alpha below fully opaque
+fn has_transparency(image: &Image) -> bool {
+    if !image.pixel_format().has_alpha() {
+        return false;
+    }
+
+    let bytes_per_pixel = image.bytes_per_pixel();
+    image
+        .data()
+        .chunks_exact(bytes_per_pixel)
+        .any(|pixel| pixel[bytes_per_pixel - 1] != u8::MAX)
+}
+
+/// Check if an opaque 8-bit image looks like a flat-color graphic rather than a photo
+fn is_graphic(image: &Image) -> bool {
+    let channel_count = image.pixel_format().channel_count();
+    let max_colors = if channel_count <= 2 {
+        GRAPHIC_MAX_GRAY_LEVELS
+    } else {
+        GRAPHIC_MAX_COLORS
+    };
+
+    // Alpha is ignored since the image is opaque
+    let color_bytes = if image.pixel_format().has_alpha() { channel_count - 1 } else { channel_count };
+    let mut colors = HashSet::new();
+    for pixel in image.data().chunks_exact(channel_count) {
+        colors.insert(&pixel[..color_bytes]);
+        if colors.len() > max_colors {
+            return false;
+        }
+    }
+    true
+}
+
+/// Candidate formats in order of preference
+fn candidates(image: &Image, constraints: &SaveConstraints) -> Vec<ImageFormat> {
+    match image.component_type() {
+        ComponentType::F16 | ComponentType::F32 => vec![ImageFormat::Exr],
+        // TIFF is the only encoder that keeps signed integers as stored
+        ComponentType::I8 | ComponentType::I16 => vec![ImageFormat::Tiff],
+        ComponentType::U16 => vec![ImageFormat::Png],
+        ComponentType::U8 if constraints.lossless || has_transparency(image) => vec![ImageFormat::Png],
+        ComponentType::U8 if is_graphic(image) => vec![ImageFormat::Png, ImageFormat::Jpeg],
+        ComponentType::U8 => vec![ImageFormat::Jpeg],
+    }
+}
+
+/// Encode an image with the most appropriate format for its content
+///
+/// Returns the chosen format and the encoded bytes.
+pub(crate) fn encode_auto(
+    image: &Image,
+    constraints: &SaveConstraints,
+) -> ImageResult<(ImageFormat, Vec<u8>)> {
+    let fits = |bytes: &[u8]| constraints.max_size_bytes.is_none_or(|max| bytes.len() <= max);
+    let mut smallest = None;
+
+    for format in candidates(image, constraints) {
+        let bytes = GalaxyImage::save_to_bytes_with_options(image, format, &constraints.options)?;
+        if fits(&bytes) {
+            return Ok((format, bytes));
+        }
+        smallest = Some(smallest.map_or(bytes.len(), |len: usize| len.min(bytes.len())));
+
+        // Trade quality for size
+        if format == ImageFormat::Jpeg {
+            let start = constraints.options.jpeg.quality;
+            for quality in FALLBACK_JPEG_QUALITIES.into_iter().filter(|&q| q < start) {
+                let options = constraints.options.clone().with_jpeg_quality(quality);
+                let bytes = GalaxyImage::save_to_bytes_with_options(image, format, &options)?;
+                if fits(&bytes) {
+                    return Ok((format, bytes));
+                }
+                smallest = Some(smallest.map_or(bytes.len(), |len: usize| len.min(bytes.len())));
+            }
+        }
+    }
+
+    Err(ImageError::Other(format!(
+        "No format fits within {} bytes (smallest encoding: {} bytes)",
+        constraints.max_size_bytes.unwrap_or(0),
+        smallest.unwrap_or(0),
+    )))
+}
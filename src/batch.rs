@@ -0,0 +1,254 @@
+/// Directory batch conversion
+use crate::{GalaxyImage, Image, ImageError, ImageFormat, ImageResult, LoadOptions, SaveOptions};
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Operation applied to every image of a [`ConvertJob`] between loading and saving
+pub type ImageOperation = Arc<dyn Fn(&mut Image) -> ImageResult<()> + Send + Sync>;
+
+/// Description of a directory conversion run by
+/// [`GalaxyImage::convert_directory`](crate::GalaxyImage::convert_directory)
+#[derive(Clone)]
+pub struct ConvertJob {
+    /// Input file extensions to convert, case-insensitive without the dot
+    /// (default: empty, every supported format)
+    pub extensions: Vec<String>,
+
+    /// Walk subdirectories and mirror them in the output directory (default: true)
+    pub recursive: bool,
+
+    /// Options used to load every input file
+    pub load_options: LoadOptions,
+
+    /// Operations applied in order to every image after loading (default: none)
+    pub operations: Vec<ImageOperation>,
+
+    /// Output format (default: None, keep the input format)
+    pub format: Option<ImageFormat>,
+
+    /// Options used to save every output file
+    pub save_options: SaveOptions,
+
+    /// Number of files converted in parallel (default: 0, one per CPU core)
+    pub threads: usize,
+}
+
+impl Default for ConvertJob {
+    fn default() -> Self {
+        Self {
+            extensions: Vec::new(),
+            recursive: true,
+            load_options: LoadOptions::default(),
+            operations: Vec::new(),
+            format: None,
+            save_options: SaveOptions::default(),
+            threads: 0,
+        }
+    }
+}
+
+impl fmt::Debug for ConvertJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConvertJob")
+            .field("extensions", &self.extensions)
+            .field("recursive", &self.recursive)
+            .field("load_options", &self.load_options)
+            .field("operations", &self.operations.len())
+            .field("format", &self.format)
+            .field("save_options", &self.save_options)
+            .field("threads", &self.threads)
+            .finish()
+    }
+}
+
+impl ConvertJob {
+    /// Create a job converting every supported file to a format
+    pub fn new(format: ImageFormat) -> Self {
+        Self {
+            format: Some(format),
+            ..Self::default()
+        }
+    }
+
+    /// Only convert files with one of these extensions
+    pub fn with_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Append an operation applied to every image after loading
+    pub fn with_operation<F>(mut self, operation: F) -> Self
+    where
+        F: Fn(&mut Image) -> ImageResult<()> + Send + Sync + 'static,
+    {
+        self.operations.push(Arc::new(operation));
+        self
+    }
+
+    /// Set the load options
+    pub fn with_load_options(mut self, options: LoadOptions) -> Self {
+        self.load_options = options;
+        self
+    }
+
+    /// Set the save options
+    pub fn with_save_options(mut self, options: SaveOptions) -> Self {
+        self.save_options = options;
+        self
+    }
+
+    /// Check whether a file is an input of the job
+    fn accepts(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return ImageFormat::from_path(path) != ImageFormat::Unknown;
+        }
+        path.extension().is_some_and(|extension| {
+            self.extensions
+                .iter()
+                .any(|wanted| extension.eq_ignore_ascii_case(wanted.trim_start_matches('.')))
+        })
+    }
+}
+
+/// Outcome of one file of a directory conversion
+#[derive(Debug)]
+pub struct ConvertedFile {
+    /// Input file
+    pub source: PathBuf,
+
+    /// Output file (not written if `result` is an error)
+    pub output: PathBuf,
+
+    /// Success, or the error that stopped the conversion of this file
+    pub result: ImageResult<()>,
+}
+
+/// Per-file results of [`GalaxyImage::convert_directory`](crate::GalaxyImage::convert_directory),
+/// in path order
+#[derive(Debug, Default)]
+pub struct ConvertReport {
+    /// One entry per input file
+    pub files: Vec<ConvertedFile>,
+}
+
+impl ConvertReport {
+    /// Files converted successfully
+    pub fn succeeded(&self) -> impl Iterator<Item = &ConvertedFile> {
+        self.files.iter().filter(|file| file.result.is_ok())
+    }
+
+    /// Files that failed to convert
+    pub fn failed(&self) -> impl Iterator<Item = &ConvertedFile> {
+        self.files.iter().filter(|file| file.result.is_err())
+    }
+
+    /// Check if every file was converted
+    pub fn is_success(&self) -> bool {
+        self.files.iter().all(|file| file.result.is_ok())
+    }
+}
+
+/// Collect the input files under `dir`, sorted, skipping the output directory
+///
+/// `skip` is the canonical output directory, if it exists.
+fn collect_inputs(dir: &Path, skip: Option<&Path>, job: &ConvertJob, files: &mut Vec<PathBuf>) -> ImageResult<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            let is_output = skip.is_some_and(|skip| fs::canonicalize(&path).is_ok_and(|path| path == skip));
+            if job.recursive && !is_output {
+                collect_inputs(&path, skip, job, files)?;
+            }
+        } else if job.accepts(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Output path of an input: same relative path, extension of the output format
+fn output_path(source: &Path, src_dir: &Path, dst_dir: &Path, format: ImageFormat) -> PathBuf {
+    let relative = source.strip_prefix(src_dir).unwrap_or(source);
+    dst_dir.join(relative).with_extension(format.extension())
+}
+
+/// Load, process and save one file
+fn convert_file(source: &Path, output: &Path, format: ImageFormat, job: &ConvertJob) -> ImageResult<()> {
+    let mut image = GalaxyImage::load_from_file_with_options(source, &job.load_options)?;
+    for operation in &job.operations {
+        operation(&mut image)?;
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    GalaxyImage::save_to_file_with_options(&image, output, format, &job.save_options)
+}
+
+pub(crate) fn convert_directory(src_dir: &Path, dst_dir: &Path, job: &ConvertJob) -> ImageResult<ConvertReport> {
+    if job.format == Some(ImageFormat::Unknown) {
+        return Err(ImageError::UnsupportedFormat("Cannot convert to an unknown format".to_string()));
+    }
+
+    let mut sources = Vec::new();
+    let skip = fs::canonicalize(dst_dir).ok();
+    collect_inputs(src_dir, skip.as_deref(), job, &mut sources)?;
+
+    let thread_count = match job.threads {
+        0 => thread::available_parallelism().map_or(4, |n| n.get()),
+        threads => threads,
+    }
+    .min(sources.len())
+    .max(1);
+
+    // Workers take the next file from a shared index
+    let next = AtomicUsize::new(0);
+    let mut files: Vec<(usize, ConvertedFile)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..thread_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(source) = sources.get(index) else {
+                            break;
+                        };
+                        let format = job.format.unwrap_or_else(|| ImageFormat::from_path(source));
+                        let output = output_path(source, src_dir, dst_dir, format);
+                        let result = match format {
+                            ImageFormat::Unknown => Err(ImageError::UnsupportedFormat(format!(
+                                "Cannot infer output format of '{}'",
+                                source.display()
+                            ))),
+                            // A panicking operation fails its file, not the whole run
+                            format => panic::catch_unwind(AssertUnwindSafe(|| convert_file(source, &output, format, job)))
+                                .unwrap_or_else(|_| Err(ImageError::Other("Conversion panicked".to_string()))),
+                        };
+                        done.push((index, ConvertedFile { source: source.clone(), output, result }));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+
+    files.sort_by_key(|(index, _)| *index);
+    Ok(ConvertReport { files: files.into_iter().map(|(_, file)| file).collect() })
+}
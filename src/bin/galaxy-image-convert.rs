@@ -0,0 +1,265 @@
+//! Command-line image converter for galaxy_image
+//!
+//! Converts one or more images between the supported formats, optionally
+//! resizing them and changing their pixel format or component type.
+
+use galaxy_image::{ComponentType, GalaxyImage, ImageFormat, LdrConversion, PixelFormat, ResizeFilter, SaveOptions, TonemapOperator};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: galaxy-image-convert [OPTIONS] <INPUT>...
+
+Loads images, optionally resizes and converts them, and saves them in
+another format.
+
+Options:
+  -o, --output <FILE>      Output file (single input only)
+  -d, --out-dir <DIR>      Output directory (one file per input)
+  -f, --format <FORMAT>    Output format: png, bmp, jpg, exr, tga, hdr, tif,
+                           pnm, pfm
+                           (default: from the output file extension)
+  -q, --quality <1-100>    JPEG quality (default: 90)
+      --deterministic      Byte-identical output for identical input
+      --ldr <MODE>         Convert HDR/16-bit images for 8-bit formats:
+                           clamp, normalize, tonemap (default: error)
+      --tonemap <CURVE>    Tone mapping curve, implies --ldr tonemap:
+                           reinhard, aces, exposure, clamp
+                           (default: reinhard)
+      --resize <WxH>       Resize to W x H pixels before saving
+      --filter <FILTER>    Resize filter: nearest, bilinear, area, bicubic,
+                           lanczos3 (default: area)
+      --pixel-format <PF>  Convert the channel layout: r, rg, rgb, rgba,
+                           bgr, bgra
+      --component-type <CT>
+                           Convert the component type: u8, u16, f16, f32,
+                           i8, i16
+  -h, --help               Print this help
+";
+
+/// Parsed command-line arguments
+struct Args {
+    inputs: Vec<PathBuf>,
+    output: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    format: Option<ImageFormat>,
+    options: SaveOptions,
+    resize: Option<(u32, u32)>,
+    filter: ResizeFilter,
+    pixel_format: Option<PixelFormat>,
+    component_type: Option<ComponentType>,
+}
+
+fn parse_format(name: &str) -> Result<ImageFormat, String> {
+    match ImageFormat::from_extension(&format!(".{}", name)) {
+        ImageFormat::Unknown => Err(format!("unknown format '{}'", name)),
+        format => Ok(format),
+    }
+}
+
+fn parse_ldr(name: &str) -> Result<LdrConversion, String> {
+    match name {
+        "clamp" => Ok(LdrConversion::Clamp),
+        "normalize" => Ok(LdrConversion::Normalize),
+        "tonemap" => Ok(LdrConversion::Tonemap),
+        "error" => Ok(LdrConversion::Error),
+        _ => Err(format!("unknown LDR conversion '{}'", name)),
+    }
+}
+
+fn parse_tonemap(name: &str) -> Result<TonemapOperator, String> {
+    match name {
+        "reinhard" => Ok(TonemapOperator::Reinhard),
+        "aces" => Ok(TonemapOperator::AcesFilmic),
+        "exposure" => Ok(TonemapOperator::Exposure),
+        "clamp" => Ok(TonemapOperator::Clamp),
+        _ => Err(format!("unknown tone mapping curve '{}'", name)),
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let size = value.split_once(['x', 'X']).and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+    match size {
+        Some((width, height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(format!("invalid size '{}', expected WxH", value)),
+    }
+}
+
+fn parse_filter(name: &str) -> Result<ResizeFilter, String> {
+    match name {
+        "nearest" => Ok(ResizeFilter::Nearest),
+        "bilinear" => Ok(ResizeFilter::Bilinear),
+        "area" => Ok(ResizeFilter::Area),
+        "bicubic" => Ok(ResizeFilter::Bicubic),
+        "lanczos3" => Ok(ResizeFilter::Lanczos3),
+        _ => Err(format!("unknown resize filter '{}'", name)),
+    }
+}
+
+fn parse_pixel_format(name: &str) -> Result<PixelFormat, String> {
+    match name {
+        "r" => Ok(PixelFormat::R),
+        "rg" => Ok(PixelFormat::RG),
+        "rgb" => Ok(PixelFormat::RGB),
+        "rgba" => Ok(PixelFormat::RGBA),
+        "bgr" => Ok(PixelFormat::BGR),
+        "bgra" => Ok(PixelFormat::BGRA),
+        _ => Err(format!("unknown pixel format '{}'", name)),
+    }
+}
+
+fn parse_component_type(name: &str) -> Result<ComponentType, String> {
+    match name {
+        "u8" => Ok(ComponentType::U8),
+        "u16" => Ok(ComponentType::U16),
+        "f16" => Ok(ComponentType::F16),
+        "f32" => Ok(ComponentType::F32),
+        "i8" => Ok(ComponentType::I8),
+        "i16" => Ok(ComponentType::I16),
+        _ => Err(format!("unknown component type '{}'", name)),
+    }
+}
+
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut args = Args {
+        inputs: Vec::new(),
+        output: None,
+        out_dir: None,
+        format: None,
+        options: SaveOptions::default(),
+        resize: None,
+        filter: ResizeFilter::default(),
+        pixel_format: None,
+        component_type: None,
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .ok_or_else(|| format!("missing value for {}", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => args.output = Some(PathBuf::from(value(&arg)?)),
+            "-d" | "--out-dir" => args.out_dir = Some(PathBuf::from(value(&arg)?)),
+            "-f" | "--format" => args.format = Some(parse_format(&value(&arg)?)?),
+            "-q" | "--quality" => {
+                let quality = value(&arg)?;
+                args.options.jpeg.quality = match quality.parse::<u8>() {
+                    Ok(q @ 1..=100) => q,
+                    _ => return Err(format!("invalid JPEG quality '{}'", quality)),
+                };
+            }
+            "--deterministic" => args.options.deterministic = true,
+            "--ldr" => args.options.ldr_conversion = parse_ldr(&value(&arg)?)?,
+            "--tonemap" => {
+                args.options.ldr_tonemap = parse_tonemap(&value(&arg)?)?;
+                args.options.ldr_conversion = LdrConversion::Tonemap;
+            }
+            "--resize" => args.resize = Some(parse_size(&value(&arg)?)?),
+            "--filter" => args.filter = parse_filter(&value(&arg)?)?,
+            "--pixel-format" => args.pixel_format = Some(parse_pixel_format(&value(&arg)?)?),
+            "--component-type" => args.component_type = Some(parse_component_type(&value(&arg)?)?),
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option '{}'", arg));
+            }
+            _ => args.inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if args.inputs.is_empty() {
+        return Err("no input file".to_string());
+    }
+    if args.output.is_some() && args.out_dir.is_some() {
+        return Err("--output and --out-dir are mutually exclusive".to_string());
+    }
+    if args.output.is_some() && args.inputs.len() > 1 {
+        return Err("--output requires a single input, use --out-dir".to_string());
+    }
+    if args.output.is_none() && args.out_dir.is_none() {
+        return Err("missing --output or --out-dir".to_string());
+    }
+    if args.out_dir.is_some() && args.format.is_none() {
+        return Err("--out-dir requires --format".to_string());
+    }
+    Ok(Some(args))
+}
+
+/// Resolve the output path and format for one input
+fn output_for(args: &Args, input: &Path) -> Result<(PathBuf, ImageFormat), String> {
+    if let Some(output) = &args.output {
+        let format = match args.format {
+            Some(format) => format,
+            None => match ImageFormat::from_path(output) {
+                ImageFormat::Unknown => {
+                    return Err(format!(
+                        "cannot infer format from '{}', use --format",
+                        output.display()
+                    ));
+                }
+                format => format,
+            },
+        };
+        return Ok((output.clone(), format));
+    }
+
+    // --out-dir implies --format (checked while parsing)
+    let format = args.format.unwrap_or(ImageFormat::Unknown);
+    let out_dir = args.out_dir.as_deref().unwrap_or(Path::new("."));
+    let mut file_name = input.file_stem().unwrap_or(input.as_os_str()).to_os_string();
+    file_name.push(".");
+    file_name.push(format.extension());
+    let output = out_dir.join(file_name);
+    Ok((output, format))
+}
+
+fn convert(args: &Args, input: &Path) -> Result<PathBuf, String> {
+    let (output, format) = output_for(args, input)?;
+    if let Some(out_dir) = &args.out_dir {
+        std::fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+    }
+    let mut image = GalaxyImage::load_from_file(input).map_err(|e| e.to_string())?;
+    if let Some((width, height)) = args.resize {
+        image = image.resize(width, height, args.filter);
+    }
+    if args.pixel_format.is_some() || args.component_type.is_some() {
+        let pixel_format = args.pixel_format.unwrap_or(image.pixel_format());
+        let component_type = args.component_type.unwrap_or(image.component_type());
+        image = image.convert(pixel_format, component_type).map_err(|e| e.to_string())?;
+    }
+    GalaxyImage::save_to_file_with_options(&image, &output, format, &args.options)
+        .map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut failed = false;
+    for input in &args.inputs {
+        match convert(&args, input) {
+            Ok(output) => println!("{} -> {}", input.display(), output.display()),
+            Err(msg) => {
+                eprintln!("error: {}: {}", input.display(), msg);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
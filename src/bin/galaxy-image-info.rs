@@ -0,0 +1,112 @@
+//! Command-line image inspection tool for galaxy_image
+//!
+//! Prints format, layout, and per-channel statistics of image files.
+
+use galaxy_image::{GalaxyImage, ImageFormat, PixelFormat};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: galaxy-image-info [OPTIONS] <FILE>...
+
+Prints dimensions, pixel format, component type, detected format,
+layer/mip counts, color metadata and per-channel statistics of image files.
+
+Options:
+      --no-stats    Only read headers, skip decoding, metadata and statistics
+  -h, --help        Print this help
+";
+
+/// Channel names in storage order
+fn channel_names(pixel_format: PixelFormat) -> &'static [&'static str] {
+    match pixel_format {
+        PixelFormat::R => &["Y"],
+        PixelFormat::RG => &["Y", "A"],
+        PixelFormat::RGB => &["R", "G", "B"],
+        PixelFormat::RGBA => &["R", "G", "B", "A"],
+        PixelFormat::BGR => &["B", "G", "R"],
+        PixelFormat::BGRA => &["B", "G", "R", "A"],
+    }
+}
+
+fn print_info(path: &Path, with_stats: bool) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let detected = ImageFormat::detect_from_bytes(&bytes);
+    let from_extension = ImageFormat::from_path(path);
+
+    println!("{}", path.display());
+    println!("  File size:      {} bytes", bytes.len());
+    println!("  Detected:       {:?} (extension: {:?})", detected, from_extension);
+
+    let info = GalaxyImage::probe_file(path).map_err(|e| e.to_string())?;
+    println!("  Dimensions:     {}x{}", info.width, info.height);
+    println!("  Pixel format:   {:?}", info.pixel_format);
+    println!("  Component type: {:?}", info.component_type);
+    println!("  Layers:         {}", info.layer_count);
+    println!("  Mip levels:     {}", info.mip_levels);
+
+    if with_stats {
+        let image = GalaxyImage::load_from_bytes(&bytes, info.format).map_err(|e| e.to_string())?;
+        let metadata = image.metadata();
+        println!("  Color space:    {:?}", image.color_space());
+        println!("  Metadata:");
+        match &metadata.icc_profile {
+            Some(profile) => println!("    ICC profile:  {} bytes", profile.len()),
+            None => println!("    ICC profile:  none"),
+        }
+        match metadata.gamma {
+            Some(gamma) => println!("    Gamma:        {:.5}", gamma),
+            None => println!("    Gamma:        none"),
+        }
+        println!("    sRGB:         {}", if metadata.srgb { "yes" } else { "no" });
+        println!("    Orientation:  {:?} (EXIF {})", metadata.orientation, metadata.orientation.to_exif());
+        println!("  Decoded size:   {} bytes", image.size_bytes());
+        println!("  Channels:");
+        let names = channel_names(image.pixel_format());
+        for (name, stats) in names.iter().zip(image.channel_stats()) {
+            println!(
+                "    {:<2} min {:>10.6}  max {:>10.6}  mean {:>10.6}",
+                name, stats.min, stats.max, stats.mean
+            );
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut with_stats = true;
+    let mut files = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "--no-stats" => with_stats = false,
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                eprintln!("error: unknown option '{}'\n\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => files.push(arg),
+        }
+    }
+
+    if files.is_empty() {
+        eprintln!("error: no input file\n\n{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for file in &files {
+        if let Err(msg) = print_info(Path::new(file), with_stats) {
+            eprintln!("error: {}: {}", file, msg);
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
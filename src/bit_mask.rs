@@ -0,0 +1,206 @@
+/// Bit-packed 1-bit masks (collision, visibility, selection)
+use crate::sample::read_normalized_slice;
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+use std::path::Path;
+
+/// File formats for [`BitMask`] loading and saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaskFormat {
+    /// 1-bit grayscale PNG
+    Png,
+
+    /// Binary portable bitmap (`P4`, `.pbm`)
+    Pbm,
+}
+
+impl MaskFormat {
+    /// Format of a path: PBM for `.pbm` files, PNG otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("pbm") => MaskFormat::Pbm,
+            _ => MaskFormat::Png,
+        }
+    }
+}
+
+/// Single-channel image with one bit per pixel
+///
+/// Rows are packed most significant bit first and padded to whole bytes, the
+/// layout of 1-bit PNG and PBM files, so a mask takes 8 times less memory than
+/// an R/U8 image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMask {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl BitMask {
+    /// Create a mask with every pixel cleared
+    pub fn new(width: u32, height: u32) -> Self {
+        let row_bytes = (width as usize).div_ceil(8);
+        Self { data: vec![0; row_bytes * height as usize], width, height }
+    }
+
+    /// Create a mask from packed rows
+    ///
+    /// Returns an error if `data` is not `row_bytes * height` bytes long.
+    pub fn from_raw(data: Vec<u8>, width: u32, height: u32) -> ImageResult<Self> {
+        let expected = (width as usize).div_ceil(8) * height as usize;
+        if data.len() != expected {
+            return Err(ImageError::Other(format!(
+                "{} bytes for a {}x{} bit mask, expected {}",
+                data.len(), width, height, expected
+            )));
+        }
+        let mut mask = Self { data, width, height };
+        mask.clear_padding();
+        Ok(mask)
+    }
+
+    /// Threshold one channel of an image into a mask
+    ///
+    /// A pixel is set when its normalized channel value is at or above
+    /// `threshold`.
+    ///
+    /// # Arguments
+    /// * `image` - Source image
+    /// * `channel` - Channel index in the pixel format order (e.g. 3 for RGBA alpha)
+    /// * `threshold` - Normalized threshold (e.g. 0.5)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{BitMask, GalaxyImage};
+    ///
+    /// let sprite = GalaxyImage::load_from_file("sprite.png")?;
+    /// let collision = BitMask::from_channel(&sprite, 3, 0.5)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn from_channel(image: &Image, channel: usize, threshold: f32) -> ImageResult<Self> {
+        let channel_count = image.pixel_format().channel_count();
+        if channel >= channel_count {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "Channel {} does not exist in {:?} images",
+                channel,
+                image.pixel_format()
+            )));
+        }
+
+        let mut mask = Self::new(image.width(), image.height());
+        let width = image.width() as usize;
+        let row_bytes = width * image.bytes_per_pixel();
+        if row_bytes == 0 {
+            return Ok(mask);
+        }
+        let mask_row_bytes = mask.row_bytes();
+        let mut values = vec![0f32; width * channel_count];
+        for (row, mask_row) in image.data().chunks_exact(row_bytes).zip(mask.data.chunks_exact_mut(mask_row_bytes)) {
+            read_normalized_slice(row, image.component_type(), &mut values);
+            for (x, pixel) in values.chunks_exact(channel_count).enumerate() {
+                if pixel[channel] >= threshold {
+                    mask_row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        Ok(mask)
+    }
+
+    /// Expand to an R/U8 image (255 for set pixels, 0 otherwise)
+    pub fn to_image(&self) -> Image {
+        let mut image = Image::new(self.width, self.height, PixelFormat::R, ComponentType::U8);
+        let width = self.width as usize;
+        if width == 0 {
+            return image;
+        }
+        for (row, mask_row) in image.data_mut().chunks_exact_mut(width).zip(self.data.chunks_exact(self.row_bytes())) {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = if mask_row[x / 8] & (0x80 >> (x % 8)) != 0 { u8::MAX } else { 0 };
+            }
+        }
+        image
+    }
+
+    /// Get mask width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get mask height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Bytes per packed row
+    pub fn row_bytes(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+
+    /// Get the packed rows
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the packed rows as owned Vec
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn bit(&self, x: u32, y: u32) -> (usize, u8) {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) out of bounds", x, y);
+        (y as usize * self.row_bytes() + x as usize / 8, 0x80 >> (x % 8))
+    }
+
+    /// Check if a pixel is set
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x, y) is outside the mask.
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        let (index, bit) = self.bit(x, y);
+        self.data[index] & bit != 0
+    }
+
+    /// Set or clear a pixel
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x, y) is outside the mask.
+    pub fn set(&mut self, x: u32, y: u32, value: bool) {
+        let (index, bit) = self.bit(x, y);
+        if value {
+            self.data[index] |= bit;
+        } else {
+            self.data[index] &= !bit;
+        }
+    }
+
+    /// Set or clear every pixel
+    pub fn fill(&mut self, value: bool) {
+        self.data.fill(if value { u8::MAX } else { 0 });
+        self.clear_padding();
+    }
+
+    /// Invert every pixel
+    pub fn invert(&mut self) {
+        self.data.iter_mut().for_each(|byte| *byte = !*byte);
+        self.clear_padding();
+    }
+
+    /// Number of set pixels
+    pub fn count_set(&self) -> usize {
+        self.data.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    /// Keep the padding bits at the end of every row cleared
+    fn clear_padding(&mut self) {
+        let used = self.width % 8;
+        let row_bytes = self.row_bytes();
+        if used == 0 || row_bytes == 0 {
+            return;
+        }
+        let keep = !(0xFFu8 >> used);
+        for row in self.data.chunks_exact_mut(row_bytes) {
+            row[row_bytes - 1] &= keep;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/// Recycled scratch buffers for loaders and encoders
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Usage counters of the manager buffer pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// Requests served with a recycled buffer
+    pub hits: u64,
+
+    /// Requests that had to allocate a new buffer
+    pub misses: u64,
+
+    /// Number of buffers currently held by the pool
+    pub buffers: usize,
+
+    /// Total capacity in bytes of the buffers currently held
+    pub held_bytes: usize,
+}
+
+/// Pool of byte buffers recycled between decode and encode calls
+///
+/// Buffers keep their capacity while pooled. The pool never holds more than
+/// `max_bytes` of capacity: recycling a buffer that does not fit evicts the
+/// smallest pooled buffers first, or drops the buffer if it alone exceeds the limit.
+#[derive(Debug)]
+pub(crate) struct BufferPool {
+    max_bytes: usize,
+    buffers: Vec<Vec<u8>>,
+    stats: BufferPoolStats,
+}
+
+impl BufferPool {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self { max_bytes, buffers: Vec::new(), stats: BufferPoolStats::default() }
+    }
+
+    /// Take an empty buffer with at least `min_capacity` bytes of capacity
+    ///
+    /// Picks the smallest pooled buffer that is large enough.
+    pub(crate) fn take(&mut self, min_capacity: usize) -> Vec<u8> {
+        let best = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.capacity() >= min_capacity)
+            .min_by_key(|(_, buffer)| buffer.capacity())
+            .map(|(index, _)| index);
+
+        match best {
+            Some(index) => {
+                let buffer = self.buffers.swap_remove(index);
+                self.stats.hits += 1;
+                self.stats.buffers -= 1;
+                self.stats.held_bytes -= buffer.capacity();
+                buffer
+            }
+            None => {
+                self.stats.misses += 1;
+                Vec::with_capacity(min_capacity)
+            }
+        }
+    }
+
+    /// Return a buffer to the pool
+    pub(crate) fn recycle(&mut self, mut buffer: Vec<u8>) {
+        let capacity = buffer.capacity();
+        if capacity == 0 || capacity > self.max_bytes {
+            return;
+        }
+
+        // Make room by dropping the smallest buffers, which are the least useful
+        self.buffers.sort_unstable_by_key(|buffer| std::cmp::Reverse(buffer.capacity()));
+        while self.stats.held_bytes + capacity > self.max_bytes {
+            let Some(evicted) = self.buffers.pop() else { break };
+            self.stats.buffers -= 1;
+            self.stats.held_bytes -= evicted.capacity();
+        }
+
+        buffer.clear();
+        self.stats.buffers += 1;
+        self.stats.held_bytes += capacity;
+        self.buffers.push(buffer);
+    }
+
+    pub(crate) fn stats(&self) -> BufferPoolStats {
+        self.stats
+    }
+}
+
+/// Pool shared by the `GalaxyImage` manager, disabled until enabled explicitly
+fn global_pool() -> MutexGuard<'static, Option<BufferPool>> {
+    static POOL: OnceLock<Mutex<Option<BufferPool>>> = OnceLock::new();
+    // Pooled buffers are plain bytes, so a poisoned lock is still usable
+    POOL.get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn enable(max_bytes: usize) {
+    let mut pool = global_pool();
+    match pool.as_mut() {
+        Some(pool) => {
+            pool.max_bytes = max_bytes;
+            // Re-recycling everything applies the new limit
+            for buffer in std::mem::take(&mut pool.buffers) {
+                pool.stats.buffers -= 1;
+                pool.stats.held_bytes -= buffer.capacity();
+                pool.recycle(buffer);
+            }
+        }
+        None => *pool = Some(BufferPool::new(max_bytes)),
+    }
+}
+
+pub(crate) fn disable() {
+    *global_pool() = None;
+}
+
+pub(crate) fn stats() -> Option<BufferPoolStats> {
+    global_pool().as_ref().map(BufferPool::stats)
+}
+
+/// Take an empty buffer from the manager pool, or allocate one if pooling is off
+pub(crate) fn take(min_capacity: usize) -> Vec<u8> {
+    match global_pool().as_mut() {
+        Some(pool) => pool.take(min_capacity),
+        None => Vec::with_capacity(min_capacity),
+    }
+}
+
+/// Take a zero-filled buffer of `len` bytes from the manager pool
+pub(crate) fn take_zeroed(len: usize) -> Vec<u8> {
+    let mut buffer = take(len);
+    buffer.resize(len, 0);
+    buffer
+}
+
+/// Return a buffer to the manager pool (dropped if pooling is off)
+pub(crate) fn recycle(buffer: Vec<u8>) {
+    if let Some(pool) = global_pool().as_mut() {
+        pool.recycle(buffer);
+    }
+}
@@ -0,0 +1,194 @@
+/// LRU cache for decoded images
+use crate::{GalaxyImage, Image, ImageResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Default byte budget of the manager cache (256 MiB)
+pub const DEFAULT_CACHE_BUDGET: usize = 256 * 1024 * 1024;
+
+/// Cached image with its source modification time
+#[derive(Debug)]
+struct CacheEntry {
+    modified: Option<SystemTime>,
+    image: Arc<Image>,
+    last_used: u64,
+}
+
+/// Image cache with a byte budget and least-recently-used eviction
+///
+/// Entries are keyed by file path and modification time: a file modified on disk
+/// is decoded again on the next load.
+#[derive(Debug)]
+pub struct ImageCache {
+    budget_bytes: usize,
+    used_bytes: usize,
+    clock: u64,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ImageCache {
+    /// Create an empty cache with the given byte budget
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            budget_bytes,
+            used_bytes: 0,
+            clock: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Load an image through the cache
+    ///
+    /// Returns the cached image if the file is unchanged since it was cached,
+    /// otherwise decodes the file and caches the result.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> ImageResult<Arc<Image>> {
+        let (key, modified) = cache_key(path.as_ref())?;
+        if let Some(image) = self.get(&key, modified) {
+            return Ok(image);
+        }
+
+        let image = Arc::new(GalaxyImage::load_from_file(&key)?);
+        self.insert(key, modified, Arc::clone(&image));
+        Ok(image)
+    }
+
+    /// Get the byte budget
+    pub fn budget(&self) -> usize {
+        self.budget_bytes
+    }
+
+    /// Change the byte budget, evicting entries if needed
+    pub fn set_budget(&mut self, budget_bytes: usize) {
+        self.budget_bytes = budget_bytes;
+        self.evict_to_budget();
+    }
+
+    /// Get the number of pixel bytes currently cached
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    /// Get the number of cached images
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove the entry for a path, if any
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) {
+        let key = canonical_path(path.as_ref());
+        if let Some(entry) = self.entries.remove(&key) {
+            self.used_bytes -= entry.image.size_bytes();
+        }
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used_bytes = 0;
+    }
+
+    fn get(&mut self, key: &Path, modified: Option<SystemTime>) -> Option<Arc<Image>> {
+        self.clock += 1;
+        let clock = self.clock;
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.modified == modified => {
+                entry.last_used = clock;
+                Some(Arc::clone(&entry.image))
+            }
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, key: PathBuf, modified: Option<SystemTime>, image: Arc<Image>) {
+        // Drop any stale entry for this path first
+        if let Some(old) = self.entries.remove(&key) {
+            self.used_bytes -= old.image.size_bytes();
+        }
+
+        // Images larger than the whole budget are never cached
+        let size = image.size_bytes();
+        if size > self.budget_bytes {
+            return;
+        }
+
+        self.clock += 1;
+        self.used_bytes += size;
+        self.entries.insert(key, CacheEntry {
+            modified,
+            image,
+            last_used: self.clock,
+        });
+        self.evict_to_budget();
+    }
+
+    fn evict_to_budget(&mut self) {
+        while self.used_bytes > self.budget_bytes {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+
+            match oldest.and_then(|key| self.entries.remove(&key)) {
+                Some(entry) => self.used_bytes -= entry.image.size_bytes(),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_BUDGET)
+    }
+}
+
+/// Canonicalize a path so different spellings share a cache entry
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn cache_key(path: &Path) -> ImageResult<(PathBuf, Option<SystemTime>)> {
+    let key = canonical_path(path);
+    let modified = fs::metadata(&key)?.modified().ok();
+    Ok((key, modified))
+}
+
+/// Cache shared by the `GalaxyImage` manager
+fn global_cache() -> &'static Mutex<ImageCache> {
+    static CACHE: OnceLock<Mutex<ImageCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(ImageCache::default()))
+}
+
+fn lock_global_cache() -> std::sync::MutexGuard<'static, ImageCache> {
+    // A panic while holding the lock cannot leave the cache inconsistent enough to matter
+    global_cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn load_cached(path: &Path) -> ImageResult<Arc<Image>> {
+    let (key, modified) = cache_key(path)?;
+    if let Some(image) = lock_global_cache().get(&key, modified) {
+        return Ok(image);
+    }
+
+    // Decode without holding the lock so other threads can keep hitting the cache
+    let image = Arc::new(GalaxyImage::load_from_file(&key)?);
+    lock_global_cache().insert(key, modified, Arc::clone(&image));
+    Ok(image)
+}
+
+pub(crate) fn set_cache_budget(budget_bytes: usize) {
+    lock_global_cache().set_budget(budget_bytes);
+}
+
+pub(crate) fn clear_cache() {
+    lock_global_cache().clear();
+}
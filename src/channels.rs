@@ -0,0 +1,89 @@
+/// Channel split and merge
+use crate::{Image, ImageError, ImageResult, PixelFormat, PlanarImage};
+
+impl Image {
+    /// Split the image into one single-channel image per channel
+    ///
+    /// Channels come in storage order (B, G, R for BGR), every image is
+    /// [`PixelFormat::R`] with the component type of the source.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    ///
+    /// let orm = GalaxyImage::load_from_file("rock_orm.png")?;
+    /// let channels = orm.split_channels();
+    /// GalaxyImage::save_to_file(&channels[1], "rock_roughness.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn split_channels(&self) -> Vec<Image> {
+        let planar = self.to_planar();
+        planar
+            .planes()
+            .map(|plane| Image::from_raw(plane.to_vec(), self.width(), self.height(), PixelFormat::R, self.component_type()))
+            .collect()
+    }
+
+    /// Merge single-channel images into the channels of one image
+    ///
+    /// One to four images give R, RG, RGB or RGBA. Returns an error if there
+    /// are no images or more than four, if an image is not
+    /// [`PixelFormat::R`], or if sizes or component types differ; use
+    /// [`Image::convert`] to bring inputs to a common component type first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, Image, ImageFormat};
+    ///
+    /// // Pack occlusion, roughness and metalness into one texture
+    /// let occlusion = GalaxyImage::load_from_file("rock_ao.png")?;
+    /// let roughness = GalaxyImage::load_from_file("rock_roughness.png")?;
+    /// let metalness = GalaxyImage::load_from_file("rock_metalness.png")?;
+    /// let orm = Image::from_channels(&[&occlusion, &roughness, &metalness])?;
+    /// GalaxyImage::save_to_file(&orm, "rock_orm.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn from_channels(channels: &[&Image]) -> ImageResult<Image> {
+        let pixel_format = match channels.len() {
+            1 => PixelFormat::R,
+            2 => PixelFormat::RG,
+            3 => PixelFormat::RGB,
+            4 => PixelFormat::RGBA,
+            count => {
+                return Err(ImageError::InvalidPixelFormat(format!(
+                    "Cannot merge {} channels, expected 1 to 4",
+                    count
+                )))
+            }
+        };
+
+        let first = channels[0];
+        for channel in channels {
+            if channel.pixel_format() != PixelFormat::R {
+                return Err(ImageError::InvalidPixelFormat(format!(
+                    "Channel images must be R, got {:?}",
+                    channel.pixel_format()
+                )));
+            }
+            if channel.component_type() != first.component_type() {
+                return Err(ImageError::InvalidPixelFormat(format!(
+                    "Channel images mix {:?} and {:?} components",
+                    first.component_type(),
+                    channel.component_type()
+                )));
+            }
+            if (channel.width(), channel.height()) != (first.width(), first.height()) {
+                return Err(ImageError::InvalidDimensions {
+                    width: channel.width(),
+                    height: channel.height(),
+                });
+            }
+        }
+
+        let data = channels.iter().flat_map(|channel| channel.data().iter().copied()).collect();
+        let planar = PlanarImage::from_raw(data, first.width(), first.height(), pixel_format, first.component_type())?;
+        Ok(planar.to_interleaved())
+    }
+}
@@ -0,0 +1,209 @@
+/// Color spaces and transfer functions
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image};
+
+/// Color space of pixel values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// sRGB primaries with the sRGB transfer function (typical 8-bit color textures)
+    Srgb,
+
+    /// Linear values (HDR images and data textures such as normal or roughness maps)
+    Linear,
+
+    /// Rec.2020 primaries with the SMPTE ST 2084 (PQ) transfer function, as in HDR10
+    ///
+    /// Display-referred: linear 1.0 is 100 cd/m², so the PQ range of 10000 cd/m²
+    /// decodes to 100.0.
+    Pq,
+
+    /// Rec.2020 primaries with the ARIB STD-B67 hybrid log-gamma transfer function (BT.2100 HLG)
+    ///
+    /// Scene-referred: linear 1.0 is the nominal peak. The display OOTF is not applied.
+    Hlg,
+}
+
+/// Linear value of 10000 cd/m², the top of the PQ range
+const PQ_PEAK: f32 = 100.0;
+
+// SMPTE ST 2084 constants
+const PQ_M1: f32 = 2610.0 / 16384.0;
+const PQ_M2: f32 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f32 = 3424.0 / 4096.0;
+const PQ_C2: f32 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+// BT.2100 HLG constants
+const HLG_A: f32 = 0.178_832_77;
+const HLG_B: f32 = 1.0 - 4.0 * HLG_A;
+const HLG_C: f32 = 0.559_910_7;
+
+impl ColorSpace {
+    /// Tag of new images: sRGB for U8, linear for everything else
+    pub(crate) fn default_for(component_type: ComponentType) -> ColorSpace {
+        match component_type {
+            ComponentType::U8 => ColorSpace::Srgb,
+            _ => ColorSpace::Linear,
+        }
+    }
+
+    /// Decode a stored value to linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::ColorSpace;
+    ///
+    /// // 100 cd/m² in PQ
+    /// assert!((ColorSpace::Pq.decode(0.508_08) - 1.0).abs() < 1e-3);
+    /// assert_eq!(ColorSpace::Linear.decode(0.25), 0.25);
+    /// ```
+    pub fn decode(self, value: f32) -> f32 {
+        match self {
+            ColorSpace::Srgb => srgb_to_linear(value),
+            ColorSpace::Linear => value,
+            ColorSpace::Pq => pq_to_linear(value),
+            ColorSpace::Hlg => hlg_to_linear(value),
+        }
+    }
+
+    /// Encode a linear value for storage
+    pub fn encode(self, value: f32) -> f32 {
+        match self {
+            ColorSpace::Srgb => linear_to_srgb(value),
+            ColorSpace::Linear => value,
+            ColorSpace::Pq => linear_to_pq(value),
+            ColorSpace::Hlg => linear_to_hlg(value),
+        }
+    }
+}
+
+/// Encode a linear value with the sRGB transfer function
+pub(crate) fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decode an sRGB-encoded value to linear
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear value (1.0 = 100 cd/m²) with the PQ inverse EOTF
+pub(crate) fn linear_to_pq(value: f32) -> f32 {
+    let y = (value / PQ_PEAK).clamp(0.0, 1.0).powf(PQ_M1);
+    ((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y)).powf(PQ_M2)
+}
+
+/// Decode a PQ-encoded value to linear (1.0 = 100 cd/m²)
+pub(crate) fn pq_to_linear(value: f32) -> f32 {
+    let e = value.clamp(0.0, 1.0).powf(1.0 / PQ_M2);
+    ((e - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * e)).powf(1.0 / PQ_M1) * PQ_PEAK
+}
+
+/// Encode a scene-linear value with the HLG OETF
+pub(crate) fn linear_to_hlg(value: f32) -> f32 {
+    let e = value.clamp(0.0, 1.0);
+    if e <= 1.0 / 12.0 {
+        (3.0 * e).sqrt()
+    } else {
+        HLG_A * (12.0 * e - HLG_B).ln() + HLG_C
+    }
+}
+
+/// Decode an HLG-encoded value to scene-linear
+pub(crate) fn hlg_to_linear(value: f32) -> f32 {
+    let e = value.clamp(0.0, 1.0);
+    if e <= 0.5 {
+        e * e / 3.0
+    } else {
+        (((e - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+    }
+}
+
+/// Linear sRGB color of a blackbody illuminant, scaled to unit luminance
+///
+/// Uses the Kim et al. cubic fit of the Planckian locus (1667 K to 25000 K).
+pub(crate) fn blackbody_linear_srgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin.clamp(1667.0, 25000.0) as f64;
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.266_123_9e9 / t3 - 0.234_358_9e6 / t2 + 0.877_695_6e3 / t + 0.179_910
+    } else {
+        -3.025_846_9e9 / t3 + 2.107_037_9e6 / t2 + 0.222_634_7e3 / t + 0.240_390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.106_381_4 * x3 - 1.348_110_20 * x2 + 2.185_558_32 * x - 0.202_196_83
+    } else if t <= 4000.0 {
+        -0.954_947_6 * x3 - 1.374_185_93 * x2 + 2.091_370_15 * x - 0.167_488_67
+    } else {
+        3.081_758_0 * x3 - 5.873_386_70 * x2 + 3.751_129_97 * x - 0.370_014_83
+    };
+
+    // xyY (Y = 1) to XYZ to linear sRGB
+    let (cx, cz) = (x / y, (1.0 - x - y) / y);
+    let r = 3.240_454_2 * cx - 1.537_138_5 - 0.498_531_4 * cz;
+    let g = -0.969_266_0 * cx + 1.876_010_8 + 0.041_556_0 * cz;
+    let b = 0.055_643_4 * cx - 0.204_025_9 + 1.057_225_2 * cz;
+    [r as f32, g as f32, b as f32]
+}
+
+impl Image {
+    /// Re-encode the color channels to another transfer function
+    ///
+    /// Values are decoded with the transfer function of the image's
+    /// [`Image::color_space`] tag and encoded with the one of `to`, which
+    /// becomes the new tag; alpha is kept. Only the transfer function changes, use
+    /// [`Image::convert_color_space`] on linear values to convert primaries
+    /// (see [`ColorSpace::primaries`]). 8 and 16-bit images clamp to
+    /// `0.0..=1.0` after every step, so decode PQ or HLG to linear in F16/F32
+    /// images.
+    ///
+    /// # Arguments
+    /// * `to` - Color space to store the values in
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{ColorSpace, GalaxyImage, ImageFormat};
+    ///
+    /// // Linear HDR render (1.0 = 100 cd/m²) to PQ-encoded values for HDR10 export
+    /// let mut frame = GalaxyImage::load_from_file("capture_rec2020.exr")?;
+    /// assert_eq!(frame.color_space(), ColorSpace::Linear);
+    /// frame.convert_transfer(ColorSpace::Pq);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert_transfer(&mut self, to: ColorSpace) {
+        let from = self.color_space();
+        if from == to {
+            return;
+        }
+        self.set_color_space(to);
+        let component_type = self.component_type();
+        let pixel_format = self.pixel_format();
+        let channel_count = pixel_format.channel_count();
+        let color_channels = if pixel_format.has_alpha() { channel_count - 1 } else { channel_count };
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return;
+        }
+
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data_mut().chunks_exact_mut(row_bytes) {
+            read_normalized_slice(row, component_type, &mut values);
+            for pixel in values.chunks_exact_mut(channel_count) {
+                for value in &mut pixel[..color_channels] {
+                    *value = to.encode(from.decode(*value));
+                }
+            }
+            write_normalized_slice(row, component_type, &values);
+        }
+    }
+}
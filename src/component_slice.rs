@@ -0,0 +1,99 @@
+/// Zero-copy views of the pixel buffer as typed component slices
+use crate::typed::Component;
+use crate::Image;
+use half::f16;
+
+impl Image {
+    /// Check that the buffer can be reinterpreted as components of type `T`
+    fn viewable_as<T: Component>(&self) -> bool {
+        // Samples are stored little-endian, the native order of nearly every target
+        self.component_type() == T::COMPONENT_TYPE && (cfg!(target_endian = "little") || T::COMPONENT_TYPE.size_bytes() == 1)
+    }
+
+    /// Borrow the pixel data as a slice of components without copying
+    ///
+    /// Components are in storage order, `channel_count` per pixel, rows top
+    /// to bottom. Returns `None` if `T` does not match the component type, if
+    /// the buffer is not aligned for `T` (allocators align it in practice), or
+    /// for multi-byte components on a big-endian target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let image = Image::new(4, 4, PixelFormat::RGBA, ComponentType::F32);
+    /// let samples = image.as_slice_of::<f32>().unwrap();
+    /// assert_eq!(samples.len(), 4 * 4 * 4);
+    /// assert!(image.as_slice_of::<u16>().is_none());
+    /// ```
+    pub fn as_slice_of<T: Component>(&self) -> Option<&[T]> {
+        if !self.viewable_as::<T>() {
+            return None;
+        }
+        bytemuck::try_cast_slice(self.data()).ok()
+    }
+
+    /// Borrow the pixel data mutably as a slice of components without copying
+    ///
+    /// Same conditions as [`Image::as_slice_of`]. Copies the pixel data first
+    /// if it is shared with another image.
+    pub fn as_mut_slice_of<T: Component>(&mut self) -> Option<&mut [T]> {
+        if !self.viewable_as::<T>() {
+            return None;
+        }
+        bytemuck::try_cast_slice_mut(self.data_mut()).ok()
+    }
+
+    /// Borrow the samples of a U16 image, see [`Image::as_slice_of`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let heightmap = GalaxyImage::load_from_file("terrain.png")?;
+    /// if let Some(heights) = heightmap.as_u16_slice() {
+    ///     let highest = heights.iter().max();
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn as_u16_slice(&self) -> Option<&[u16]> {
+        self.as_slice_of()
+    }
+
+    /// Borrow the samples of a U16 image mutably, see [`Image::as_mut_slice_of`]
+    pub fn as_u16_slice_mut(&mut self) -> Option<&mut [u16]> {
+        self.as_mut_slice_of()
+    }
+
+    /// Borrow the samples of an F16 image, see [`Image::as_slice_of`]
+    pub fn as_f16_slice(&self) -> Option<&[f16]> {
+        self.as_slice_of()
+    }
+
+    /// Borrow the samples of an F16 image mutably, see [`Image::as_mut_slice_of`]
+    pub fn as_f16_slice_mut(&mut self) -> Option<&mut [f16]> {
+        self.as_mut_slice_of()
+    }
+
+    /// Borrow the samples of an F32 image, see [`Image::as_slice_of`]
+    pub fn as_f32_slice(&self) -> Option<&[f32]> {
+        self.as_slice_of()
+    }
+
+    /// Borrow the samples of an F32 image mutably, see [`Image::as_mut_slice_of`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let mut image = Image::new(2, 2, PixelFormat::R, ComponentType::F32);
+    /// image.as_f32_slice_mut().unwrap().fill(1.5);
+    /// assert_eq!(image.read_pixel_f32(1, 1)[0], 1.5);
+    /// ```
+    pub fn as_f32_slice_mut(&mut self) -> Option<&mut [f32]> {
+        self.as_mut_slice_of()
+    }
+}
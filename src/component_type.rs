@@ -12,6 +12,12 @@ pub enum ComponentType {
 
     /// 32-bit floating point (0.0-1.0)
     F32,
+
+    /// Signed-normalized 8-bit integer (-127 to 127 maps to -1.0 to 1.0)
+    I8,
+
+    /// Signed-normalized 16-bit integer (-32767 to 32767 maps to -1.0 to 1.0)
+    I16,
 }
 
 impl ComponentType {
@@ -22,6 +28,13 @@ impl ComponentType {
             ComponentType::U16 => 2,
             ComponentType::F16 => 2,
             ComponentType::F32 => 4,
+            ComponentType::I8 => 1,
+            ComponentType::I16 => 2,
         }
     }
+
+    /// Check if this is a signed-normalized type (I8, I16)
+    pub fn is_signed(&self) -> bool {
+        matches!(self, ComponentType::I8 | ComponentType::I16)
+    }
 }
@@ -0,0 +1,188 @@
+/// Blitting and alpha compositing
+use crate::roi::Rect;
+use crate::{AlphaMode, ComponentType, Image};
+
+/// How [`Image::composite`] combines source and destination colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// Source over destination
+    #[default]
+    Normal,
+
+    /// Sum of source and destination (glows, light accumulation)
+    Add,
+
+    /// Product of source and destination (shadows, tinting)
+    Multiply,
+
+    /// Inverted product of the inverted colors (brightens without overshoot)
+    Screen,
+}
+
+impl BlendMode {
+    /// Blended color of opaque source and destination values
+    fn blend(self, source: f32, destination: f32) -> f32 {
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Add => source + destination,
+            BlendMode::Multiply => source * destination,
+            BlendMode::Screen => source + destination - source * destination,
+        }
+    }
+}
+
+/// Composite straight RGBA colors
+///
+/// Uses the separable blending of the W3C compositing model: where the
+/// destination is transparent the source shows unblended, and the result
+/// is placed over the destination with `source` alpha. Blended colors are
+/// capped at `limit` before coverage is applied.
+fn composite_pixel(source: [f32; 4], destination: [f32; 4], mode: BlendMode, limit: f32) -> [f32; 4] {
+    let source_alpha = source[3].clamp(0.0, 1.0);
+    let destination_alpha = destination[3].clamp(0.0, 1.0);
+    let alpha = source_alpha + destination_alpha * (1.0 - source_alpha);
+    if alpha <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let mut output = [0.0, 0.0, 0.0, alpha];
+    for c in 0..3 {
+        let blended = (1.0 - destination_alpha) * source[c] + destination_alpha * mode.blend(source[c], destination[c]).min(limit);
+        output[c] = (source_alpha * blended + destination_alpha * (1.0 - source_alpha) * destination[c]) / alpha;
+    }
+    output
+}
+
+/// Region of `source` that lands inside `target` when placed at (x, y), and its target position
+fn overlap(target: &Image, source: &Image, x: i32, y: i32) -> Option<(Rect, u32, u32)> {
+    let (x, y) = (x as i64, y as i64);
+    let x0 = x.max(0);
+    let y0 = y.max(0);
+    let x1 = (x + source.width() as i64).min(target.width() as i64);
+    let y1 = (y + source.height() as i64).min(target.height() as i64);
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    let region = Rect::new((x0 - x) as u32, (y0 - y) as u32, (x1 - x0) as u32, (y1 - y0) as u32);
+    Some((region, x0 as u32, y0 as u32))
+}
+
+impl Image {
+    /// Copy an image into this one with its top-left corner at (x, y)
+    ///
+    /// Pixels replace the destination, alpha included. The source is clipped
+    /// to the image, so it may start at negative coordinates or hang over the
+    /// edges. Images of the same layout are copied row by row; otherwise each
+    /// pixel is converted like [`Image::write_pixel_f32`].
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Image to copy
+    /// * `x` - Destination column of the left edge of `source`
+    /// * `y` - Destination row of the top edge of `source`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, GalaxyImage, Image, PixelFormat};
+    ///
+    /// // Pack four 256x256 textures into a 512x512 atlas
+    /// let mut atlas = Image::new(512, 512, PixelFormat::RGBA, ComponentType::U8);
+    /// for (index, name) in ["grass.png", "dirt.png", "rock.png", "sand.png"].iter().enumerate() {
+    ///     let tile = GalaxyImage::load_from_file(name)?;
+    ///     atlas.blit(&tile, (index as i32 % 2) * 256, (index as i32 / 2) * 256);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn blit(&mut self, source: &Image, x: i32, y: i32) {
+        let Some((region, target_x, target_y)) = overlap(self, source, x, y) else {
+            return;
+        };
+
+        if source.pixel_format() == self.pixel_format() && source.component_type() == self.component_type() {
+            let bytes_per_pixel = self.bytes_per_pixel();
+            let row_bytes = self.width() as usize * bytes_per_pixel;
+            let source_row_bytes = source.width() as usize * bytes_per_pixel;
+            let span = region.width as usize * bytes_per_pixel;
+            let data = self.data_mut();
+            for row in 0..region.height as usize {
+                let start = (region.y as usize + row) * source_row_bytes + region.x as usize * bytes_per_pixel;
+                let target = (target_y as usize + row) * row_bytes + target_x as usize * bytes_per_pixel;
+                data[target..target + span].copy_from_slice(&source.data()[start..start + span]);
+            }
+            return;
+        }
+
+        for row in 0..region.height {
+            for column in 0..region.width {
+                let rgba = source.read_pixel_f32(region.x + column, region.y + row);
+                self.write_pixel_f32(target_x + column, target_y + row, rgba);
+            }
+        }
+    }
+
+    /// Blend an image over this one with its top-left corner at (x, y)
+    ///
+    /// Source alpha controls coverage and the result alpha is the union of
+    /// both, so transparent sprites can be stacked onto transparent sheets.
+    /// Images tagged [`AlphaMode::Premultiplied`] are handled as such. Works
+    /// with any pixel format and component type on either side: formats
+    /// without alpha count as opaque, and grayscale destinations store the
+    /// red result. The source is clipped like [`Image::blit`].
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Image to draw
+    /// * `x` - Destination column of the left edge of `source`
+    /// * `y` - Destination row of the top edge of `source`
+    /// * `mode` - How source and destination colors are combined
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{BlendMode, GalaxyImage};
+    ///
+    /// let mut hud = GalaxyImage::load_from_file("hud_background.png")?;
+    /// let icon = GalaxyImage::load_from_file("icon.png")?;
+    /// let glow = GalaxyImage::load_from_file("glow.png")?;
+    /// hud.composite(&icon, 16, 16, BlendMode::Normal);
+    /// hud.composite(&glow, 8, 8, BlendMode::Add);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn composite(&mut self, source: &Image, x: i32, y: i32, mode: BlendMode) {
+        let Some((region, target_x, target_y)) = overlap(self, source, x, y) else {
+            return;
+        };
+
+        // Integer storage saturates, so sums must not spill over partially covered pixels
+        let limit = match self.component_type() {
+            ComponentType::F16 | ComponentType::F32 => f32::INFINITY,
+            _ => 1.0,
+        };
+        let premultiplied = |image: &Image| image.alpha_mode() == AlphaMode::Premultiplied && image.pixel_format().has_alpha();
+        let source_premultiplied = premultiplied(source);
+        let target_premultiplied = premultiplied(self);
+        let unpremultiply = |[r, g, b, a]: [f32; 4]| match a {
+            0.0 => [0.0, 0.0, 0.0, 0.0],
+            a => [r / a, g / a, b / a, a],
+        };
+
+        for row in 0..region.height {
+            for column in 0..region.width {
+                let (tx, ty) = (target_x + column, target_y + row);
+                let mut color = source.read_pixel_f32(region.x + column, region.y + row);
+                let mut destination = self.read_pixel_f32(tx, ty);
+                if source_premultiplied {
+                    color = unpremultiply(color);
+                }
+                if target_premultiplied {
+                    destination = unpremultiply(destination);
+                }
+
+                let [r, g, b, a] = composite_pixel(color, destination, mode, limit);
+                let output = if target_premultiplied { [r * a, g * a, b * a, a] } else { [r, g, b, a] };
+                self.write_pixel_f32(tx, ty, output);
+            }
+        }
+    }
+}
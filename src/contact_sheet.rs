@@ -0,0 +1,141 @@
+/// Contact sheets: labeled grids of many images in one picture
+use crate::font::{chars_fitting, draw_text, GLYPH_HEIGHT};
+use crate::thumbnail::thumbnail_size;
+use crate::{ldr, ComponentType, Image, LdrConversion, PixelFormat, ResizeFilter, TonemapOperator};
+
+/// Layout options of [`GalaxyImage::contact_sheet`](crate::GalaxyImage::contact_sheet)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContactSheetOptions {
+    /// Number of columns (default: 0, a near-square grid)
+    pub columns: u32,
+
+    /// Downscale images whose largest side exceeds this size (default: Some(256))
+    ///
+    /// `None` keeps every image at full size.
+    pub max_cell_size: Option<u32>,
+
+    /// Gap between cells and around the sheet in pixels (default: 8)
+    pub spacing: u32,
+
+    /// Integer scale of the 5x7 label font, 0 to hide labels (default: 1)
+    pub label_scale: u32,
+
+    /// Background color, RGBA (default: dark gray)
+    pub background: [u8; 4],
+
+    /// Label color, RGBA (default: light gray)
+    pub label_color: [u8; 4],
+}
+
+impl Default for ContactSheetOptions {
+    fn default() -> Self {
+        Self {
+            columns: 0,
+            max_cell_size: Some(256),
+            spacing: 8,
+            label_scale: 1,
+            background: [32, 32, 32, 255],
+            label_color: [220, 220, 220, 255],
+        }
+    }
+}
+
+/// Convert an image to an RGBA/U8 preview
+///
+/// Float images are tone mapped (Reinhard + sRGB), U16 images are quantized,
+/// signed images are shown 0.5-biased, grayscale is expanded to gray RGB.
+pub(crate) fn to_preview(image: &Image) -> Image {
+    let ldr = match image.component_type() {
+        ComponentType::U8 => image.clone(),
+        ComponentType::U16 => ldr::to_ldr(image, LdrConversion::Clamp, TonemapOperator::Reinhard),
+        ComponentType::F16 | ComponentType::F32 => ldr::to_ldr(image, LdrConversion::Tonemap, TonemapOperator::Reinhard),
+        ComponentType::I8 | ComponentType::I16 => image.snorm_to_unorm(),
+    };
+    if ldr.pixel_format() == PixelFormat::RGBA {
+        return ldr;
+    }
+
+    let mut preview = Image::new(ldr.width(), ldr.height(), PixelFormat::RGBA, ComponentType::U8);
+    let width = ldr.width().max(1) as usize;
+    for (index, pixel) in preview.data_mut().chunks_exact_mut(4).enumerate() {
+        let rgba = ldr.read_pixel_f32((index % width) as u32, (index / width) as u32);
+        for (byte, value) in pixel.iter_mut().zip(rgba) {
+            *byte = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+    preview
+}
+
+/// Alpha-blend an RGBA/U8 image onto an RGBA/U8 canvas at (x, y)
+///
+/// The source must fit in the canvas.
+pub(crate) fn blend_onto(canvas: &mut Image, x: u32, y: u32, source: &Image) {
+    let canvas_width = canvas.width() as usize;
+    let source_width = source.width() as usize;
+    if source_width == 0 {
+        return;
+    }
+    let data = canvas.data_mut();
+    for (row, source_row) in source.data().chunks_exact(source_width * 4).enumerate() {
+        let start = ((y as usize + row) * canvas_width + x as usize) * 4;
+        for (target, pixel) in data[start..start + source_width * 4].chunks_exact_mut(4).zip(source_row.chunks_exact(4)) {
+            let alpha = pixel[3] as u32;
+            for c in 0..3 {
+                target[c] = ((pixel[c] as u32 * alpha + target[c] as u32 * (255 - alpha) + 127) / 255) as u8;
+            }
+            target[3] = target[3].max(pixel[3]);
+        }
+    }
+}
+
+/// Lay out labeled images in a grid
+pub(crate) fn contact_sheet(entries: Vec<(String, &Image)>, options: &ContactSheetOptions) -> Image {
+    let count = entries.len() as u32;
+    let columns = match options.columns {
+        0 => (count as f64).sqrt().ceil().max(1.0) as u32,
+        columns => columns,
+    };
+    let rows = count.div_ceil(columns);
+
+    let cells: Vec<(String, Image)> = entries
+        .into_iter()
+        .map(|(label, image)| {
+            let image = match options.max_cell_size {
+                Some(max) => {
+                    let (width, height) = thumbnail_size(image.width(), image.height(), max);
+                    image.resize(width, height, ResizeFilter::Area)
+                }
+                None => image.clone(),
+            };
+            (label, to_preview(&image))
+        })
+        .collect();
+
+    let scale = options.label_scale;
+    let label_height = if scale > 0 { (GLYPH_HEIGHT + 2) * scale } else { 0 };
+    let cell_width = cells.iter().map(|(_, image)| image.width()).max().unwrap_or(0);
+    let cell_height = cells.iter().map(|(_, image)| image.height()).max().unwrap_or(0) + label_height;
+    let spacing = options.spacing;
+    let sheet_width = columns * cell_width + (columns + 1) * spacing;
+    let sheet_height = rows * cell_height + (rows + 1) * spacing;
+
+    let mut sheet = Image::new(sheet_width, sheet_height, PixelFormat::RGBA, ComponentType::U8);
+    for pixel in sheet.data_mut().chunks_exact_mut(4) {
+        pixel.copy_from_slice(&options.background);
+    }
+
+    for (index, (label, image)) in cells.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = spacing + column * (cell_width + spacing);
+        let y = spacing + row * (cell_height + spacing);
+
+        // Centered horizontally, label under the image
+        blend_onto(&mut sheet, x + (cell_width - image.width()) / 2, y, image);
+        if scale > 0 {
+            let text: String = label.chars().take(chars_fitting(cell_width, scale)).collect();
+            draw_text(&mut sheet, x, y + cell_height - label_height + scale, &text, scale, options.label_color);
+        }
+    }
+    sheet
+}
@@ -0,0 +1,112 @@
+/// Conversion between pixel formats and component types
+use crate::instrument;
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+
+/// Pixel decoded from any pixel format
+struct Pixel {
+    rgb: [f32; 3],
+    alpha: f32,
+    /// Stored gray value, kept so gray to gray conversions are exact
+    gray: Option<f32>,
+}
+
+impl Pixel {
+    fn read(pixel_format: PixelFormat, values: &[f32]) -> Self {
+        let color = |rgb: [f32; 3], alpha: f32| Pixel { rgb, alpha, gray: None };
+        let gray = |value: f32, alpha: f32| Pixel { rgb: [value; 3], alpha, gray: Some(value) };
+        match pixel_format {
+            PixelFormat::R => gray(values[0], 1.0),
+            PixelFormat::RG => gray(values[0], values[1]),
+            PixelFormat::RGB => color([values[0], values[1], values[2]], 1.0),
+            PixelFormat::RGBA => color([values[0], values[1], values[2]], values[3]),
+            PixelFormat::BGR => color([values[2], values[1], values[0]], 1.0),
+            PixelFormat::BGRA => color([values[2], values[1], values[0]], values[3]),
+        }
+    }
+
+    fn write(&self, pixel_format: PixelFormat, values: &mut [f32]) {
+        let [r, g, b] = self.rgb;
+        let a = self.alpha;
+        let gray = self.gray.unwrap_or(0.2126 * r + 0.7152 * g + 0.0722 * b);
+        match pixel_format {
+            PixelFormat::R => values.copy_from_slice(&[gray]),
+            PixelFormat::RG => values.copy_from_slice(&[gray, a]),
+            PixelFormat::RGB => values.copy_from_slice(&[r, g, b]),
+            PixelFormat::RGBA => values.copy_from_slice(&[r, g, b, a]),
+            PixelFormat::BGR => values.copy_from_slice(&[b, g, r]),
+            PixelFormat::BGRA => values.copy_from_slice(&[b, g, r, a]),
+        }
+    }
+}
+
+impl Image {
+    /// Convert to another pixel format and component type
+    ///
+    /// Channels are mapped by meaning rather than position: BGR orders are
+    /// swapped, grayscale is replicated to R, G and B, color becomes gray through
+    /// its Rec.709 luminance, alpha is dropped or added as opaque (1.0).
+    /// Components are scaled between the full ranges of the types like
+    /// [`Image::convert_component_type`], with integer targets clamped and
+    /// rounded to nearest. Returns a clone when nothing changes.
+    ///
+    /// Returns an error if the image data does not match its dimensions.
+    ///
+    /// # Arguments
+    /// * `pixel_format` - Target channel layout
+    /// * `component_type` - Target component type
+    ///
+    /// # Examples
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let bgr = Image::from_raw(vec![0, 128, 255], 1, 1, PixelFormat::BGR, ComponentType::U8);
+    /// let rgba = bgr.convert(PixelFormat::RGBA, ComponentType::U16)?;
+    /// assert_eq!(rgba.data(), &[0xFF, 0xFF, 0x80, 0x80, 0, 0, 0xFF, 0xFF]);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert(&self, pixel_format: PixelFormat, component_type: ComponentType) -> ImageResult<Image> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        if self.data().len() != width * height * self.bytes_per_pixel() {
+            return Err(ImageError::Other(format!(
+                "{} bytes of data for a {}x{} {:?} {:?} image, expected {}",
+                self.data().len(),
+                width,
+                height,
+                self.pixel_format(),
+                self.component_type(),
+                width * height * self.bytes_per_pixel()
+            )));
+        }
+        if pixel_format == self.pixel_format() && component_type == self.component_type() {
+            return Ok(self.clone());
+        }
+
+        Ok(instrument::convert("convert", self, || convert_impl(self, pixel_format, component_type)))
+    }
+}
+
+fn convert_impl(image: &Image, pixel_format: PixelFormat, component_type: ComponentType) -> Image {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut output = Image::new(image.width(), image.height(), pixel_format, component_type);
+    output.set_alpha_mode(image.alpha_mode());
+    output.set_color_space(image.color_space());
+    output.set_metadata(image.metadata().converted(image.pixel_format(), pixel_format));
+    if width == 0 || height == 0 {
+        return output;
+    }
+
+    let (source_channels, target_channels) = (image.pixel_format().channel_count(), pixel_format.channel_count());
+    let mut source = vec![0f32; width * source_channels];
+    let mut target = vec![0f32; width * target_channels];
+    let row_bytes = width * image.bytes_per_pixel();
+    let output_row_bytes = width * output.bytes_per_pixel();
+    for (row, output_row) in image.data().chunks_exact(row_bytes).zip(output.data_mut().chunks_exact_mut(output_row_bytes)) {
+        read_normalized_slice(row, image.component_type(), &mut source);
+        for (input, converted) in source.chunks_exact(source_channels).zip(target.chunks_exact_mut(target_channels)) {
+            Pixel::read(image.pixel_format(), input).write(pixel_format, converted);
+        }
+        write_normalized_slice(output_row, component_type, &target);
+    }
+    output
+}
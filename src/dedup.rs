@@ -0,0 +1,74 @@
+/// Content-based image deduplication
+use crate::Image;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// Map resolving identical images to a single shared instance
+///
+/// Images are grouped by [`Image::content_hash`] and compared in full on hash
+/// collisions. Only weak references are kept: an image is forgotten as soon as
+/// the last `Arc` to it is dropped.
+#[derive(Debug, Default)]
+pub struct ImageDedup {
+    entries: HashMap<u64, Vec<Weak<Image>>>,
+}
+
+impl ImageDedup {
+    /// Create an empty deduplication map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the shared instance of an image
+    ///
+    /// Returns the already registered image if an identical one is alive,
+    /// otherwise registers `image` and returns it.
+    pub fn intern(&mut self, image: Image) -> Arc<Image> {
+        let candidates = self.entries.entry(image.content_hash()).or_default();
+        candidates.retain(|weak| weak.strong_count() > 0);
+
+        if let Some(existing) = candidates
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|existing| **existing == image)
+        {
+            return existing;
+        }
+
+        let image = Arc::new(image);
+        candidates.push(Arc::downgrade(&image));
+        image
+    }
+
+    /// Get the number of distinct images still alive
+    pub fn len(&self) -> usize {
+        self.entries
+            .values()
+            .flatten()
+            .filter(|weak| weak.strong_count() > 0)
+            .count()
+    }
+
+    /// Check if no registered image is alive
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget entries whose images have been dropped
+    pub fn purge(&mut self) {
+        self.entries.retain(|_, candidates| {
+            candidates.retain(|weak| weak.strong_count() > 0);
+            !candidates.is_empty()
+        });
+    }
+}
+
+/// Deduplication map shared by the `GalaxyImage` manager
+pub(crate) fn deduplicate(image: Image) -> Arc<Image> {
+    static DEDUP: OnceLock<Mutex<ImageDedup>> = OnceLock::new();
+    DEDUP
+        .get_or_init(|| Mutex::new(ImageDedup::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .intern(image)
+}
@@ -0,0 +1,174 @@
+/// Bayer color filter array demosaicing
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{Image, ImageError, ImageResult, PixelFormat};
+
+/// Layout of the 2x2 Bayer color filter tile, starting at the top-left pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BayerPattern {
+    /// R G / G B
+    Rggb,
+
+    /// B G / G R
+    Bggr,
+
+    /// G R / B G
+    Grbg,
+
+    /// G B / R G
+    Gbrg,
+}
+
+/// Interpolation used to reconstruct the missing colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DemosaicMethod {
+    /// Average of the nearest samples of each color (fast, soft edges)
+    Bilinear,
+
+    /// Malvar-He-Cutler gradient-corrected linear interpolation (sharper, fewer color fringes)
+    #[default]
+    Malvar,
+}
+
+/// Color channel index (0 = R, 1 = G, 2 = B)
+type Channel = usize;
+
+impl BayerPattern {
+    /// Color of the filter at a pixel
+    fn color_at(&self, x: usize, y: usize) -> Channel {
+        let tile: [Channel; 4] = match self {
+            BayerPattern::Rggb => [0, 1, 1, 2],
+            BayerPattern::Bggr => [2, 1, 1, 0],
+            BayerPattern::Grbg => [1, 0, 2, 1],
+            BayerPattern::Gbrg => [1, 2, 0, 1],
+        };
+        tile[(y & 1) * 2 + (x & 1)]
+    }
+}
+
+/// Kernel taps as (dx, dy, weight)
+type Kernel = &'static [(isize, isize, f32)];
+
+// Bilinear kernels
+const BILINEAR_CROSS: Kernel = &[(0, -1, 0.25), (-1, 0, 0.25), (1, 0, 0.25), (0, 1, 0.25)];
+const BILINEAR_DIAGONAL: Kernel = &[(-1, -1, 0.25), (1, -1, 0.25), (-1, 1, 0.25), (1, 1, 0.25)];
+const BILINEAR_HORIZONTAL: Kernel = &[(-1, 0, 0.5), (1, 0, 0.5)];
+const BILINEAR_VERTICAL: Kernel = &[(0, -1, 0.5), (0, 1, 0.5)];
+
+// Malvar-He-Cutler kernels (weights already divided by 8)
+const MALVAR_G_AT_RB: Kernel = &[
+    (0, -2, -0.125), (0, -1, 0.25),
+    (-2, 0, -0.125), (-1, 0, 0.25), (0, 0, 0.5), (1, 0, 0.25), (2, 0, -0.125),
+    (0, 1, 0.25), (0, 2, -0.125),
+];
+const MALVAR_AT_G_HORIZONTAL: Kernel = &[
+    (0, -2, 0.0625),
+    (-1, -1, -0.125), (1, -1, -0.125),
+    (-2, 0, -0.125), (-1, 0, 0.5), (0, 0, 0.625), (1, 0, 0.5), (2, 0, -0.125),
+    (-1, 1, -0.125), (1, 1, -0.125),
+    (0, 2, 0.0625),
+];
+const MALVAR_AT_G_VERTICAL: Kernel = &[
+    (0, -2, -0.125),
+    (-1, -1, -0.125), (0, -1, 0.5), (1, -1, -0.125),
+    (-2, 0, 0.0625), (0, 0, 0.625), (2, 0, 0.0625),
+    (-1, 1, -0.125), (0, 1, 0.5), (1, 1, -0.125),
+    (0, 2, -0.125),
+];
+const MALVAR_RB_AT_BR: Kernel = &[
+    (0, -2, -0.1875),
+    (-1, -1, 0.25), (1, -1, 0.25),
+    (-2, 0, -0.1875), (0, 0, 0.75), (2, 0, -0.1875),
+    (-1, 1, 0.25), (1, 1, 0.25),
+    (0, 2, -0.1875),
+];
+
+/// Reflect a coordinate into `0..len` without repeating the edge sample
+fn mirror(value: isize, len: usize) -> usize {
+    let len = len as isize;
+    if len == 1 {
+        return 0;
+    }
+    let period = 2 * (len - 1);
+    let v = value.rem_euclid(period);
+    (if v >= len { period - v } else { v }) as usize
+}
+
+/// Reconstruct RGB from single-channel Bayer samples
+pub(crate) fn demosaic(image: &Image, pattern: BayerPattern, method: DemosaicMethod) -> ImageResult<Image> {
+    if image.pixel_format() != PixelFormat::R {
+        return Err(ImageError::InvalidPixelFormat(format!(
+            "Demosaicing requires single-channel (R) sensor data, got {:?}",
+            image.pixel_format()
+        )));
+    }
+
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+    let mut raw = vec![0f32; width * height];
+    read_normalized_slice(image.data(), image.component_type(), &mut raw);
+
+    let sample = |x: usize, y: usize, kernel: Kernel| -> f32 {
+        let sum: f32 = kernel
+            .iter()
+            .map(|&(dx, dy, weight)| {
+                let sx = mirror(x as isize + dx, width);
+                let sy = mirror(y as isize + dy, height);
+                raw[sy * width + sx] * weight
+            })
+            .sum();
+        sum.max(0.0)
+    };
+
+    let (g_at_rb, at_g_horizontal, at_g_vertical, rb_at_br) = match method {
+        DemosaicMethod::Bilinear => (BILINEAR_CROSS, BILINEAR_HORIZONTAL, BILINEAR_VERTICAL, BILINEAR_DIAGONAL),
+        DemosaicMethod::Malvar => (MALVAR_G_AT_RB, MALVAR_AT_G_HORIZONTAL, MALVAR_AT_G_VERTICAL, MALVAR_RB_AT_BR),
+    };
+
+    let mut rgb = vec![0f32; width * height * 3];
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = &mut rgb[(y * width + x) * 3..(y * width + x) * 3 + 3];
+            let own = pattern.color_at(x, y);
+            pixel[own] = raw[y * width + x];
+
+            if own == 1 {
+                // Green pixel: the horizontal neighbor tells which of R/B shares the row
+                let row_color = pattern.color_at(x + 1, y);
+                let column_color = 2 - row_color;
+                pixel[row_color] = sample(x, y, at_g_horizontal);
+                pixel[column_color] = sample(x, y, at_g_vertical);
+            } else {
+                pixel[1] = sample(x, y, g_at_rb);
+                pixel[2 - own] = sample(x, y, rb_at_br);
+            }
+        }
+    }
+
+    let mut output = Image::new(image.width(), image.height(), PixelFormat::RGB, image.component_type());
+    write_normalized_slice(output.data_mut(), image.component_type(), &rgb);
+    Ok(output)
+}
+
+impl Image {
+    /// Reconstruct an RGB image from single-channel Bayer sensor data
+    ///
+    /// The input is a single-channel (R) image holding the raw color filter
+    /// samples, as stored in grayscale PNG captures of machine-vision cameras.
+    /// The output is RGB with the same component type. Borders are mirrored.
+    ///
+    /// # Arguments
+    /// * `pattern` - Color filter layout of the top-left 2x2 tile
+    /// * `method` - Interpolation method
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{BayerPattern, DemosaicMethod, GalaxyImage};
+    ///
+    /// let raw = GalaxyImage::load_from_file("capture_raw.png")?;
+    /// let rgb = raw.demosaic(BayerPattern::Rggb, DemosaicMethod::Malvar)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn demosaic(&self, pattern: BayerPattern, method: DemosaicMethod) -> ImageResult<Image> {
+        demosaic(self, pattern, method)
+    }
+}
@@ -0,0 +1,289 @@
+/// Component type conversion with optional dithering
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, ImageResult, PixelFormat};
+use std::sync::OnceLock;
+
+/// Dithering applied when reducing to an integer component type
+///
+/// Dithering replaces the banding of smooth gradients (skyboxes, lightmaps)
+/// with fine noise. Values the target type represents exactly are never
+/// changed, and alpha is never dithered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dither {
+    /// Round to nearest
+    #[default]
+    None,
+
+    /// 8x8 Bayer matrix, a regular cross-hatch pattern that compresses well
+    Ordered,
+
+    /// 64x64 blue-noise mask, high-frequency noise without visible structure
+    ///
+    /// The mask is generated once, on first use.
+    BlueNoise,
+}
+
+/// Side of the blue-noise mask
+const BLUE_NOISE_SIZE: usize = 64;
+
+impl Dither {
+    /// Quantization threshold in `0.0..1.0` for a pixel, or 0.5 (rounding) for `None`
+    fn threshold(self, x: usize, y: usize) -> f32 {
+        match self {
+            Dither::None => 0.5,
+            Dither::Ordered => {
+                let rank = bayer_rank(x & 7, y & 7, 3);
+                (rank as f32 + 0.5) / 64.0
+            }
+            Dither::BlueNoise => {
+                blue_noise()[(y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE]
+            }
+        }
+    }
+}
+
+/// Rank of a cell in a Bayer matrix of side `2^order`
+fn bayer_rank(x: usize, y: usize, order: u32) -> u32 {
+    let mut rank = 0;
+    for bit in (0..order).rev() {
+        let bx = (x >> bit) & 1;
+        let by = (y >> bit) & 1;
+        rank = rank * 4 + [0, 2, 3, 1][by * 2 + bx];
+    }
+    rank
+}
+
+/// Blue-noise thresholds, generated once with the void-and-cluster method
+fn blue_noise() -> &'static [f32] {
+    static MASK: OnceLock<Vec<f32>> = OnceLock::new();
+    MASK.get_or_init(generate_blue_noise)
+}
+
+/// Generate a tileable blue-noise threshold mask (Ulichney's void-and-cluster)
+///
+/// Pixels are ranked by repeatedly filling the largest void of the pattern,
+/// where void and cluster sizes are measured with a toroidal Gaussian filter.
+fn generate_blue_noise() -> Vec<f32> {
+    const SIGMA: f32 = 1.5;
+    let size = BLUE_NOISE_SIZE;
+    let count = size * size;
+
+    // Gaussian weight for every toroidal offset
+    let kernel: Vec<f32> = (0..count)
+        .map(|index| {
+            let dx = (index % size).min(size - index % size) as f32;
+            let dy = (index / size).min(size - index / size) as f32;
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect();
+
+    let mut energy = vec![0f32; count];
+    let mut pattern = vec![false; count];
+    let update = |energy: &mut [f32], index: usize, sign: f32| {
+        let (px, py) = (index % size, index / size);
+        for (cy, row) in energy.chunks_exact_mut(size).enumerate() {
+            let dy = (cy + size - py) % size;
+            let weights = &kernel[dy * size..(dy + 1) * size];
+            // Columns from px wrap around to the start of the kernel row
+            let (left, right) = row.split_at_mut(px);
+            for (value, weight) in right.iter_mut().chain(left).zip(weights) {
+                *value += sign * weight;
+            }
+        }
+    };
+    let tightest_cluster = |energy: &[f32], pattern: &[bool]| {
+        (0..count).filter(|&i| pattern[i]).max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+    };
+    let largest_void = |energy: &[f32], pattern: &[bool]| {
+        (0..count).filter(|&i| !pattern[i]).min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+    };
+
+    // Initial pattern: a deterministic pseudo-random tenth of the pixels
+    let mut state = 0x9E37_79B9u32;
+    let mut ones = 0;
+    while ones < count / 10 {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let index = state as usize % count;
+        if !pattern[index] {
+            pattern[index] = true;
+            update(&mut energy, index, 1.0);
+            ones += 1;
+        }
+    }
+
+    // Spread it evenly: move the tightest cluster into the largest void until stable
+    loop {
+        let cluster = tightest_cluster(&energy, &pattern).unwrap();
+        pattern[cluster] = false;
+        update(&mut energy, cluster, -1.0);
+        let void = largest_void(&energy, &pattern).unwrap();
+        pattern[void] = true;
+        update(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0usize; count];
+
+    // Rank the initial pixels by removing clusters from a copy
+    let mut removal_pattern = pattern.clone();
+    let mut removal_energy = energy.clone();
+    for rank in (0..ones).rev() {
+        let cluster = tightest_cluster(&removal_energy, &removal_pattern).unwrap();
+        removal_pattern[cluster] = false;
+        update(&mut removal_energy, cluster, -1.0);
+        ranks[cluster] = rank;
+    }
+
+    // Rank the remaining pixels by filling voids
+    for rank in ones..count {
+        let void = largest_void(&energy, &pattern).unwrap();
+        pattern[void] = true;
+        update(&mut energy, void, 1.0);
+        ranks[void] = rank;
+    }
+
+    ranks.iter().map(|&rank| (rank as f32 + 0.5) / count as f32).collect()
+}
+
+/// Largest integer level of a component type, or `None` for float types
+fn max_level(component_type: ComponentType) -> Option<f32> {
+    match component_type {
+        ComponentType::U8 => Some(255.0),
+        ComponentType::U16 => Some(65535.0),
+        ComponentType::I8 => Some(127.0),
+        ComponentType::I16 => Some(32767.0),
+        ComponentType::F16 | ComponentType::F32 => None,
+    }
+}
+
+impl Image {
+    /// Convert to another component type, keeping the pixel format
+    ///
+    /// Integer components are scaled between their full ranges (`0..=255` and
+    /// `0..=65535` both map to `0.0..=1.0`, signed `-127..=127` and
+    /// `-32767..=32767` to `-1.0..=1.0`). Converting to an integer type clamps
+    /// values to its range (non-finite values become 0) and quantizes with the
+    /// given dithering; it is ignored for float targets. Values are kept, not
+    /// rebiased: use [`Image::unorm_to_snorm`] for 0.5-biased normal maps.
+    /// Alpha mode, color space and metadata are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `component_type` - Target component type
+    /// * `dither` - Dithering used when quantizing to an integer type
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, Dither, GalaxyImage, ImageFormat};
+    ///
+    /// let sky = GalaxyImage::load_from_file("sky_gradient.exr")?;
+    /// let sky8 = sky.convert_component_type(ComponentType::U8, Dither::BlueNoise);
+    /// GalaxyImage::save_to_file(&sky8, "sky_gradient.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert_component_type(&self, component_type: ComponentType, dither: Dither) -> Image {
+        let pixel_format = self.pixel_format();
+        if component_type == self.component_type() {
+            return self.clone();
+        }
+
+        let width = self.width() as usize;
+        let channel_count = pixel_format.channel_count();
+        let alpha_channel = pixel_format.has_alpha().then(|| channel_count - 1);
+        let mut output = Image::new(self.width(), self.height(), pixel_format, component_type);
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().clone());
+
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        let output_row_bytes = output.width() as usize * output.bytes_per_pixel();
+        if row_bytes == 0 {
+            return output;
+        }
+
+        let low = if component_type.is_signed() { -1.0 } else { 0.0 };
+        let mut values = vec![0f32; width * channel_count];
+        let output_data = output.data_mut();
+        for (y, row) in self.data().chunks_exact(row_bytes).enumerate() {
+            read_normalized_slice(row, self.component_type(), &mut values);
+            let output_row = &mut output_data[y * output_row_bytes..(y + 1) * output_row_bytes];
+
+            let Some(max) = max_level(component_type) else {
+                write_normalized_slice(output_row, component_type, &values);
+                continue;
+            };
+
+            for (index, value) in values.iter_mut().enumerate() {
+                let channel = index % channel_count;
+                let v = if value.is_finite() { value.clamp(low, 1.0) } else { 0.0 };
+                let threshold = if Some(channel) == alpha_channel {
+                    0.5
+                } else {
+                    dither.threshold(index / channel_count, y)
+                };
+                // Rounds up with a probability equal to the fractional part over the mask
+                let level = (v * max + threshold).floor().min(max);
+                *value = level / max;
+            }
+            write_normalized_slice(output_row, component_type, &values);
+        }
+
+        output
+    }
+
+    /// Convert to a single-channel F32 image with values in `0.0..=1.0`
+    ///
+    /// Prepares 8 and 16-bit heightmaps and masks for numeric processing:
+    /// `0..=65535` maps to `0.0..=1.0` exactly, color images become gray through
+    /// their Rec.709 luminance and alpha is dropped, as with
+    /// [`Image::convert`]. [`Image::to_u16_normalized`] converts back without
+    /// loss.
+    ///
+    /// Returns an error if the image data does not match its dimensions.
+    ///
+    /// # Examples
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let heights: Vec<u8> = [0u16, 32768, 65535].iter().flat_map(|h| h.to_le_bytes()).collect();
+    /// let heightmap = Image::from_raw(heights, 3, 1, PixelFormat::R, ComponentType::U16);
+    ///
+    /// let normalized = heightmap.to_f32_normalized()?;
+    /// assert_eq!(normalized.component_type(), ComponentType::F32);
+    /// assert_eq!(normalized.read_pixel_f32(1, 0)[0], 32768.0 / 65535.0);
+    ///
+    /// // Round trip back to a 16-bit PNG heightmap
+    /// assert_eq!(normalized.to_u16_normalized()?, heightmap);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn to_f32_normalized(&self) -> ImageResult<Image> {
+        self.convert(PixelFormat::R, ComponentType::F32)
+    }
+
+    /// Quantize to a single-channel U16 image, for 16-bit PNG heightmaps
+    ///
+    /// Values are clamped to `0.0..=1.0` and rounded to the nearest of the
+    /// 65536 levels; NaN becomes 0. Heights in world units must be
+    /// divided by the terrain height range first. Color images become gray as
+    /// in [`Image::to_f32_normalized`].
+    ///
+    /// Returns an error if the image data does not match its dimensions.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    ///
+    /// let terrain = GalaxyImage::load_from_file("terrain.png")?.to_f32_normalized()?;
+    /// // ... erosion, smoothing ...
+    /// GalaxyImage::save_to_file(&terrain.to_u16_normalized()?, "terrain_eroded.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn to_u16_normalized(&self) -> ImageResult<Image> {
+        self.convert(PixelFormat::R, ComponentType::U16)
+    }
+}
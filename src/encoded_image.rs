@@ -0,0 +1,128 @@
+/// Compressed in-memory images decoded on demand
+use crate::{GalaxyImage, Image, ImageError, ImageFormat, ImageInfo, ImageResult};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Image kept as its original encoded file bytes, decoded on first pixel access
+///
+/// Header properties are probed when the handle is created, so dimensions and
+/// formats are available without decoding. The decoded image is kept until
+/// [`EncodedImage::evict`] is called, which makes it cheap to keep thousands
+/// of images resident and only pay for the pixels actually in use.
+///
+/// The handle can be shared between threads; concurrent first accesses decode
+/// the image once.
+#[derive(Debug)]
+pub struct EncodedImage {
+    bytes: Vec<u8>,
+    info: ImageInfo,
+    decoded: Mutex<Option<Arc<Image>>>,
+}
+
+impl EncodedImage {
+    /// Create a handle from encoded file bytes with automatic format detection
+    ///
+    /// Format is detected from magic bytes and the header is probed immediately.
+    pub fn from_bytes(bytes: Vec<u8>) -> ImageResult<Self> {
+        let format = ImageFormat::detect_from_bytes(&bytes);
+        Self::from_bytes_with_format(bytes, format)
+    }
+
+    /// Create a handle from encoded file bytes in a known format
+    pub fn from_bytes_with_format(bytes: Vec<u8>, format: ImageFormat) -> ImageResult<Self> {
+        if format == ImageFormat::Unknown {
+            return Err(ImageError::UnsupportedFormat("Unknown format".to_string()));
+        }
+        let info = GalaxyImage::probe_from_bytes(&bytes, format)?;
+        Ok(Self {
+            bytes,
+            info,
+            decoded: Mutex::new(None),
+        })
+    }
+
+    /// Get the header properties
+    pub fn info(&self) -> &ImageInfo {
+        &self.info
+    }
+
+    /// Get the file format
+    pub fn format(&self) -> ImageFormat {
+        self.info.format
+    }
+
+    /// Get the image width in pixels
+    pub fn width(&self) -> u32 {
+        self.info.width
+    }
+
+    /// Get the image height in pixels
+    pub fn height(&self) -> u32 {
+        self.info.height
+    }
+
+    /// Get the encoded file bytes
+    pub fn encoded_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Get the size of the encoded data in bytes
+    pub fn encoded_size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Get the decoded image, decoding it on first access
+    pub fn image(&self) -> ImageResult<Arc<Image>> {
+        // Holding the lock while decoding makes concurrent callers wait for one decode
+        let mut decoded = self.lock_decoded();
+        if let Some(image) = decoded.as_ref() {
+            return Ok(Arc::clone(image));
+        }
+
+        let image = Arc::new(GalaxyImage::load_from_bytes(&self.bytes, self.info.format)?);
+        *decoded = Some(Arc::clone(&image));
+        Ok(image)
+    }
+
+    /// Decode a new copy of the image without keeping it
+    pub fn decode(&self) -> ImageResult<Image> {
+        GalaxyImage::load_from_bytes(&self.bytes, self.info.format)
+    }
+
+    /// Check if the decoded image is currently kept
+    pub fn is_decoded(&self) -> bool {
+        self.lock_decoded().is_some()
+    }
+
+    /// Drop the kept decoded image; the next access decodes again
+    ///
+    /// Images still referenced through an `Arc` returned by [`EncodedImage::image`]
+    /// stay alive until those references are dropped.
+    pub fn evict(&self) {
+        self.lock_decoded().take();
+    }
+
+    /// Get the number of decoded pixel bytes currently kept
+    pub fn decoded_size(&self) -> usize {
+        self.lock_decoded().as_ref().map_or(0, |image| image.size_bytes())
+    }
+
+    /// Consume the handle and return the encoded file bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn lock_decoded(&self) -> MutexGuard<'_, Option<Arc<Image>>> {
+        // A panic during decode leaves the slot empty, which is a valid state
+        self.decoded.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Clone for EncodedImage {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            info: self.info,
+            decoded: Mutex::new(self.lock_decoded().clone()),
+        }
+    }
+}
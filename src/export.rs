@@ -0,0 +1,108 @@
+/// Multi-format export sets
+use crate::{atomic_write, GalaxyImage, Image, ImageError, ImageFormat, ImageResult, PixelFormat, SaveOptions};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// One output of an export set
+///
+/// The output path is the export base path followed by `suffix` and the format
+/// extension, e.g. `textures/rock` + `_preview` + `.jpg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportTarget {
+    /// Output format
+    pub format: ImageFormat,
+
+    /// Text appended to the base file name before the extension
+    pub suffix: String,
+
+    /// Encoding options for this output
+    pub options: SaveOptions,
+}
+
+impl ExportTarget {
+    /// Create a target with no suffix and default options
+    pub fn new(format: ImageFormat) -> Self {
+        Self {
+            format,
+            suffix: String::new(),
+            options: SaveOptions::default(),
+        }
+    }
+
+    /// Set the file name suffix
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Set the encoding options
+    pub fn with_options(mut self, options: SaveOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Build the output path for a base path
+    pub fn output_path(&self, base_path: &Path) -> PathBuf {
+        let mut name = base_path.as_os_str().to_os_string();
+        name.push(&self.suffix);
+        name.push(".");
+        name.push(self.format.extension());
+        PathBuf::from(name)
+    }
+}
+
+pub(crate) fn export(
+    image: &Image,
+    base_path: &Path,
+    targets: &[ExportTarget],
+) -> ImageResult<Vec<PathBuf>> {
+    if let Some(target) = targets.iter().find(|t| t.format == ImageFormat::Unknown) {
+        return Err(ImageError::UnsupportedFormat(format!(
+            "Cannot export target with suffix '{}': unknown format",
+            target.suffix
+        )));
+    }
+
+    // Every encoder wants RGB order, so swap BGR images once for all targets
+    let converted;
+    let source = match image.pixel_format() {
+        PixelFormat::BGR | PixelFormat::BGRA => {
+            let mut rgb = image.clone();
+            rgb.bgr_to_rgb();
+            converted = rgb;
+            &converted
+        }
+        _ => image,
+    };
+
+    let paths: Vec<PathBuf> = targets.iter().map(|t| t.output_path(base_path)).collect();
+
+    // Encode and write every target on its own thread
+    let results: Vec<ImageResult<()>> = thread::scope(|scope| {
+        let handles: Vec<_> = targets
+            .iter()
+            .zip(&paths)
+            .map(|(target, path)| {
+                scope.spawn(move || {
+                    let bytes =
+                        GalaxyImage::save_to_bytes_with_options(source, target.format, &target.options)?;
+                    atomic_write::write_file(path, &bytes, target.options.atomic)?;
+                    Ok(())
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(ImageError::Other("Export thread panicked".to_string())))
+            })
+            .collect()
+    });
+
+    // Report the first failure in target order
+    results.into_iter().collect::<ImageResult<Vec<()>>>()?;
+    Ok(paths)
+}
@@ -0,0 +1,204 @@
+/// False-color and zebra exposure previews for lighting review
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use crate::sample::read_normalized_slice;
+use crate::{ComponentType, Image, PixelFormat};
+
+/// Luminance of 0 EV in false-color previews (18% gray)
+const MIDDLE_GRAY: f32 = 0.18;
+
+/// Color of a false-color gradient at an exposure value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FalseColorStop {
+    /// Exposure value in stops relative to 18% gray
+    pub ev: f32,
+
+    /// sRGB color at this exposure value
+    pub color: [u8; 3],
+}
+
+impl FalseColorStop {
+    /// Create a stop
+    pub fn new(ev: f32, color: [u8; 3]) -> Self {
+        Self { ev, color }
+    }
+}
+
+/// Options of [`Image::false_color`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FalseColorOptions {
+    /// Exposure adjustment in stops, applied before mapping (default: 0.0)
+    pub exposure: f32,
+
+    /// Gradient stops in ascending EV order (default: black at -7 EV through
+    /// purple, blue, cyan, gray at 0 EV, green, yellow, orange and red to white
+    /// at +9 EV)
+    ///
+    /// Colors are interpolated between stops and clamped beyond the ends.
+    pub stops: Vec<FalseColorStop>,
+}
+
+impl Default for FalseColorOptions {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            stops: vec![
+                FalseColorStop::new(-7.0, [0, 0, 0]),
+                FalseColorStop::new(-5.0, [96, 0, 160]),
+                FalseColorStop::new(-3.0, [0, 48, 255]),
+                FalseColorStop::new(-1.0, [0, 176, 255]),
+                FalseColorStop::new(0.0, [128, 128, 128]),
+                FalseColorStop::new(1.0, [0, 200, 0]),
+                FalseColorStop::new(3.0, [255, 240, 0]),
+                FalseColorStop::new(5.0, [255, 128, 0]),
+                FalseColorStop::new(7.0, [255, 0, 0]),
+                FalseColorStop::new(9.0, [255, 255, 255]),
+            ],
+        }
+    }
+}
+
+/// Options of [`Image::zebra`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZebraOptions {
+    /// Exposure adjustment in stops, applied before the preview and the tests (default: 0.0)
+    pub exposure: f32,
+
+    /// Linear luminance at or above which pixels get red stripes (default: 1.0, display white)
+    pub over: Option<f32>,
+
+    /// Linear luminance at or below which pixels get blue stripes (default: 1/1024)
+    pub under: Option<f32>,
+
+    /// Width of the diagonal stripes in pixels (default: 4)
+    pub stripe_width: u32,
+}
+
+impl Default for ZebraOptions {
+    fn default() -> Self {
+        Self { exposure: 0.0, over: Some(1.0), under: Some(1.0 / 1024.0), stripe_width: 4 }
+    }
+}
+
+/// Call `f` with the coordinates, linear RGB and luminance of every pixel
+///
+/// Integer images are decoded from sRGB, float images are taken as linear.
+/// Luminance uses Rec.709 weights; grayscale images use their value.
+fn for_each_linear_pixel(image: &Image, exposure: f32, mut f: impl FnMut(usize, usize, [f32; 3], f32)) {
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let (red, green, blue) = match pixel_format {
+        PixelFormat::R | PixelFormat::RG => (0, 0, 0),
+        PixelFormat::RGB | PixelFormat::RGBA => (0, 1, 2),
+        PixelFormat::BGR | PixelFormat::BGRA => (2, 1, 0),
+    };
+    let decode = matches!(image.component_type(), ComponentType::U8 | ComponentType::U16);
+    let scale = exposure.exp2();
+
+    let width = image.width() as usize;
+    let row_bytes = width * image.bytes_per_pixel();
+    if row_bytes == 0 {
+        return;
+    }
+    let mut values = vec![0f32; width * channel_count];
+    for (y, row) in image.data().chunks_exact(row_bytes).enumerate() {
+        read_normalized_slice(row, image.component_type(), &mut values);
+        for (x, pixel) in values.chunks_exact(channel_count).enumerate() {
+            let linear = |v: f32| (if decode { srgb_to_linear(v) } else { v }) * scale;
+            let rgb = [linear(pixel[red]), linear(pixel[green]), linear(pixel[blue])];
+            let luminance = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+            f(x, y, rgb, luminance);
+        }
+    }
+}
+
+/// Interpolate the gradient color at an exposure value
+fn gradient_color(stops: &[FalseColorStop], ev: f32) -> [u8; 3] {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return [0, 0, 0];
+    };
+    if ev.is_nan() || ev <= first.ev {
+        return first.color;
+    }
+    if ev >= last.ev {
+        return last.color;
+    }
+
+    let upper = stops.iter().position(|stop| stop.ev > ev).unwrap_or(stops.len() - 1);
+    let (a, b) = (stops[upper - 1], stops[upper]);
+    let t = (ev - a.ev) / (b.ev - a.ev).max(f32::EPSILON);
+    std::array::from_fn(|c| (a.color[c] as f32 + (b.color[c] as f32 - a.color[c] as f32) * t).round() as u8)
+}
+
+impl Image {
+    /// Map luminance to a false-color gradient
+    ///
+    /// Each pixel gets the gradient color of its exposure value relative to 18%
+    /// gray, so lighting artists can read absolute light levels of an HDR
+    /// render or capture at a glance. 8 and 16-bit images are decoded from sRGB,
+    /// float images are taken as linear. Returns an RGB/U8 image.
+    ///
+    /// # Arguments
+    /// * `options` - Exposure and gradient stops
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{FalseColorOptions, GalaxyImage, ImageFormat};
+    ///
+    /// let lightmap = GalaxyImage::load_from_file("lightmap.exr")?;
+    /// let preview = lightmap.false_color(&FalseColorOptions::default());
+    /// GalaxyImage::save_to_file(&preview, "lightmap_false_color.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn false_color(&self, options: &FalseColorOptions) -> Image {
+        let mut output = Image::new(self.width(), self.height(), PixelFormat::RGB, ComponentType::U8);
+        let width = self.width() as usize;
+        let data = output.data_mut();
+        for_each_linear_pixel(self, options.exposure, |x, y, _, luminance| {
+            let ev = (luminance / MIDDLE_GRAY).log2();
+            let offset = (y * width + x) * 3;
+            data[offset..offset + 3].copy_from_slice(&gradient_color(&options.stops, ev));
+        });
+        output
+    }
+
+    /// Show over and under-exposed areas with zebra stripes
+    ///
+    /// Returns an RGB/U8 sRGB preview of the image (clamped, no tone mapping)
+    /// with diagonal red stripes over pixels at or above `over` and blue
+    /// stripes over pixels at or below `under`. 8 and 16-bit images are decoded
+    /// from sRGB, float images are taken as linear.
+    ///
+    /// # Arguments
+    /// * `options` - Exposure, thresholds and stripe width
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ZebraOptions};
+    ///
+    /// let capture = GalaxyImage::load_from_file("sky_capture.exr")?;
+    /// let preview = capture.zebra(&ZebraOptions { exposure: -1.0, ..Default::default() });
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn zebra(&self, options: &ZebraOptions) -> Image {
+        const OVER_COLOR: [u8; 3] = [255, 0, 0];
+        const UNDER_COLOR: [u8; 3] = [0, 64, 255];
+
+        let mut output = Image::new(self.width(), self.height(), PixelFormat::RGB, ComponentType::U8);
+        let width = self.width() as usize;
+        let stripe_width = options.stripe_width.max(1) as usize;
+        let data = output.data_mut();
+        for_each_linear_pixel(self, options.exposure, |x, y, rgb, luminance| {
+            let stripe = ((x + y) / stripe_width).is_multiple_of(2);
+            let color = if stripe && options.over.is_some_and(|over| luminance >= over) {
+                OVER_COLOR
+            } else if stripe && options.under.is_some_and(|under| luminance <= under) {
+                UNDER_COLOR
+            } else {
+                rgb.map(|v| (linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8)
+            };
+            let offset = (y * width + x) * 3;
+            data[offset..offset + 3].copy_from_slice(&color);
+        });
+        output
+    }
+}
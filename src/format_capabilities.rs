@@ -0,0 +1,65 @@
+/// Features of an image file format
+use crate::{ComponentType, ImageFormat, PixelFormat};
+
+/// What an image format can store, as reported by [`ImageFormat::capabilities`]
+///
+/// [`ImageFormat::capabilities`]: crate::ImageFormat::capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatCapabilities {
+    /// Pixel formats saved without dropping channels
+    ///
+    /// BGR orders are reordered on save and load back as RGB.
+    pub pixel_formats: &'static [PixelFormat],
+
+    /// Component types saved without conversion
+    pub component_types: &'static [ComponentType],
+
+    /// Whether the format stores an alpha channel
+    pub alpha: bool,
+
+    /// Whether the format stores samples above 1.0 (floating-point HDR)
+    pub hdr: bool,
+
+    /// Whether multi-frame animations are loaded
+    pub animation: bool,
+
+    /// Whether metadata (color profiles, EXIF, custom attributes) is preserved
+    pub metadata: bool,
+
+    /// Whether encoding loses information
+    pub lossy: bool,
+
+    /// Whether images of this format can be loaded
+    pub decode: bool,
+
+    /// Whether images can be saved to this format
+    pub encode: bool,
+}
+
+impl FormatCapabilities {
+    /// Check if an image layout can be saved without converting or dropping data
+    pub fn supports(&self, pixel_format: PixelFormat, component_type: ComponentType) -> bool {
+        self.encode
+            && self.pixel_formats.contains(&pixel_format)
+            && self.component_types.contains(&component_type)
+    }
+}
+
+/// Description of a supported format, as listed by [`ImageFormat::all_supported`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatDescriptor {
+    /// Format identifier
+    pub format: ImageFormat,
+
+    /// Human-readable name (e.g. "JPEG")
+    pub name: &'static str,
+
+    /// Recognized file extensions, canonical first (lowercase, without dot)
+    pub extensions: &'static [&'static str],
+
+    /// Canonical MIME type
+    pub mime_type: &'static str,
+
+    /// What the format can store
+    pub capabilities: FormatCapabilities,
+}
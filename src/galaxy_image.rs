@@ -1,7 +1,22 @@
-use crate::{Image, ImageError, ImageFormat, ImageResult};
-use crate::loaders::{load_png, save_png, load_bmp, save_bmp, load_jpeg, save_jpeg, load_exr, save_exr};
+use crate::{AnimatedImage, EncodedImage, ExportTarget, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LdrConversion, MaskFormat, PixelFormat, SaveOptions};
+use crate::{AtlasOptions, BitMask, BufferPoolStats, JpegQuality, ComponentType, ContactSheetOptions, ConvertJob, ConvertReport, MipPreviewOptions, ImageFuture, LoadOptions, PyramidOptions, PyramidTile, SaveConstraints, TextureAtlas, TileCoord};
+use crate::loaders::{load_png, save_png, load_bmp, save_bmp, load_jpeg, save_jpeg, load_exr, save_exr, load_tga, save_tga, load_dds, load_hdr, save_hdr, load_tiff, save_tiff, load_webp, load_gif, load_gif_animation, load_ico, load_ico_all, load_ico_for_size, load_pnm, save_pnm, load_pfm, save_pfm, load_jxl, load_avif, load_psd};
+use crate::loaders::{probe_png, probe_bmp, probe_jpeg, probe_exr, probe_tga, probe_dds, probe_hdr, probe_tiff, probe_webp, probe_gif, probe_ico, probe_ico_all, probe_pnm, probe_pfm, probe_jxl, probe_avif, probe_psd};
+use crate::loaders::{load_dds_all, load_exr_all, load_exr_channels, load_exr_layers, probe_dds_all, probe_exr_all};
+use crate::loaders::{load_bmp_from_reader, load_exr_from_reader, load_jpeg_from_reader, load_png_from_reader};
+use crate::loaders::{load_pbm_mask, load_png_mask, save_pbm_mask, save_png_mask};
+use crate::{atlas, atomic_write, auto_format, batch, buffer_pool, contact_sheet, dedup, mip_preview, export, instrument, jpeg_quality, ldr, pool, pyramid, sequence, size_estimate, thumbnail};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[cfg(feature = "cache")]
+use crate::cache;
+#[cfg(feature = "watch")]
+use crate::watch::{spawn_watcher, ImageWatcher, WatchOptions};
 
 /// Main manager/factory for image operations
 ///
@@ -28,19 +43,35 @@ impl GalaxyImage {
     /// println!("Loaded {}x{} image", image.width(), image.height());
     /// ```
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> ImageResult<Image> {
-        let bytes = fs::read(&path)?;
-
-        // Detect format from magic bytes
-        let mut format = ImageFormat::detect_from_bytes(&bytes);
-
-        // Fallback to extension if magic bytes didn't work
-        if format == ImageFormat::Unknown {
-            format = ImageFormat::from_extension(
-                path.as_ref().to_str().unwrap_or("")
-            );
-        }
+        Self::load_from_file_with_options(path, &LoadOptions::default())
+    }
 
-        Self::load_from_bytes(&bytes, format)
+    /// Load an image from a file path with load options
+    ///
+    /// Format is detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `options` - Decoding options (e.g. bottom-up row order)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, LoadOptions};
+    ///
+    /// let options = LoadOptions { flip_y: true, ..Default::default() };
+    /// let image = GalaxyImage::load_from_file_with_options("texture.png", &options).unwrap();
+    /// ```
+    pub fn load_from_file_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> ImageResult<Image> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_from_bytes_with_options(&bytes, format, options)
+        })
     }
 
     /// Load an image from a byte buffer
@@ -61,15 +92,52 @@ impl GalaxyImage {
     /// let image = GalaxyImage::load_from_bytes(&bytes, ImageFormat::Png).unwrap();
     /// ```
     pub fn load_from_bytes(bytes: &[u8], format: ImageFormat) -> ImageResult<Image> {
-        match format {
-            ImageFormat::Png => load_png(bytes),
-            ImageFormat::Bmp => load_bmp(bytes),
-            ImageFormat::Jpeg => load_jpeg(bytes),
-            ImageFormat::Exr => load_exr(bytes),
+        Self::load_from_bytes_with_options(bytes, format, &LoadOptions::default())
+    }
+
+    /// Load an image from a byte buffer with load options
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    /// * `options` - Decoding options (e.g. bottom-up row order)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, LoadOptions};
+    ///
+    /// let bytes = vec![/* ... */];
+    /// let options = LoadOptions { flip_y: true, ..Default::default() };
+    /// let image = GalaxyImage::load_from_bytes_with_options(&bytes, ImageFormat::Png, &options).unwrap();
+    /// ```
+    pub fn load_from_bytes_with_options(
+        bytes: &[u8],
+        format: ImageFormat,
+        options: &LoadOptions,
+    ) -> ImageResult<Image> {
+        instrument::load(format, bytes.len(), || match format {
+            ImageFormat::Png => load_png(bytes, options),
+            ImageFormat::Bmp => load_bmp(bytes, options),
+            ImageFormat::Jpeg => load_jpeg(bytes, options),
+            ImageFormat::Exr => load_exr(bytes, options),
+            ImageFormat::Tga => load_tga(bytes, options),
+            ImageFormat::Dds => load_dds(bytes, options),
+            ImageFormat::Hdr => load_hdr(bytes, options),
+            ImageFormat::Tiff => load_tiff(bytes, options),
+            ImageFormat::WebP => load_webp(bytes, options),
+            ImageFormat::Gif => load_gif(bytes, options),
+            ImageFormat::Ico => load_ico(bytes, options),
+            ImageFormat::Pnm => load_pnm(bytes, options),
+            ImageFormat::Pfm => load_pfm(bytes, options),
+            ImageFormat::Jxl => load_jxl(bytes, options),
+            ImageFormat::Avif => load_avif(bytes, options),
+            ImageFormat::Psd => load_psd(bytes, options),
             ImageFormat::Unknown => {
                 Err(ImageError::UnsupportedFormat("Unknown format".to_string()))
             }
-        }
+        })
     }
 
     /// Load an image from a byte buffer with automatic format detection
@@ -93,92 +161,1585 @@ impl GalaxyImage {
         Self::load_from_bytes(bytes, format)
     }
 
-    /// Save an image to a file
+    /// Load an image from a reader with automatic format detection
+    ///
+    /// Reading starts at the current position, and the format is detected
+    /// from magic bytes. PNG, JPEG, BMP and EXR are decoded as they are read,
+    /// without holding the file in memory; other formats are read into a
+    /// buffer from the manager buffer pool first. Decoders read ahead, so the
+    /// reader may be left past the end of the image.
     ///
     /// # Arguments
     ///
-    /// * `image` - Image to save
-    /// * `path` - Output file path
-    /// * `format` - Output format (PNG, BMP, JPEG)
+    /// * `reader` - Source positioned at the start of the image (file, archive entry, ...)
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use galaxy_image::{GalaxyImage, ImageFormat};
-    /// # use galaxy_image::Image;
-    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    /// use galaxy_image::GalaxyImage;
+    /// use std::io::{Seek, SeekFrom};
     ///
-    /// GalaxyImage::save_to_file(&image, "output.png", ImageFormat::Png).unwrap();
+    /// // Texture stored at a known offset in a pak file
+    /// let mut pak = std::fs::File::open("textures.pak")?;
+    /// pak.seek(SeekFrom::Start(4096))?;
+    /// let image = GalaxyImage::load_from_reader(&mut pak)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
     /// ```
-    pub fn save_to_file<P: AsRef<Path>>(
-        image: &Image,
-        path: P,
-        format: ImageFormat,
-    ) -> ImageResult<()> {
-        let bytes = Self::save_to_bytes(image, format, 90)?;
-        fs::write(path, bytes)?;
-        Ok(())
+    pub fn load_from_reader<R: Read + Seek>(reader: R) -> ImageResult<Image> {
+        Self::load_from_reader_with_options(reader, &LoadOptions::default())
     }
 
-    /// Save an image to a file with JPEG quality parameter
+    /// Load an image from a reader with load options
+    ///
+    /// Reads like [`GalaxyImage::load_from_reader`].
     ///
     /// # Arguments
     ///
-    /// * `image` - Image to save
-    /// * `path` - Output file path
-    /// * `format` - Output format (PNG, BMP, JPEG)
-    /// * `jpeg_quality` - JPEG quality (1-100, only used for JPEG format)
+    /// * `reader` - Source positioned at the start of the image
+    /// * `options` - Decoding options (e.g. bottom-up row order)
+    pub fn load_from_reader_with_options<R: Read + Seek>(mut reader: R, options: &LoadOptions) -> ImageResult<Image> {
+        let start = reader.stream_position()?;
+        let input_bytes = reader.seek(SeekFrom::End(0))?.saturating_sub(start) as usize;
+        reader.seek(SeekFrom::Start(start))?;
+        let mut header = Vec::with_capacity(16);
+        reader.by_ref().take(16).read_to_end(&mut header)?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let format = ImageFormat::detect_from_bytes(&header);
+        match format {
+            ImageFormat::Png => instrument::load(format, input_bytes, || load_png_from_reader(BufReader::new(reader), options)),
+            ImageFormat::Jpeg => instrument::load(format, input_bytes, || load_jpeg_from_reader(BufReader::new(reader), options)),
+            ImageFormat::Bmp => instrument::load(format, input_bytes, || load_bmp_from_reader(BufReader::new(reader), options)),
+            ImageFormat::Exr => instrument::load(format, input_bytes, || load_exr_from_reader(BufReader::new(reader), options)),
+            // Other signatures need more than the header (TGA footer, ICO directory)
+            _ => {
+                let mut bytes = buffer_pool::take(input_bytes);
+                reader.read_to_end(&mut bytes)?;
+                let result = Self::load_from_bytes_with_options(&bytes, ImageFormat::detect_from_bytes(&bytes), options);
+                buffer_pool::recycle(bytes);
+                result
+            }
+        }
+    }
+
+    /// Read image properties from a file header without decoding pixels
+    ///
+    /// Format is detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use galaxy_image::{GalaxyImage, ImageFormat};
-    /// # use galaxy_image::Image;
-    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    /// use galaxy_image::GalaxyImage;
     ///
-    /// GalaxyImage::save_to_file_with_quality(&image, "output.jpg", ImageFormat::Jpeg, 95).unwrap();
+    /// let info = GalaxyImage::probe_file("texture.exr").unwrap();
+    /// println!("{}x{}, {} mip levels", info.width, info.height, info.mip_levels);
     /// ```
-    pub fn save_to_file_with_quality<P: AsRef<Path>>(
-        image: &Image,
-        path: P,
-        format: ImageFormat,
-        jpeg_quality: u8,
-    ) -> ImageResult<()> {
-        let bytes = Self::save_to_bytes(image, format, jpeg_quality)?;
-        fs::write(path, bytes)?;
-        Ok(())
+    pub fn probe_file<P: AsRef<Path>>(path: P) -> ImageResult<ImageInfo> {
+        let bytes = fs::read(&path)?;
+        let format = detect_file_format(path.as_ref(), &bytes);
+        Self::probe_from_bytes(&bytes, format)
     }
 
-    /// Save an image to a byte buffer
+    /// Read image properties from a byte buffer without decoding pixels
     ///
     /// # Arguments
     ///
-    /// * `image` - Image to save
-    /// * `format` - Output format (PNG, BMP, JPEG)
-    /// * `jpeg_quality` - JPEG quality (1-100, only used for JPEG format)
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format (PNG, BMP, JPEG, EXR)
+    pub fn probe_from_bytes(bytes: &[u8], format: ImageFormat) -> ImageResult<ImageInfo> {
+        match format {
+            ImageFormat::Png => probe_png(bytes),
+            ImageFormat::Bmp => probe_bmp(bytes),
+            ImageFormat::Jpeg => probe_jpeg(bytes),
+            ImageFormat::Exr => probe_exr(bytes),
+            ImageFormat::Tga => probe_tga(bytes),
+            ImageFormat::Dds => probe_dds(bytes),
+            ImageFormat::Hdr => probe_hdr(bytes),
+            ImageFormat::Tiff => probe_tiff(bytes),
+            ImageFormat::WebP => probe_webp(bytes),
+            ImageFormat::Gif => probe_gif(bytes),
+            ImageFormat::Ico => probe_ico(bytes),
+            ImageFormat::Pnm => probe_pnm(bytes),
+            ImageFormat::Pfm => probe_pfm(bytes),
+            ImageFormat::Jxl => probe_jxl(bytes),
+            ImageFormat::Avif => probe_avif(bytes),
+            ImageFormat::Psd => probe_psd(bytes),
+            ImageFormat::Unknown => {
+                Err(ImageError::UnsupportedFormat("Unknown format".to_string()))
+            }
+        }
+    }
+
+    /// Load every image stored in a file
+    ///
+    /// Container formats return all their sub-images in file order (every layer
+    /// with recognized channels of a multipart EXR, every mip level of every
+    /// array element or cube face of a DDS texture, every composited frame of a
+    /// GIF); single-image formats return one image. Format is detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use galaxy_image::{GalaxyImage, ImageFormat};
-    /// # use galaxy_image::Image;
-    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    /// use galaxy_image::GalaxyImage;
     ///
-    /// let bytes = GalaxyImage::save_to_bytes(&image, ImageFormat::Png, 90).unwrap();
+    /// let parts = GalaxyImage::load_all_from_file("render_passes.exr").unwrap();
+    /// println!("{} images", parts.len());
     /// ```
-    pub fn save_to_bytes(
-        image: &Image,
-        format: ImageFormat,
-        jpeg_quality: u8,
-    ) -> ImageResult<Vec<u8>> {
+    pub fn load_all_from_file<P: AsRef<Path>>(path: P) -> ImageResult<Vec<Image>> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_all_from_bytes(&bytes, format)
+        })
+    }
+
+    /// Load every image stored in a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    pub fn load_all_from_bytes(bytes: &[u8], format: ImageFormat) -> ImageResult<Vec<Image>> {
         match format {
-            ImageFormat::Png => save_png(image),
-            ImageFormat::Bmp => save_bmp(image),
-            ImageFormat::Jpeg => save_jpeg(image, jpeg_quality.clamp(1, 100)),
-            ImageFormat::Exr => save_exr(image),
-            ImageFormat::Unknown => {
-                Err(ImageError::UnsupportedFormat("Unknown format".to_string()))
-            }
+            ImageFormat::Exr => load_exr_all(bytes, &LoadOptions::default()),
+            ImageFormat::Dds => load_dds_all(bytes, &LoadOptions::default()),
+            ImageFormat::Gif => load_gif_animation(bytes, &LoadOptions::default()).map(AnimatedImage::into_images),
+            ImageFormat::Ico => load_ico_all(bytes, &LoadOptions::default()),
+            _ => Self::load_from_bytes(bytes, format).map(|image| vec![image]),
+        }
+    }
+
+    /// Load every layer of a file with its name, e.g. the render passes of a multi-layer EXR
+    ///
+    /// EXR channels are grouped into layers by name prefix (`beauty.R`,
+    /// `normal.X`, `depth.Z`) within each part; a layer name joins the part
+    /// name and the prefix with a `.`, and unprefixed channels of an unnamed
+    /// part form the layer `""`. X/Y/Z(/W) vector layers load as RGB(A) and
+    /// single-channel layers of any name as R. Other formats return their
+    /// image as the layer `""`. Format is detected the same way as
+    /// [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// for (name, pass) in GalaxyImage::load_layers_from_file("bake/passes.exr")? {
+    ///     println!("{}: {}x{} {:?}", name, pass.width(), pass.height(), pass.pixel_format());
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_layers_from_file<P: AsRef<Path>>(path: P) -> ImageResult<Vec<(String, Image)>> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_layers_from_bytes(&bytes, format, &LoadOptions::default())
+        })
+    }
+
+    /// Load every layer of a file with its name from a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    /// * `options` - Decoding options, applied to every layer
+    pub fn load_layers_from_bytes(bytes: &[u8], format: ImageFormat, options: &LoadOptions) -> ImageResult<Vec<(String, Image)>> {
+        match format {
+            ImageFormat::Exr => load_exr_layers(bytes, options),
+            _ => Self::load_from_bytes_with_options(bytes, format, options).map(|image| vec![(String::new(), image)]),
+        }
+    }
+
+    /// Load every channel of a file as a grayscale image, keyed by channel name
+    ///
+    /// Gives access to EXR channels that do not map to R/G/B/A/Y, such as `Z`,
+    /// `velocity.x` or object IDs. Keys are the full EXR channel names,
+    /// prefixed with the part name and a `.` in named parts; channels load as
+    /// R images (F16 or F32, U32 as F32). Deep data is not supported. Other
+    /// formats return their channels as `R`, `G`, `B` and `A`. Format is
+    /// detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let channels = GalaxyImage::load_channels_from_file("render.exr")?;
+    /// if let (Some(vx), Some(vy)) = (channels.get("velocity.x"), channels.get("velocity.y")) {
+    ///     println!("motion vectors: {}x{}", vx.width(), vy.height());
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_channels_from_file<P: AsRef<Path>>(path: P) -> ImageResult<BTreeMap<String, Image>> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_channels_from_bytes(&bytes, format, &LoadOptions::default())
+        })
+    }
+
+    /// Load every channel of a file as a grayscale image from a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    /// * `options` - Decoding options, applied to every channel
+    pub fn load_channels_from_bytes(bytes: &[u8], format: ImageFormat, options: &LoadOptions) -> ImageResult<BTreeMap<String, Image>> {
+        if format == ImageFormat::Exr {
+            return load_exr_channels(bytes, options);
+        }
+
+        let image = Self::load_from_bytes_with_options(bytes, format, options)?;
+        // Channel names in storage order
+        let names: &[&str] = match image.pixel_format() {
+            PixelFormat::R => &["R"],
+            PixelFormat::RG => &["R", "G"],
+            PixelFormat::RGB => &["R", "G", "B"],
+            PixelFormat::RGBA => &["R", "G", "B", "A"],
+            PixelFormat::BGR => &["B", "G", "R"],
+            PixelFormat::BGRA => &["B", "G", "R", "A"],
+        };
+        Ok(names.iter().map(|name| name.to_string()).zip(image.split_channels()).collect())
+    }
+
+    /// Load an animation with per-frame timing, e.g. an animated GIF sprite
+    ///
+    /// Every frame is the full canvas with earlier frames and their disposal
+    /// already applied. Still formats return a one-frame animation. Format is
+    /// detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let animation = GalaxyImage::load_animation_from_file("sprites/coin.gif")?;
+    /// for frame in &animation.frames {
+    ///     println!("{}x{} for {} ms", frame.image.width(), frame.image.height(), frame.delay_ms);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_animation_from_file<P: AsRef<Path>>(path: P) -> ImageResult<AnimatedImage> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_animation_from_bytes(&bytes, format, &LoadOptions::default())
+        })
+    }
+
+    /// Load an animation with per-frame timing from a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    /// * `options` - Decoding options, applied to every frame
+    pub fn load_animation_from_bytes(bytes: &[u8], format: ImageFormat, options: &LoadOptions) -> ImageResult<AnimatedImage> {
+        match format {
+            ImageFormat::Gif => load_gif_animation(bytes, options),
+            _ => Self::load_from_bytes_with_options(bytes, format, options).map(AnimatedImage::from_image),
+        }
+    }
+
+    /// Load the resolution of an icon closest to a size, e.g. for window and taskbar icons
+    ///
+    /// ICO and CUR files store several resolutions; the one whose largest
+    /// dimension is closest to `size` is decoded, preferring the larger entry on
+    /// a tie. Use [`GalaxyImage::probe_all_file`] to list the sizes and
+    /// [`GalaxyImage::load_all_from_file`] to load them all. Other formats load
+    /// their only image. Format is detected the same way as
+    /// [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `size` - Wanted width and height in pixels
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let small = GalaxyImage::load_icon_from_file("app.ico", 16)?;
+    /// let taskbar = GalaxyImage::load_icon_from_file("app.ico", 48)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_icon_from_file<P: AsRef<Path>>(path: P, size: u32) -> ImageResult<Image> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            let format = detect_file_format(path.as_ref(), &bytes);
+            Self::load_icon_from_bytes(&bytes, format, size, &LoadOptions::default())
+        })
+    }
+
+    /// Load the resolution of an icon closest to a size from a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    /// * `size` - Wanted width and height in pixels
+    /// * `options` - Decoding options
+    pub fn load_icon_from_bytes(bytes: &[u8], format: ImageFormat, size: u32, options: &LoadOptions) -> ImageResult<Image> {
+        match format {
+            ImageFormat::Ico => instrument::load(format, bytes.len(), || load_ico_for_size(bytes, size, options)),
+            _ => Self::load_from_bytes_with_options(bytes, format, options),
+        }
+    }
+
+    /// Load a numbered image sequence, e.g. the frames of a rendered flipbook
+    ///
+    /// `pattern` is a path with a printf-style frame number: `%d`, or `%04d` for
+    /// zero-padded numbers (`%%` is a literal `%`). Frames are loaded in
+    /// parallel and returned in the order of `frames`. Every frame must have
+    /// the dimensions, pixel format and component type of the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Frame path pattern
+    /// * `frames` - Frame numbers, e.g. `1..=48`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let frames = GalaxyImage::load_sequence("fx/explosion/frame_%04d.png", 1..=48)?;
+    /// println!("{} frames", frames.len());
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_sequence(pattern: &str, frames: impl IntoIterator<Item = u32>) -> ImageResult<Vec<Image>> {
+        sequence::load_sequence(pattern, frames, &LoadOptions::default())
+    }
+
+    /// Load a numbered image sequence with load options
+    ///
+    /// See [`GalaxyImage::load_sequence`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Frame path pattern
+    /// * `frames` - Frame numbers
+    /// * `options` - Decoding options applied to every frame
+    pub fn load_sequence_with_options(
+        pattern: &str,
+        frames: impl IntoIterator<Item = u32>,
+        options: &LoadOptions,
+    ) -> ImageResult<Vec<Image>> {
+        sequence::load_sequence(pattern, frames, options)
+    }
+
+    /// Find the sequence a numbered file belongs to
+    ///
+    /// Takes the last run of digits of the file name as the frame number and
+    /// scans the directory for the contiguous run of frames around it. Returns
+    /// a pattern and frame range for [`GalaxyImage::load_sequence`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of any frame of the sequence
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// // With smoke_0001.exr to smoke_0064.exr on disk
+    /// let (pattern, frames) = GalaxyImage::detect_sequence("fx/smoke_0017.exr")?;
+    /// assert_eq!(pattern, "fx/smoke_%04d.exr");
+    /// assert_eq!(frames, 1..=64);
+    /// let frames = GalaxyImage::load_sequence(&pattern, frames)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn detect_sequence<P: AsRef<Path>>(path: P) -> ImageResult<(String, RangeInclusive<u32>)> {
+        sequence::detect_sequence(path.as_ref())
+    }
+
+    /// Read the properties of every image stored in a file without decoding pixels
+    ///
+    /// Describes the images returned by [`GalaxyImage::load_all_from_file`], in
+    /// the same order.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// for info in GalaxyImage::probe_all_file("render_passes.exr").unwrap() {
+    ///     println!("{}x{} {:?}", info.width, info.height, info.pixel_format);
+    /// }
+    /// ```
+    pub fn probe_all_file<P: AsRef<Path>>(path: P) -> ImageResult<Vec<ImageInfo>> {
+        let bytes = fs::read(&path)?;
+        let format = detect_file_format(path.as_ref(), &bytes);
+        Self::probe_all_from_bytes(&bytes, format)
+    }
+
+    /// Read the properties of every image stored in a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format
+    pub fn probe_all_from_bytes(bytes: &[u8], format: ImageFormat) -> ImageResult<Vec<ImageInfo>> {
+        match format {
+            ImageFormat::Exr => probe_exr_all(bytes),
+            ImageFormat::Dds => probe_dds_all(bytes),
+            ImageFormat::Gif => probe_gif(bytes).map(|info| vec![info; info.layer_count]),
+            ImageFormat::Ico => probe_ico_all(bytes),
+            _ => Self::probe_from_bytes(bytes, format).map(|info| vec![info]),
         }
     }
+
+    /// Estimate the quality a JPEG file was encoded with
+    ///
+    /// Compares the file's quantization tables with the standard tables scaled
+    /// for every libjpeg quality setting, without decoding pixels. Useful to
+    /// reject sources that were already heavily compressed before re-encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the JPEG file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let estimate = GalaxyImage::estimate_jpeg_quality_from_file("source.jpg")?;
+    /// if estimate.quality < 80 {
+    ///     println!("source.jpg is over-compressed (quality ~{})", estimate.quality);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn estimate_jpeg_quality_from_file<P: AsRef<Path>>(path: P) -> ImageResult<JpegQuality> {
+        let bytes = fs::read(&path)?;
+        Self::estimate_jpeg_quality_from_bytes(&bytes)
+    }
+
+    /// Estimate the quality a JPEG byte buffer was encoded with
+    ///
+    /// See [`GalaxyImage::estimate_jpeg_quality_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw JPEG file data
+    pub fn estimate_jpeg_quality_from_bytes(bytes: &[u8]) -> ImageResult<JpegQuality> {
+        jpeg_quality::estimate_jpeg_quality(bytes)
+    }
+
+    /// Generate a thumbnail of an image file
+    ///
+    /// The result fits in `max_dim` x `max_dim` pixels and keeps the aspect ratio.
+    /// Much faster than a full load followed by a resize: the header is probed
+    /// first, JPEG files are decoded at a reduced DCT scale, mipmapped EXR files
+    /// only decode the smallest sufficient level, and the final size is reached
+    /// with a fast box downscale. Images already fitting are returned as-is.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `max_dim` - Maximum width and height of the thumbnail
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let preview = GalaxyImage::thumbnail("photo.jpg", 128).unwrap();
+    /// assert!(preview.width() <= 128 && preview.height() <= 128);
+    /// ```
+    pub fn thumbnail<P: AsRef<Path>>(path: P, max_dim: u32) -> ImageResult<Image> {
+        let bytes = fs::read(&path)?;
+        let format = detect_file_format(path.as_ref(), &bytes);
+        thumbnail::thumbnail_from_bytes(&bytes, format, max_dim)
+    }
+
+    /// Generate a thumbnail from a byte buffer
+    ///
+    /// See [`GalaxyImage::thumbnail`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw image file data
+    /// * `format` - Image format (PNG, BMP, JPEG, EXR)
+    /// * `max_dim` - Maximum width and height of the thumbnail
+    pub fn thumbnail_from_bytes(
+        bytes: &[u8],
+        format: ImageFormat,
+        max_dim: u32,
+    ) -> ImageResult<Image> {
+        thumbnail::thumbnail_from_bytes(bytes, format, max_dim)
+    }
+
+    /// Lay out labeled images in a single grid image
+    ///
+    /// Cells are as large as the largest (downscaled) image, with the label
+    /// drawn under each image in a built-in 5x7 font (ASCII only, truncated to
+    /// the cell width). Float images are tone mapped and grayscale images
+    /// expanded, so any mix of images can share a sheet. Returns an RGBA/U8 image.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - Labels and images, in grid order
+    /// * `options` - Grid layout, downscaling and colors
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ContactSheetOptions, GalaxyImage, ImageFormat};
+    ///
+    /// let names = ["rock_albedo.png", "rock_normal.png", "rock_orm.png"];
+    /// let images = names
+    ///     .iter()
+    ///     .map(|name| GalaxyImage::load_from_file(name))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let options = ContactSheetOptions { columns: 3, max_cell_size: Some(128), ..Default::default() };
+    /// let sheet = GalaxyImage::contact_sheet(names.iter().zip(&images), &options);
+    /// GalaxyImage::save_to_file(&sheet, "report.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn contact_sheet<'a, L: AsRef<str>>(
+        images: impl IntoIterator<Item = (L, &'a Image)>,
+        options: &ContactSheetOptions,
+    ) -> Image {
+        let entries = images
+            .into_iter()
+            .map(|(label, image)| (label.as_ref().to_string(), image))
+            .collect();
+        contact_sheet::contact_sheet(entries, options)
+    }
+
+    /// Render the levels of a mip chain side by side in one annotated image
+    ///
+    /// Each level is labeled with its index and size. Levels can be tinted with
+    /// a distinct color each, magnified to the base size, and shown with their
+    /// alpha channel in a second row, to check filtering and alpha coverage
+    /// across the chain. Returns an RGBA/U8 image.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - Mip levels, base level first
+    /// * `options` - Tinting, alpha row, magnification and labels
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, MipPreviewOptions};
+    ///
+    /// let normals = GalaxyImage::load_from_file("rock_normal.png")?;
+    /// let orm = GalaxyImage::load_from_file("rock_orm.png")?;
+    /// let mips = orm.toksvig_roughness_mips(&normals, 1)?;
+    /// let options = MipPreviewOptions { magnify: true, ..Default::default() };
+    /// let preview = GalaxyImage::mip_chain_preview(&mips, &options);
+    /// GalaxyImage::save_to_file(&preview, "rock_orm_mips.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn mip_chain_preview(levels: &[Image], options: &MipPreviewOptions) -> Image {
+        mip_preview::mip_chain_preview(levels, options)
+    }
+
+    /// Pack sprites into texture atlas pages
+    ///
+    /// Sprites are placed with the max-rects algorithm (best short side fit),
+    /// largest first, and a new page is opened whenever a sprite does not fit
+    /// on the existing ones. Pages are trimmed to the area in use and take the
+    /// pixel format and component type of the first sprite; other layouts are
+    /// converted like [`Image::blit`]. The asset pipeline and the runtime can
+    /// rebuild the same layout from the same inputs: packing is deterministic.
+    ///
+    /// Returns an error if a sprite is empty or does not fit on a page with
+    /// its padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - Sprites, in the order of [`TextureAtlas::sprites`]
+    /// * `options` - Page size, padding, edge bleeding and power-of-two pages
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{AtlasOptions, GalaxyImage, ImageFormat};
+    ///
+    /// let names = ["button.png", "slider.png", "checkbox.png"];
+    /// let sprites = names
+    ///     .iter()
+    ///     .map(|name| GalaxyImage::load_from_file(name))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let options = AtlasOptions { max_page_size: 1024, ..Default::default() };
+    /// let atlas = GalaxyImage::pack_atlas(&sprites, &options)?;
+    /// for (name, sprite) in names.iter().zip(&atlas.sprites) {
+    ///     println!("{name}: page {} uv {:?}", sprite.page, sprite.uv);
+    /// }
+    /// GalaxyImage::save_to_file(&atlas.pages[0], "ui_atlas.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn pack_atlas<'a>(images: impl IntoIterator<Item = &'a Image>, options: &AtlasOptions) -> ImageResult<TextureAtlas> {
+        let images: Vec<&Image> = images.into_iter().collect();
+        atlas::pack(&images, options)
+    }
+
+    /// Save an image to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path` - Output file path
+    /// * `format` - Output format (PNG, BMP, JPEG)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// GalaxyImage::save_to_file(&image, "output.png", ImageFormat::Png).unwrap();
+    /// ```
+    pub fn save_to_file<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        format: ImageFormat,
+    ) -> ImageResult<()> {
+        Self::save_to_file_with_options(image, path, format, &SaveOptions::default())
+    }
+
+    /// Save an image to a file with JPEG quality parameter
+    ///
+    /// Shorthand for [`GalaxyImage::save_to_file_with_options`] with
+    /// [`SaveOptions::with_jpeg_quality`]; other formats ignore the quality.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path` - Output file path
+    /// * `format` - Output format (PNG, BMP, JPEG)
+    /// * `jpeg_quality` - JPEG quality (1-100, only used for JPEG format)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// GalaxyImage::save_to_file_with_quality(&image, "output.jpg", ImageFormat::Jpeg, 95).unwrap();
+    /// ```
+    pub fn save_to_file_with_quality<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        format: ImageFormat,
+        jpeg_quality: u8,
+    ) -> ImageResult<()> {
+        let options = SaveOptions::default().with_jpeg_quality(jpeg_quality);
+        Self::save_to_file_with_options(image, path, format, &options)
+    }
+
+    /// Save an image to a file with encoding options
+    ///
+    /// With `options.atomic` (the default) the file is written to a temporary
+    /// file next to the destination, flushed and renamed over it, so other
+    /// processes never see a partial file.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path` - Output file path
+    /// * `format` - Output format (PNG, BMP, JPEG, EXR)
+    /// * `options` - Encoding options
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, SaveOptions};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// let options = SaveOptions { deterministic: true, ..SaveOptions::default() };
+    /// GalaxyImage::save_to_file_with_options(&image, "output.png", ImageFormat::Png, &options).unwrap();
+    /// ```
+    pub fn save_to_file_with_options<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        format: ImageFormat,
+        options: &SaveOptions,
+    ) -> ImageResult<()> {
+        instrument::file("save", path.as_ref(), || {
+            let bytes = Self::save_to_bytes_with_options(image, format, options)?;
+            atomic_write::write_file(path.as_ref(), &bytes, options.atomic)?;
+            Ok(())
+        })
+    }
+
+    /// Save an image with the most appropriate format for its content
+    ///
+    /// HDR images (F16/F32) are saved as EXR, signed (I8/I16) images as TIFF,
+    /// 16-bit images and images with transparency as PNG, flat-color graphics
+    /// as PNG and opaque photos as JPEG.
+    /// The format extension is appended to `path_without_ext`.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `path_without_ext` - Output path without extension
+    /// * `constraints` - Lossless requirement, size limit and encoder options
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, SaveConstraints};
+    ///
+    /// let image = GalaxyImage::load_from_file("render.exr").unwrap();
+    /// let constraints = SaveConstraints { max_size_bytes: Some(512 * 1024), ..Default::default() };
+    /// let format = GalaxyImage::save_auto(&image, "out/render", &constraints).unwrap();
+    /// println!("saved as out/render.{}", format.extension());
+    /// ```
+    pub fn save_auto<P: AsRef<Path>>(
+        image: &Image,
+        path_without_ext: P,
+        constraints: &SaveConstraints,
+    ) -> ImageResult<ImageFormat> {
+        let (format, bytes) = auto_format::encode_auto(image, constraints)?;
+        let mut path = path_without_ext.as_ref().as_os_str().to_os_string();
+        path.push(".");
+        path.push(format.extension());
+        let path = Path::new(&path);
+        instrument::file("save", path, || {
+            atomic_write::write_file(path, &bytes, constraints.options.atomic).map_err(ImageError::from)
+        })?;
+        Ok(format)
+    }
+
+    /// Export an image to several files in one call
+    ///
+    /// Each target is written to `base_path` followed by its suffix and format
+    /// extension. Conversions shared by all targets (BGR to RGB) are done once,
+    /// then the targets are encoded and written in parallel.
+    ///
+    /// GPU container formats such as KTX2 are not supported yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to export
+    /// * `base_path` - Output path without extension
+    /// * `targets` - Formats, suffixes and options of the outputs
+    ///
+    /// Returns the written paths in target order. If a target fails, the first
+    /// error in target order is returned; other targets may still have been written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ExportTarget, GalaxyImage, ImageFormat, SaveOptions};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// let preview = SaveOptions::new().with_jpeg_quality(75);
+    /// let paths = GalaxyImage::export(&image, "textures/rock", &[
+    ///     ExportTarget::new(ImageFormat::Png),
+    ///     ExportTarget::new(ImageFormat::Jpeg).with_suffix("_preview").with_options(preview),
+    /// ]).unwrap();
+    /// // textures/rock.png, textures/rock_preview.jpg
+    /// ```
+    pub fn export<P: AsRef<Path>>(
+        image: &Image,
+        base_path: P,
+        targets: &[ExportTarget],
+    ) -> ImageResult<Vec<PathBuf>> {
+        export::export(image, base_path.as_ref(), targets)
+    }
+
+    /// Convert every image of a directory tree
+    ///
+    /// Walks `src_dir` (recursively unless `job.recursive` is false), keeps the
+    /// files matching `job.extensions`, then loads, processes and saves them in
+    /// parallel. Outputs mirror the input tree under `dst_dir`, with the
+    /// extension of the output format; missing directories are created. A
+    /// `dst_dir` inside `src_dir` is not walked.
+    ///
+    /// A file that fails does not stop the others: the report holds the result
+    /// of every file in path order. An error is only returned if the job is
+    /// invalid or a directory cannot be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_dir` - Input directory
+    /// * `dst_dir` - Output directory
+    /// * `job` - Extension filter, load options, operations, output format and save options
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ConvertJob, GalaxyImage, ImageFormat, ResizeFilter};
+    ///
+    /// let job = ConvertJob::new(ImageFormat::Png)
+    ///     .with_extensions(["jpg", "bmp"])
+    ///     .with_operation(|image| {
+    ///         *image = image.resize(image.width() / 2, image.height() / 2, ResizeFilter::Area);
+    ///         Ok(())
+    ///     });
+    /// let report = GalaxyImage::convert_directory("raw/textures", "cooked/textures", &job)?;
+    /// for file in report.failed() {
+    ///     eprintln!("{}: {:?}", file.source.display(), file.result);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert_directory<P: AsRef<Path>, Q: AsRef<Path>>(
+        src_dir: P,
+        dst_dir: Q,
+        job: &ConvertJob,
+    ) -> ImageResult<ConvertReport> {
+        batch::convert_directory(src_dir.as_ref(), dst_dir.as_ref(), job)
+    }
+
+    /// Build a multi-resolution tile pyramid
+    ///
+    /// Level 0 is the full-resolution image, each next level halves the size
+    /// (rounding up) until a level fits in a single tile. Every tile is
+    /// `tile_size + 2 * overlap` pixels square; pixels outside the level are
+    /// filled with the nearest edge pixel. Tiles are passed to `callback` level by
+    /// level, in row-major order, so only one level is held in memory at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Source image
+    /// * `options` - Tile size and overlap (format options are ignored)
+    /// * `callback` - Receives each tile; returning an error stops the build
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, PyramidOptions};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(4096, 4096, galaxy_image::PixelFormat::RGBA, galaxy_image::ComponentType::U8);
+    ///
+    /// let options = PyramidOptions { tile_size: 128, overlap: 4, ..PyramidOptions::default() };
+    /// GalaxyImage::build_pyramid(&image, &options, |tile| {
+    ///     println!("level {} tile {},{}", tile.coord.level, tile.coord.x, tile.coord.y);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn build_pyramid<F>(image: &Image, options: &PyramidOptions, callback: F) -> ImageResult<()>
+    where
+        F: FnMut(PyramidTile) -> ImageResult<()>,
+    {
+        pyramid::build_pyramid(image, options, callback)
+    }
+
+    /// Build a tile pyramid and write each tile to its own file
+    ///
+    /// See [`GalaxyImage::build_pyramid`] for the tiling scheme. Tiles are encoded
+    /// with `options.format` and `options.save_options`. Parent directories are
+    /// created as needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Source image
+    /// * `options` - Tile size, overlap and encoding
+    /// * `naming` - Returns the output path of a tile
+    ///
+    /// Returns the written paths.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, PyramidOptions};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(4096, 4096, galaxy_image::PixelFormat::RGBA, galaxy_image::ComponentType::U8);
+    ///
+    /// GalaxyImage::export_pyramid(&image, &PyramidOptions::default(), |coord| {
+    ///     format!("vt/terrain/{}/{}_{}.png", coord.level, coord.x, coord.y).into()
+    /// }).unwrap();
+    /// ```
+    pub fn export_pyramid<F>(image: &Image, options: &PyramidOptions, naming: F) -> ImageResult<Vec<PathBuf>>
+    where
+        F: FnMut(TileCoord) -> PathBuf,
+    {
+        pyramid::export_pyramid(image, options, naming)
+    }
+
+    /// Build a tile pyramid and write it as a single packed file
+    ///
+    /// See [`GalaxyImage::build_pyramid`] for the tiling scheme. All integers are
+    /// little-endian:
+    ///
+    /// * Header: magic `GXPY`, then u32 version, width, height, tile size,
+    ///   overlap, tile format, level count and tile count
+    /// * Index, one entry per tile: u32 level, x, y, then u64 offset from the
+    ///   start of the file and u64 byte length
+    /// * Encoded tile data
+    ///
+    /// Tile format ids: 0 PNG, 1 BMP, 2 JPEG, 3 EXR, 4 TGA, 5 DDS, 6 HDR,
+    /// 7 TIFF, 8 WebP, 9 GIF, 10 ICO, 11 PNM, 12 PFM, 13 JPEG XL, 14 AVIF,
+    /// 15 PSD. Like per-tile exports, the file is written through a temporary
+    /// file unless [`SaveOptions::atomic`] is turned off in `save_options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Source image
+    /// * `path` - Output file path
+    /// * `options` - Tile size, overlap and encoding
+    pub fn export_pyramid_packed<P: AsRef<Path>>(
+        image: &Image,
+        path: P,
+        options: &PyramidOptions,
+    ) -> ImageResult<()> {
+        pyramid::export_pyramid_packed(image, path.as_ref(), options)
+    }
+
+    /// Save an image to a byte buffer with a JPEG quality
+    ///
+    /// Shorthand for [`GalaxyImage::save_to_bytes_with_options`] with
+    /// [`SaveOptions::with_jpeg_quality`]; other formats ignore the quality.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `format` - Output format (PNG, BMP, JPEG)
+    /// * `jpeg_quality` - JPEG quality (1-100, only used for JPEG format)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// let bytes = GalaxyImage::save_to_bytes(&image, ImageFormat::Jpeg, 85).unwrap();
+    /// ```
+    pub fn save_to_bytes(
+        image: &Image,
+        format: ImageFormat,
+        jpeg_quality: u8,
+    ) -> ImageResult<Vec<u8>> {
+        let options = SaveOptions::default().with_jpeg_quality(jpeg_quality);
+        Self::save_to_bytes_with_options(image, format, &options)
+    }
+
+    /// Save an image to a byte buffer with encoding options
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to save
+    /// * `format` - Output format (PNG, BMP, JPEG, EXR)
+    /// * `options` - Encoding options
+    ///
+    /// Images with a component type the format cannot store are converted to U8
+    /// according to `options.ldr_conversion` (an error by default).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, LdrConversion, SaveOptions};
+    /// # use galaxy_image::Image;
+    /// # let image = Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// // Default settings
+    /// let png = GalaxyImage::save_to_bytes_with_options(&image, ImageFormat::Png, &SaveOptions::new()).unwrap();
+    ///
+    /// // Byte-identical output across machines and runs
+    /// let options = SaveOptions { deterministic: true, ..SaveOptions::default() };
+    /// let bytes = GalaxyImage::save_to_bytes_with_options(&image, ImageFormat::Exr, &options).unwrap();
+    ///
+    /// // JPEG preview of an HDR image
+    /// let options = SaveOptions::new().with_ldr_conversion(LdrConversion::Tonemap).with_jpeg_quality(80);
+    /// let preview = GalaxyImage::save_to_bytes_with_options(&image, ImageFormat::Jpeg, &options).unwrap();
+    /// ```
+    pub fn save_to_bytes_with_options(
+        image: &Image,
+        format: ImageFormat,
+        options: &SaveOptions,
+    ) -> ImageResult<Vec<u8>> {
+        // Typical compressed sizes, to pick a pooled buffer that rarely grows
+        let capacity = match format {
+            ImageFormat::Png => image.size_bytes() / 2,
+            ImageFormat::Bmp => image.width() as usize * image.height() as usize * 4 + 122,
+            ImageFormat::Tga => image.width() as usize * image.height() as usize * image.pixel_format().channel_count() + 44,
+            ImageFormat::Jpeg => image.size_bytes() / 8,
+            ImageFormat::Hdr => image.width() as usize * image.height() as usize * 4 + 64,
+            ImageFormat::Tiff => image.size_bytes() / 2,
+            ImageFormat::Pnm => image.width() as usize * image.height() as usize * 3 * image.component_type().size_bytes() + 32,
+            ImageFormat::Pfm => image.width() as usize * image.height() as usize * 12 + 32,
+            ImageFormat::Exr | ImageFormat::Dds | ImageFormat::WebP | ImageFormat::Gif | ImageFormat::Ico | ImageFormat::Jxl | ImageFormat::Avif | ImageFormat::Psd | ImageFormat::Unknown => 0,
+        };
+        let mut buffer = buffer_pool::take(capacity);
+        Self::save_to_bytes_into(&mut buffer, image, format, options)?;
+        Ok(buffer)
+    }
+
+    /// Save an image into an existing byte buffer, reusing its capacity
+    ///
+    /// The buffer is cleared and then holds the encoded file. Encoding many
+    /// images through the same buffer (e.g. frame captures) avoids an
+    /// allocation per image once the buffer has grown to the largest output.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Buffer receiving the encoded file
+    /// * `image` - Image to save
+    /// * `format` - Output format (PNG, BMP, JPEG, EXR)
+    /// * `options` - Encoding options, see [`GalaxyImage::save_to_bytes_with_options`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, SaveOptions};
+    /// # let frames: Vec<galaxy_image::Image> = Vec::new();
+    ///
+    /// let options = SaveOptions::new().with_jpeg_quality(85);
+    /// let mut buffer = Vec::new();
+    /// for (index, frame) in frames.iter().enumerate() {
+    ///     GalaxyImage::save_to_bytes_into(&mut buffer, frame, ImageFormat::Jpeg, &options)?;
+    ///     std::fs::write(format!("capture/{:05}.jpg", index), &buffer)?;
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn save_to_bytes_into(
+        buffer: &mut Vec<u8>,
+        image: &Image,
+        format: ImageFormat,
+        options: &SaveOptions,
+    ) -> ImageResult<()> {
+        if let Some(converted) = Self::ldr_converted(image, format, options) {
+            return Self::save_to_bytes_into(buffer, &converted, format, options);
+        }
+
+        buffer.clear();
+        instrument::save(image, format, buffer, |buffer| match format {
+            ImageFormat::Png => save_png(image, options, buffer),
+            ImageFormat::Bmp => save_bmp(image, options, buffer),
+            ImageFormat::Jpeg => save_jpeg(image, options, buffer),
+            ImageFormat::Exr => save_exr(image, options, buffer),
+            ImageFormat::Tga => save_tga(image, options, buffer),
+            ImageFormat::Dds => {
+                Err(ImageError::UnsupportedFormat("DDS encoding is not supported".to_string()))
+            }
+            ImageFormat::Hdr => save_hdr(image, buffer),
+            ImageFormat::Tiff => save_tiff(image, options, buffer),
+            ImageFormat::WebP => {
+                Err(ImageError::UnsupportedFormat("WebP encoding is not supported".to_string()))
+            }
+            ImageFormat::Gif => {
+                Err(ImageError::UnsupportedFormat("GIF encoding is not supported".to_string()))
+            }
+            ImageFormat::Ico => {
+                Err(ImageError::UnsupportedFormat("ICO encoding is not supported".to_string()))
+            }
+            ImageFormat::Pnm => save_pnm(image, options, buffer),
+            ImageFormat::Pfm => save_pfm(image, buffer),
+            ImageFormat::Jxl => {
+                Err(ImageError::UnsupportedFormat("JPEG XL encoding is not supported".to_string()))
+            }
+            ImageFormat::Avif => {
+                Err(ImageError::UnsupportedFormat("AVIF encoding is not supported".to_string()))
+            }
+            ImageFormat::Psd => {
+                Err(ImageError::UnsupportedFormat("PSD encoding is not supported".to_string()))
+            }
+            ImageFormat::Unknown => {
+                Err(ImageError::UnsupportedFormat("Unknown format".to_string()))
+            }
+        })
+    }
+
+    /// Convert to U8 first if the format cannot store the component type
+    fn ldr_converted(image: &Image, format: ImageFormat, options: &SaveOptions) -> Option<Image> {
+        (options.ldr_conversion != LdrConversion::Error
+            && image.component_type() != ComponentType::U8
+            && matches!(format, ImageFormat::Png | ImageFormat::Bmp | ImageFormat::Jpeg | ImageFormat::Tga | ImageFormat::Pnm)
+            && !ldr::format_supports(format, image.component_type()))
+        .then(|| ldr::to_ldr(image, options.ldr_conversion, options.ldr_tonemap))
+    }
+
+    /// Save an image to a writer
+    ///
+    /// PNG and JPEG are encoded straight into the writer as rows are
+    /// compressed, so wrap files and sockets in a [`std::io::BufWriter`].
+    /// Other formats are encoded in a buffer from the manager buffer pool (see
+    /// [`GalaxyImage::enable_buffer_pool`]) and written in one call.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination (socket, archive entry, ...)
+    /// * `image` - Image to save
+    /// * `format` - Output format (PNG, BMP, JPEG, EXR)
+    /// * `options` - Encoding options, see [`GalaxyImage::save_to_bytes_with_options`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, SaveOptions};
+    /// # let image = galaxy_image::Image::new(100, 100, galaxy_image::PixelFormat::RGB, galaxy_image::ComponentType::U8);
+    ///
+    /// let mut file = std::io::BufWriter::new(std::fs::File::create("frame.png")?);
+    /// GalaxyImage::save_to_writer(&mut file, &image, ImageFormat::Png, &SaveOptions::default())?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn save_to_writer(
+        mut writer: impl Write,
+        image: &Image,
+        format: ImageFormat,
+        options: &SaveOptions,
+    ) -> ImageResult<()> {
+        if matches!(format, ImageFormat::Png | ImageFormat::Jpeg) {
+            let converted = Self::ldr_converted(image, format, options);
+            let image = converted.as_ref().unwrap_or(image);
+            return instrument::save_streamed(image, format, || match format {
+                ImageFormat::Png => save_png(image, options, &mut writer),
+                _ => save_jpeg(image, options, &mut writer),
+            });
+        }
+
+        let buffer = Self::save_to_bytes_with_options(image, format, options)?;
+        let result = writer.write_all(&buffer);
+        buffer_pool::recycle(buffer);
+        Ok(result?)
+    }
+
+    /// Estimate the size of an image encoded with options, without a full encode
+    ///
+    /// Exact for BMP, uncompressed TGA and images up to 512 rows, which are
+    /// encoded. Taller images in compressed formats encode evenly spaced bands
+    /// of 256 rows in total and extrapolate, typically within a few percent for
+    /// photographic and rendered content at a fraction of the encoding time.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to estimate
+    /// * `format` - Target format
+    /// * `options` - Encoding options, as passed to [`GalaxyImage::save_to_bytes_with_options`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, SaveOptions};
+    ///
+    /// let image = GalaxyImage::load_from_file("albedo.png")?;
+    /// let size = GalaxyImage::estimate_encoded_size(&image, ImageFormat::Jpeg, &SaveOptions::default())?;
+    /// println!("~{} KiB as JPEG", size / 1024);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn estimate_encoded_size(image: &Image, format: ImageFormat, options: &SaveOptions) -> ImageResult<usize> {
+        size_estimate::estimate_encoded_size(image, format, options)
+    }
+
+    /// Load a bit mask from a file
+    ///
+    /// 1-bit PNG and PBM files are read without expansion. Other images are
+    /// decoded and their first channel thresholded at 0.5, set pixels being
+    /// the bright ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the mask file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let walkable = GalaxyImage::load_mask_from_file("levels/walkable.png")?;
+    /// println!("{} walkable cells", walkable.count_set());
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn load_mask_from_file<P: AsRef<Path>>(path: P) -> ImageResult<BitMask> {
+        instrument::file("load", path.as_ref(), || {
+            let bytes = fs::read(&path)?;
+            Self::load_mask_from_bytes(&bytes)
+        })
+    }
+
+    /// Load a bit mask from a byte buffer
+    ///
+    /// The format is detected from magic bytes, see
+    /// [`GalaxyImage::load_mask_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Raw file data
+    pub fn load_mask_from_bytes(bytes: &[u8]) -> ImageResult<BitMask> {
+        if matches!(bytes.get(..2), Some(b"P1" | b"P4")) {
+            return load_pbm_mask(bytes);
+        }
+        match ImageFormat::detect_from_bytes(bytes) {
+            ImageFormat::Png => load_png_mask(bytes),
+            format => BitMask::from_channel(&Self::load_from_bytes(bytes, format)?, 0, 0.5),
+        }
+    }
+
+    /// Save a bit mask to a file
+    ///
+    /// `.pbm` paths are written as binary PBM, other paths as 1-bit PNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Mask to save
+    /// * `path` - Output file path
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{BitMask, GalaxyImage};
+    ///
+    /// let mut mask = BitMask::new(64, 64);
+    /// mask.set(10, 20, true);
+    /// GalaxyImage::save_mask_to_file(&mask, "collision.png")?;
+    /// GalaxyImage::save_mask_to_file(&mask, "collision.pbm")?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn save_mask_to_file<P: AsRef<Path>>(mask: &BitMask, path: P) -> ImageResult<()> {
+        let options = SaveOptions::default();
+        instrument::file("save", path.as_ref(), || {
+            let bytes = Self::save_mask_to_bytes(mask, MaskFormat::from_path(path.as_ref()), &options)?;
+            atomic_write::write_file(path.as_ref(), &bytes, options.atomic)?;
+            Ok(())
+        })
+    }
+
+    /// Save a bit mask to a byte buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Mask to save
+    /// * `format` - Output format
+    /// * `options` - Encoding options (only `png` compression and filter apply, to PNG)
+    pub fn save_mask_to_bytes(mask: &BitMask, format: MaskFormat, options: &SaveOptions) -> ImageResult<Vec<u8>> {
+        match format {
+            MaskFormat::Png => save_png_mask(mask, options),
+            MaskFormat::Pbm => Ok(save_pbm_mask(mask)),
+        }
+    }
+
+    /// Queue an image file for decoding on the manager worker pool
+    ///
+    /// Returns immediately; the file is read and decoded on a background worker.
+    /// The pool is started on first use with one worker per available CPU core,
+    /// see [`GalaxyImage::set_decode_threads`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let pending: Vec<_> = ["a.png", "b.jpg", "c.exr"]
+    ///     .iter()
+    ///     .map(|path| GalaxyImage::load_async_pooled(path))
+    ///     .collect();
+    ///
+    /// for future in pending {
+    ///     let image = future.wait().unwrap();
+    ///     println!("{}x{}", image.width(), image.height());
+    /// }
+    /// ```
+    pub fn load_async_pooled<P: AsRef<Path>>(path: P) -> ImageFuture {
+        pool::load_async_pooled(path.as_ref())
+    }
+
+    /// Load several image files in parallel on the decode pool
+    ///
+    /// Every file is queued on the worker pool (see
+    /// [`GalaxyImage::set_decode_threads`]) and the call blocks until all are
+    /// decoded. Results are in the order of `paths`, and a file that fails to
+    /// load does not affect the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Paths of the image files
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let paths = ["rock_albedo.png", "rock_normal.png", "rock_orm.png"];
+    /// for (path, result) in paths.iter().zip(GalaxyImage::load_many(&paths)) {
+    ///     match result {
+    ///         Ok(image) => println!("{}: {}x{}", path, image.width(), image.height()),
+    ///         Err(error) => eprintln!("{}: {}", path, error),
+    ///     }
+    /// }
+    /// ```
+    pub fn load_many<P: AsRef<Path>>(paths: &[P]) -> Vec<ImageResult<Image>> {
+        let pending: Vec<ImageFuture> = paths.iter().map(|path| pool::load_async_pooled(path.as_ref())).collect();
+        pending.into_iter().map(ImageFuture::wait).collect()
+    }
+
+    /// Set the number of worker threads of the decode pool
+    ///
+    /// Replaces the pool; decodes already queued still complete on the previous
+    /// workers.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Number of worker threads (at least 1)
+    pub fn set_decode_threads(count: usize) {
+        pool::set_decode_threads(count);
+    }
+
+    /// Get the number of worker threads of the decode pool
+    pub fn decode_threads() -> usize {
+        pool::decode_threads()
+    }
+
+    /// Load an image file as a lazily decoded [`EncodedImage`]
+    ///
+    /// Only the file header is parsed; pixels are decoded on first access.
+    /// Format is detected the same way as [`GalaxyImage::load_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let encoded = GalaxyImage::load_encoded("thumbnails/0001.jpg").unwrap();
+    /// println!("{}x{}, {} bytes", encoded.width(), encoded.height(), encoded.encoded_size());
+    ///
+    /// let pixels = encoded.image().unwrap(); // Decoded here
+    /// encoded.evict(); // Release the decoded pixels
+    /// ```
+    pub fn load_encoded<P: AsRef<Path>>(path: P) -> ImageResult<EncodedImage> {
+        let bytes = fs::read(&path)?;
+        let format = detect_file_format(path.as_ref(), &bytes);
+        EncodedImage::from_bytes_with_format(bytes, format)
+    }
+
+    /// Load an image through the manager cache
+    ///
+    /// Decoded images are kept in a shared LRU cache keyed by path and modification
+    /// time, so repeated loads of the same unchanged file return the same image
+    /// without decoding it again. The cache budget defaults to
+    /// [`DEFAULT_CACHE_BUDGET`](crate::DEFAULT_CACHE_BUDGET) bytes of pixel data.
+    /// Requires the `cache` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let first = GalaxyImage::load_cached("shared/rock.png").unwrap();
+    /// let second = GalaxyImage::load_cached("shared/rock.png").unwrap(); // No decode
+    /// assert!(std::sync::Arc::ptr_eq(&first, &second));
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn load_cached<P: AsRef<Path>>(path: P) -> ImageResult<Arc<Image>> {
+        cache::load_cached(path.as_ref())
+    }
+
+    /// Set the byte budget of the manager cache
+    ///
+    /// Least recently used images are evicted until the cache fits the new budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget_bytes` - Maximum number of pixel bytes kept in the cache
+    #[cfg(feature = "cache")]
+    pub fn set_cache_budget(budget_bytes: usize) {
+        cache::set_cache_budget(budget_bytes);
+    }
+
+    /// Remove all images from the manager cache
+    #[cfg(feature = "cache")]
+    pub fn clear_cache() {
+        cache::clear_cache();
+    }
+
+    /// Enable the manager buffer pool
+    ///
+    /// Once enabled, loaders and encoders take their pixel, row and output
+    /// buffers from the pool, and buffers handed back with
+    /// [`GalaxyImage::recycle_buffer`] or [`GalaxyImage::recycle_image`] are
+    /// reused instead of freed. This cuts allocations in loops that encode or
+    /// decode many images of similar size. Calling it again changes the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - Maximum total capacity kept in the pool
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat, SaveOptions};
+    /// # let frames: Vec<galaxy_image::Image> = Vec::new();
+    ///
+    /// GalaxyImage::enable_buffer_pool(64 * 1024 * 1024);
+    /// let options = SaveOptions::new();
+    /// for (index, frame) in frames.iter().enumerate() {
+    ///     let png = GalaxyImage::save_to_bytes_with_options(frame, ImageFormat::Png, &options)?;
+    ///     std::fs::write(format!("capture/{:05}.png", index), &png)?;
+    ///     GalaxyImage::recycle_buffer(png); // Reused by the next encode
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn enable_buffer_pool(max_bytes: usize) {
+        buffer_pool::enable(max_bytes);
+    }
+
+    /// Disable the manager buffer pool and free the pooled buffers
+    pub fn disable_buffer_pool() {
+        buffer_pool::disable();
+    }
+
+    /// Hand a buffer back to the manager buffer pool
+    ///
+    /// Typically the encoded bytes returned by a save call once they are written.
+    /// The buffer is simply dropped if the pool is disabled.
+    pub fn recycle_buffer(buffer: Vec<u8>) {
+        buffer_pool::recycle(buffer);
+    }
+
+    /// Hand the pixel buffer of an image back to the manager buffer pool
+    ///
+    /// The buffer is only recycled if no other image shares it.
+    pub fn recycle_image(image: Image) {
+        if !image.is_data_shared() {
+            buffer_pool::recycle(image.into_data());
+        }
+    }
+
+    /// Get the usage counters of the manager buffer pool, or `None` if it is disabled
+    pub fn buffer_pool_stats() -> Option<BufferPoolStats> {
+        buffer_pool::stats()
+    }
+
+    /// Resolve an image to a shared instance by content
+    ///
+    /// Identical images (same dimensions, formats and pixel data) passed to this
+    /// method resolve to a single in-memory image, even if they were imported under
+    /// different names.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image to deduplicate
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let a = GalaxyImage::deduplicate(GalaxyImage::load_from_file("rock.png").unwrap());
+    /// let b = GalaxyImage::deduplicate(GalaxyImage::load_from_file("rock_copy.png").unwrap());
+    /// assert!(std::sync::Arc::ptr_eq(&a, &b));
+    /// ```
+    pub fn deduplicate(image: Image) -> Arc<Image> {
+        dedup::deduplicate(image)
+    }
+
+    /// Load an image from a file and resolve it to a shared instance by content
+    ///
+    /// Equivalent to [`GalaxyImage::load_from_file`] followed by
+    /// [`GalaxyImage::deduplicate`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    pub fn load_deduplicated<P: AsRef<Path>>(path: P) -> ImageResult<Arc<Image>> {
+        Ok(Self::deduplicate(Self::load_from_file(path)?))
+    }
+
+    /// Watch an image file and reload it on change
+    ///
+    /// The file is polled with default [`WatchOptions`]. When a change is detected and
+    /// the file stays stable for the debounce duration, it is decoded on a background
+    /// thread and the result is passed to `callback`. Reloads whose pixel content is
+    /// unchanged (same [`Image::checksum`]) are skipped. Watching stops when the returned
+    /// [`ImageWatcher`] is dropped.
+    ///
+    /// Requires the `watch` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `callback` - Called with each reloaded image (or the decoding error)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let watcher = GalaxyImage::watch("texture.png", |result| match result {
+    ///     Ok(image) => println!("Reloaded {}x{} image", image.width(), image.height()),
+    ///     Err(e) => eprintln!("Reload failed: {}", e),
+    /// }).unwrap();
+    /// // ... keep `watcher` alive while editing ...
+    /// ```
+    #[cfg(feature = "watch")]
+    pub fn watch<P, F>(path: P, callback: F) -> ImageResult<ImageWatcher>
+    where
+        P: AsRef<Path>,
+        F: FnMut(ImageResult<Image>) + Send + 'static,
+    {
+        spawn_watcher(path.as_ref(), WatchOptions::default(), callback)
+    }
+
+    /// Watch an image file with custom polling and debounce settings
+    ///
+    /// See [`GalaxyImage::watch`]. Requires the `watch` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the image file
+    /// * `options` - Poll interval and debounce duration
+    /// * `callback` - Called with each reloaded image (or the decoding error)
+    #[cfg(feature = "watch")]
+    pub fn watch_with_options<P, F>(
+        path: P,
+        options: WatchOptions,
+        callback: F,
+    ) -> ImageResult<ImageWatcher>
+    where
+        P: AsRef<Path>,
+        F: FnMut(ImageResult<Image>) + Send + 'static,
+    {
+        spawn_watcher(path.as_ref(), options, callback)
+    }
+}
+
+/// Detect the format of a file from its content, falling back to its extension
+pub(crate) fn detect_file_format(path: &Path, bytes: &[u8]) -> ImageFormat {
+    // Detect format from magic bytes
+    let format = ImageFormat::detect_from_bytes(bytes);
+    if format != ImageFormat::Unknown {
+        return format;
+    }
+
+    // Fallback to extension if magic bytes didn't work
+    ImageFormat::from_path(path)
 }
@@ -0,0 +1,257 @@
+/// Preparation of images for GPU texture upload
+use crate::color::ColorSpace;
+use crate::mipmap::{alpha_coverage, mip_count, next_mip, next_mip_size, preserve_alpha_coverage};
+use crate::{ComponentType, Image, PixelFormat};
+
+/// Layout requirements of a GPU texture upload
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuRequirements {
+    /// Expand RGB/BGR to RGBA/BGRA with opaque alpha (default: true)
+    ///
+    /// Most GPU APIs have no 3-channel texture formats.
+    pub expand_rgb: bool,
+
+    /// Store rows bottom-to-top (default: false)
+    pub flip_y: bool,
+
+    /// Alignment in bytes of every row (default: 1)
+    ///
+    /// D3D12 and WebGPU buffer-to-texture copies require 256.
+    pub row_alignment: usize,
+
+    /// Alignment in bytes of every mip level offset (default: 1)
+    pub offset_alignment: usize,
+
+    /// Generate a full mip chain down to 1x1 (default: false)
+    pub generate_mips: bool,
+
+    /// Alpha-test threshold whose coverage generated mips preserve (default: None)
+    ///
+    /// Averaged alpha drops below the threshold, so alpha-tested foliage and
+    /// fences thin out and vanish in the distance. With a threshold, the alpha
+    /// of every mip is rescaled so the same fraction of texels passes the test
+    /// as in the base level. Ignored for images without alpha.
+    pub alpha_test_threshold: Option<f32>,
+}
+
+impl Default for GpuRequirements {
+    fn default() -> Self {
+        Self {
+            expand_rgb: true,
+            flip_y: false,
+            row_alignment: 1,
+            offset_alignment: 1,
+            generate_mips: false,
+            alpha_test_threshold: None,
+        }
+    }
+}
+
+/// Placement of one mip level in a GPU upload buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GpuMipLevel {
+    /// Width in pixels
+    pub width: u32,
+
+    /// Height in pixels
+    pub height: u32,
+
+    /// Byte offset of the level in the buffer
+    pub offset: usize,
+
+    /// Bytes between the starts of consecutive rows, including padding
+    pub row_pitch: usize,
+
+    /// Size of the level in bytes (`row_pitch * height`)
+    pub size: usize,
+}
+
+/// Description of the texture stored in a GPU upload buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuImageDesc {
+    /// Width of the base level in pixels
+    pub width: u32,
+
+    /// Height of the base level in pixels
+    pub height: u32,
+
+    /// Pixel format after RGB expansion
+    pub pixel_format: PixelFormat,
+
+    /// Component type
+    pub component_type: ComponentType,
+
+    /// Color space tag (pick the `_SRGB` texture format for `ColorSpace::Srgb`)
+    pub color_space: ColorSpace,
+
+    /// Bytes per pixel after RGB expansion
+    pub bytes_per_pixel: usize,
+
+    /// Whether rows are stored bottom-to-top
+    pub flipped_y: bool,
+
+    /// Mip levels, base level first
+    pub levels: Vec<GpuMipLevel>,
+}
+
+/// Pixel data laid out for a GPU upload, with its description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuUpload {
+    /// Bytes of all mip levels
+    pub data: Vec<u8>,
+
+    /// Layout of the bytes
+    pub desc: GpuImageDesc,
+}
+
+/// Round a value up to a multiple of an alignment
+fn align_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+/// Encoded value of an opaque alpha component
+fn opaque_alpha(component_type: ComponentType) -> Vec<u8> {
+    match component_type {
+        ComponentType::U8 => vec![u8::MAX],
+        ComponentType::U16 => u16::MAX.to_le_bytes().to_vec(),
+        ComponentType::F16 => half::f16::ONE.to_le_bytes().to_vec(),
+        ComponentType::F32 => 1f32.to_le_bytes().to_vec(),
+        ComponentType::I8 => vec![i8::MAX as u8],
+        ComponentType::I16 => i16::MAX.to_le_bytes().to_vec(),
+    }
+}
+
+/// Lay out an image and its mip levels for a GPU upload
+pub(crate) fn prepare_for_gpu(image: &Image, requirements: &GpuRequirements) -> GpuUpload {
+    let component_type = image.component_type();
+    let expand = requirements.expand_rgb
+        && matches!(image.pixel_format(), PixelFormat::RGB | PixelFormat::BGR);
+    let pixel_format = match image.pixel_format() {
+        PixelFormat::RGB if expand => PixelFormat::RGBA,
+        PixelFormat::BGR if expand => PixelFormat::BGRA,
+        pixel_format => pixel_format,
+    };
+    let color_space = image.color_space();
+    let bytes_per_pixel = pixel_format.channel_count() * component_type.size_bytes();
+    let row_alignment = requirements.row_alignment.max(1);
+    let offset_alignment = requirements.offset_alignment.max(1);
+
+    let level_count = if requirements.generate_mips {
+        mip_count(image.width(), image.height())
+    } else {
+        1
+    };
+
+    // Compute the layout first so the buffer is allocated once
+    let mut levels = Vec::with_capacity(level_count);
+    let mut offset = 0;
+    let (mut width, mut height) = (image.width(), image.height());
+    for _ in 0..level_count {
+        offset = align_up(offset, offset_alignment);
+        let row_pitch = align_up(width as usize * bytes_per_pixel, row_alignment);
+        let size = row_pitch * height as usize;
+        levels.push(GpuMipLevel { width, height, offset, row_pitch, size });
+        offset += size;
+        (width, height) = next_mip_size(width, height);
+    }
+
+    let mut data = vec![0u8; offset];
+    let alpha = opaque_alpha(component_type);
+    let coverage = requirements
+        .alpha_test_threshold
+        .map(|threshold| (threshold, alpha_coverage(image, threshold)));
+    let mut level_image = image.clone();
+    for (index, level) in levels.iter().enumerate() {
+        if index > 0 {
+            level_image = next_mip(&level_image, color_space);
+            if let Some((threshold, coverage)) = coverage {
+                preserve_alpha_coverage(&mut level_image, threshold, coverage);
+            }
+        }
+        write_level(&level_image, level, requirements.flip_y, expand, &alpha, &mut data);
+    }
+
+    GpuUpload {
+        data,
+        desc: GpuImageDesc {
+            width: image.width(),
+            height: image.height(),
+            pixel_format,
+            component_type,
+            color_space,
+            bytes_per_pixel,
+            flipped_y: requirements.flip_y,
+            levels,
+        },
+    }
+}
+
+/// Copy one level into the upload buffer, flipping and expanding rows in one pass
+fn write_level(
+    image: &Image,
+    level: &GpuMipLevel,
+    flip_y: bool,
+    expand: bool,
+    alpha: &[u8],
+    data: &mut [u8],
+) {
+    let source_row_bytes = image.width() as usize * image.bytes_per_pixel();
+    let source_pixel_bytes = image.bytes_per_pixel();
+    let height = image.height() as usize;
+    if source_row_bytes == 0 {
+        return;
+    }
+
+    let level_data = &mut data[level.offset..level.offset + level.size];
+    for (y, source_row) in image.data().chunks_exact(source_row_bytes).enumerate() {
+        let target_y = if flip_y { height - 1 - y } else { y };
+        let target_row = &mut level_data[target_y * level.row_pitch..][..level.row_pitch];
+
+        if expand {
+            let target_pixel_bytes = source_pixel_bytes + alpha.len();
+            for (source, target) in source_row
+                .chunks_exact(source_pixel_bytes)
+                .zip(target_row.chunks_exact_mut(target_pixel_bytes))
+            {
+                target[..source_pixel_bytes].copy_from_slice(source);
+                target[source_pixel_bytes..].copy_from_slice(alpha);
+            }
+        } else {
+            target_row[..source_row_bytes].copy_from_slice(source_row);
+        }
+    }
+}
+
+impl Image {
+    /// Lay out the image for a GPU texture upload
+    ///
+    /// Performs RGB to RGBA expansion, Y-flip, row pitch padding and optional
+    /// mip generation, and returns the final bytes with a descriptor giving the
+    /// offset and row pitch of every level. The descriptor carries the
+    /// [`Image::color_space`] tag, and mips of sRGB, PQ and HLG images are
+    /// averaged in linear space.
+    ///
+    /// # Arguments
+    /// * `requirements` - Layout expected by the graphics API
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, GpuRequirements};
+    ///
+    /// let image = GalaxyImage::load_from_file("albedo.png")?;
+    /// let upload = image.prepare_for_gpu(&GpuRequirements {
+    ///     row_alignment: 256,
+    ///     offset_alignment: 512,
+    ///     generate_mips: true,
+    ///     ..Default::default()
+    /// });
+    /// for level in &upload.desc.levels {
+    ///     let bytes = &upload.data[level.offset..level.offset + level.size];
+    ///     println!("{}x{} pitch {} ({} bytes)", level.width, level.height, level.row_pitch, bytes.len());
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn prepare_for_gpu(&self, requirements: &GpuRequirements) -> GpuUpload {
+        prepare_for_gpu(self, requirements)
+    }
+}
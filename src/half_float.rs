@@ -0,0 +1,45 @@
+/// Batched half-float conversions
+///
+/// Little-endian F16 bytes are staged through a small buffer and converted with
+/// the `half` slice routines, which use hardware F16C/FP16 instructions when the
+/// CPU supports them (detected at runtime).
+use half::f16;
+use half::slice::HalfFloatSliceExt;
+
+/// Number of samples converted per batch
+const BATCH: usize = 1024;
+
+/// Convert little-endian F16 bytes to f32 values
+///
+/// `src` must hold `2 * dst.len()` bytes.
+pub(crate) fn f16_bytes_to_f32(src: &[u8], dst: &mut [f32]) {
+    let mut staging = [f16::ZERO; BATCH];
+    for (src, dst) in src.chunks(BATCH * 2).zip(dst.chunks_mut(BATCH)) {
+        let staging = &mut staging[..dst.len()];
+        for (half, bytes) in staging.iter_mut().zip(src.chunks_exact(2)) {
+            *half = f16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        staging.convert_to_f32_slice(dst);
+    }
+}
+
+/// Convert f32 values to little-endian F16 bytes
+///
+/// `dst` must hold `2 * src.len()` bytes.
+pub(crate) fn f32_to_f16_bytes(src: &[f32], dst: &mut [u8]) {
+    let mut staging = [f16::ZERO; BATCH];
+    for (src, dst) in src.chunks(BATCH).zip(dst.chunks_mut(BATCH * 2)) {
+        let staging = &mut staging[..src.len()];
+        staging.convert_from_f32_slice(src);
+        for (half, bytes) in staging.iter().zip(dst.chunks_exact_mut(2)) {
+            bytes.copy_from_slice(&half.to_le_bytes());
+        }
+    }
+}
+
+/// Convert F16 values to f32 values
+pub(crate) fn f16_to_f32(src: &[f16]) -> Vec<f32> {
+    let mut dst = vec![0.0; src.len()];
+    src.convert_to_f32_slice(&mut dst);
+    dst
+}
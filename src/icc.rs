@@ -0,0 +1,159 @@
+/// ICC profile parsing for matrix/TRC and gray profiles
+///
+/// Covers the display profiles embedded by image editors (sRGB, Display P3,
+/// Adobe RGB, ProPhoto RGB, gray gamma): per-channel tone curves followed by a
+/// 3x3 matrix to the profile connection space. LUT-based profiles are not
+/// supported.
+use crate::primaries::pcs_to_rec709;
+
+/// Tone response curve of one channel, decoding stored values to linear
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Curve {
+    /// ICC parametric curve with parameters `[g, a, b, c, d, e, f]`:
+    /// `(a x + b)^g + e` from `x >= d`, `c x + f` below
+    Parametric([f32; 7]),
+
+    /// Samples evenly spaced over `0.0..=1.0`, linearly interpolated
+    Table(Vec<f32>),
+}
+
+impl Curve {
+    /// Linear value of a stored value in `0.0..=1.0`
+    pub(crate) fn eval(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Curve::Parametric([g, a, b, c, d, e, f]) => {
+                if x >= *d {
+                    (a * x + b).max(0.0).powf(*g) + e
+                } else {
+                    c * x + f
+                }
+            }
+            Curve::Table(table) => {
+                let position = x * (table.len() - 1) as f32;
+                let index = (position as usize).min(table.len() - 2);
+                let t = position - index as f32;
+                table[index] + (table[index + 1] - table[index]) * t
+            }
+        }
+    }
+}
+
+/// Decoding of an ICC profile to linear Rec.709
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum IccTransform {
+    /// RGB profile: tone curves, then a matrix to linear Rec.709 (D65)
+    Rgb {
+        curves: [Curve; 3],
+        matrix: [[f32; 3]; 3],
+    },
+
+    /// Gray profile: a tone curve to linear luminance
+    Gray(Curve),
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Signed 15.16 fixed-point number
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Option<f64> {
+    Some(read_u32(data, offset)? as i32 as f64 / 65536.0)
+}
+
+/// Data of the tag with the given signature
+fn find_tag<'a>(profile: &'a [u8], signature: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = read_u32(profile, 128)? as usize;
+    (0..count).find_map(|i| {
+        let entry = 132 + i * 12;
+        if profile.get(entry..entry + 4)? != signature {
+            return None;
+        }
+        let offset = read_u32(profile, entry + 4)? as usize;
+        let size = read_u32(profile, entry + 8)? as usize;
+        profile.get(offset..offset.checked_add(size)?)
+    })
+}
+
+/// Parse an `XYZ ` tag
+fn parse_xyz(tag: &[u8]) -> Option<[f64; 3]> {
+    if tag.get(0..4)? != b"XYZ " {
+        return None;
+    }
+    Some([read_s15_fixed16(tag, 8)?, read_s15_fixed16(tag, 12)?, read_s15_fixed16(tag, 16)?])
+}
+
+/// Parse a `curv` or `para` tag
+fn parse_curve(tag: &[u8]) -> Option<Curve> {
+    match tag.get(0..4)? {
+        b"curv" => {
+            let count = read_u32(tag, 8)? as usize;
+            match count {
+                0 => Some(Curve::Parametric([1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0])),
+                // Unsigned 8.8 fixed-point gamma
+                1 => Some(Curve::Parametric([read_u16(tag, 12)? as f32 / 256.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0])),
+                _ => {
+                    let table = (0..count)
+                        .map(|i| Some(read_u16(tag, 12 + i * 2)? as f32 / 65535.0))
+                        .collect::<Option<Vec<f32>>>()?;
+                    Some(Curve::Table(table))
+                }
+            }
+        }
+        b"para" => {
+            let function = read_u16(tag, 8)?;
+            let parameter_count = match function {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return None,
+            };
+            let mut p = [0f32; 7];
+            for (i, value) in p.iter_mut().take(parameter_count).enumerate() {
+                *value = read_s15_fixed16(tag, 12 + i * 4)? as f32;
+            }
+            let [g, a, b, c, d, e, f] = p;
+            // Express every function type as type 4
+            let parameters = match function {
+                0 => [g, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                1 => [g, a, b, 0.0, -b / a, 0.0, 0.0],
+                2 => [g, a, b, 0.0, -b / a, c, c],
+                3 => [g, a, b, c, d, 0.0, 0.0],
+                _ => [g, a, b, c, d, e, f],
+            };
+            parameters.iter().all(|v| v.is_finite()).then_some(Curve::Parametric(parameters))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a matrix/TRC RGB or gray ICC profile
+///
+/// Returns `None` for malformed profiles and profiles with other color spaces
+/// or without tone curves and colorants.
+pub(crate) fn parse_icc_profile(profile: &[u8]) -> Option<IccTransform> {
+    if profile.get(36..40)? != b"acsp" || profile.get(20..24)? != b"XYZ " {
+        return None;
+    }
+    match profile.get(16..20)? {
+        b"RGB " => {
+            let curves = [
+                parse_curve(find_tag(profile, b"rTRC")?)?,
+                parse_curve(find_tag(profile, b"gTRC")?)?,
+                parse_curve(find_tag(profile, b"bTRC")?)?,
+            ];
+            let [r, g, b] = [b"rXYZ", b"gXYZ", b"bXYZ"].map(|signature| find_tag(profile, signature).and_then(parse_xyz));
+            let (r, g, b) = (r?, g?, b?);
+            let colorants = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+            Some(IccTransform::Rgb { curves, matrix: pcs_to_rec709(colorants) })
+        }
+        b"GRAY" => Some(IccTransform::Gray(parse_curve(find_tag(profile, b"kTRC")?)?)),
+        _ => None,
+    }
+}
@@ -1,10 +1,32 @@
-use crate::{ComponentType, PixelFormat};
+use crate::{AlphaMode, ColorSpace, ComponentType, ImageMetadata, PixelFormat};
+use std::sync::Arc;
+
+/// CRC-64/XZ lookup table (reflected ECMA-182 polynomial)
+const CRC64_TABLE: [u64; 256] = {
+    const POLY: u64 = 0xC96C_5795_D787_0F42;
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
 
 /// Image data container
-#[derive(Debug, Clone)]
+///
+/// Pixel data is shared copy-on-write: cloning an image is cheap and the buffer is
+/// only copied when one of the clones is modified.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Image {
     /// Raw pixel data
-    data: Vec<u8>,
+    data: Arc<Vec<u8>>,
 
     /// Image width in pixels
     width: u32,
@@ -17,10 +39,21 @@ pub struct Image {
 
     /// Component type (U8, U16, F32)
     component_type: ComponentType,
+
+    /// Whether color channels are premultiplied by alpha
+    alpha_mode: AlphaMode,
+
+    /// Transfer function the color channels are encoded with
+    color_space: ColorSpace,
+
+    /// Color and orientation metadata of the source file
+    metadata: ImageMetadata,
 }
 
 impl Image {
     /// Create a new image with specified dimensions and format
+    ///
+    /// U8 images are tagged [`ColorSpace::Srgb`], all others [`ColorSpace::Linear`].
     pub fn new(
         width: u32,
         height: u32,
@@ -31,15 +64,20 @@ impl Image {
         let total_bytes = (width as usize) * (height as usize) * bytes_per_pixel;
 
         Self {
-            data: vec![0; total_bytes],
+            data: Arc::new(vec![0; total_bytes]),
             width,
             height,
             pixel_format,
             component_type,
+            alpha_mode: AlphaMode::Straight,
+            color_space: ColorSpace::default_for(component_type),
+            metadata: ImageMetadata::default(),
         }
     }
 
     /// Create an image from raw pixel data
+    ///
+    /// Tagged with the same color space as [`Image::new`].
     pub fn from_raw(
         data: Vec<u8>,
         width: u32,
@@ -48,11 +86,14 @@ impl Image {
         component_type: ComponentType,
     ) -> Self {
         Self {
-            data,
+            data: Arc::new(data),
             width,
             height,
             pixel_format,
             component_type,
+            alpha_mode: AlphaMode::Straight,
+            color_space: ColorSpace::default_for(component_type),
+            metadata: ImageMetadata::default(),
         }
     }
 
@@ -76,19 +117,71 @@ impl Image {
         self.component_type
     }
 
+    /// Get the alpha mode tag (straight unless set or premultiplied)
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Tag the color channels as straight or premultiplied, without changing pixels
+    ///
+    /// Use [`Image::premultiply_alpha`] and [`Image::unpremultiply_alpha`] to
+    /// convert the pixels instead.
+    pub fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    /// Get the color space tag (set by the loaders, sRGB for U8 and linear otherwise)
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    /// Tag the values as encoded in a color space, without changing pixels
+    ///
+    /// Use [`Image::convert_transfer`] to re-encode the pixels instead.
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+    }
+
+    /// Get the metadata (ICC profile, gamma, orientation) read from the source file
+    pub fn metadata(&self) -> &ImageMetadata {
+        &self.metadata
+    }
+
+    /// Replace the metadata, without changing pixels
+    ///
+    /// Use [`Image::convert_to_srgb`] and [`Image::apply_orientation`] to
+    /// convert the pixels instead.
+    pub fn set_metadata(&mut self, metadata: ImageMetadata) {
+        self.metadata = metadata;
+    }
+
     /// Get raw pixel data as slice
     pub fn data(&self) -> &[u8] {
         &self.data
     }
 
     /// Get mutable raw pixel data
+    ///
+    /// Copies the pixel data first if it is shared with another image.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        &mut self.data
+        Arc::make_mut(&mut self.data).as_mut_slice()
     }
 
     /// Get pixel data as owned Vec
+    ///
+    /// Moves the buffer out without copying unless it is shared with another image.
     pub fn into_data(self) -> Vec<u8> {
-        self.data
+        Arc::try_unwrap(self.data).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// Check if the pixel data is shared with another image
+    pub fn is_data_shared(&self) -> bool {
+        Arc::strong_count(&self.data) > 1
+    }
+
+    /// Check if two images share the same pixel buffer
+    pub fn shares_data_with(&self, other: &Image) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
     }
 
     /// Calculate bytes per pixel
@@ -101,16 +194,69 @@ impl Image {
         self.data.len()
     }
 
+    /// Compute a stable 64-bit hash of the image content
+    ///
+    /// The hash covers the dimensions, pixel format, component type and pixel data
+    /// (FNV-1a), and does not change between runs, platforms or crate versions.
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = FNV_OFFSET;
+        for &byte in self.descriptor_bytes().iter().chain(self.data.iter()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+
+    /// Compute a CRC-64 checksum of the image content
+    ///
+    /// Uses CRC-64/XZ over the same descriptor and pixel data as
+    /// [`Image::content_hash`], for integrity checks of stored images and cheap
+    /// change detection.
+    pub fn checksum(&self) -> u64 {
+        let mut crc = !0u64;
+        for &byte in self.descriptor_bytes().iter().chain(self.data.iter()) {
+            crc = CRC64_TABLE[((crc ^ byte as u64) & 0xFF) as usize] ^ (crc >> 8);
+        }
+        !crc
+    }
+
+    /// Serialize the image descriptor with stable identifiers
+    fn descriptor_bytes(&self) -> [u8; 10] {
+        let pixel_format_id: u8 = match self.pixel_format {
+            PixelFormat::R => 0,
+            PixelFormat::RG => 1,
+            PixelFormat::RGB => 2,
+            PixelFormat::RGBA => 3,
+            PixelFormat::BGR => 4,
+            PixelFormat::BGRA => 5,
+        };
+        let component_type_id: u8 = match self.component_type {
+            ComponentType::U8 => 0,
+            ComponentType::U16 => 1,
+            ComponentType::F16 => 2,
+            ComponentType::F32 => 3,
+            ComponentType::I8 => 4,
+            ComponentType::I16 => 5,
+        };
+
+        let mut bytes = [0u8; 10];
+        bytes[0..4].copy_from_slice(&self.width.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.height.to_le_bytes());
+        bytes[8] = pixel_format_id;
+        bytes[9] = component_type_id;
+        bytes
+    }
+
     /// Convert BGR to RGB in-place (for BMP files)
     pub fn bgr_to_rgb(&mut self) {
         if self.pixel_format != PixelFormat::BGR && self.pixel_format != PixelFormat::BGRA {
             return;
         }
 
-        let channels = self.pixel_format.channel_count();
-        for pixel in self.data.chunks_exact_mut(channels) {
-            pixel.swap(0, 2); // Swap B and R channels
-        }
+        self.swap_red_blue(); // Swap B and R channels
 
         // Update pixel format
         self.pixel_format = if self.pixel_format == PixelFormat::BGR {
@@ -126,10 +272,7 @@ impl Image {
             return;
         }
 
-        let channels = self.pixel_format.channel_count();
-        for pixel in self.data.chunks_exact_mut(channels) {
-            pixel.swap(0, 2); // Swap R and B channels
-        }
+        self.swap_red_blue(); // Swap R and B channels
 
         // Update pixel format
         self.pixel_format = if self.pixel_format == PixelFormat::RGB {
@@ -138,4 +281,29 @@ impl Image {
             PixelFormat::BGRA
         };
     }
+
+    /// Swap the first and third component of every pixel
+    fn swap_red_blue(&mut self) {
+        let component_size = self.component_type.size_bytes();
+        let bytes_per_pixel = self.bytes_per_pixel();
+        for pixel in self.data_mut().chunks_exact_mut(bytes_per_pixel) {
+            let (first, rest) = pixel.split_at_mut(component_size);
+            first.swap_with_slice(&mut rest[component_size..2 * component_size]);
+        }
+    }
+
+    /// Reverse the row order in-place
+    pub(crate) fn flip_rows(&mut self) {
+        let row_bytes = self.width as usize * self.bytes_per_pixel();
+        let height = self.height as usize;
+        if row_bytes == 0 {
+            return;
+        }
+
+        let data = self.data_mut();
+        for y in 0..height / 2 {
+            let (top, bottom) = data.split_at_mut((height - 1 - y) * row_bytes);
+            top[y * row_bytes..(y + 1) * row_bytes].swap_with_slice(&mut bottom[..row_bytes]);
+        }
+    }
 }
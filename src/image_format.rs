@@ -1,3 +1,11 @@
+use crate::loaders::{dds_loader, hdr_loader, tga_loader, tiff_loader, webp_loader, gif_loader, ico_loader, jxl_loader, avif_loader, psd_loader, pbm_loader};
+use crate::{ComponentType, FormatCapabilities, FormatDescriptor, PixelFormat};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// Formats with a built-in decoder or encoder, in [`ImageFormat::all_supported`] order
+const SUPPORTED: &[ImageFormat] = &[ImageFormat::Png, ImageFormat::Bmp, ImageFormat::Jpeg, ImageFormat::Exr, ImageFormat::Tga, ImageFormat::Dds, ImageFormat::Hdr, ImageFormat::Tiff, ImageFormat::WebP, ImageFormat::Gif, ImageFormat::Ico, ImageFormat::Pnm, ImageFormat::Pfm, ImageFormat::Psd, #[cfg(feature = "jxl")] ImageFormat::Jxl, #[cfg(feature = "avif")] ImageFormat::Avif];
+
 /// Supported image file formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ImageFormat {
@@ -13,6 +21,42 @@ pub enum ImageFormat {
     /// OpenEXR format (HDR)
     Exr,
 
+    /// Truevision TGA format (uncompressed or RLE)
+    Tga,
+
+    /// DirectDraw Surface textures (block-compressed or uncompressed, decode only)
+    Dds,
+
+    /// Radiance RGBE format (HDR)
+    Hdr,
+
+    /// TIFF format (strip-based, uncompressed, LZW or Deflate)
+    Tiff,
+
+    /// WebP format (lossy and lossless still images, decode only)
+    WebP,
+
+    /// GIF format (animated or still, decode only)
+    Gif,
+
+    /// Windows icon and cursor files (every embedded resolution, decode only)
+    Ico,
+
+    /// Netpbm PBM, PGM and PPM images (ASCII or binary)
+    Pnm,
+
+    /// Portable float map (HDR, F32)
+    Pfm,
+
+    /// JPEG XL format (decode only, requires the `jxl` feature)
+    Jxl,
+
+    /// AVIF format (decode only, requires the `avif` feature)
+    Avif,
+
+    /// Photoshop PSD and PSB documents (flattened composite image, decode only)
+    Psd,
+
     /// Unknown or unsupported format
     Unknown,
 }
@@ -20,10 +64,6 @@ pub enum ImageFormat {
 impl ImageFormat {
     /// Detect format from file magic bytes
     pub fn detect_from_bytes(data: &[u8]) -> Self {
-        if data.len() < 8 {
-            return ImageFormat::Unknown;
-        }
-
         // PNG: 0x89 'P' 'N' 'G' 0x0D 0x0A 0x1A 0x0A
         if data.len() >= 8 && data[0..8] == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] {
             return ImageFormat::Png;
@@ -44,24 +84,101 @@ impl ImageFormat {
             return ImageFormat::Exr;
         }
 
+        // DDS: 'D' 'D' 'S' ' '
+        if dds_loader::is_dds(data) {
+            return ImageFormat::Dds;
+        }
+
+        // Radiance HDR: '#?RADIANCE' or '#?RGBE' header line
+        if hdr_loader::is_hdr(data) {
+            return ImageFormat::Hdr;
+        }
+
+        // TIFF: 'I' 'I' 42 0 (little-endian) or 'M' 'M' 0 42 (big-endian)
+        if tiff_loader::is_tiff(data) {
+            return ImageFormat::Tiff;
+        }
+
+        // WebP: 'R' 'I' 'F' 'F' <size> 'W' 'E' 'B' 'P'
+        if webp_loader::is_webp(data) {
+            return ImageFormat::WebP;
+        }
+
+        // GIF: 'G' 'I' 'F' '8' '7' 'a' or 'G' 'I' 'F' '8' '9' 'a'
+        if gif_loader::is_gif(data) {
+            return ImageFormat::Gif;
+        }
+
+        // ICO/CUR: reserved 0, type 1 (icon) or 2 (cursor), then a directory whose first entry fits the file
+        if ico_loader::is_ico(data) {
+            return ImageFormat::Ico;
+        }
+
+        // PNM: 'P' '1'-'6' then whitespace; PFM: 'P' 'F' or 'P' 'f' then whitespace
+        if pbm_loader::is_pnm(data) {
+            return ImageFormat::Pnm;
+        }
+        if pbm_loader::is_pfm(data) {
+            return ImageFormat::Pfm;
+        }
+
+        // JPEG XL: 0xFF 0x0A codestream or a 'JXL ' signature box
+        if jxl_loader::is_jxl(data) {
+            return ImageFormat::Jxl;
+        }
+
+        // AVIF: 'ftyp' box listing the 'avif' brand
+        if avif_loader::is_avif(data) {
+            return ImageFormat::Avif;
+        }
+
+        // PSD: '8BPS' followed by version 1 (PSD) or 2 (PSB)
+        if psd_loader::is_psd(data) {
+            return ImageFormat::Psd;
+        }
+
+        // TGA has no magic number: TGA 2.0 footer or a consistent header, checked last
+        if tga_loader::is_tga(data) {
+            return ImageFormat::Tga;
+        }
+
         ImageFormat::Unknown
     }
 
     /// Detect format from file extension
+    ///
+    /// Only the text after the last `.` is considered, case-insensitively.
+    /// Prefer [`ImageFormat::from_path`] for file system paths.
     pub fn from_extension(path: &str) -> Self {
-        let path_lower = path.to_lowercase();
-
-        if path_lower.ends_with(".png") {
-            ImageFormat::Png
-        } else if path_lower.ends_with(".bmp") {
-            ImageFormat::Bmp
-        } else if path_lower.ends_with(".jpg") || path_lower.ends_with(".jpeg") {
-            ImageFormat::Jpeg
-        } else if path_lower.ends_with(".exr") {
-            ImageFormat::Exr
-        } else {
-            ImageFormat::Unknown
-        }
+        path.rsplit_once('.')
+            .map_or(ImageFormat::Unknown, |(_, extension)| Self::from_extension_os(OsStr::new(extension)))
+    }
+
+    /// Detect format from the extension of a file system path
+    ///
+    /// Matches the extension as an `OsStr`, so paths that are not valid UTF-8
+    /// are still recognized. Aliases such as `.jfif`, `.jpe` and `.dib` are
+    /// accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::ImageFormat;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(ImageFormat::from_path(Path::new("photos/IMG_0001.JFIF")), ImageFormat::Jpeg);
+    /// assert_eq!(ImageFormat::from_path(Path::new("icon.dib")), ImageFormat::Bmp);
+    /// ```
+    pub fn from_path(path: &Path) -> Self {
+        path.extension().map_or(ImageFormat::Unknown, Self::from_extension_os)
+    }
+
+    fn from_extension_os(extension: &OsStr) -> Self {
+        SUPPORTED
+            .iter()
+            .copied()
+            .find(|format| format.extensions().iter().any(|known| extension.eq_ignore_ascii_case(known)))
+            .unwrap_or(ImageFormat::Unknown)
     }
 
     /// Get file extension for this format
@@ -71,7 +188,403 @@ impl ImageFormat {
             ImageFormat::Bmp => "bmp",
             ImageFormat::Jpeg => "jpg",
             ImageFormat::Exr => "exr",
+            ImageFormat::Tga => "tga",
+            ImageFormat::Dds => "dds",
+            ImageFormat::Hdr => "hdr",
+            ImageFormat::Tiff => "tif",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Ico => "ico",
+            ImageFormat::Pnm => "pnm",
+            ImageFormat::Pfm => "pfm",
+            ImageFormat::Jxl => "jxl",
+            ImageFormat::Avif => "avif",
+            ImageFormat::Psd => "psd",
             ImageFormat::Unknown => "",
         }
     }
+
+    /// Get every file extension recognized for this format (lowercase, without dot)
+    ///
+    /// The canonical [`ImageFormat::extension`] comes first, followed by aliases.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            ImageFormat::Png => &["png"],
+            ImageFormat::Bmp => &["bmp", "dib"],
+            ImageFormat::Jpeg => &["jpg", "jpeg", "jpe", "jfif", "jif"],
+            ImageFormat::Exr => &["exr"],
+            ImageFormat::Tga => &["tga", "targa", "icb", "vda", "vst"],
+            ImageFormat::Dds => &["dds"],
+            ImageFormat::Hdr => &["hdr", "rgbe"],
+            ImageFormat::Tiff => &["tif", "tiff"],
+            ImageFormat::WebP => &["webp"],
+            ImageFormat::Gif => &["gif"],
+            ImageFormat::Ico => &["ico", "cur"],
+            ImageFormat::Pnm => &["pnm", "ppm", "pgm", "pbm"],
+            ImageFormat::Pfm => &["pfm"],
+            ImageFormat::Jxl => &["jxl"],
+            ImageFormat::Avif => &["avif"],
+            ImageFormat::Psd => &["psd", "psb"],
+            ImageFormat::Unknown => &[],
+        }
+    }
+
+    /// Get the human-readable format name (e.g. for file dialog filters)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Exr => "OpenEXR",
+            ImageFormat::Tga => "TGA",
+            ImageFormat::Dds => "DDS",
+            ImageFormat::Hdr => "Radiance HDR",
+            ImageFormat::Tiff => "TIFF",
+            ImageFormat::WebP => "WebP",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::Ico => "ICO",
+            ImageFormat::Pnm => "PNM",
+            ImageFormat::Pfm => "PFM",
+            ImageFormat::Jxl => "JPEG XL",
+            ImageFormat::Avif => "AVIF",
+            ImageFormat::Psd => "PSD",
+            ImageFormat::Unknown => "Unknown",
+        }
+    }
+
+    /// Get descriptors of every format compiled into the library
+    ///
+    /// Lets tools build file dialog filters and validation lists without
+    /// hardcoding formats. Formats gated behind cargo features only appear
+    /// when the feature is enabled; [`ImageFormat::Unknown`] is never listed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::ImageFormat;
+    ///
+    /// let filters: Vec<String> = ImageFormat::all_supported()
+    ///     .iter()
+    ///     .filter(|descriptor| descriptor.capabilities.decode)
+    ///     .map(|descriptor| format!("{} (*.{})", descriptor.name, descriptor.extensions.join(", *.")))
+    ///     .collect();
+    /// assert_eq!(filters[0], "PNG (*.png)");
+    /// ```
+    pub fn all_supported() -> Vec<FormatDescriptor> {
+        SUPPORTED.iter().map(|format| format.descriptor()).collect()
+    }
+
+    /// Get the descriptor of this format
+    pub fn descriptor(&self) -> FormatDescriptor {
+        FormatDescriptor {
+            format: *self,
+            name: self.name(),
+            extensions: self.extensions(),
+            mime_type: self.mime_type(),
+            capabilities: self.capabilities(),
+        }
+    }
+
+    /// Detect format from a MIME type (e.g. an HTTP `Content-Type` header)
+    ///
+    /// Matching is case-insensitive, ignores parameters such as `; charset=...`
+    /// and accepts common non-standard aliases (`image/x-png`, `image/jpg`,
+    /// `image/x-ms-bmp`, ...).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::ImageFormat;
+    ///
+    /// assert_eq!(ImageFormat::from_mime("image/PNG; foo=bar"), ImageFormat::Png);
+    /// assert_eq!(ImageFormat::from_mime("image/pjpeg"), ImageFormat::Jpeg);
+    /// assert_eq!(ImageFormat::from_mime("text/html"), ImageFormat::Unknown);
+    /// ```
+    pub fn from_mime(mime: &str) -> Self {
+        let essence = mime.split(';').next().unwrap_or("").trim().to_lowercase();
+
+        match essence.as_str() {
+            "image/png" | "image/x-png" | "image/apng" => ImageFormat::Png,
+            "image/bmp" | "image/x-bmp" | "image/x-ms-bmp" | "image/x-windows-bmp" => ImageFormat::Bmp,
+            "image/jpeg" | "image/jpg" | "image/pjpeg" | "image/x-citrix-jpeg" => ImageFormat::Jpeg,
+            "image/x-exr" | "image/exr" | "image/aces" => ImageFormat::Exr,
+            "image/x-tga" | "image/x-targa" | "image/tga" | "image/targa" | "application/tga" | "application/x-tga" => ImageFormat::Tga,
+            "image/vnd-ms.dds" | "image/vnd.ms-dds" | "image/x-dds" | "image/dds" => ImageFormat::Dds,
+            "image/vnd.radiance" | "image/x-hdr" | "image/x-radiance" | "image/hdr" => ImageFormat::Hdr,
+            "image/tiff" | "image/tif" | "image/x-tiff" | "image/x-tif" => ImageFormat::Tiff,
+            "image/webp" | "image/x-webp" => ImageFormat::WebP,
+            "image/gif" => ImageFormat::Gif,
+            "image/vnd.microsoft.icon" | "image/x-icon" | "image/ico" | "image/icon" | "image/x-win-bitmap" => ImageFormat::Ico,
+            "image/x-portable-anymap" | "image/x-portable-pixmap" | "image/x-portable-graymap" | "image/x-portable-bitmap" => ImageFormat::Pnm,
+            "image/x-portable-floatmap" => ImageFormat::Pfm,
+            "image/jxl" => ImageFormat::Jxl,
+            "image/avif" => ImageFormat::Avif,
+            "image/vnd.adobe.photoshop" | "image/x-photoshop" | "image/photoshop" | "image/psd" | "application/x-photoshop" | "application/photoshop" | "application/psd" => ImageFormat::Psd,
+            _ => ImageFormat::Unknown,
+        }
+    }
+
+    /// Get the canonical MIME type for this format
+    ///
+    /// Returns `application/octet-stream` for [`ImageFormat::Unknown`].
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Bmp => "image/bmp",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Exr => "image/x-exr",
+            ImageFormat::Tga => "image/x-tga",
+            ImageFormat::Dds => "image/vnd-ms.dds",
+            ImageFormat::Hdr => "image/vnd.radiance",
+            ImageFormat::Tiff => "image/tiff",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Ico => "image/vnd.microsoft.icon",
+            ImageFormat::Pnm => "image/x-portable-anymap",
+            ImageFormat::Pfm => "image/x-portable-floatmap",
+            ImageFormat::Jxl => "image/jxl",
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Psd => "image/vnd.adobe.photoshop",
+            ImageFormat::Unknown => "application/octet-stream",
+        }
+    }
+
+    /// Determine the format of uploaded content from its bytes and declared MIME type
+    ///
+    /// Mirrors the image sniffing of browsers and common servers: recognized
+    /// magic bytes win over the declared type, which is only used when the content
+    /// is not recognized. Mislabeled uploads (e.g. a PNG sent as `image/jpeg`)
+    /// are therefore decoded with the right loader.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Content bytes
+    /// * `declared_mime` - `Content-Type` sent with the content, if any
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, ImageFormat};
+    ///
+    /// let body: Vec<u8> = vec![/* ... */];
+    /// let format = ImageFormat::sniff(&body, Some("image/jpeg"));
+    /// let image = GalaxyImage::load_from_bytes(&body, format)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn sniff(data: &[u8], declared_mime: Option<&str>) -> Self {
+        match ImageFormat::detect_from_bytes(data) {
+            ImageFormat::Unknown => declared_mime.map_or(ImageFormat::Unknown, ImageFormat::from_mime),
+            sniffed => sniffed,
+        }
+    }
+
+    /// Get the pixel formats, component types and features supported by this format
+    ///
+    /// Lets tools offer only valid export choices instead of failing at save time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, ImageFormat, PixelFormat};
+    ///
+    /// let caps = ImageFormat::Jpeg.capabilities();
+    /// assert!(caps.lossy && !caps.alpha);
+    /// assert!(!caps.supports(PixelFormat::RGBA, ComponentType::U8));
+    /// assert!(ImageFormat::Png.capabilities().supports(PixelFormat::RGBA, ComponentType::U16));
+    /// ```
+    pub fn capabilities(&self) -> FormatCapabilities {
+        use ComponentType::*;
+        use PixelFormat::*;
+
+        match self {
+            ImageFormat::Png => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA, BGR, BGRA],
+                component_types: &[U8, U16],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: true,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            // Alpha is stored with `BmpBitDepth::Bits32`
+            ImageFormat::Bmp => FormatCapabilities {
+                pixel_formats: &[RGB, RGBA, BGR, BGRA],
+                component_types: &[U8],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Jpeg => FormatCapabilities {
+                pixel_formats: &[R, RGB, BGR],
+                component_types: &[U8],
+                alpha: false,
+                hdr: false,
+                animation: false,
+                metadata: true,
+                lossy: true,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Exr => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA, BGR, BGRA],
+                component_types: &[F16, F32],
+                alpha: true,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Tga => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA, BGR, BGRA],
+                component_types: &[U8],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Dds => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA, BGRA],
+                component_types: &[U8, U16, F16, F32, I8, I16],
+                alpha: true,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: true,
+                decode: true,
+                encode: false,
+            },
+            ImageFormat::Hdr => FormatCapabilities {
+                pixel_formats: &[RGB, BGR],
+                component_types: &[F16, F32],
+                alpha: false,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: true,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Tiff => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA, BGR, BGRA],
+                component_types: &[U8, U16, F16, F32, I8, I16],
+                alpha: true,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::WebP => FormatCapabilities {
+                pixel_formats: &[RGB, RGBA],
+                component_types: &[U8],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: true,
+                decode: true,
+                encode: false,
+            },
+            ImageFormat::Gif => FormatCapabilities {
+                pixel_formats: &[RGBA],
+                component_types: &[U8],
+                alpha: true,
+                hdr: false,
+                animation: true,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: false,
+            },
+            ImageFormat::Ico => FormatCapabilities {
+                pixel_formats: &[RGBA],
+                component_types: &[U8],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: false,
+            },
+            ImageFormat::Pnm => FormatCapabilities {
+                pixel_formats: &[R, RGB, BGR],
+                component_types: &[U8, U16],
+                alpha: false,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Pfm => FormatCapabilities {
+                pixel_formats: &[R, RGB, BGR],
+                component_types: &[F16, F32],
+                alpha: false,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: true,
+            },
+            ImageFormat::Jxl => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA],
+                component_types: &[U8, U16, F32],
+                alpha: true,
+                hdr: true,
+                animation: false,
+                metadata: false,
+                lossy: true,
+                decode: cfg!(feature = "jxl"),
+                encode: false,
+            },
+            ImageFormat::Avif => FormatCapabilities {
+                pixel_formats: &[RGB, RGBA],
+                component_types: &[U8, U16],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: true,
+                decode: cfg!(feature = "avif"),
+                encode: false,
+            },
+            ImageFormat::Psd => FormatCapabilities {
+                pixel_formats: &[R, RG, RGB, RGBA],
+                component_types: &[U8, U16],
+                alpha: true,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: true,
+                encode: false,
+            },
+            ImageFormat::Unknown => FormatCapabilities {
+                pixel_formats: &[],
+                component_types: &[],
+                alpha: false,
+                hdr: false,
+                animation: false,
+                metadata: false,
+                lossy: false,
+                decode: false,
+                encode: false,
+            },
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use crate::{ComponentType, ImageFormat, PixelFormat};
+
+/// Image properties read from the file header, without decoding pixels
+///
+/// Pixel format and component type describe the image as it would be returned
+/// by the loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// File format
+    pub format: ImageFormat,
+
+    /// Image width in pixels
+    pub width: u32,
+
+    /// Image height in pixels
+    pub height: u32,
+
+    /// Pixel format (channel layout)
+    pub pixel_format: PixelFormat,
+
+    /// Component type (U8, U16, F16, F32)
+    pub component_type: ComponentType,
+
+    /// Number of layers (parts) stored in the file
+    pub layer_count: usize,
+
+    /// Number of mip levels stored in the file (1 if not mipmapped)
+    pub mip_levels: usize,
+}
@@ -0,0 +1,173 @@
+/// Conversions to and from the `image` crate (feature `image`)
+use crate::sample::read_normalized_slice;
+use crate::{ComponentType, Image, ImageError, PixelFormat};
+use image::{DynamicImage, ImageBuffer, Luma, LumaA, Rgb, Rgba};
+
+/// Get the pixel data in RGB channel order (BGR images are swapped)
+fn rgb_order(image: &Image) -> Image {
+    let mut image = image.clone();
+    image.bgr_to_rgb();
+    image
+}
+
+fn u16_samples(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect()
+}
+
+fn f32_samples(data: &[u8], component_type: ComponentType) -> Vec<f32> {
+    let mut values = vec![0f32; data.len() / component_type.size_bytes()];
+    read_normalized_slice(data, component_type, &mut values);
+    values
+}
+
+fn size_mismatch(image: &Image) -> ImageError {
+    ImageError::InvalidDimensions {
+        width: image.width(),
+        height: image.height(),
+    }
+}
+
+/// Convert to a `DynamicImage`
+///
+/// BGR(A) images are converted to RGB(A) and F16 samples to F32. Fails for
+/// F16/F32 grayscale images, which `DynamicImage` cannot represent.
+impl TryFrom<&Image> for DynamicImage {
+    type Error = ImageError;
+
+    fn try_from(image: &Image) -> Result<Self, Self::Error> {
+        let rgb = rgb_order(image);
+        let (width, height) = (rgb.width(), rgb.height());
+        let dynamic = match (rgb.component_type(), rgb.pixel_format()) {
+            (ComponentType::U8, format) => {
+                let data = rgb.into_data();
+                match format {
+                    PixelFormat::R => ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma8),
+                    PixelFormat::RG => ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA8),
+                    PixelFormat::RGBA | PixelFormat::BGRA => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+                    }
+                    PixelFormat::RGB | PixelFormat::BGR => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+                    }
+                }
+            }
+            (ComponentType::U16, format) => {
+                let data = u16_samples(rgb.data());
+                match format {
+                    PixelFormat::R => ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma16),
+                    PixelFormat::RG => ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA16),
+                    PixelFormat::RGBA | PixelFormat::BGRA => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba16)
+                    }
+                    PixelFormat::RGB | PixelFormat::BGR => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb16)
+                    }
+                }
+            }
+            (component_type, format) => {
+                let data = f32_samples(rgb.data(), component_type);
+                match format {
+                    PixelFormat::RGBA | PixelFormat::BGRA => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba32F)
+                    }
+                    PixelFormat::RGB | PixelFormat::BGR => {
+                        ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb32F)
+                    }
+                    PixelFormat::R | PixelFormat::RG => {
+                        return Err(ImageError::InvalidPixelFormat(format!(
+                            "DynamicImage has no {:?} {:?} equivalent",
+                            format, component_type
+                        )));
+                    }
+                }
+            }
+        };
+        dynamic.ok_or_else(|| size_mismatch(image))
+    }
+}
+
+impl TryFrom<Image> for DynamicImage {
+    type Error = ImageError;
+
+    fn try_from(image: Image) -> Result<Self, Self::Error> {
+        DynamicImage::try_from(&image)
+    }
+}
+
+fn from_u16(data: Vec<u16>, width: u32, height: u32, pixel_format: PixelFormat) -> Image {
+    let bytes = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+    Image::from_raw(bytes, width, height, pixel_format, ComponentType::U16)
+}
+
+fn from_f32(data: Vec<f32>, width: u32, height: u32, pixel_format: PixelFormat) -> Image {
+    let bytes = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+    Image::from_raw(bytes, width, height, pixel_format, ComponentType::F32)
+}
+
+/// Convert from a `DynamicImage`
+///
+/// Layouts map to the matching pixel format and component type. Layouts added
+/// to `DynamicImage` in the future are converted to RGBA F32.
+impl From<DynamicImage> for Image {
+    fn from(dynamic: DynamicImage) -> Self {
+        let (width, height) = (dynamic.width(), dynamic.height());
+        match dynamic {
+            DynamicImage::ImageLuma8(b) => Image::from_raw(b.into_raw(), width, height, PixelFormat::R, ComponentType::U8),
+            DynamicImage::ImageLumaA8(b) => Image::from_raw(b.into_raw(), width, height, PixelFormat::RG, ComponentType::U8),
+            DynamicImage::ImageRgb8(b) => Image::from_raw(b.into_raw(), width, height, PixelFormat::RGB, ComponentType::U8),
+            DynamicImage::ImageRgba8(b) => Image::from_raw(b.into_raw(), width, height, PixelFormat::RGBA, ComponentType::U8),
+            DynamicImage::ImageLuma16(b) => from_u16(b.into_raw(), width, height, PixelFormat::R),
+            DynamicImage::ImageLumaA16(b) => from_u16(b.into_raw(), width, height, PixelFormat::RG),
+            DynamicImage::ImageRgb16(b) => from_u16(b.into_raw(), width, height, PixelFormat::RGB),
+            DynamicImage::ImageRgba16(b) => from_u16(b.into_raw(), width, height, PixelFormat::RGBA),
+            DynamicImage::ImageRgb32F(b) => from_f32(b.into_raw(), width, height, PixelFormat::RGB),
+            DynamicImage::ImageRgba32F(b) => from_f32(b.into_raw(), width, height, PixelFormat::RGBA),
+            other => from_f32(other.to_rgba32f().into_raw(), width, height, PixelFormat::RGBA),
+        }
+    }
+}
+
+/// `From<ImageBuffer>` and strict `TryFrom<&Image>` for each typed buffer
+///
+/// The `TryFrom` direction requires the matching channel layout and component
+/// type (BGR(A) is accepted and swapped to RGB(A)).
+macro_rules! image_buffer_conversions {
+    ($($pixel:ty => $variant:ident;)*) => {$(
+        impl From<ImageBuffer<$pixel, Vec<<$pixel as image::Pixel>::Subpixel>>> for Image {
+            fn from(buffer: ImageBuffer<$pixel, Vec<<$pixel as image::Pixel>::Subpixel>>) -> Self {
+                Image::from(DynamicImage::$variant(buffer))
+            }
+        }
+
+        impl TryFrom<&Image> for ImageBuffer<$pixel, Vec<<$pixel as image::Pixel>::Subpixel>> {
+            type Error = ImageError;
+
+            fn try_from(image: &Image) -> Result<Self, Self::Error> {
+                // F16 and signed types are widened to F32 by the DynamicImage conversion, only keep exact matches
+                let exact = !matches!(image.component_type(), ComponentType::F16 | ComponentType::I8 | ComponentType::I16);
+                match DynamicImage::try_from(image)? {
+                    DynamicImage::$variant(buffer) if exact => Ok(buffer),
+                    _ => Err(ImageError::InvalidPixelFormat(format!(
+                        "{:?} {:?} image does not match {}",
+                        image.pixel_format(),
+                        image.component_type(),
+                        stringify!($pixel)
+                    ))),
+                }
+            }
+        }
+    )*};
+}
+
+image_buffer_conversions! {
+    Luma<u8> => ImageLuma8;
+    LumaA<u8> => ImageLumaA8;
+    Rgb<u8> => ImageRgb8;
+    Rgba<u8> => ImageRgba8;
+    Luma<u16> => ImageLuma16;
+    LumaA<u16> => ImageLumaA16;
+    Rgb<u16> => ImageRgb16;
+    Rgba<u16> => ImageRgba16;
+    Rgb<f32> => ImageRgb32F;
+    Rgba<f32> => ImageRgba32F;
+}
@@ -0,0 +1,155 @@
+/// Optional tracing instrumentation (feature `tracing`)
+///
+/// Without the feature the wrappers call the operation directly and compile away.
+use crate::{Image, ImageFormat, ImageResult};
+
+#[cfg(feature = "tracing")]
+pub(crate) fn load<F>(format: ImageFormat, input_bytes: usize, decode: F) -> ImageResult<Image>
+where
+    F: FnOnce() -> ImageResult<Image>,
+{
+    let _span = tracing::debug_span!("galaxy_image::load", ?format, input_bytes).entered();
+    let start = std::time::Instant::now();
+    let result = decode();
+    let elapsed_us = start.elapsed().as_micros() as u64;
+    match &result {
+        Ok(image) => tracing::debug!(
+            width = image.width(),
+            height = image.height(),
+            pixel_format = ?image.pixel_format(),
+            component_type = ?image.component_type(),
+            output_bytes = image.size_bytes(),
+            elapsed_us,
+            "image decoded"
+        ),
+        Err(error) => tracing::debug!(%error, elapsed_us, "image decode failed"),
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn load<F>(_format: ImageFormat, _input_bytes: usize, decode: F) -> ImageResult<Image>
+where
+    F: FnOnce() -> ImageResult<Image>,
+{
+    decode()
+}
+
+#[cfg(feature = "tracing")]
+fn save_span(image: &Image, format: ImageFormat) -> tracing::Span {
+    tracing::debug_span!(
+        "galaxy_image::save",
+        ?format,
+        width = image.width(),
+        height = image.height(),
+        pixel_format = ?image.pixel_format(),
+        component_type = ?image.component_type(),
+        input_bytes = image.size_bytes()
+    )
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn save<F>(image: &Image, format: ImageFormat, buffer: &mut Vec<u8>, encode: F) -> ImageResult<()>
+where
+    F: FnOnce(&mut Vec<u8>) -> ImageResult<()>,
+{
+    let _span = save_span(image, format).entered();
+    let start = std::time::Instant::now();
+    let result = encode(buffer);
+    let elapsed_us = start.elapsed().as_micros() as u64;
+    match &result {
+        Ok(()) => tracing::debug!(output_bytes = buffer.len(), elapsed_us, "image encoded"),
+        Err(error) => tracing::debug!(%error, elapsed_us, "image encode failed"),
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn save<F>(_image: &Image, _format: ImageFormat, buffer: &mut Vec<u8>, encode: F) -> ImageResult<()>
+where
+    F: FnOnce(&mut Vec<u8>) -> ImageResult<()>,
+{
+    encode(buffer)
+}
+
+/// Same span as [`save`] for encoders writing straight to a writer, without the output size
+#[cfg(feature = "tracing")]
+pub(crate) fn save_streamed<F>(image: &Image, format: ImageFormat, encode: F) -> ImageResult<()>
+where
+    F: FnOnce() -> ImageResult<()>,
+{
+    let _span = save_span(image, format).entered();
+    let start = std::time::Instant::now();
+    let result = encode();
+    let elapsed_us = start.elapsed().as_micros() as u64;
+    match &result {
+        Ok(()) => tracing::debug!(elapsed_us, "image encoded"),
+        Err(error) => tracing::debug!(%error, elapsed_us, "image encode failed"),
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn save_streamed<F>(_image: &Image, _format: ImageFormat, encode: F) -> ImageResult<()>
+where
+    F: FnOnce() -> ImageResult<()>,
+{
+    encode()
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn file<T, F>(operation: &'static str, path: &std::path::Path, run: F) -> ImageResult<T>
+where
+    F: FnOnce() -> ImageResult<T>,
+{
+    let _span = tracing::debug_span!("galaxy_image::file", operation, path = %path.display()).entered();
+    run()
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn file<T, F>(_operation: &'static str, _path: &std::path::Path, run: F) -> ImageResult<T>
+where
+    F: FnOnce() -> ImageResult<T>,
+{
+    run()
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) fn convert<F>(operation: &'static str, image: &Image, run: F) -> Image
+where
+    F: FnOnce() -> Image,
+{
+    let _span = tracing::debug_span!(
+        "galaxy_image::convert",
+        operation,
+        width = image.width(),
+        height = image.height(),
+        pixel_format = ?image.pixel_format(),
+        component_type = ?image.component_type()
+    )
+    .entered();
+    let start = std::time::Instant::now();
+    let output = run();
+    tracing::debug!(
+        width = output.width(),
+        height = output.height(),
+        pixel_format = ?output.pixel_format(),
+        component_type = ?output.component_type(),
+        elapsed_us = start.elapsed().as_micros() as u64,
+        "image converted"
+    );
+    output
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn convert<F>(_operation: &'static str, _image: &Image, run: F) -> Image
+where
+    F: FnOnce() -> Image,
+{
+    run()
+}
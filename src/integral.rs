@@ -0,0 +1,162 @@
+/// Summed-area tables for constant-time box sums
+use crate::sample::read_normalized_slice;
+use crate::{ComponentType, Image, PixelFormat, Rect};
+
+/// Summed-area table of an image
+///
+/// Entry (x, y) holds the sum of all normalized samples above and to the left
+/// of pixel (x, y), exclusive, so the table is one larger than the image in
+/// both directions and the sum over any rectangle takes four lookups. Sums are
+/// accumulated in f64, so large tables do not lose the precision of small
+/// regions. Channels are in storage order (e.g. B, G, R for BGR images).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegralImage {
+    width: u32,
+    height: u32,
+    channel_count: usize,
+    sums: Vec<f64>,
+}
+
+impl IntegralImage {
+    /// Width of the source image
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of the source image
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of channels
+    pub fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    /// Raw table: `(width + 1) x (height + 1)` entries of `channel_count` sums, row-major
+    pub fn sums(&self) -> &[f64] {
+        &self.sums
+    }
+
+    fn at(&self, x: u32, y: u32, channel: usize) -> f64 {
+        let index = y as usize * (self.width as usize + 1) + x as usize;
+        self.sums[index * self.channel_count + channel]
+    }
+
+    /// Sum of one channel over a rectangle
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle does not fit in the image or `channel` is out of range.
+    pub fn sum(&self, rect: Rect, channel: usize) -> f64 {
+        assert!(rect.fits(self.width, self.height), "region {:?} out of bounds", rect);
+        assert!(channel < self.channel_count, "channel {} out of range", channel);
+        let (x1, y1) = (rect.x + rect.width, rect.y + rect.height);
+        self.at(x1, y1, channel) - self.at(rect.x, y1, channel) - self.at(x1, rect.y, channel)
+            + self.at(rect.x, rect.y, channel)
+    }
+
+    /// Mean of one channel over a rectangle, or 0 for an empty rectangle
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle does not fit in the image or `channel` is out of range.
+    pub fn mean(&self, rect: Rect, channel: usize) -> f64 {
+        let area = rect.width as f64 * rect.height as f64;
+        if area == 0.0 {
+            return 0.0;
+        }
+        self.sum(rect, channel) / area
+    }
+
+    /// Mean of one channel over the square of side `2 * radius + 1` centered on
+    /// (x, y), clipped to the image
+    ///
+    /// The building block of box filters and adaptive thresholding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x, y) is outside the image or `channel` is out of range.
+    pub fn box_mean(&self, x: u32, y: u32, radius: u32, channel: usize) -> f64 {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) out of bounds", x, y);
+        let x0 = x.saturating_sub(radius);
+        let y0 = y.saturating_sub(radius);
+        let x1 = x.saturating_add(radius).min(self.width - 1) + 1;
+        let y1 = y.saturating_add(radius).min(self.height - 1) + 1;
+        self.mean(Rect::new(x0, y0, x1 - x0, y1 - y0), channel)
+    }
+
+    /// Convert to an F32 image of inclusive sums (entry (x, y) covers pixels 0..=x, 0..=y)
+    ///
+    /// F32 loses precision on large images; prefer [`IntegralImage::sum`] for exact queries.
+    pub fn to_image(&self) -> Image {
+        let pixel_format = match self.channel_count {
+            1 => PixelFormat::R,
+            2 => PixelFormat::RG,
+            3 => PixelFormat::RGB,
+            _ => PixelFormat::RGBA,
+        };
+        let mut image = Image::new(self.width, self.height, pixel_format, ComponentType::F32);
+        let channel_count = self.channel_count;
+        let width = self.width;
+
+        for (index, sample) in image.data_mut().chunks_exact_mut(4).enumerate() {
+            let pixel = index / channel_count;
+            let (x, y) = ((pixel % width as usize) as u32, (pixel / width as usize) as u32);
+            let value = self.at(x + 1, y + 1, index % channel_count) as f32;
+            sample.copy_from_slice(&value.to_le_bytes());
+        }
+        image
+    }
+}
+
+impl Image {
+    /// Compute the summed-area table of the image
+    ///
+    /// Samples are normalized (integer components map to `0.0..=1.0`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, Rect};
+    ///
+    /// let image = GalaxyImage::load_from_file("albedo.png")?;
+    /// let table = image.integral_image();
+    /// let mean_red = table.mean(Rect::new(16, 16, 64, 64), 0);
+    /// let local = table.box_mean(100, 40, 7, 0); // 15x15 box filter tap
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn integral_image(&self) -> IntegralImage {
+        let channel_count = self.pixel_format().channel_count();
+        let width = self.width() as usize;
+        let stride = (width + 1) * channel_count;
+        let mut sums = vec![0f64; stride * (self.height() as usize + 1)];
+
+        let row_bytes = width * self.bytes_per_pixel();
+        let mut values = vec![0f32; width * channel_count];
+        let mut row_sum = vec![0f64; channel_count];
+        if row_bytes > 0 {
+            for (y, row) in self.data().chunks_exact(row_bytes).enumerate() {
+                read_normalized_slice(row, self.component_type(), &mut values);
+                row_sum.fill(0.0);
+
+                // Entry = running sum of this row + entry of the previous row
+                let (previous, current) = sums.split_at_mut((y + 1) * stride);
+                let previous = &previous[y * stride..];
+                for (index, &value) in values.iter().enumerate() {
+                    let channel = index % channel_count;
+                    row_sum[channel] += value as f64;
+                    let entry = index + channel_count;
+                    current[entry] = previous[entry] + row_sum[channel];
+                }
+            }
+        }
+
+        IntegralImage {
+            width: self.width(),
+            height: self.height(),
+            channel_count,
+            sums,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+/// Estimation of the encoding quality of JPEG files from their quantization tables
+use crate::{ImageError, ImageResult};
+
+/// Annex K luminance quantization table, natural order
+const STANDARD_LUMINANCE: [u16; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61,
+    12, 12, 14, 19, 26, 58, 60, 55,
+    14, 13, 16, 24, 40, 57, 69, 56,
+    14, 17, 22, 29, 51, 87, 80, 62,
+    18, 22, 37, 56, 68, 109, 103, 77,
+    24, 35, 55, 64, 81, 104, 113, 92,
+    49, 64, 78, 87, 103, 121, 120, 101,
+    72, 92, 95, 98, 112, 100, 103, 99,
+];
+
+/// Annex K chrominance quantization table, natural order
+const STANDARD_CHROMINANCE: [u16; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99,
+    18, 21, 26, 66, 99, 99, 99, 99,
+    24, 26, 56, 99, 99, 99, 99, 99,
+    47, 66, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+/// Natural order position of each coefficient in zigzag order
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10,
+    17, 24, 32, 25, 18, 11, 4, 5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13, 6, 7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// Estimated encoding quality of a JPEG file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JpegQuality {
+    /// Closest quality on the libjpeg 1-100 scale
+    pub quality: u8,
+
+    /// True when the tables are exactly the standard tables scaled for `quality`
+    ///
+    /// Encoders derived from libjpeg (including this crate's) produce exact
+    /// matches. Encoders with their own tables (Photoshop, mozjpeg, cameras)
+    /// only get an approximate quality.
+    pub exact: bool,
+}
+
+/// Standard table scaled the way libjpeg does for a quality setting
+fn scaled_table(standard: &[u16; 64], quality: u32) -> [u16; 64] {
+    let scale = if quality < 50 { 5000 / quality } else { 200 - quality * 2 };
+    standard.map(|value| ((value as u32 * scale + 50) / 100).clamp(1, 255) as u16)
+}
+
+/// Read the quantization tables of a JPEG file, indexed by table id, in natural order
+fn read_quantization_tables(bytes: &[u8]) -> ImageResult<[Option<[u16; 64]>; 4]> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return Err(ImageError::UnsupportedFormat("Not a JPEG file".to_string()));
+    }
+    let truncated = || ImageError::Other("Truncated JPEG segment".to_string());
+
+    let mut tables = [None; 4];
+    let mut position = 2;
+    loop {
+        // Skip fill bytes before the marker code
+        while bytes.get(position) == Some(&0xFF) && bytes.get(position + 1) == Some(&0xFF) {
+            position += 1;
+        }
+        let marker = match bytes.get(position..position + 2) {
+            Some(&[0xFF, marker]) => marker,
+            Some(_) => return Err(ImageError::Other(format!("Invalid JPEG marker at offset {}", position))),
+            None => break,
+        };
+        // Tables needed to decode the first scan come before it
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let length = bytes.get(position + 2..position + 4).ok_or_else(truncated)?;
+        let length = u16::from_be_bytes([length[0], length[1]]) as usize;
+        let segment = bytes.get(position + 4..position + 2 + length.max(2)).ok_or_else(truncated)?;
+
+        if marker == 0xDB {
+            let mut rest = segment;
+            while let Some((&header, data)) = rest.split_first() {
+                let wide = header >> 4 != 0;
+                let id = (header & 0x0F) as usize;
+                let size = if wide { 128 } else { 64 };
+                if id >= tables.len() || data.len() < size {
+                    return Err(ImageError::Other("Invalid JPEG quantization table".to_string()));
+                }
+                let mut table = [0u16; 64];
+                for (zigzag, &natural) in ZIGZAG.iter().enumerate() {
+                    table[natural] = if wide {
+                        u16::from_be_bytes([data[zigzag * 2], data[zigzag * 2 + 1]])
+                    } else {
+                        data[zigzag] as u16
+                    };
+                }
+                tables[id] = Some(table);
+                rest = &data[size..];
+            }
+        }
+        position += 2 + length;
+    }
+    Ok(tables)
+}
+
+pub(crate) fn estimate_jpeg_quality(bytes: &[u8]) -> ImageResult<JpegQuality> {
+    let tables = read_quantization_tables(bytes)?;
+    let luminance = tables[0].ok_or_else(|| ImageError::Other("JPEG has no luminance quantization table".to_string()))?;
+    let chrominance = tables[1];
+
+    // Quality whose scaled standard tables are closest to the file's tables
+    let mut best = (u64::MAX, 0);
+    for quality in 1..=100 {
+        let mut error = table_error(&luminance, &scaled_table(&STANDARD_LUMINANCE, quality));
+        if let Some(chrominance) = &chrominance {
+            error += table_error(chrominance, &scaled_table(&STANDARD_CHROMINANCE, quality));
+        }
+        if error < best.0 {
+            best = (error, quality);
+        }
+    }
+
+    Ok(JpegQuality { quality: best.1 as u8, exact: best.0 == 0 })
+}
+
+fn table_error(actual: &[u16; 64], expected: &[u16; 64]) -> u64 {
+    actual
+        .iter()
+        .zip(expected)
+        .map(|(&a, &e)| (a.abs_diff(e) as u64).pow(2))
+        .sum()
+}
@@ -0,0 +1,61 @@
+/// Conversion of HDR and 16-bit images for formats that only store 8-bit samples
+use crate::color::linear_to_srgb;
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::tonemap::TonemapOperator;
+use crate::{ComponentType, Image, ImageFormat, LdrConversion};
+
+/// Check if a format can store a component type
+pub(crate) fn format_supports(format: ImageFormat, component_type: ComponentType) -> bool {
+    format.capabilities().component_types.contains(&component_type)
+}
+
+/// Convert an image to U8 following a conversion policy
+///
+/// Alpha is always clamped to `0.0..=1.0`; the policy applies to color channels,
+/// `tonemap` being the curve of [`LdrConversion::Tonemap`]. Non-finite values map to 0.
+pub(crate) fn to_ldr(image: &Image, conversion: LdrConversion, tonemap: TonemapOperator) -> Image {
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let alpha_channel = pixel_format.has_alpha().then(|| channel_count - 1);
+
+    let mut values = vec![0f32; image.size_bytes() / image.component_type().size_bytes()];
+    read_normalized_slice(image.data(), image.component_type(), &mut values);
+
+    // Normalize maps the brightest color sample to 1.0
+    let scale = match conversion {
+        LdrConversion::Normalize => {
+            let max = values
+                .chunks_exact(channel_count)
+                .flat_map(|pixel| {
+                    pixel
+                        .iter()
+                        .enumerate()
+                        .filter(|&(channel, _)| Some(channel) != alpha_channel)
+                        .map(|(_, &value)| value)
+                })
+                .filter(|value| value.is_finite())
+                .fold(0f32, f32::max);
+            if max > 0.0 { 1.0 / max } else { 1.0 }
+        }
+        _ => 1.0,
+    };
+
+    for pixel in values.chunks_exact_mut(channel_count) {
+        for (channel, value) in pixel.iter_mut().enumerate() {
+            let v = if value.is_finite() { *value } else { 0.0 };
+            *value = if Some(channel) == alpha_channel {
+                v
+            } else {
+                match conversion {
+                    LdrConversion::Error | LdrConversion::Clamp => v,
+                    LdrConversion::Normalize => v * scale,
+                    LdrConversion::Tonemap => linear_to_srgb(tonemap.apply(v)),
+                }
+            };
+        }
+    }
+
+    let mut output = Image::new(image.width(), image.height(), pixel_format, ComponentType::U8);
+    write_normalized_slice(output.data_mut(), ComponentType::U8, &values);
+    output
+}
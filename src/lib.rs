@@ -2,15 +2,28 @@
 //!
 //! Image loading and saving library for the Galaxy3D engine.
 //!
-//! Supports PNG, BMP, JPEG, and EXR (HDR) formats with automatic format detection.
+//! Supports PNG, BMP, JPEG, TGA, EXR, Radiance HDR, TIFF, PNM and PFM formats with automatic format detection,
+//! and loads DDS textures (BC1-BC5, BC7 and uncompressed), WebP images (lossy and lossless),
+//! animated GIFs, ICO/CUR icons, the composite image of PSD files and, with the `jxl` and `avif`
+//! features, JPEG XL and AVIF images.
 //!
 //! ## Features
 //!
-//! - **Multiple formats**: PNG, BMP, JPEG, EXR (HDR)
+//! - **Multiple formats**: PNG, BMP, JPEG, TGA, EXR (HDR), Radiance HDR, TIFF, PNM, PFM, plus DDS, WebP, GIF, ICO/CUR, PSD, JPEG XL and AVIF (read-only)
 //! - **Automatic detection**: Magic byte recognition for format detection
 //! - **Simple API**: Manager/Factory pattern with `GalaxyImage`
 //! - **Flexible pixel formats**: RGB, RGBA, BGR, BGRA, Grayscale
 //! - **Type safety**: Strongly typed pixel formats and component types
+//! - **GPU upload preparation**: RGBA expansion, row pitch padding and mips in one call
+//! - **Image cache** (feature `cache`): LRU cache with a byte budget for shared textures
+//! - **Hot reload** (feature `watch`): Reload images when their file changes
+//! - **Async I/O** (feature `async`): Load and save on the tokio runtime without blocking tasks
+//! - **Tracing** (feature `tracing`): Spans and debug events around load/save
+//! - **image crate interop** (feature `image`): Conversions with `DynamicImage` and `ImageBuffer`
+//! - **rgb / imgref interop** (feature `rgb`): Typed pixel slices and `ImgVec` conversions
+//! - **ndarray interop** (feature `ndarray`): `Array3<f32>` conversions for numeric analysis
+//! - **JPEG XL** (feature `jxl`): Decoding to U8/U16/F32 images
+//! - **AVIF** (feature `avif`): Decoding of 8/10/12-bit images to RGB/RGBA U8/U16
 //!
 //! ## Example
 //!
@@ -29,13 +42,135 @@ mod error;
 mod component_type;
 mod pixel_format;
 mod image_format;
+mod format_capabilities;
+mod save_options;
+mod load_options;
 mod image;
+mod alpha;
+mod bit_mask;
+mod roi;
+mod planar;
+mod channels;
+mod swizzle;
+mod tiled;
+mod transform;
+mod composite;
+mod typed;
+mod component_slice;
+mod row_view;
+mod buffer_pool;
+mod image_info;
+mod sample;
+mod half_float;
+mod ldr;
+mod depth;
+mod convert;
+mod snorm;
+mod tonemap;
+mod false_color;
+mod adjust;
+mod lut;
+mod morphology;
+mod regions;
+mod demosaic;
+mod resample;
+mod resize;
+mod stats;
+mod integral;
+mod non_finite;
+mod pixel_access;
+mod sampler;
+mod color;
+mod icc;
+mod metadata;
+mod primaries;
+mod mipmap;
+mod toksvig;
+mod gpu;
 mod galaxy_image;
 mod loaders;
+mod jpeg_quality;
+mod dedup;
+mod thumbnail;
+mod font;
+mod contact_sheet;
+mod atlas;
+mod mip_preview;
+mod export;
+mod batch;
+mod sequence;
+mod animation;
+mod auto_format;
+mod size_estimate;
+mod pyramid;
+mod encoded_image;
+mod pool;
+mod instrument;
+mod atomic_write;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "image")]
+mod image_interop;
+#[cfg(feature = "rgb")]
+mod rgb_interop;
+#[cfg(feature = "ndarray")]
+mod ndarray_interop;
 
 pub use error::{ImageError, ImageResult};
 pub use component_type::ComponentType;
 pub use pixel_format::PixelFormat;
 pub use image_format::ImageFormat;
+pub use format_capabilities::{FormatCapabilities, FormatDescriptor};
+pub use save_options::{BmpBitDepth, BmpSaveOptions, ExrCompression, ExrSaveOptions, JpegSaveOptions, JpegSubsampling, LdrConversion, PngCompression, PngFilter, PngSaveOptions, PnmSaveOptions, SaveOptions, TgaSaveOptions, TiffCompression, TiffSaveOptions};
+pub use load_options::LoadOptions;
 pub use image::Image;
+pub use metadata::{ImageMetadata, Orientation};
+pub use alpha::AlphaMode;
+pub use bit_mask::{BitMask, MaskFormat};
+pub use roi::Rect;
+pub use planar::PlanarImage;
+pub use tiled::{TiledImage, TILE_SIZE};
+pub use composite::BlendMode;
+pub use swizzle::SwizzleSource;
+pub use typed::{Component, GrayImage16, GrayImage8, RgbImage8, RgbaImage16, RgbaImage8, RgbaImageF16, RgbaImageF32, TypedImage};
+pub use image_info::ImageInfo;
+pub use animation::{AnimatedImage, AnimationFrame, FrameDisposal};
+pub use jpeg_quality::JpegQuality;
+pub use stats::ChannelStats;
+pub use integral::IntegralImage;
+pub use non_finite::NonFiniteReport;
+pub use pixel_access::{Pixel, Rgba};
+pub use depth::Dither;
+pub use lut::{Lut3d, LutInterpolation};
+pub use resize::ResizeFilter;
+pub use sampler::{AddressMode, Sampler, SamplerFilter};
+pub use morphology::MorphShape;
+pub use regions::{ComponentLabels, ComponentStats, Connectivity};
+pub use demosaic::{BayerPattern, DemosaicMethod};
+pub use color::ColorSpace;
+pub use primaries::Primaries;
+pub use false_color::{FalseColorOptions, FalseColorStop, ZebraOptions};
+pub use tonemap::{BakeOptions, ExpandOptions, HdrExpansion, LdrEncoding, TonemapOperator};
+pub use gpu::{GpuImageDesc, GpuMipLevel, GpuRequirements, GpuUpload};
 pub use galaxy_image::GalaxyImage;
+pub use buffer_pool::BufferPoolStats;
+pub use dedup::ImageDedup;
+pub use export::ExportTarget;
+pub use batch::{ConvertJob, ConvertReport, ConvertedFile, ImageOperation};
+pub use contact_sheet::ContactSheetOptions;
+pub use atlas::{AtlasOptions, AtlasSprite, TextureAtlas};
+pub use mip_preview::MipPreviewOptions;
+pub use auto_format::SaveConstraints;
+pub use encoded_image::EncodedImage;
+pub use pool::ImageFuture;
+pub use pyramid::{pyramid_levels, PyramidOptions, PyramidTile, TileCoord, PYRAMID_MAGIC, PYRAMID_VERSION};
+#[cfg(feature = "cache")]
+pub use cache::{ImageCache, DEFAULT_CACHE_BUDGET};
+#[cfg(feature = "watch")]
+pub use watch::{ImageWatcher, WatchOptions};
+#[cfg(feature = "rgb")]
+pub use rgb_interop::RgbPixel;
@@ -0,0 +1,80 @@
+use crate::{ImageError, ImageResult};
+
+/// Default of [`LoadOptions::max_pixels`], a 16384x16384 image
+const DEFAULT_MAX_PIXELS: u64 = 1 << 28;
+
+/// Decoded bytes allowed per pixel of [`LoadOptions::max_pixels`], four 64-bit samples
+const MAX_BYTES_PER_PIXEL: u64 = 32;
+
+/// Options controlling how images are decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Store rows bottom-to-top, as expected by OpenGL-style texture origins
+    ///
+    /// Applied while rows are copied out of the decoder where the format allows
+    /// it (PNG, BMP, EXR), otherwise right after decoding (JPEG, interlaced PNG).
+    pub flip_y: bool,
+
+    /// Fail with an error when a float image (EXR, PFM) contains NaN or infinite samples
+    ///
+    /// Use [`Image::sanitize_non_finite`](crate::Image::sanitize_non_finite) to
+    /// repair such images instead of rejecting them.
+    pub reject_non_finite: bool,
+
+    /// Convert colors with an embedded ICC profile or gamma to sRGB (PNG)
+    ///
+    /// Applies [`Image::convert_to_srgb`](crate::Image::convert_to_srgb) after
+    /// decoding. Images with an unsupported ICC profile are loaded unconverted,
+    /// with the profile left in [`Image::metadata`](crate::Image::metadata).
+    pub convert_to_srgb: bool,
+
+    /// Rotate and mirror JPEG photos upright from their EXIF orientation
+    ///
+    /// Applies [`Image::apply_orientation`](crate::Image::apply_orientation)
+    /// before [`LoadOptions::flip_y`]. Without it the pixels are kept as stored
+    /// and the orientation is left in [`Image::metadata`](crate::Image::metadata).
+    pub auto_orient: bool,
+
+    /// Largest width times height accepted from a file header (default: 2^28)
+    ///
+    /// Every loader except BMP and EXR, whose decoders read their headers
+    /// themselves, rejects larger images with
+    /// [`ImageError::InvalidDimensions`] before allocating pixel buffers, so a
+    /// small corrupt file cannot request gigabytes; the frames of a GIF
+    /// animation count together. Use `u64::MAX` to accept any size.
+    pub max_pixels: u64,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            flip_y: false,
+            reject_non_finite: false,
+            convert_to_srgb: false,
+            auto_orient: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Check header dimensions against [`LoadOptions::max_pixels`]
+    pub(crate) fn check_dimensions(&self, width: u32, height: u32) -> ImageResult<()> {
+        if width as u64 * height as u64 > self.max_pixels {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+        Ok(())
+    }
+
+    /// Check the size of a decoded buffer against [`LoadOptions::max_pixels`]
+    /// pixels of four 64-bit samples, for layers, mips and extra samples
+    pub(crate) fn check_bytes(&self, bytes: u64) -> ImageResult<()> {
+        if bytes > self.max_pixels.saturating_mul(MAX_BYTES_PER_PIXEL) {
+            return Err(ImageError::Other(format!(
+                "{} decoded bytes exceed the limit of {} pixels",
+                bytes, self.max_pixels
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,675 @@
+use crate::{Image, ImageError, ImageInfo, ImageResult, LoadOptions};
+#[cfg(feature = "avif")]
+use crate::{ColorSpace, ComponentType, ImageFormat, PixelFormat};
+#[cfg(feature = "avif")]
+use crate::buffer_pool;
+#[cfg(feature = "avif")]
+use re_rav1d::pixel::YUVRange;
+#[cfg(feature = "avif")]
+use re_rav1d::{Decoder, Picture, PixelLayout, PlanarImageComponent, Settings};
+
+/// Auxiliary image types marking an alpha plane (AVIF, and the HEVC one older writers use)
+#[cfg(feature = "avif")]
+const ALPHA_URNS: [&[u8]; 2] = [b"urn:mpeg:mpegB:cicp:systems:auxiliary:alpha", b"urn:mpeg:hevc:2015:auxid:1"];
+
+/// Check if data starts with an ISO BMFF `ftyp` box listing the `avif` brand
+pub fn is_avif(data: &[u8]) -> bool {
+    if data.len() < 16 || &data[4..8] != b"ftyp" {
+        return false;
+    }
+    let size = (u32::from_be_bytes(data[0..4].try_into().unwrap()) as usize).clamp(16, data.len());
+    // Major brand, minor version, then the compatible brands
+    &data[8..12] == b"avif" || data[16..size].chunks_exact(4).any(|brand| brand == b"avif")
+}
+
+#[cfg(feature = "avif")]
+fn corrupt(message: &str) -> ImageError {
+    ImageError::Other(format!("Corrupt AVIF data: {}", message))
+}
+
+#[cfg(feature = "avif")]
+fn decode_error(error: re_rav1d::Error) -> ImageError {
+    ImageError::Other(format!("AVIF decoding error: {}", error))
+}
+
+/// Big-endian cursor over a box body
+#[cfg(feature = "avif")]
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+#[cfg(feature = "avif")]
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> ImageResult<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or_else(|| corrupt("truncated box"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> ImageResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> ImageResult<u16> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> ImageResult<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    /// Read an unsigned integer of 0, 4 or 8 bytes, as sized in `iloc`
+    fn uint(&mut self, size: u8) -> ImageResult<u64> {
+        match size {
+            0 => Ok(0),
+            4 => Ok(self.u32()? as u64),
+            8 => Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap())),
+            _ => Err(corrupt("invalid item location field size")),
+        }
+    }
+
+    /// Read an item ID, 16-bit in version 0 boxes and 32-bit otherwise
+    fn item_id(&mut self, version: u8) -> ImageResult<u32> {
+        if version == 0 {
+            Ok(self.u16()? as u32)
+        } else {
+            self.u32()
+        }
+    }
+
+    /// Read the version of a full box and skip its flags
+    fn version(&mut self) -> ImageResult<u8> {
+        let header = self.u32()?;
+        Ok((header >> 24) as u8)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos.min(self.data.len())..]
+    }
+}
+
+/// Split a sequence of boxes into (type, body) pairs
+#[cfg(feature = "avif")]
+fn parse_boxes(data: &[u8]) -> ImageResult<Vec<([u8; 4], &[u8])>> {
+    let mut boxes = Vec::new();
+    let mut reader = Reader::new(data);
+    while !reader.rest().is_empty() {
+        let start = reader.pos;
+        let size = reader.u32()? as u64;
+        let box_type: [u8; 4] = reader.bytes(4)?.try_into().unwrap();
+        let size = match size {
+            0 => (data.len() - start) as u64,
+            1 => reader.uint(8)?,
+            size => size,
+        };
+        let header = (reader.pos - start) as u64;
+        if size < header || size > (data.len() - start) as u64 {
+            return Err(corrupt("invalid box size"));
+        }
+        boxes.push((box_type, &data[reader.pos..start + size as usize]));
+        reader.pos = start + size as usize;
+    }
+    Ok(boxes)
+}
+
+/// Item property of the `ipco` box
+#[cfg(feature = "avif")]
+enum Property<'a> {
+    /// `ispe`: image size
+    Size { width: u32, height: u32 },
+    /// `av1C`: bit depth of the AV1 sequence
+    Av1Config { bit_depth: u8 },
+    /// `colr` of type `nclx`: CICP transfer characteristics, matrix coefficients and range
+    Nclx { transfer: u16, matrix: u16, full_range: bool },
+    /// `auxC`: auxiliary image type URN
+    AuxType(&'a [u8]),
+    Other,
+}
+
+#[cfg(feature = "avif")]
+impl<'a> Property<'a> {
+    fn parse(box_type: [u8; 4], body: &'a [u8]) -> ImageResult<Self> {
+        let mut reader = Reader::new(body);
+        Ok(match &box_type {
+            b"ispe" => {
+                reader.version()?;
+                Property::Size { width: reader.u32()?, height: reader.u32()? }
+            }
+            b"av1C" => {
+                let flags = reader.bytes(3)?[2];
+                let bit_depth = match (flags & 0x40 != 0, flags & 0x20 != 0) {
+                    (false, _) => 8,
+                    (true, false) => 10,
+                    (true, true) => 12,
+                };
+                Property::Av1Config { bit_depth }
+            }
+            b"colr" if reader.bytes(4)? == b"nclx" => {
+                let _primaries = reader.u16()?;
+                let transfer = reader.u16()?;
+                Property::Nclx { transfer, matrix: reader.u16()?, full_range: reader.u8()? & 0x80 != 0 }
+            }
+            b"auxC" => {
+                reader.version()?;
+                let urn = reader.rest();
+                Property::AuxType(&urn[..urn.iter().position(|&byte| byte == 0).unwrap_or(urn.len())])
+            }
+            _ => Property::Other,
+        })
+    }
+}
+
+/// Where the data of an item is stored (`iloc` entry)
+#[cfg(feature = "avif")]
+struct Location {
+    item: u32,
+    /// 0: file offsets, 1: offsets in the `idat` box
+    construction_method: u16,
+    base_offset: u64,
+    /// (offset, length) pairs; a length of 0 runs to the end of the source
+    extents: Vec<(u64, u64)>,
+}
+
+/// Item structure of the `meta` box of an AVIF file
+#[cfg(feature = "avif")]
+struct Container<'a> {
+    file: &'a [u8],
+    primary: u32,
+    /// (item ID, item type)
+    items: Vec<(u32, [u8; 4])>,
+    locations: Vec<Location>,
+    properties: Vec<Property<'a>>,
+    /// (item ID, 1-based property indices)
+    associations: Vec<(u32, Vec<usize>)>,
+    /// `auxl` references: (auxiliary item, master item)
+    auxiliaries: Vec<(u32, u32)>,
+    idat: &'a [u8],
+}
+
+#[cfg(feature = "avif")]
+impl<'a> Container<'a> {
+    fn parse(data: &'a [u8]) -> ImageResult<Self> {
+        if !is_avif(data) {
+            return Err(ImageError::UnsupportedFormat("Invalid AVIF header: missing avif brand".to_string()));
+        }
+        let (_, meta) = parse_boxes(data)?
+            .into_iter()
+            .find(|(box_type, _)| box_type == b"meta")
+            .ok_or_else(|| corrupt("missing meta box"))?;
+
+        let mut container = Container {
+            file: data,
+            primary: 0,
+            items: Vec::new(),
+            locations: Vec::new(),
+            properties: Vec::new(),
+            associations: Vec::new(),
+            auxiliaries: Vec::new(),
+            idat: &[],
+        };
+        let mut primary = None;
+        for (box_type, body) in parse_boxes(meta.get(4..).ok_or_else(|| corrupt("truncated meta box"))?)? {
+            let mut reader = Reader::new(body);
+            match &box_type {
+                b"pitm" => {
+                    let version = reader.version()?;
+                    primary = Some(reader.item_id(version)?);
+                }
+                b"iinf" => {
+                    let version = reader.version()?;
+                    let _entry_count = if version == 0 { reader.u16()? as u32 } else { reader.u32()? };
+                    for (_, entry) in parse_boxes(reader.rest())? {
+                        let mut reader = Reader::new(entry);
+                        let version = reader.version()?;
+                        // Versions 0 and 1 predate item types
+                        if version >= 2 {
+                            let id = if version == 2 { reader.u16()? as u32 } else { reader.u32()? };
+                            let _protection_index = reader.u16()?;
+                            container.items.push((id, reader.bytes(4)?.try_into().unwrap()));
+                        }
+                    }
+                }
+                b"iloc" => container.locations = Self::parse_locations(&mut reader)?,
+                b"iref" => {
+                    let version = reader.version()?;
+                    for (reference_type, references) in parse_boxes(reader.rest())? {
+                        let mut reader = Reader::new(references);
+                        let from = reader.item_id(version)?;
+                        for _ in 0..reader.u16()? {
+                            let to = reader.item_id(version)?;
+                            if &reference_type == b"auxl" {
+                                container.auxiliaries.push((from, to));
+                            }
+                        }
+                    }
+                }
+                b"iprp" => {
+                    for (box_type, body) in parse_boxes(body)? {
+                        match &box_type {
+                            b"ipco" => {
+                                for (property_type, property) in parse_boxes(body)? {
+                                    container.properties.push(Property::parse(property_type, property)?);
+                                }
+                            }
+                            b"ipma" => container.associations.extend(Self::parse_associations(&mut Reader::new(body))?),
+                            _ => {}
+                        }
+                    }
+                }
+                b"idat" => container.idat = body,
+                _ => {}
+            }
+        }
+        container.primary = primary.ok_or_else(|| corrupt("missing primary item"))?;
+
+        let item_type = container.items.iter().find(|(id, _)| *id == container.primary).map(|(_, item_type)| *item_type);
+        match item_type.as_ref() {
+            Some(b"av01") => Ok(container),
+            Some(b"grid") => Err(ImageError::UnsupportedFormat("AVIF grid images are not supported".to_string())),
+            Some(item_type) => Err(ImageError::UnsupportedFormat(format!(
+                "Unsupported AVIF item type '{}'",
+                String::from_utf8_lossy(item_type)
+            ))),
+            None => Err(corrupt("missing primary item info")),
+        }
+    }
+
+    fn parse_locations(reader: &mut Reader) -> ImageResult<Vec<Location>> {
+        let version = reader.version()?;
+        let sizes = reader.u16()?;
+        let (offset_size, length_size) = ((sizes >> 12) as u8, (sizes >> 8 & 0xF) as u8);
+        let base_offset_size = (sizes >> 4 & 0xF) as u8;
+        let index_size = if version >= 1 { (sizes & 0xF) as u8 } else { 0 };
+        let count = if version < 2 { reader.u16()? as u32 } else { reader.u32()? };
+
+        let mut locations = Vec::new();
+        for _ in 0..count {
+            let item = if version < 2 { reader.u16()? as u32 } else { reader.u32()? };
+            let construction_method = if version >= 1 { reader.u16()? & 0xF } else { 0 };
+            let _data_reference_index = reader.u16()?;
+            let base_offset = reader.uint(base_offset_size)?;
+            let extent_count = reader.u16()?;
+            let mut extents = Vec::new();
+            for _ in 0..extent_count {
+                reader.uint(index_size)?;
+                extents.push((reader.uint(offset_size)?, reader.uint(length_size)?));
+            }
+            locations.push(Location { item, construction_method, base_offset, extents });
+        }
+        Ok(locations)
+    }
+
+    fn parse_associations(reader: &mut Reader) -> ImageResult<Vec<(u32, Vec<usize>)>> {
+        let header = reader.u32()?;
+        let (version, large_indices) = ((header >> 24) as u8, header & 1 != 0);
+        let count = reader.u32()?;
+        let mut associations = Vec::new();
+        for _ in 0..count {
+            let item = reader.item_id(version)?;
+            let mut indices = Vec::new();
+            for _ in 0..reader.u8()? {
+                // The top bit flags essential properties
+                let index = if large_indices { reader.u16()? as usize & 0x7FFF } else { reader.u8()? as usize & 0x7F };
+                indices.push(index);
+            }
+            associations.push((item, indices));
+        }
+        Ok(associations)
+    }
+
+    /// Find the first property of an item matching `select`
+    fn property<T>(&self, item: u32, select: impl Fn(&Property<'a>) -> Option<T>) -> Option<T> {
+        self.associations
+            .iter()
+            .filter(|(id, _)| *id == item)
+            .flat_map(|(_, indices)| indices)
+            .filter_map(|&index| self.properties.get(index.checked_sub(1)?))
+            .find_map(select)
+    }
+
+    /// Size of the primary item from its `ispe` property
+    fn size(&self) -> Option<(u32, u32)> {
+        self.property(self.primary, |property| match property {
+            Property::Size { width, height } => Some((*width, *height)),
+            _ => None,
+        })
+    }
+
+    /// Find the alpha plane attached to the primary item
+    fn alpha_item(&self) -> Option<u32> {
+        self.auxiliaries
+            .iter()
+            .filter(|(_, master)| *master == self.primary)
+            .map(|(auxiliary, _)| *auxiliary)
+            .find(|&auxiliary| {
+                self.property(auxiliary, |property| match property {
+                    Property::AuxType(urn) => Some(ALPHA_URNS.contains(urn)),
+                    _ => None,
+                }) == Some(true)
+            })
+    }
+
+    /// Concatenate the extents of an item
+    fn item_data(&self, item: u32) -> ImageResult<Vec<u8>> {
+        let location = self
+            .locations
+            .iter()
+            .find(|location| location.item == item)
+            .ok_or_else(|| corrupt("missing item location"))?;
+        let source = match location.construction_method {
+            0 => self.file,
+            1 => self.idat,
+            method => {
+                return Err(ImageError::UnsupportedFormat(format!("Unsupported AVIF item construction method {}", method)));
+            }
+        };
+        let mut data = Vec::new();
+        for &(offset, length) in &location.extents {
+            let start = location.base_offset.checked_add(offset).ok_or_else(|| corrupt("invalid item offset"))?;
+            let end = if length == 0 { source.len() as u64 } else { start.saturating_add(length) };
+            let extent = usize::try_from(start)
+                .ok()
+                .zip(usize::try_from(end).ok())
+                .and_then(|(start, end)| source.get(start..end))
+                .ok_or_else(|| ImageError::Other("Truncated AVIF file".to_string()))?;
+            data.extend_from_slice(extent);
+        }
+        Ok(data)
+    }
+}
+
+/// Output layout: RGB or RGBA, U8 for 8-bit files and U16 for 10/12-bit ones
+#[cfg(feature = "avif")]
+fn layout(bit_depth: u8, has_alpha: bool) -> (PixelFormat, ComponentType) {
+    let pixel_format = if has_alpha { PixelFormat::RGBA } else { PixelFormat::RGB };
+    let component_type = if bit_depth > 8 { ComponentType::U16 } else { ComponentType::U8 };
+    (pixel_format, component_type)
+}
+
+/// Decode a single AV1 frame
+#[cfg(feature = "avif")]
+fn decode_av1(obus: Vec<u8>) -> ImageResult<Picture> {
+    let mut settings = Settings::new();
+    settings.set_max_frame_delay(1);
+    // Only the highest quality layer is an image
+    settings.set_all_layers(false);
+    let mut decoder = Decoder::with_settings(&settings).map_err(decode_error)?;
+
+    let mut sent = decoder.send_data(obus, None, None, None);
+    loop {
+        let pending = match sent {
+            Ok(()) => false,
+            Err(re_rav1d::Error::Again) => true,
+            Err(error) => return Err(decode_error(error)),
+        };
+        match decoder.get_picture() {
+            Ok(picture) => return Ok(picture),
+            Err(re_rav1d::Error::Again) if pending => sent = decoder.send_pending_data(),
+            Err(re_rav1d::Error::Again) => return Err(corrupt("no frame in AV1 data")),
+            Err(error) => return Err(decode_error(error)),
+        }
+    }
+}
+
+/// Samples of one plane of a decoded frame
+#[cfg(feature = "avif")]
+struct Plane {
+    data: re_rav1d::Plane,
+    /// Row stride in bytes
+    stride: usize,
+    /// Samples are stored as 16-bit (10/12-bit streams) instead of 8-bit
+    wide: bool,
+}
+
+#[cfg(feature = "avif")]
+impl Plane {
+    fn new(picture: &Picture, component: PlanarImageComponent) -> Self {
+        Plane {
+            data: picture.plane(component),
+            stride: picture.stride(component) as usize,
+            wide: picture.bit_depth() > 8,
+        }
+    }
+
+    fn sample(&self, x: usize, y: usize) -> f32 {
+        if self.wide {
+            let offset = y * self.stride + x * 2;
+            u16::from_ne_bytes([self.data[offset], self.data[offset + 1]]) as f32
+        } else {
+            self.data[y * self.stride + x] as f32
+        }
+    }
+}
+
+/// Neighbour and weight of a subsampled chroma position
+///
+/// Chroma samples sit between two luma samples, so each luma position blends
+/// the nearest chroma sample (3/4) with the next one on its side (1/4).
+#[cfg(feature = "avif")]
+fn chroma_taps(position: usize, subsampled: bool, len: usize) -> (usize, usize, f32) {
+    if !subsampled {
+        return (position, position, 1.0);
+    }
+    let near = (position / 2).min(len - 1);
+    let far = if position.is_multiple_of(2) { near.saturating_sub(1) } else { (near + 1).min(len - 1) };
+    (near, far, 0.75)
+}
+
+/// Color space tag of a CICP transfer characteristic (sRGB unless linear, PQ or HLG)
+#[cfg(feature = "avif")]
+fn transfer_color_space(transfer: u16) -> ColorSpace {
+    match transfer {
+        8 => ColorSpace::Linear,
+        16 => ColorSpace::Pq,
+        18 => ColorSpace::Hlg,
+        _ => ColorSpace::Srgb,
+    }
+}
+
+/// Red and blue luma weights (Kr, Kb) of a CICP YCbCr matrix
+///
+/// Unspecified and unsupported matrices use BT.601, as libavif does.
+#[cfg(feature = "avif")]
+fn matrix_weights(matrix: u16) -> (f32, f32) {
+    match matrix {
+        1 => (0.2126, 0.0722),
+        4 => (0.30, 0.11),
+        7 => (0.212, 0.087),
+        9 | 10 => (0.2627, 0.0593),
+        _ => (0.299, 0.114),
+    }
+}
+
+/// Normalize the samples of a plane to 0..1, expanding limited range
+#[cfg(feature = "avif")]
+fn expand_range(bit_depth: usize, full_range: bool) -> impl Fn(f32) -> f32 {
+    let max = ((1u32 << bit_depth) - 1) as f32;
+    let (offset, scale) = if full_range { (0.0, max) } else { ((16 << (bit_depth - 8)) as f32, (219 << (bit_depth - 8)) as f32) };
+    move |sample| (sample - offset) / scale
+}
+
+#[cfg(feature = "avif")]
+fn bit_depth(picture: &Picture) -> usize {
+    picture.bits_per_component().map_or(8, |bits| bits.0)
+}
+
+/// Read AVIF header information without decoding pixels
+///
+/// The size comes from the `ispe` property and the bit depth from the AV1
+/// configuration of the primary image.
+#[cfg(feature = "avif")]
+pub fn probe_avif(data: &[u8]) -> ImageResult<ImageInfo> {
+    let container = Container::parse(data)?;
+    let (width, height) = container.size().ok_or_else(|| corrupt("missing image size"))?;
+    let bit_depth = container
+        .property(container.primary, |property| match property {
+            Property::Av1Config { bit_depth } => Some(*bit_depth),
+            _ => None,
+        })
+        .ok_or_else(|| corrupt("missing AV1 configuration"))?;
+    let (pixel_format, component_type) = layout(bit_depth, container.alpha_item().is_some());
+    Ok(ImageInfo {
+        format: ImageFormat::Avif,
+        width,
+        height,
+        pixel_format,
+        component_type,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Load the primary image of an AVIF file (feature `avif`)
+///
+/// 8-bit files load as U8 and 10/12-bit files as U16 (rescaled to the full
+/// range), RGBA when the file has an alpha plane and RGB otherwise; gray
+/// images are expanded to RGB. YUV is converted with the matrix and range of
+/// the `colr` box (or of the AV1 stream), and subsampled chroma is upsampled
+/// bilinearly. The image is tagged with the color space of the CICP transfer
+/// characteristics (PQ and HLG for HDR files, sRGB otherwise). Rotation, mirroring and cropping properties are not applied,
+/// nor is the ICC profile; grid images and image sequences without a primary
+/// image are rejected.
+#[cfg(feature = "avif")]
+pub fn load_avif(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let container = Container::parse(data)?;
+    if let Some((width, height)) = container.size() {
+        options.check_dimensions(width, height)?;
+    }
+    let color = decode_av1(container.item_data(container.primary)?)?;
+    let alpha = match container.alpha_item() {
+        Some(item) => Some(decode_av1(container.item_data(item)?)?),
+        None => None,
+    };
+
+    let (width, height) = (color.width() as usize, color.height() as usize);
+    let bits = bit_depth(&color);
+    let (pixel_format, component_type) = layout(bits as u8, alpha.is_some());
+    if let Some(alpha) = &alpha {
+        if alpha.width() != color.width() || alpha.height() != color.height() {
+            return Err(corrupt("alpha plane size does not match the image"));
+        }
+    }
+
+    let (transfer, matrix, full_range) = container
+        .property(container.primary, |property| match property {
+            Property::Nclx { transfer, matrix, full_range } => Some((*transfer, *matrix, *full_range)),
+            _ => None,
+        })
+        .unwrap_or((
+            color.transfer_characteristic() as u16,
+            color.matrix_coefficients() as u16,
+            color.color_range() == YUVRange::Full,
+        ));
+    let luma = Plane::new(&color, PlanarImageComponent::Y);
+    let chroma = match color.pixel_layout() {
+        PixelLayout::I400 => None,
+        layout => Some((
+            Plane::new(&color, PlanarImageComponent::U),
+            Plane::new(&color, PlanarImageComponent::V),
+            layout != PixelLayout::I444,
+            layout == PixelLayout::I420,
+        )),
+    };
+    let chroma_width = width.div_ceil(2);
+    let chroma_height = height.div_ceil(2);
+    let normalize = expand_range(bits, full_range);
+    let max = ((1u32 << bits) - 1) as f32;
+    let chroma_scale = if full_range { max } else { (224 << (bits - 8)) as f32 };
+    let chroma_offset = (1u32 << (bits - 1)) as f32;
+    let (kr, kb) = matrix_weights(matrix);
+    let alpha_plane = alpha.as_ref().map(|alpha| {
+        (Plane::new(alpha, PlanarImageComponent::Y), expand_range(bit_depth(alpha), alpha.color_range() == YUVRange::Full))
+    });
+
+    let channels = pixel_format.channel_count();
+    let mut output = buffer_pool::take(width * height * channels * component_type.size_bytes());
+    let mut store = |value: f32| match component_type {
+        ComponentType::U8 => output.push((value.clamp(0.0, 1.0) * 255.0 + 0.5) as u8),
+        _ => output.extend_from_slice(&((value.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16).to_le_bytes()),
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let y_sample = luma.sample(x, y);
+            let [r, g, b] = match &chroma {
+                None => {
+                    let gray = normalize(y_sample);
+                    [gray, gray, gray]
+                }
+                Some((u, v, horizontal, vertical)) => {
+                    let (columns, rows) = (
+                        if *horizontal { chroma_width } else { width },
+                        if *vertical { chroma_height } else { height },
+                    );
+                    let (x_near, x_far, x_weight) = chroma_taps(x, *horizontal, columns);
+                    let (y_near, y_far, y_weight) = chroma_taps(y, *vertical, rows);
+                    let upsample = |plane: &Plane| {
+                        let near = plane.sample(x_near, y_near) * x_weight + plane.sample(x_far, y_near) * (1.0 - x_weight);
+                        let far = plane.sample(x_near, y_far) * x_weight + plane.sample(x_far, y_far) * (1.0 - x_weight);
+                        near * y_weight + far * (1.0 - y_weight)
+                    };
+                    let (u_sample, v_sample) = (upsample(u), upsample(v));
+                    match matrix {
+                        // GBR: Y carries green, U blue and V red
+                        0 => [normalize(v_sample), normalize(y_sample), normalize(u_sample)],
+                        // YCgCo: U carries Cg and V carries Co
+                        8 => {
+                            let (luma, cg, co) = (
+                                normalize(y_sample),
+                                (u_sample - chroma_offset) / chroma_scale,
+                                (v_sample - chroma_offset) / chroma_scale,
+                            );
+                            let t = luma - cg;
+                            [t + co, luma + cg, t - co]
+                        }
+                        _ => {
+                            let luma = normalize(y_sample);
+                            let cb = (u_sample - chroma_offset) / chroma_scale;
+                            let cr = (v_sample - chroma_offset) / chroma_scale;
+                            let r = luma + 2.0 * (1.0 - kr) * cr;
+                            let b = luma + 2.0 * (1.0 - kb) * cb;
+                            [r, (luma - kr * r - kb * b) / (1.0 - kr - kb), b]
+                        }
+                    }
+                }
+            };
+            store(r);
+            store(g);
+            store(b);
+            if let Some((plane, normalize)) = &alpha_plane {
+                store(normalize(plane.sample(x, y)));
+            }
+        }
+    }
+
+    let mut image = Image::from_raw(output, width as u32, height as u32, pixel_format, component_type);
+    image.set_color_space(transfer_color_space(transfer));
+    if options.flip_y {
+        image.flip_rows();
+    }
+    Ok(image)
+}
+
+#[cfg(not(feature = "avif"))]
+fn feature_disabled() -> ImageError {
+    ImageError::UnsupportedFormat("AVIF decoding requires the `avif` feature".to_string())
+}
+
+/// Read AVIF header information (requires the `avif` feature)
+#[cfg(not(feature = "avif"))]
+pub fn probe_avif(_data: &[u8]) -> ImageResult<ImageInfo> {
+    Err(feature_disabled())
+}
+
+/// Load an AVIF image (requires the `avif` feature)
+#[cfg(not(feature = "avif"))]
+pub fn load_avif(_data: &[u8], _options: &LoadOptions) -> ImageResult<Image> {
+    Err(feature_disabled())
+}
@@ -1,8 +1,105 @@
-use crate::{Image, ImageError, ImageResult, PixelFormat, ComponentType};
-use std::io::Cursor;
+use crate::{BmpBitDepth, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, ComponentType, SaveOptions};
+use crate::buffer_pool;
+use crate::row_view::RowView;
+use std::io::{Cursor, Read};
 
-pub fn load_bmp(data: &[u8]) -> ImageResult<Image> {
-    let img = bmp::from_reader(&mut Cursor::new(data))?;
+/// Bytes up to the bit count of the DIB header
+const BIT_COUNT_END: usize = 30;
+
+/// BITMAPFILEHEADER followed by a BITMAPINFOHEADER
+const INFO_HEADER_END: usize = 54;
+
+/// BITMAPFILEHEADER followed by a BITMAPV4HEADER, as written for 32-bit files
+const V4_HEADER_END: usize = 122;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+fn invalid_header() -> ImageError {
+    ImageError::UnsupportedFormat("Invalid BMP header".to_string())
+}
+
+/// Read the bit count from the start of a BMP file
+fn bit_count(header: &[u8]) -> ImageResult<u16> {
+    if header.len() < BIT_COUNT_END || header[0..2] != [0x42, 0x4D] {
+        return Err(invalid_header());
+    }
+    Ok(u16::from_le_bytes([header[28], header[29]]))
+}
+
+/// Red, green, blue and alpha masks of a 32-bit BMP, from the headers before the pixels
+///
+/// Masks follow a BITMAPINFOHEADER for `BI_BITFIELDS` and are part of V4/V5
+/// headers; `BI_RGB` files store BGRX with no alpha.
+fn masks_32(header: &[u8]) -> ImageResult<[u32; 4]> {
+    let u32_at = |offset: usize| header.get(offset..offset + 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+    let dib_size = u32_at(14).ok_or_else(invalid_header)? as usize;
+    match u32_at(30).ok_or_else(invalid_header)? {
+        BI_RGB => Ok([0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0]),
+        compression @ (BI_BITFIELDS | BI_ALPHABITFIELDS) => {
+            let mask = |index: usize| u32_at(INFO_HEADER_END + index * 4).ok_or_else(invalid_header);
+            // Alpha masks exist from the 56-byte V3 header on, or with BI_ALPHABITFIELDS
+            let alpha = if dib_size >= 56 || compression == BI_ALPHABITFIELDS { mask(3)? } else { 0 };
+            Ok([mask(0)?, mask(1)?, mask(2)?, alpha])
+        }
+        compression => Err(ImageError::UnsupportedFormat(format!("Unsupported 32-bit BMP compression {}", compression))),
+    }
+}
+
+/// Extract a masked channel as 8 bits
+fn masked_channel(pixel: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let shift = mask.trailing_zeros();
+    let bits = (mask >> shift).count_ones();
+    let value = (pixel & mask) >> shift;
+    if bits >= 8 {
+        (value >> (bits - 8)) as u8
+    } else {
+        (value * 255 / ((1 << bits) - 1)) as u8
+    }
+}
+
+/// Read BMP header information without decoding pixels
+///
+/// 32-bit images with an alpha mask are loaded as RGBA U8, others as RGB U8.
+pub fn probe_bmp(data: &[u8]) -> ImageResult<ImageInfo> {
+    // BITMAPFILEHEADER (14 bytes) followed by the DIB header width/height
+    let bit_count = bit_count(data)?;
+    let width = i32::from_le_bytes([data[18], data[19], data[20], data[21]]);
+    let height = i32::from_le_bytes([data[22], data[23], data[24], data[25]]);
+    let pixel_format = if bit_count == 32 && masks_32(data)?[3] != 0 { PixelFormat::RGBA } else { PixelFormat::RGB };
+
+    Ok(ImageInfo {
+        format: ImageFormat::Bmp,
+        width: width.unsigned_abs(),
+        // Negative height means top-down row order
+        height: height.unsigned_abs(),
+        pixel_format,
+        component_type: ComponentType::U8,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+pub fn load_bmp(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    load_bmp_from_reader(Cursor::new(data), options)
+}
+
+/// Decode a BMP image as it is read from `reader`
+///
+/// 1, 4, 8 and 24-bit files load as RGB U8. 32-bit files load as RGBA U8 when
+/// they have an alpha mask and as RGB U8 otherwise.
+pub fn load_bmp_from_reader<R: Read>(mut reader: R, options: &LoadOptions) -> ImageResult<Image> {
+    // The bmp crate does not read 32-bit files, which are decoded here
+    let mut header = Vec::with_capacity(V4_HEADER_END);
+    reader.by_ref().take(BIT_COUNT_END as u64).read_to_end(&mut header)?;
+    if bit_count(&header).ok() == Some(32) {
+        return load_bmp_32(header, reader, options);
+    }
+    let img = bmp::from_reader(&mut Cursor::new(header).chain(reader))?;
 
     let width = img.get_width();
     let height = img.get_height();
@@ -16,9 +113,10 @@ pub fn load_bmp(data: &[u8]) -> ImageResult<Image> {
     };
 
     // Convert bmp pixels to raw bytes
-    let mut data = Vec::with_capacity((width * height * 3) as usize);
+    let mut data = buffer_pool::take((width * height * 3) as usize);
 
-    for y in 0..height {
+    for row in 0..height {
+        let y = if options.flip_y { height - 1 - row } else { row };
         for x in 0..width {
             let pixel = img.get_pixel(x, y);
             data.push(pixel.b);
@@ -41,7 +139,51 @@ pub fn load_bmp(data: &[u8]) -> ImageResult<Image> {
     Ok(image)
 }
 
-pub fn save_bmp(image: &Image) -> ImageResult<Vec<u8>> {
+/// Decode a 32-bit BMP whose first `header` bytes were already read
+fn load_bmp_32<R: Read>(mut header: Vec<u8>, mut reader: R, options: &LoadOptions) -> ImageResult<Image> {
+    let pixel_offset = u32::from_le_bytes(header[10..14].try_into().unwrap()) as usize;
+    if pixel_offset < INFO_HEADER_END {
+        return Err(invalid_header());
+    }
+    reader.by_ref().take((pixel_offset - header.len()) as u64).read_to_end(&mut header)?;
+    let masks = masks_32(&header)?;
+
+    let width = i32::from_le_bytes(header[18..22].try_into().unwrap());
+    let height = i32::from_le_bytes(header[22..26].try_into().unwrap());
+    if width <= 0 || height == 0 {
+        return Err(ImageError::InvalidDimensions { width: width.unsigned_abs(), height: height.unsigned_abs() });
+    }
+    // Negative height means top-down row order
+    let top_down = (height < 0) != options.flip_y;
+    let (width, height) = (width as usize, height.unsigned_abs() as usize);
+
+    // Read the rows before allocating the output, so a corrupt header cannot request more than the file holds
+    let size = (width * 4) as u64 * height as u64;
+    let mut stored = Vec::new();
+    reader.take(size).read_to_end(&mut stored)?;
+    if (stored.len() as u64) < size {
+        return Err(ImageError::Other(format!("Truncated BMP pixel data: {} bytes, expected {}", stored.len(), size)));
+    }
+
+    let pixel_format = if masks[3] != 0 { PixelFormat::RGBA } else { PixelFormat::RGB };
+    let channels = pixel_format.channel_count();
+    let mut data = buffer_pool::take(width * height * channels);
+    for row in 0..height {
+        let stored_y = if top_down { row } else { height - 1 - row };
+        for pixel in stored[stored_y * width * 4..(stored_y + 1) * width * 4].chunks_exact(4) {
+            let pixel = u32::from_le_bytes(pixel.try_into().unwrap());
+            data.extend(masks[..channels].iter().map(|&mask| masked_channel(pixel, mask)));
+        }
+    }
+    Ok(Image::from_raw(data, width as u32, height as u32, pixel_format, ComponentType::U8))
+}
+
+/// Encode an image as a BMP, appending to `buffer`
+///
+/// Writes 24-bit files that drop alpha, or 32-bit BGRA files with a
+/// `BITMAPV4HEADER` when `options.bmp.bit_depth` is [`BmpBitDepth::Bits32`].
+/// 32-bit saves reject empty images with [`ImageError::InvalidDimensions`].
+pub fn save_bmp(image: &Image, options: &SaveOptions, buffer: &mut Vec<u8>) -> ImageResult<()> {
     // BMP only supports U8 component type
     if image.component_type() != ComponentType::U8 {
         return Err(ImageError::UnsupportedFormat(
@@ -49,90 +191,79 @@ pub fn save_bmp(image: &Image) -> ImageResult<Vec<u8>> {
         ));
     }
 
-    // BMP only supports RGB format (no alpha)
-    let image_rgb = match image.pixel_format() {
-        PixelFormat::RGB => image.clone(),
-        PixelFormat::BGR => {
-            let mut img = image.clone();
-            img.bgr_to_rgb();
-            img
-        }
-        PixelFormat::RGBA | PixelFormat::BGRA => {
-            // Strip alpha channel
-            let mut rgb_data = Vec::with_capacity((image.width() * image.height() * 3) as usize);
-            let channels = image.pixel_format().channel_count();
-            for pixel in image.data().chunks_exact(channels) {
-                if image.pixel_format() == PixelFormat::RGBA {
-                    rgb_data.push(pixel[0]); // R
-                    rgb_data.push(pixel[1]); // G
-                    rgb_data.push(pixel[2]); // B
-                } else {
-                    // BGRA -> RGB
-                    rgb_data.push(pixel[2]); // R
-                    rgb_data.push(pixel[1]); // G
-                    rgb_data.push(pixel[0]); // B
-                }
-            }
-            Image::from_raw(
-                rgb_data,
-                image.width(),
-                image.height(),
-                PixelFormat::RGB,
-                ComponentType::U8,
-            )
-        }
-        PixelFormat::R => {
-            // Convert grayscale to RGB
-            let mut rgb_data = Vec::with_capacity((image.width() * image.height() * 3) as usize);
-            for gray in image.data() {
-                rgb_data.push(*gray);
-                rgb_data.push(*gray);
-                rgb_data.push(*gray);
-            }
-            Image::from_raw(
-                rgb_data,
-                image.width(),
-                image.height(),
-                PixelFormat::RGB,
-                ComponentType::U8,
-            )
-        }
-        PixelFormat::RG => {
-            // Convert grayscale+alpha to RGB (ignore alpha)
-            let mut rgb_data = Vec::with_capacity((image.width() * image.height() * 3) as usize);
-            for pixel in image.data().chunks_exact(2) {
-                let gray = pixel[0];
-                rgb_data.push(gray);
-                rgb_data.push(gray);
-                rgb_data.push(gray);
-            }
-            Image::from_raw(
-                rgb_data,
-                image.width(),
-                image.height(),
-                PixelFormat::RGB,
-                ComponentType::U8,
-            )
-        }
+    if options.bmp.bit_depth == BmpBitDepth::Bits32 {
+        return save_bmp_32(image, buffer);
+    }
+
+    // 24-bit BMP has no alpha: reorder, strip or expand channels per row
+    let channels: &[usize] = match image.pixel_format() {
+        PixelFormat::R | PixelFormat::RG => &[0, 0, 0],
+        PixelFormat::RGB | PixelFormat::RGBA => &[0, 1, 2],
+        PixelFormat::BGR | PixelFormat::BGRA => &[2, 1, 0],
     };
+    let mut rows = RowView::new(image, channels, false);
 
     // Create BMP image
-    let mut bmp_img = bmp::Image::new(image_rgb.width(), image_rgb.height());
-
-    let rgb_data = image_rgb.data();
-    for y in 0..image_rgb.height() {
-        for x in 0..image_rgb.width() {
-            let idx = ((y * image_rgb.width() + x) * 3) as usize;
-            let r = rgb_data[idx];
-            let g = rgb_data[idx + 1];
-            let b = rgb_data[idx + 2];
-            bmp_img.set_pixel(x, y, bmp::Pixel::new(r, g, b));
+    let mut bmp_img = bmp::Image::new(image.width(), image.height());
+
+    for y in 0..image.height() {
+        for (x, rgb) in (0..).zip(rows.row(y).chunks_exact(3)) {
+            bmp_img.set_pixel(x, y, bmp::Pixel::new(rgb[0], rgb[1], rgb[2]));
         }
     }
 
-    // Write to buffer
-    let mut buffer = Vec::new();
-    bmp_img.to_writer(&mut buffer)?;
+    bmp_img.to_writer(buffer)?;
+    Ok(())
+}
+
+/// Write a bottom-up 32-bit BGRA BMP with a `BITMAPV4HEADER`
+fn save_bmp_32(image: &Image, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    let (width, height) = (image.width(), image.height());
+    let image_size = width as usize * height as usize * 4;
+    // Sizes are signed in the DIB header and the file size is 32-bit
+    if width == 0 || height == 0 || width > i32::MAX as u32 || height > i32::MAX as u32 || V4_HEADER_END + image_size > u32::MAX as usize {
+        return Err(ImageError::InvalidDimensions { width, height });
+    }
+    let push_u32 = |buffer: &mut Vec<u8>, value: u32| buffer.extend_from_slice(&value.to_le_bytes());
+
+    // BITMAPFILEHEADER
+    buffer.extend_from_slice(b"BM");
+    push_u32(buffer, (V4_HEADER_END + image_size) as u32);
+    push_u32(buffer, 0);
+    push_u32(buffer, V4_HEADER_END as u32);
 
-    Ok(buffer)
+    // BITMAPV4HEADER: 2835 pixels per meter (72 DPI), byte masks, sRGB color space
+    push_u32(buffer, (V4_HEADER_END - 14) as u32);
+    push_u32(buffer, width);
+    push_u32(buffer, height);
+    buffer.extend_from_slice(&1u16.to_le_bytes());
+    buffer.extend_from_slice(&32u16.to_le_bytes());
+    for value in [BI_BITFIELDS, image_size as u32, 2835, 2835, 0, 0, 0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+        push_u32(buffer, value);
+    }
+    buffer.extend_from_slice(b"BGRs");
+    // Unused CIE endpoints and gamma
+    buffer.extend_from_slice(&[0; 48]);
+
+    let (channels, has_alpha): (&[usize], bool) = match image.pixel_format() {
+        PixelFormat::R => (&[0, 0, 0], false),
+        PixelFormat::RG => (&[0, 0, 0, 1], true),
+        PixelFormat::RGB => (&[2, 1, 0], false),
+        PixelFormat::RGBA => (&[2, 1, 0, 3], true),
+        PixelFormat::BGR => (&[0, 1, 2], false),
+        PixelFormat::BGRA => (&[0, 1, 2, 3], true),
+    };
+    let mut rows = RowView::new(image, channels, false);
+    buffer.reserve(image_size);
+    for y in (0..height).rev() {
+        let row = rows.row(y);
+        if has_alpha {
+            buffer.extend_from_slice(row);
+        } else {
+            for bgr in row.chunks_exact(3) {
+                buffer.extend_from_slice(&[bgr[0], bgr[1], bgr[2], u8::MAX]);
+            }
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,879 @@
+use crate::{ColorSpace, ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat};
+use crate::buffer_pool;
+
+/// "DDS " followed by the 124-byte header
+const MAGIC: &[u8; 4] = b"DDS ";
+const HEADER_END: usize = 128;
+const DX10_HEADER_END: usize = HEADER_END + 20;
+
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x2_0000;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_FACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+const DX10_DIMENSION_TEXTURE3D: u32 = 4;
+const DX10_MISC_TEXTURECUBE: u32 = 0x4;
+/// `_SRGB` DXGI formats (R8G8B8A8, BC1-BC3, B8G8R8A8, B8G8R8X8, BC7)
+const DXGI_SRGB_FORMATS: [u32; 7] = [29, 72, 75, 78, 91, 93, 99];
+
+/// How the texels of a surface are stored
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Bc1,
+    Bc2,
+    Bc3,
+    Bc4 { signed: bool },
+    Bc5 { signed: bool },
+    Bc7,
+    /// Uncompressed texels already in an output layout
+    Plain { pixel_format: PixelFormat, component_type: ComponentType },
+    /// Uncompressed DX9 texels described by channel bit masks, expanded to U8
+    Masked { pixel_bytes: usize, masks: [u32; 4], pixel_format: PixelFormat },
+}
+
+impl Encoding {
+    fn from_fourcc(fourcc: &[u8]) -> ImageResult<Self> {
+        let plain = |pixel_format, component_type| Ok(Encoding::Plain { pixel_format, component_type });
+        match fourcc {
+            b"DXT1" => Ok(Encoding::Bc1),
+            // DXT2 and DXT4 hold premultiplied alpha, decoded as stored
+            b"DXT2" | b"DXT3" => Ok(Encoding::Bc2),
+            b"DXT4" | b"DXT5" => Ok(Encoding::Bc3),
+            b"ATI1" | b"BC4U" => Ok(Encoding::Bc4 { signed: false }),
+            b"BC4S" => Ok(Encoding::Bc4 { signed: true }),
+            b"ATI2" | b"BC5U" => Ok(Encoding::Bc5 { signed: false }),
+            b"BC5S" => Ok(Encoding::Bc5 { signed: true }),
+            // D3DFORMAT values stored in the FourCC field
+            [36, 0, 0, 0] => plain(PixelFormat::RGBA, ComponentType::U16),
+            [111, 0, 0, 0] => plain(PixelFormat::R, ComponentType::F16),
+            [113, 0, 0, 0] => plain(PixelFormat::RGBA, ComponentType::F16),
+            [114, 0, 0, 0] => plain(PixelFormat::R, ComponentType::F32),
+            [116, 0, 0, 0] => plain(PixelFormat::RGBA, ComponentType::F32),
+            _ => Err(ImageError::UnsupportedFormat(format!(
+                "Unsupported DDS FourCC {:?}",
+                String::from_utf8_lossy(fourcc)
+            ))),
+        }
+    }
+
+    fn from_dxgi(dxgi_format: u32) -> ImageResult<Self> {
+        use ComponentType::*;
+        use PixelFormat::*;
+
+        let plain = |pixel_format, component_type| Ok(Encoding::Plain { pixel_format, component_type });
+        // sRGB and typeless variants are decoded like their UNORM counterparts
+        match dxgi_format {
+            2 => plain(RGBA, F32),
+            6 => plain(RGB, F32),
+            10 => plain(RGBA, F16),
+            11 => plain(RGBA, U16),
+            13 => plain(RGBA, I16),
+            27..=29 => plain(RGBA, U8),
+            31 => plain(RGBA, I8),
+            41 => plain(R, F32),
+            54 => plain(R, F16),
+            56 => plain(R, U16),
+            58 => plain(R, I16),
+            61 => plain(R, U8),
+            63 => plain(R, I8),
+            87 | 90 | 91 => plain(BGRA, U8),
+            88 | 92 | 93 => Ok(Encoding::Masked {
+                pixel_bytes: 4,
+                masks: [0xFF_0000, 0xFF00, 0xFF, 0],
+                pixel_format: RGB,
+            }),
+            70..=72 => Ok(Encoding::Bc1),
+            73..=75 => Ok(Encoding::Bc2),
+            76..=78 => Ok(Encoding::Bc3),
+            79 | 80 => Ok(Encoding::Bc4 { signed: false }),
+            81 => Ok(Encoding::Bc4 { signed: true }),
+            82 | 83 => Ok(Encoding::Bc5 { signed: false }),
+            84 => Ok(Encoding::Bc5 { signed: true }),
+            94..=96 => Err(ImageError::UnsupportedFormat("BC6H DDS textures are not supported".to_string())),
+            97..=99 => Ok(Encoding::Bc7),
+            _ => Err(ImageError::UnsupportedFormat(format!("Unsupported DDS DXGI format {}", dxgi_format))),
+        }
+    }
+
+    /// Uncompressed DX9 layout from the pixel format flags and masks
+    fn from_masks(flags: u32, bit_count: u32, masks: [u32; 4]) -> ImageResult<Self> {
+        let [r, g, b, a] = masks;
+        let alpha = flags & DDPF_ALPHAPIXELS != 0 && a != 0;
+        let (pixel_format, masks) = if flags & DDPF_RGB != 0 {
+            let pixel_format = if alpha { PixelFormat::RGBA } else { PixelFormat::RGB };
+            (pixel_format, [r, g, b, a])
+        } else if flags & DDPF_LUMINANCE != 0 {
+            let pixel_format = if alpha { PixelFormat::RG } else { PixelFormat::R };
+            (pixel_format, [r, a, 0, 0])
+        } else {
+            return Err(ImageError::UnsupportedFormat("Unsupported DDS pixel format".to_string()));
+        };
+
+        let used = &masks[..pixel_format.channel_count()];
+        if !matches!(bit_count, 8 | 16 | 24 | 32) || used.contains(&0) {
+            return Err(ImageError::UnsupportedFormat(format!(
+                "Unsupported {}-bit DDS pixel format with masks {:08X?}",
+                bit_count, used
+            )));
+        }
+        Ok(Encoding::Masked { pixel_bytes: bit_count as usize / 8, masks, pixel_format })
+    }
+
+    /// Pixel format and component type of decoded surfaces
+    fn output(&self) -> (PixelFormat, ComponentType) {
+        let component_type = |signed: bool| if signed { ComponentType::I8 } else { ComponentType::U8 };
+        match *self {
+            Encoding::Bc1 | Encoding::Bc2 | Encoding::Bc3 | Encoding::Bc7 => (PixelFormat::RGBA, ComponentType::U8),
+            Encoding::Bc4 { signed } => (PixelFormat::R, component_type(signed)),
+            Encoding::Bc5 { signed } => (PixelFormat::RGB, component_type(signed)),
+            Encoding::Plain { pixel_format, component_type } => (pixel_format, component_type),
+            Encoding::Masked { pixel_format, .. } => (pixel_format, ComponentType::U8),
+        }
+    }
+
+    /// Bytes per 4x4 block for block-compressed encodings
+    fn block_bytes(&self) -> Option<usize> {
+        match self {
+            Encoding::Bc1 | Encoding::Bc4 { .. } => Some(8),
+            Encoding::Bc2 | Encoding::Bc3 | Encoding::Bc5 { .. } | Encoding::Bc7 => Some(16),
+            Encoding::Plain { .. } | Encoding::Masked { .. } => None,
+        }
+    }
+
+    /// Stored size of one surface, saturating for absurd dimensions
+    fn surface_bytes(&self, width: u32, height: u32) -> u64 {
+        let (width, height) = (width as u64, height as u64);
+        let (texels, bytes) = match (self.block_bytes(), self) {
+            (Some(block_bytes), _) => (width.div_ceil(4).saturating_mul(height.div_ceil(4)), block_bytes),
+            (None, Encoding::Masked { pixel_bytes, .. }) => (width.saturating_mul(height), *pixel_bytes),
+            (None, _) => {
+                let (pixel_format, component_type) = self.output();
+                (width.saturating_mul(height), pixel_format.channel_count() * component_type.size_bytes())
+            }
+        };
+        texels.saturating_mul(bytes as u64)
+    }
+}
+
+/// Parsed DDS headers
+struct Header {
+    width: u32,
+    height: u32,
+    mip_levels: usize,
+    /// Array elements times cube faces
+    layer_count: usize,
+    encoding: Encoding,
+    color_space: ColorSpace,
+    data_offset: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> ImageResult<Self> {
+        if !is_dds(data) || data.len() < HEADER_END {
+            return Err(ImageError::UnsupportedFormat("Invalid DDS header".to_string()));
+        }
+        let u32_at = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+        if u32_at(4) != 124 || u32_at(76) != 32 {
+            return Err(ImageError::UnsupportedFormat("Invalid DDS header".to_string()));
+        }
+
+        let (flags, height, width, mip_count) = (u32_at(8), u32_at(12), u32_at(16), u32_at(28));
+        let (pixel_flags, fourcc) = (u32_at(80), &data[84..88]);
+        let caps2 = u32_at(112);
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+
+        let volume_error = || ImageError::UnsupportedFormat("Volume DDS textures are not supported".to_string());
+        let (encoding, color_space, layer_count, data_offset) = if pixel_flags & DDPF_FOURCC != 0 && fourcc == b"DX10" {
+            if data.len() < DX10_HEADER_END {
+                return Err(ImageError::UnsupportedFormat("Truncated DDS DX10 header".to_string()));
+            }
+            if u32_at(132) == DX10_DIMENSION_TEXTURE3D {
+                return Err(volume_error());
+            }
+            let faces = if u32_at(136) & DX10_MISC_TEXTURECUBE != 0 { 6 } else { 1 };
+            let array_size = u32_at(140).max(1) as usize;
+            let dxgi_format = u32_at(128);
+            let color_space = if DXGI_SRGB_FORMATS.contains(&dxgi_format) { ColorSpace::Srgb } else { ColorSpace::Linear };
+            (Encoding::from_dxgi(dxgi_format)?, color_space, array_size * faces, DX10_HEADER_END)
+        } else {
+            if caps2 & DDSCAPS2_VOLUME != 0 {
+                return Err(volume_error());
+            }
+            // Cube maps may store a subset of the faces
+            let faces = if caps2 & DDSCAPS2_CUBEMAP != 0 {
+                (caps2 & DDSCAPS2_CUBEMAP_FACES).count_ones().max(1) as usize
+            } else {
+                1
+            };
+            let encoding = if pixel_flags & DDPF_FOURCC != 0 {
+                Encoding::from_fourcc(fourcc)?
+            } else {
+                Encoding::from_masks(pixel_flags, u32_at(88), [u32_at(92), u32_at(96), u32_at(100), u32_at(104)])?
+            };
+            // DX9 has no sRGB flag: color formats are assumed sRGB, BC4/BC5 hold data
+            let color_space = match encoding {
+                Encoding::Bc4 { .. } | Encoding::Bc5 { .. } => ColorSpace::Linear,
+                _ => ColorSpace::default_for(encoding.output().1),
+            };
+            (encoding, color_space, faces, HEADER_END)
+        };
+
+        // Writers often leave the flag unset; the count is capped to a full chain
+        let full_chain = (u32::BITS - width.max(height).leading_zeros()) as usize;
+        let mip_levels = if flags & DDSD_MIPMAPCOUNT != 0 || mip_count > 1 { mip_count as usize } else { 1 };
+
+        Ok(Self {
+            width,
+            height,
+            mip_levels: mip_levels.clamp(1, full_chain),
+            layer_count,
+            encoding,
+            color_space,
+            data_offset,
+        })
+    }
+
+    fn level_size(&self, level: usize) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    fn info(&self, width: u32, height: u32) -> ImageInfo {
+        let (pixel_format, component_type) = self.encoding.output();
+        ImageInfo {
+            format: ImageFormat::Dds,
+            width,
+            height,
+            pixel_format,
+            component_type,
+            layer_count: self.layer_count,
+            mip_levels: self.mip_levels,
+        }
+    }
+
+    /// Stored bytes of every mip level of one layer
+    fn layer_bytes(&self) -> u64 {
+        (0..self.mip_levels)
+            .map(|level| {
+                let (width, height) = self.level_size(level);
+                self.encoding.surface_bytes(width, height)
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Check that every layer and mip level fits in the data after the headers
+    ///
+    /// Guards the surface count taken from the header before it sizes any
+    /// allocation or loop.
+    fn check_surfaces(&self, data: &[u8]) -> ImageResult<()> {
+        let available = data.len().saturating_sub(self.data_offset) as u64;
+        let total = (self.layer_count as u64).checked_mul(self.layer_bytes());
+        match total {
+            Some(total) if total <= available => Ok(()),
+            _ => Err(ImageError::Other(format!(
+                "Truncated DDS data: {} layers of {} mip levels need more than the {} bytes stored",
+                self.layer_count, self.mip_levels, available
+            ))),
+        }
+    }
+
+    /// Stored bytes of one surface, indexed by layer then mip level
+    fn surface<'a>(&self, data: &'a [u8], layer: usize, level: usize) -> ImageResult<&'a [u8]> {
+        let layer_bytes = self.layer_bytes();
+        let level_offset: u64 = (0..level)
+            .map(|level| {
+                let (width, height) = self.level_size(level);
+                self.encoding.surface_bytes(width, height)
+            })
+            .sum();
+        let (width, height) = self.level_size(level);
+        let start = (self.data_offset as u64)
+            .saturating_add(layer_bytes.saturating_mul(layer as u64))
+            .saturating_add(level_offset);
+        let end = start.saturating_add(self.encoding.surface_bytes(width, height));
+        if end > data.len() as u64 {
+            return Err(ImageError::Other(format!(
+                "Truncated DDS data: layer {} mip level {} ends at byte {}, file has {}",
+                layer,
+                level,
+                end,
+                data.len()
+            )));
+        }
+        Ok(&data[start as usize..end as usize])
+    }
+
+    fn decode(&self, data: &[u8], layer: usize, level: usize, flip_y: bool) -> ImageResult<Image> {
+        let stored = self.surface(data, layer, level)?;
+        let (width, height) = self.level_size(level);
+        let mut image = decode_surface(self.encoding, stored, width, height, flip_y);
+        image.set_color_space(self.color_space);
+        Ok(image)
+    }
+}
+
+/// Check if data starts with the DDS magic number
+pub fn is_dds(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Read DDS header information without decoding pixels
+///
+/// Describes the first surface (full-size mip level of the first array element
+/// or cube face), as returned by [`load_dds`].
+pub fn probe_dds(data: &[u8]) -> ImageResult<ImageInfo> {
+    let header = Header::parse(data)?;
+    Ok(header.info(header.width, header.height))
+}
+
+/// Read the header information of every surface
+///
+/// Describes the images returned by [`load_dds_all`], in the same order.
+/// Fails if the data is too short to hold every surface the header declares.
+pub fn probe_dds_all(data: &[u8]) -> ImageResult<Vec<ImageInfo>> {
+    let header = Header::parse(data)?;
+    header.check_surfaces(data)?;
+    let mut infos = Vec::with_capacity(header.layer_count * header.mip_levels);
+    for _ in 0..header.layer_count {
+        for level in 0..header.mip_levels {
+            let (width, height) = header.level_size(level);
+            infos.push(header.info(width, height));
+        }
+    }
+    Ok(infos)
+}
+
+/// Load the first surface of a DDS texture
+///
+/// Reads DX9 and DX10 headers. BC1-BC3 and BC7 decode to RGBA U8, BC4 to R,
+/// BC5 to RGB with a zero blue channel (U8, or I8 for SNORM variants).
+/// Uncompressed textures load in their stored layout, DX9 bit-mask layouts as
+/// U8. sRGB formats are returned as stored, without linearization, and tagged
+/// [`ColorSpace::Srgb`]; other DX10 formats and BC4/BC5 are tagged linear.
+pub fn load_dds(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let header = Header::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    header.decode(data, 0, 0, options.flip_y)
+}
+
+/// Load every surface of a DDS texture
+///
+/// Surfaces are returned layer by layer (array elements, cube faces in
+/// +X, -X, +Y, -Y, +Z, -Z order), each from the full-size mip level down to the
+/// smallest. The declared surfaces must all fit in the data before any is
+/// decoded.
+pub fn load_dds_all(data: &[u8], options: &LoadOptions) -> ImageResult<Vec<Image>> {
+    let header = Header::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    header.check_surfaces(data)?;
+    let mut images = Vec::with_capacity(header.layer_count * header.mip_levels);
+    for layer in 0..header.layer_count {
+        for level in 0..header.mip_levels {
+            images.push(header.decode(data, layer, level, options.flip_y)?);
+        }
+    }
+    Ok(images)
+}
+
+/// Load the smallest mip level of a DDS texture that still covers a size
+///
+/// Selects the smallest level of the first surface whose largest dimension is
+/// at least `min_dim`. Only that level is decoded.
+pub fn load_dds_level_for_size(data: &[u8], min_dim: u32) -> ImageResult<Image> {
+    let header = Header::parse(data)?;
+    let level = (0..header.mip_levels)
+        .rev()
+        .find(|&level| {
+            let (width, height) = header.level_size(level);
+            width.max(height) >= min_dim
+        })
+        .unwrap_or(0);
+    header.decode(data, 0, level, false)
+}
+
+/// Decode one stored surface to interleaved pixels
+fn decode_surface(encoding: Encoding, stored: &[u8], width: u32, height: u32, flip_y: bool) -> Image {
+    let (pixel_format, component_type) = encoding.output();
+    let (width_px, height_px) = (width as usize, height as usize);
+    let pixel_bytes = pixel_format.channel_count() * component_type.size_bytes();
+    let row_bytes = width_px * pixel_bytes;
+    let target_row = |y: usize| if flip_y { height_px - 1 - y } else { y };
+    let mut output = buffer_pool::take_zeroed(row_bytes * height_px);
+
+    match (encoding.block_bytes(), encoding) {
+        (Some(block_bytes), _) => {
+            let blocks_x = width_px.div_ceil(4);
+            let mut texels = [0u8; 64];
+            let texels = &mut texels[..16 * pixel_bytes];
+            for (index, block) in stored.chunks_exact(block_bytes).enumerate() {
+                decode_block(encoding, block, texels);
+                let (x, top) = (index % blocks_x * 4, index / blocks_x * 4);
+                let copy_bytes = (width_px - x).min(4) * pixel_bytes;
+                for row in 0..(height_px - top).min(4) {
+                    let start = target_row(top + row) * row_bytes + x * pixel_bytes;
+                    let source = row * 4 * pixel_bytes;
+                    output[start..start + copy_bytes].copy_from_slice(&texels[source..source + copy_bytes]);
+                }
+            }
+        }
+        (None, Encoding::Masked { pixel_bytes: stored_bytes, masks, pixel_format }) => {
+            let channels = pixel_format.channel_count();
+            for (y, stored_row) in stored.chunks_exact(width_px * stored_bytes).enumerate() {
+                let start = target_row(y) * row_bytes;
+                let output_row = &mut output[start..start + row_bytes];
+                for (texel, pixel) in stored_row.chunks_exact(stored_bytes).zip(output_row.chunks_exact_mut(channels)) {
+                    let mut raw = [0u8; 4];
+                    raw[..stored_bytes].copy_from_slice(texel);
+                    let raw = u32::from_le_bytes(raw);
+                    for (value, &mask) in pixel.iter_mut().zip(&masks) {
+                        let max = (mask >> mask.trailing_zeros()) as u64;
+                        let bits = ((raw & mask) >> mask.trailing_zeros()) as u64;
+                        *value = ((bits * 255 + max / 2) / max) as u8;
+                    }
+                }
+            }
+        }
+        (None, _) => {
+            for (y, stored_row) in stored.chunks_exact(row_bytes).enumerate() {
+                let start = target_row(y) * row_bytes;
+                output[start..start + row_bytes].copy_from_slice(stored_row);
+            }
+        }
+    }
+
+    Image::from_raw(output, width, height, pixel_format, component_type)
+}
+
+/// Decode one 4x4 block into row-major texels in the output layout
+fn decode_block(encoding: Encoding, block: &[u8], texels: &mut [u8]) {
+    match encoding {
+        Encoding::Bc1 => decode_bc1_color(block, true, texels),
+        Encoding::Bc2 => {
+            decode_bc1_color(&block[8..], false, texels);
+            let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+            for (index, texel) in texels.chunks_exact_mut(4).enumerate() {
+                texel[3] = ((alpha >> (index * 4)) & 0xF) as u8 * 17;
+            }
+        }
+        Encoding::Bc3 => {
+            decode_bc1_color(&block[8..], false, texels);
+            decode_bc4_channel(&block[..8], false, texels, 4, 3);
+        }
+        Encoding::Bc4 { signed } => decode_bc4_channel(block, signed, texels, 1, 0),
+        Encoding::Bc5 { signed } => {
+            decode_bc4_channel(&block[..8], signed, texels, 3, 0);
+            decode_bc4_channel(&block[8..], signed, texels, 3, 1);
+            for texel in texels.chunks_exact_mut(3) {
+                texel[2] = 0;
+            }
+        }
+        Encoding::Bc7 => decode_bc7(block, texels),
+        Encoding::Plain { .. } | Encoding::Masked { .. } => unreachable!("not a block encoding"),
+    }
+}
+
+/// Expand an RGB565 color to 8 bits per channel
+fn rgb565(color: u16) -> [u8; 3] {
+    let (r, g, b) = ((color >> 11) as u8, ((color >> 5) & 0x3F) as u8, (color & 0x1F) as u8);
+    [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+}
+
+/// Decode a BC1 color block into RGBA texels
+///
+/// Blocks with `color0 <= color1` use three colors plus transparent black when
+/// `punch_through` is set (BC1); BC2/BC3 color blocks always use four colors.
+fn decode_bc1_color(block: &[u8], punch_through: bool, texels: &mut [u8]) {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let (c0, c1) = (rgb565(color0), rgb565(color1));
+    let mix = |weight0: u16, weight1: u16| -> [u8; 4] {
+        let total = weight0 + weight1;
+        let channel = |i: usize| ((c0[i] as u16 * weight0 + c1[i] as u16 * weight1 + total / 2) / total) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+
+    let palette = if color0 > color1 || !punch_through {
+        [mix(1, 0), mix(0, 1), mix(2, 1), mix(1, 2)]
+    } else {
+        [mix(1, 0), mix(0, 1), mix(1, 1), [0, 0, 0, 0]]
+    };
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for (index, texel) in texels.chunks_exact_mut(4).enumerate() {
+        texel.copy_from_slice(&palette[((indices >> (index * 2)) & 3) as usize]);
+    }
+}
+
+/// Decode a BC4 block into one channel of texels with `stride` bytes
+fn decode_bc4_channel(block: &[u8], signed: bool, texels: &mut [u8], stride: usize, channel: usize) {
+    // Signed endpoints are clamped to -127 like the D3D decoder
+    let (e0, e1, min, max) = if signed {
+        ((block[0] as i8).max(-127) as i32, (block[1] as i8).max(-127) as i32, -127, 127)
+    } else {
+        (block[0] as i32, block[1] as i32, 0, 255)
+    };
+    let mix = |weight0: i32, weight1: i32, total: i32| {
+        let sum = e0 * weight0 + e1 * weight1;
+        // Round half away from zero so signed ramps are symmetric
+        (if sum >= 0 { sum + total / 2 } else { sum - total / 2 }) / total
+    };
+
+    let mut palette = [e0, e1, 0, 0, 0, 0, min, max];
+    if e0 > e1 {
+        for (i, value) in palette.iter_mut().enumerate().skip(2) {
+            *value = mix(8 - i as i32, i as i32 - 1, 7);
+        }
+    } else {
+        for (i, value) in palette.iter_mut().enumerate().take(6).skip(2) {
+            *value = mix(6 - i as i32, i as i32 - 1, 5);
+        }
+    }
+
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    for (index, texel) in texels.chunks_exact_mut(stride).enumerate() {
+        texel[channel] = palette[((indices >> (index * 3)) & 7) as usize] as u8;
+    }
+}
+
+/// Layout of one BC7 block mode
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: u32,
+    secondary_index_bits: u32,
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    Bc7Mode {
+        subsets: 3,
+        partition_bits: 4,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 4,
+        alpha_bits: 0,
+        endpoint_pbits: true,
+        shared_pbits: false,
+        index_bits: 3,
+        secondary_index_bits: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 6,
+        alpha_bits: 0,
+        endpoint_pbits: false,
+        shared_pbits: true,
+        index_bits: 3,
+        secondary_index_bits: 0,
+    },
+    Bc7Mode {
+        subsets: 3,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 5,
+        alpha_bits: 0,
+        endpoint_pbits: false,
+        shared_pbits: false,
+        index_bits: 2,
+        secondary_index_bits: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 0,
+        endpoint_pbits: true,
+        shared_pbits: false,
+        index_bits: 2,
+        secondary_index_bits: 0,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 2,
+        index_selection_bits: 1,
+        color_bits: 5,
+        alpha_bits: 6,
+        endpoint_pbits: false,
+        shared_pbits: false,
+        index_bits: 2,
+        secondary_index_bits: 3,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 2,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 8,
+        endpoint_pbits: false,
+        shared_pbits: false,
+        index_bits: 2,
+        secondary_index_bits: 2,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 7,
+        endpoint_pbits: true,
+        shared_pbits: false,
+        index_bits: 4,
+        secondary_index_bits: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 5,
+        alpha_bits: 5,
+        endpoint_pbits: true,
+        shared_pbits: false,
+        index_bits: 2,
+        secondary_index_bits: 0,
+    },
+];
+
+/// Subset of each texel for two-subset partitions, one bit per texel
+const BC7_PARTITIONS_2: [u16; 64] = [
+    0xCCCC, 0x8888, 0xEEEE, 0xECC8, 0xC880, 0xFEEC, 0xFEC8, 0xEC80,
+    0xC800, 0xFFEC, 0xFE80, 0xE800, 0xFFE8, 0xFF00, 0xFFF0, 0xF000,
+    0xF710, 0x008E, 0x7100, 0x08CE, 0x008C, 0x7310, 0x3100, 0x8CCE,
+    0x088C, 0x3110, 0x6666, 0x366C, 0x17E8, 0x0FF0, 0x718E, 0x399C,
+    0xAAAA, 0xF0F0, 0x5A5A, 0x33CC, 0x3C3C, 0x55AA, 0x9696, 0xA55A,
+    0x73CE, 0x13C8, 0x324C, 0x3BDC, 0x6996, 0xC33C, 0x9966, 0x0660,
+    0x0272, 0x04E4, 0x4E40, 0x2720, 0xC936, 0x936C, 0x39C6, 0x639C,
+    0x9336, 0x9CC6, 0x817E, 0xE718, 0xCCF0, 0x0FCC, 0x7744, 0xEE22,
+];
+
+/// Subset of each texel for three-subset partitions
+const BC7_PARTITIONS_3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+/// Anchor texel of the second subset of two-subset partitions
+const BC7_ANCHORS_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+    15, 2, 8, 2, 2, 8, 8, 15, 2, 8, 2, 2, 8, 8, 2, 2,
+    15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6,
+    6, 2, 6, 8, 15, 15, 2, 2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// Anchor texels of the second and third subsets of three-subset partitions
+const BC7_ANCHORS_3: [[u8; 64]; 2] = [
+    [
+        3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3,
+        3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5, 15, 15,
+        8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15,
+        3, 15, 5, 5, 5, 8, 5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+    ],
+    [
+        15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8,
+        15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6, 10, 15, 15, 10, 8,
+        15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8,
+        15, 3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+    ],
+];
+
+/// Interpolation weights (out of 64) for 2, 3 and 4-bit indices
+const BC7_WEIGHTS: [&[u16]; 3] = [
+    &[0, 21, 43, 64],
+    &[0, 9, 18, 27, 37, 46, 55, 64],
+    &[0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64],
+];
+
+/// Little-endian bit reader over a 128-bit block
+struct BlockBits {
+    bits: u128,
+    position: u32,
+}
+
+impl BlockBits {
+    fn take(&mut self, count: u32) -> u8 {
+        let value = (self.bits >> self.position) & ((1 << count) - 1);
+        self.position += count;
+        value as u8
+    }
+}
+
+/// Decode a BC7 block into RGBA texels
+fn decode_bc7(block: &[u8], texels: &mut [u8]) {
+    let mut bits = BlockBits { bits: u128::from_le_bytes(block.try_into().unwrap()), position: 0 };
+    // The mode is the number of zero bits before the first set bit
+    let Some(mode_index) = (0..8).find(|_| bits.take(1) == 1) else {
+        // Reserved mode 8 decodes to transparent black
+        texels.fill(0);
+        return;
+    };
+    let mode = &BC7_MODES[mode_index];
+
+    let partition = bits.take(mode.partition_bits) as usize;
+    let rotation = bits.take(mode.rotation_bits);
+    let index_selection = bits.take(mode.index_selection_bits);
+
+    // Endpoints are stored channel by channel, then p-bits
+    let endpoint_count = mode.subsets * 2;
+    let mut endpoints = [[0u8; 4]; 6];
+    for channel in 0..4 {
+        let channel_bits = if channel < 3 { mode.color_bits } else { mode.alpha_bits };
+        for endpoint in &mut endpoints[..endpoint_count] {
+            endpoint[channel] = bits.take(channel_bits);
+        }
+    }
+    let mut pbits = [0u8; 6];
+    if mode.endpoint_pbits {
+        for pbit in &mut pbits[..endpoint_count] {
+            *pbit = bits.take(1);
+        }
+    } else if mode.shared_pbits {
+        for subset in 0..mode.subsets {
+            let pbit = bits.take(1);
+            pbits[subset * 2] = pbit;
+            pbits[subset * 2 + 1] = pbit;
+        }
+    }
+    let has_pbits = mode.endpoint_pbits || mode.shared_pbits;
+    for (endpoint, &pbit) in endpoints[..endpoint_count].iter_mut().zip(&pbits) {
+        for (channel, value) in endpoint.iter_mut().enumerate() {
+            let mut channel_bits = if channel < 3 { mode.color_bits } else { mode.alpha_bits };
+            if channel_bits == 0 {
+                *value = 255;
+                continue;
+            }
+            if has_pbits {
+                *value = (*value << 1) | pbit;
+                channel_bits += 1;
+            }
+            // Replicate the high bits into the low bits
+            if channel_bits < 8 {
+                *value = (*value << (8 - channel_bits)) | (*value >> (2 * channel_bits - 8));
+            }
+        }
+    }
+
+    let subset_of = |texel: usize| match mode.subsets {
+        1 => 0,
+        2 => ((BC7_PARTITIONS_2[partition] >> texel) & 1) as usize,
+        _ => BC7_PARTITIONS_3[partition][texel] as usize,
+    };
+    // The anchor texel of each subset stores its index with one bit less
+    let is_anchor = |texel: usize, subset: usize| match (mode.subsets, subset) {
+        (_, 0) => texel == 0,
+        (2, _) => texel == BC7_ANCHORS_2[partition] as usize,
+        (_, subset) => texel == BC7_ANCHORS_3[subset - 1][partition] as usize,
+    };
+
+    let mut indices = [0u8; 16];
+    for (texel, index) in indices.iter_mut().enumerate() {
+        *index = bits.take(mode.index_bits - is_anchor(texel, subset_of(texel)) as u32);
+    }
+    let mut secondary = [0u8; 16];
+    if mode.secondary_index_bits > 0 {
+        for (texel, index) in secondary.iter_mut().enumerate() {
+            *index = bits.take(mode.secondary_index_bits - (texel == 0) as u32);
+        }
+    }
+
+    let interpolate = |e0: u8, e1: u8, index: u8, index_bits: u32| {
+        let weight = BC7_WEIGHTS[index_bits as usize - 2][index as usize];
+        (((64 - weight) * e0 as u16 + weight * e1 as u16 + 32) >> 6) as u8
+    };
+    for (texel, output) in texels.chunks_exact_mut(4).enumerate() {
+        let subset = subset_of(texel);
+        let (e0, e1) = (endpoints[subset * 2], endpoints[subset * 2 + 1]);
+        let (color_index, color_bits, alpha_index, alpha_bits) = match (mode.secondary_index_bits, index_selection) {
+            (0, _) => (indices[texel], mode.index_bits, indices[texel], mode.index_bits),
+            (_, 0) => (indices[texel], mode.index_bits, secondary[texel], mode.secondary_index_bits),
+            _ => (secondary[texel], mode.secondary_index_bits, indices[texel], mode.index_bits),
+        };
+        for channel in 0..3 {
+            output[channel] = interpolate(e0[channel], e1[channel], color_index, color_bits);
+        }
+        output[3] = interpolate(e0[3], e1[3], alpha_index, alpha_bits);
+        if rotation > 0 {
+            output.swap(rotation as usize - 1, 3);
+        }
+    }
+}
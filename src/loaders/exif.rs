@@ -0,0 +1,50 @@
+/// Orientation tag of the first IFD
+const ORIENTATION_TAG: u16 = 0x0112;
+
+/// TIFF SHORT field type
+const SHORT: u16 = 3;
+
+/// Read the orientation tag value of EXIF data starting at the TIFF header
+pub(crate) fn read_orientation(exif: &[u8]) -> Option<u16> {
+    let little_endian = match exif.get(0..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| -> Option<u16> {
+        let bytes = exif.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes = exif.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+    if u16_at(2)? != 42 {
+        return None;
+    }
+
+    let ifd = u32_at(4)? as usize;
+    let entry_count = u16_at(ifd)? as usize;
+    (0..entry_count).find_map(|i| {
+        let entry = ifd + 2 + i * 12;
+        (u16_at(entry)? == ORIENTATION_TAG && u16_at(entry + 2)? == SHORT).then(|| u16_at(entry + 8))?
+    })
+}
+
+/// EXIF data with only the orientation tag, starting at the TIFF header
+pub(crate) fn orientation_exif(value: u16) -> Vec<u8> {
+    let mut exif = Vec::with_capacity(26);
+    exif.extend_from_slice(b"II");
+    exif.extend_from_slice(&42u16.to_le_bytes());
+    // First IFD right after the header
+    exif.extend_from_slice(&8u32.to_le_bytes());
+    exif.extend_from_slice(&1u16.to_le_bytes());
+    exif.extend_from_slice(&ORIENTATION_TAG.to_le_bytes());
+    exif.extend_from_slice(&SHORT.to_le_bytes());
+    exif.extend_from_slice(&1u32.to_le_bytes());
+    exif.extend_from_slice(&value.to_le_bytes());
+    exif.extend_from_slice(&[0, 0]);
+    // No next IFD
+    exif.extend_from_slice(&0u32.to_le_bytes());
+    exif
+}
@@ -1,5 +1,8 @@
-use crate::{Image, ImageError, ImageResult, PixelFormat, ComponentType};
-use std::io::Cursor;
+use crate::{ExrCompression, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, ComponentType, SaveOptions};
+use crate::buffer_pool;
+use crate::half_float::f16_to_f32;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek};
 
 // Selective imports to avoid name conflict with our Image type
 use exr::image::read::read;
@@ -9,16 +12,156 @@ use exr::image::{
 use exr::math::Vec2;
 use exr::meta::header::{ImageAttributes, LayerAttributes};
 use exr::meta::attribute::{IntegerBounds, Text};
-use exr::image::Encoding;
+use exr::compression::Compression;
+use exr::image::{Blocks, Encoding};
+use exr::meta::attribute::{LevelMode, LineOrder, SampleType, TileDescription};
+use exr::meta::header::Header;
+use exr::meta::{compute_level_count, compute_level_size, BlockDescription, MetaData};
+use exr::block::reader::ChunksReader;
 use exr::prelude::{f16, ReadChannels, ReadLayers, WritableImage};
 
+/// Map EXR channel names to a pixel format and source channel indices
+fn select_channels(names: &[String]) -> ImageResult<(PixelFormat, Vec<usize>)> {
+    let find_channel = |name: &str| -> Option<usize> {
+        names.iter().position(|n| n == name)
+    };
+
+    let r_idx = find_channel("R");
+    let g_idx = find_channel("G");
+    let b_idx = find_channel("B");
+    let a_idx = find_channel("A");
+    let y_idx = find_channel("Y");
+
+    // Determine pixel format and channel mapping
+    match (r_idx, g_idx, b_idx, a_idx, y_idx) {
+        (Some(r), Some(g), Some(b), Some(a), _) => Ok((PixelFormat::RGBA, vec![r, g, b, a])),
+        (Some(r), Some(g), Some(b), None, _) => Ok((PixelFormat::RGB, vec![r, g, b])),
+        (_, _, _, Some(a), Some(y)) => Ok((PixelFormat::RG, vec![y, a])),
+        (_, _, _, None, Some(y)) => Ok((PixelFormat::R, vec![y])),
+        (Some(r), _, _, Some(a), _) => Ok((PixelFormat::RG, vec![r, a])),
+        (Some(r), _, _, None, _) => Ok((PixelFormat::R, vec![r])),
+        _ => Err(ImageError::UnsupportedFormat(
+            "No recognized channels (R/G/B/A/Y) in EXR file, load them by name with load_channels_from_file".to_string(),
+        )),
+    }
+}
+
+/// Determine the component type from the sample types of the selected channels
+///
+/// Returns the component type and whether samples must be converted to F32.
+fn select_component_type(sample_types: &[SampleType]) -> (ComponentType, bool) {
+    let all_same_type = sample_types.iter().all(|&t| t == sample_types[0]);
+    if all_same_type {
+        match sample_types[0] {
+            SampleType::F16 => (ComponentType::F16, false),
+            SampleType::F32 => (ComponentType::F32, false),
+            SampleType::U32 => (ComponentType::F32, true),
+        }
+    } else {
+        // Mixed sample types: convert everything to F32
+        (ComponentType::F32, true)
+    }
+}
+
+/// Read EXR header information without decoding pixels
+///
+/// Describes the first layer, as returned by [`load_exr`].
+pub fn probe_exr(data: &[u8]) -> ImageResult<ImageInfo> {
+    let meta = MetaData::read_from_buffered(Cursor::new(data), false)?;
+    let header = meta.headers.first().ok_or_else(|| {
+        ImageError::Other("EXR file contains no layers".to_string())
+    })?;
+    header_info(header, meta.headers.len())
+}
+
+/// Read the header information of every layer with recognized channels
+///
+/// Describes the images returned by [`load_exr_all`], in file order.
+pub fn probe_exr_all(data: &[u8]) -> ImageResult<Vec<ImageInfo>> {
+    let meta = MetaData::read_from_buffered(Cursor::new(data), false)?;
+    let infos: Vec<ImageInfo> = meta
+        .headers
+        .iter()
+        .filter_map(|header| header_info(header, meta.headers.len()).ok())
+        .collect();
+
+    if infos.is_empty() {
+        return Err(ImageError::UnsupportedFormat(
+            "No recognized channels (R/G/B/A/Y) in any EXR layer".to_string(),
+        ));
+    }
+    Ok(infos)
+}
+
+/// Describe one EXR layer header
+fn header_info(header: &Header, layer_count: usize) -> ImageResult<ImageInfo> {
+    let names: Vec<String> = header.channels.list.iter().map(|c| c.name.to_string()).collect();
+    let (pixel_format, channel_indices) = select_channels(&names)?;
+    let sample_types: Vec<SampleType> = channel_indices
+        .iter()
+        .map(|&idx| header.channels.list[idx].sample_type)
+        .collect();
+    let (component_type, _) = select_component_type(&sample_types);
+
+    let width = header.layer_size.0;
+    let height = header.layer_size.1;
+    let mip_levels = match header.blocks {
+        BlockDescription::Tiles(TileDescription { level_mode: LevelMode::Singular, .. })
+        | BlockDescription::ScanLines => 1,
+        BlockDescription::Tiles(TileDescription { rounding_mode, .. }) => {
+            compute_level_count(rounding_mode, width.max(height))
+        }
+    };
+
+    Ok(ImageInfo {
+        format: ImageFormat::Exr,
+        width: width as u32,
+        height: height as u32,
+        pixel_format,
+        component_type,
+        layer_count,
+        mip_levels,
+    })
+}
+
+/// Write the samples of one channel into interleaved pixel data
+///
+/// Samples are in top-to-bottom row order; `flip_y` writes the rows bottom-to-top.
+fn write_channel<const N: usize>(
+    output: &mut [u8],
+    width: usize,
+    channel: usize,
+    channel_count: usize,
+    flip_y: bool,
+    samples: impl Iterator<Item = [u8; N]>,
+) {
+    let pixel_bytes = channel_count * N;
+    let row_bytes = width * pixel_bytes;
+    if row_bytes == 0 {
+        return;
+    }
+
+    let height = output.len() / row_bytes;
+    let mut samples = samples;
+    for row in 0..height {
+        let y = if flip_y { height - 1 - row } else { row };
+        let output_row = &mut output[y * row_bytes..(y + 1) * row_bytes];
+        for (pixel, bytes) in output_row.chunks_exact_mut(pixel_bytes).zip(samples.by_ref()) {
+            pixel[channel * N..(channel + 1) * N].copy_from_slice(&bytes);
+        }
+    }
+}
+
 /// Load an EXR image from raw bytes
 ///
 /// Reads the first layer and detects channels (R/G/B/A/Y).
 /// Supports F16, F32, and U32 (converted to F32) sample types.
-pub fn load_exr(data: &[u8]) -> ImageResult<Image> {
-    let reader = Cursor::new(data);
+pub fn load_exr(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    load_exr_from_reader(Cursor::new(data), options)
+}
 
+/// Decode an EXR image from a buffered, seekable `reader`
+pub fn load_exr_from_reader<R: Read + Seek>(reader: R, options: &LoadOptions) -> ImageResult<Image> {
     // Read all channels from the first valid layer
     let exr_image = read()
         .no_deep_data()
@@ -28,9 +171,181 @@ pub fn load_exr(data: &[u8]) -> ImageResult<Image> {
         .all_attributes()
         .from_buffered(reader)?;
 
-    let layer = &exr_image.layer_data;
-    let width = layer.size.0 as u32;
-    let height = layer.size.1 as u32;
+    let image = layer_to_image(&exr_image.layer_data, options)?;
+    check_finite(&image, options)?;
+    Ok(image)
+}
+
+/// Reject images with NaN or infinite samples if the load options ask for it
+fn check_finite(image: &Image, options: &LoadOptions) -> ImageResult<()> {
+    if !options.reject_non_finite {
+        return Ok(());
+    }
+    let report = image.find_non_finite();
+    match report.pixels.first() {
+        Some((x, y)) => Err(ImageError::Other(format!(
+            "EXR image contains {} NaN and {} infinite samples (first at ({}, {}))",
+            report.nan_count, report.infinite_count, x, y
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Load every layer of a multipart EXR image
+///
+/// Layers without recognized channels (e.g. depth-only parts) are skipped.
+pub fn load_exr_all(data: &[u8], options: &LoadOptions) -> ImageResult<Vec<Image>> {
+    let exr_image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(Cursor::new(data))?;
+
+    let images: Vec<Image> = exr_image
+        .layer_data
+        .iter()
+        .filter_map(|layer| layer_to_image(layer, options).ok())
+        .collect();
+    for image in &images {
+        check_finite(image, options)?;
+    }
+
+    if images.is_empty() {
+        return Err(ImageError::UnsupportedFormat(
+            "No recognized channels (R/G/B/A/Y) in any EXR layer".to_string(),
+        ));
+    }
+    Ok(images)
+}
+
+/// Channels of one EXR layer: name prefix, names without prefix, channels
+type ChannelGroup<'a> = (String, Vec<String>, Vec<&'a AnyChannel<FlatSamples>>);
+
+/// Load every layer of an EXR image with its name, e.g. the render passes of a bake
+///
+/// Channels are grouped by the prefix before their last `.` (`beauty.R`,
+/// `normal.X`, `depth.Z`) within each part, and the layer name joins the part
+/// name and the prefix with a `.`; channels without prefix in an unnamed part
+/// form the layer `""`. Parts are returned in file order, their layers by
+/// channel name. Besides R/G/B/A/Y, X/Y/Z(/W) vector channels load as RGB(A)
+/// and a single channel of any name loads as R; other layers are skipped.
+pub fn load_exr_layers(data: &[u8], options: &LoadOptions) -> ImageResult<Vec<(String, Image)>> {
+    let exr_image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(Cursor::new(data))?;
+
+    let mut layers = Vec::new();
+    for part in &exr_image.layer_data {
+        let part_name = part.attributes.layer_name.as_ref().map(Text::to_string);
+
+        // Group in order of first channel; nested prefixes can interleave channels
+        let mut groups: Vec<ChannelGroup> = Vec::new();
+        for channel in &part.channel_data.list {
+            let name = channel.name.to_string();
+            let (prefix, base_name) = match name.rsplit_once('.') {
+                Some((prefix, base_name)) => (prefix.to_string(), base_name.to_string()),
+                None => (String::new(), name),
+            };
+            match groups.iter_mut().find(|(group, _, _)| *group == prefix) {
+                Some((_, base_names, channels)) => {
+                    base_names.push(base_name);
+                    channels.push(channel);
+                }
+                None => groups.push((prefix, vec![base_name], vec![channel])),
+            }
+        }
+
+        for (prefix, base_names, channels) in groups {
+            let Some((pixel_format, indices)) = select_layer_channels(&base_names) else {
+                continue;
+            };
+            let selected: Vec<&AnyChannel<FlatSamples>> = indices.iter().map(|&i| channels[i]).collect();
+            let image = channels_to_image(part.size, &selected, pixel_format, options);
+            check_finite(&image, options)?;
+
+            let name = match &part_name {
+                Some(part_name) if prefix.is_empty() => part_name.clone(),
+                Some(part_name) => format!("{}.{}", part_name, prefix),
+                None => prefix,
+            };
+            layers.push((name, image));
+        }
+    }
+
+    if layers.is_empty() {
+        return Err(ImageError::UnsupportedFormat(
+            "No loadable layer in EXR file".to_string(),
+        ));
+    }
+    Ok(layers)
+}
+
+/// Load every channel of an EXR image as a grayscale image, by channel name
+///
+/// Keys are the full channel names (`Z`, `velocity.x`), prefixed with the part
+/// name and a `.` in named parts; the first of duplicate names wins. Channels
+/// load as R images, F16 or F32 as stored and U32 (e.g. object IDs) as F32.
+/// Deep data parts are not supported.
+pub fn load_exr_channels(data: &[u8], options: &LoadOptions) -> ImageResult<BTreeMap<String, Image>> {
+    let exr_image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(Cursor::new(data))?;
+
+    let mut channels = BTreeMap::new();
+    for part in &exr_image.layer_data {
+        let part_name = part.attributes.layer_name.as_ref().map(Text::to_string);
+        for channel in &part.channel_data.list {
+            let name = match &part_name {
+                Some(part_name) => format!("{}.{}", part_name, channel.name),
+                None => channel.name.to_string(),
+            };
+            if channels.contains_key(&name) {
+                continue;
+            }
+            let image = channels_to_image(part.size, &[channel], PixelFormat::R, options);
+            check_finite(&image, options)?;
+            channels.insert(name, image);
+        }
+    }
+
+    if channels.is_empty() {
+        return Err(ImageError::Other(
+            "EXR file contains no channels".to_string(),
+        ));
+    }
+    Ok(channels)
+}
+
+/// Map the channel names of one layer, without prefix, to a pixel format
+fn select_layer_channels(names: &[String]) -> Option<(PixelFormat, Vec<usize>)> {
+    let find_channel = |name: &str| names.iter().position(|n| n == name);
+
+    // Checked first: the Y of a vector is not luminance
+    if let (Some(x), Some(y), Some(z)) = (find_channel("X"), find_channel("Y"), find_channel("Z")) {
+        return Some(match find_channel("W") {
+            Some(w) => (PixelFormat::RGBA, vec![x, y, z, w]),
+            None => (PixelFormat::RGB, vec![x, y, z]),
+        });
+    }
+    match select_channels(names) {
+        Ok(selection) => Some(selection),
+        Err(_) if names.len() == 1 => Some((PixelFormat::R, vec![0])),
+        Err(_) => None,
+    }
+}
+
+/// Convert the channels of one EXR layer to an interleaved image
+fn layer_to_image(layer: &Layer<AnyChannels<FlatSamples>>, options: &LoadOptions) -> ImageResult<Image> {
     let channels = &layer.channel_data.list;
 
     if channels.is_empty() {
@@ -39,96 +354,188 @@ pub fn load_exr(data: &[u8]) -> ImageResult<Image> {
         ));
     }
 
-    // Find channels by name
-    let find_channel = |name: &str| -> Option<usize> {
-        channels.iter().position(|c| c.name.to_string() == name)
-    };
+    let names: Vec<String> = channels.iter().map(|c| c.name.to_string()).collect();
+    let (pixel_format, channel_indices) = select_channels(&names)?;
+    let selected: Vec<&AnyChannel<FlatSamples>> = channel_indices.iter().map(|&idx| &channels[idx]).collect();
+    Ok(channels_to_image(layer.size, &selected, pixel_format, options))
+}
 
-    let r_idx = find_channel("R");
-    let g_idx = find_channel("G");
-    let b_idx = find_channel("B");
-    let a_idx = find_channel("A");
-    let y_idx = find_channel("Y");
+/// Interleave EXR channels, in output channel order, into an image
+fn channels_to_image(
+    size: Vec2<usize>,
+    channels: &[&AnyChannel<FlatSamples>],
+    pixel_format: PixelFormat,
+    options: &LoadOptions,
+) -> Image {
+    let width = size.0 as u32;
+    let height = size.1 as u32;
 
-    // Determine pixel format and channel mapping
-    let (pixel_format, channel_indices) = match (r_idx, g_idx, b_idx, a_idx, y_idx) {
-        (Some(r), Some(g), Some(b), Some(a), _) => (PixelFormat::RGBA, vec![r, g, b, a]),
-        (Some(r), Some(g), Some(b), None, _) => (PixelFormat::RGB, vec![r, g, b]),
-        (_, _, _, Some(a), Some(y)) => (PixelFormat::RG, vec![y, a]),
-        (_, _, _, None, Some(y)) => (PixelFormat::R, vec![y]),
-        (Some(r), _, _, Some(a), _) => (PixelFormat::RG, vec![r, a]),
-        (Some(r), _, _, None, _) => (PixelFormat::R, vec![r]),
-        _ => {
-            return Err(ImageError::UnsupportedFormat(
-                "No recognized channels (R/G/B/A/Y) in EXR file".to_string(),
-            ));
+    let sample_types: Vec<SampleType> = channels
+        .iter()
+        .map(|channel| match channel.sample_data {
+            FlatSamples::F16(_) => SampleType::F16,
+            FlatSamples::F32(_) => SampleType::F32,
+            FlatSamples::U32(_) => SampleType::U32,
+        })
+        .collect();
+    let (component_type, force_f32) = select_component_type(&sample_types);
+
+    // Build interleaved pixel data
+    let pixel_count = (width as usize) * (height as usize);
+    let channel_count = pixel_format.channel_count();
+    let bytes_per_component = component_type.size_bytes();
+    let total_bytes = pixel_count * channel_count * bytes_per_component;
+    let mut output = buffer_pool::take_zeroed(total_bytes);
+
+    // Interleave one channel at a time; F16 planes are converted in batches
+    for (ch_out, channel) in channels.iter().enumerate() {
+        match (&channel.sample_data, force_f32) {
+            (FlatSamples::F16(s), false) => {
+                write_channel(&mut output, width as usize, ch_out, channel_count, options.flip_y, s.iter().map(|v| v.to_le_bytes()));
+            }
+            (FlatSamples::F16(s), true) => {
+                let values = f16_to_f32(s);
+                write_channel(&mut output, width as usize, ch_out, channel_count, options.flip_y, values.iter().map(|v| v.to_le_bytes()));
+            }
+            (FlatSamples::F32(s), _) => {
+                write_channel(&mut output, width as usize, ch_out, channel_count, options.flip_y, s.iter().map(|v| v.to_le_bytes()));
+            }
+            (FlatSamples::U32(s), _) => {
+                // U32 always forces F32 output
+                write_channel(&mut output, width as usize, ch_out, channel_count, options.flip_y, s.iter().map(|&v| (v as f32).to_le_bytes()));
+            }
         }
+    }
+
+    Image::from_raw(output, width, height, pixel_format, component_type)
+}
+
+/// Load the smallest mip level of an EXR image that still covers a size
+///
+/// Selects the smallest level of the first layer whose largest dimension is at
+/// least `min_dim`. Only the chunks of that level are decompressed. Falls back to
+/// [`load_exr`] for files without mip levels.
+pub fn load_exr_level_for_size(data: &[u8], min_dim: u32) -> ImageResult<Image> {
+    let reader = exr::block::read(Cursor::new(data), false)?;
+    let header = match reader.headers().first() {
+        Some(header) => header.clone(),
+        None => return Err(ImageError::Other("EXR file contains no layers".to_string())),
     };
 
-    // Determine component type from channel sample types
-    let first_sample = &channels[channel_indices[0]].sample_data;
-    let all_same_type = channel_indices.iter().all(|&idx| {
-        std::mem::discriminant(&channels[idx].sample_data)
-            == std::mem::discriminant(first_sample)
-    });
-
-    let (component_type, force_f32) = if all_same_type {
-        match first_sample {
-            FlatSamples::F16(_) => (ComponentType::F16, false),
-            FlatSamples::F32(_) => (ComponentType::F32, false),
-            FlatSamples::U32(_) => (ComponentType::F32, true),
-        }
-    } else {
-        // Mixed sample types: convert everything to F32
-        (ComponentType::F32, true)
+    let rounding_mode = match header.blocks {
+        BlockDescription::Tiles(TileDescription {
+            level_mode: LevelMode::MipMap,
+            rounding_mode,
+            ..
+        }) => rounding_mode,
+        _ => return load_exr(data, &LoadOptions::default()),
     };
 
-    // Build interleaved pixel data
-    let pixel_count = (width as usize) * (height as usize);
+    // Pick the smallest level that is still large enough
+    let full_size = header.layer_size;
+    let level_count = compute_level_count(rounding_mode, full_size.0.max(full_size.1));
+    let level_size = |level: usize| {
+        Vec2(
+            compute_level_size(rounding_mode, full_size.0, level),
+            compute_level_size(rounding_mode, full_size.1, level),
+        )
+    };
+    let level = (0..level_count)
+        .rev()
+        .find(|&level| {
+            let size = level_size(level);
+            size.0.max(size.1) >= min_dim as usize
+        })
+        .unwrap_or(0);
+    let size = level_size(level);
+
+    let names: Vec<String> = header.channels.list.iter().map(|c| c.name.to_string()).collect();
+    let (pixel_format, channel_indices) = select_channels(&names)?;
+    let sample_types: Vec<SampleType> = channel_indices
+        .iter()
+        .map(|&idx| header.channels.list[idx].sample_type)
+        .collect();
+    let (component_type, force_f32) = select_component_type(&sample_types);
+
     let channel_count = pixel_format.channel_count();
     let bytes_per_component = component_type.size_bytes();
-    let total_bytes = pixel_count * channel_count * bytes_per_component;
-    let mut output = vec![0u8; total_bytes];
-
-    for pixel_idx in 0..pixel_count {
-        for (ch_out, &ch_in) in channel_indices.iter().enumerate() {
-            let byte_offset = (pixel_idx * channel_count + ch_out) * bytes_per_component;
-            let sample = &channels[ch_in].sample_data;
-
-            if force_f32 {
-                // Convert all sample types to F32
-                let value: f32 = match sample {
-                    FlatSamples::F16(s) => s[pixel_idx].to_f32(),
-                    FlatSamples::F32(s) => s[pixel_idx],
-                    FlatSamples::U32(s) => s[pixel_idx] as f32,
-                };
-                output[byte_offset..byte_offset + 4]
-                    .copy_from_slice(&value.to_le_bytes());
-            } else {
-                // Keep native format
-                match sample {
-                    FlatSamples::F16(s) => {
-                        output[byte_offset..byte_offset + 2]
-                            .copy_from_slice(&s[pixel_idx].to_le_bytes());
+    let mut output = buffer_pool::take_zeroed(size.0 * size.1 * channel_count * bytes_per_component);
+
+    let chunks = reader.filter_chunks(false, |_, _, block| {
+        block.layer == 0 && block.level == Vec2(level, level)
+    })?;
+
+    chunks.decompress_parallel(false, |meta, block| {
+        for line in block.lines(&meta.headers[0].channels) {
+            let Some(ch_out) = channel_indices.iter().position(|&c| c == line.location.channel) else {
+                continue;
+            };
+
+            let position = line.location.position;
+            let start = position.1 * size.0 + position.0;
+            let mut write = |i: usize, bytes: &[u8]| {
+                let offset = ((start + i) * channel_count + ch_out) * bytes_per_component;
+                output[offset..offset + bytes.len()].copy_from_slice(bytes);
+            };
+
+            match header.channels.list[line.location.channel].sample_type {
+                SampleType::F16 if !force_f32 => {
+                    for (i, v) in line.read_samples::<f16>().enumerate() {
+                        write(i, &v?.to_le_bytes());
+                    }
+                }
+                SampleType::F16 => {
+                    let halves = line.read_samples::<f16>().collect::<exr::error::Result<Vec<f16>>>()?;
+                    for (i, v) in f16_to_f32(&halves).iter().enumerate() {
+                        write(i, &v.to_le_bytes());
+                    }
+                }
+                SampleType::F32 => {
+                    for (i, v) in line.read_samples::<f32>().enumerate() {
+                        write(i, &v?.to_le_bytes());
                     }
-                    FlatSamples::F32(s) => {
-                        output[byte_offset..byte_offset + 4]
-                            .copy_from_slice(&s[pixel_idx].to_le_bytes());
+                }
+                SampleType::U32 => {
+                    for (i, v) in line.read_samples::<u32>().enumerate() {
+                        write(i, &(v? as f32).to_le_bytes());
                     }
-                    FlatSamples::U32(_) => unreachable!(),
                 }
             }
         }
-    }
+        Ok(())
+    })?;
 
-    Ok(Image::from_raw(output, width, height, pixel_format, component_type))
+    Ok(Image::from_raw(output, size.0 as u32, size.1 as u32, pixel_format, component_type))
 }
 
-/// Save an image as EXR format bytes
+/// Block layout and compression of an EXR compression choice
+fn exr_encoding(compression: ExrCompression) -> Encoding {
+    let compression = match compression {
+        // Tiles keep the run-length encoder effective, as in `Encoding::FAST_LOSSLESS`
+        ExrCompression::Rle => return Encoding::FAST_LOSSLESS,
+        ExrCompression::None => Compression::Uncompressed,
+        ExrCompression::Zip => Compression::ZIP16,
+        ExrCompression::Zips => Compression::ZIP1,
+        ExrCompression::Piz => Compression::PIZ,
+        ExrCompression::Pxr24 => Compression::PXR24,
+        ExrCompression::B44 => Compression::B44,
+        ExrCompression::B44a => Compression::B44A,
+        ExrCompression::Dwaa { level } => Compression::DWAA(Some(level as f32)),
+        ExrCompression::Dwab { level } => Compression::DWAB(Some(level as f32)),
+    };
+    Encoding {
+        compression,
+        blocks: Blocks::ScanLines,
+        // Scan line files must declare their order
+        line_order: LineOrder::Increasing,
+    }
+}
+
+/// Encode an image as a single-part EXR into an empty `buffer`
 ///
-/// Supports F16 and F32 component types. Uses ZIP compression (lossless).
-/// Automatically handles BGR/BGRA to RGB/RGBA conversion.
-pub fn save_exr(image: &Image) -> ImageResult<Vec<u8>> {
+/// Supports F16 and F32 component types, compressed as selected by
+/// [`SaveOptions::exr`]. BGR/BGRA are written as RGB/RGBA channels.
+pub fn save_exr(image: &Image, options: &SaveOptions, buffer: &mut Vec<u8>) -> ImageResult<()> {
     // EXR only supports F16 and F32
     match image.component_type() {
         ComponentType::F16 | ComponentType::F32 => {}
@@ -141,10 +548,7 @@ pub fn save_exr(image: &Image) -> ImageResult<Vec<u8>> {
 
     let width = image.width() as usize;
     let height = image.height() as usize;
-    let pixel_count = width * height;
-    let data = image.data();
-    let bytes_per_component = image.component_type().size_bytes();
-    let source_channel_count = image.pixel_format().channel_count();
+    let planar = image.to_planar();
 
     // Map pixel format to EXR channel names and source channel indices
     // BGR/BGRA are handled by remapping source indices (no clone needed)
@@ -157,34 +561,25 @@ pub fn save_exr(image: &Image) -> ImageResult<Vec<u8>> {
         PixelFormat::BGRA => (&["R", "G", "B", "A"], &[2, 1, 0, 3]),
     };
 
-    // De-interleave pixel data into separate EXR channels
+    // Each EXR channel is one plane of the planar layout
     let mut channel_list: Vec<AnyChannel<FlatSamples>> = channel_names
         .iter()
         .zip(source_indices.iter())
         .map(|(name, &src_ch)| {
             let sample_data = match image.component_type() {
                 ComponentType::F16 => {
-                    let samples: Vec<f16> = (0..pixel_count)
-                        .map(|pixel_idx| {
-                            let offset =
-                                (pixel_idx * source_channel_count + src_ch) * bytes_per_component;
-                            f16::from_le_bytes([data[offset], data[offset + 1]])
-                        })
+                    let samples: Vec<f16> = planar
+                        .plane(src_ch)
+                        .chunks_exact(2)
+                        .map(|bytes| f16::from_le_bytes([bytes[0], bytes[1]]))
                         .collect();
                     FlatSamples::F16(samples)
                 }
                 ComponentType::F32 => {
-                    let samples: Vec<f32> = (0..pixel_count)
-                        .map(|pixel_idx| {
-                            let offset =
-                                (pixel_idx * source_channel_count + src_ch) * bytes_per_component;
-                            f32::from_le_bytes([
-                                data[offset],
-                                data[offset + 1],
-                                data[offset + 2],
-                                data[offset + 3],
-                            ])
-                        })
+                    let samples: Vec<f32> = planar
+                        .plane(src_ch)
+                        .chunks_exact(4)
+                        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
                         .collect();
                     FlatSamples::F32(samples)
                 }
@@ -199,14 +594,21 @@ pub fn save_exr(image: &Image) -> ImageResult<Vec<u8>> {
             }
         })
         .collect();
+    buffer_pool::recycle(planar.into_data());
 
     // EXR spec requires channels sorted alphabetically by name
-    channel_list.sort_by(|a, b| a.name.to_string().cmp(&b.name.to_string()));
+    channel_list.sort_by_key(|a| a.name.to_string());
 
     let exr_channels = AnyChannels {
         list: channel_list.into_iter().collect(),
     };
 
+    // Increasing line order makes the writer sort chunks compressed in parallel
+    let mut encoding = exr_encoding(options.exr.compression);
+    if options.deterministic {
+        encoding.line_order = LineOrder::Increasing;
+    }
+
     // Build EXR image structure
     let exr_image = ExrImage {
         attributes: ImageAttributes::new(IntegerBounds::from_dimensions(Vec2(width, height))),
@@ -214,12 +616,11 @@ pub fn save_exr(image: &Image) -> ImageResult<Vec<u8>> {
             channel_data: exr_channels,
             attributes: LayerAttributes::named(Text::new_or_panic("main")),
             size: Vec2(width, height),
-            encoding: Encoding::FAST_LOSSLESS,
+            encoding,
         },
     };
 
-    // Write to buffer
-    let mut cursor = Cursor::new(Vec::new());
-    exr_image.write().to_buffered(&mut cursor)?;
-    Ok(cursor.into_inner())
+    // The writer seeks back to fill the offset tables, so it starts at offset 0
+    exr_image.write().to_buffered(Cursor::new(buffer))?;
+    Ok(())
 }
@@ -0,0 +1,415 @@
+use crate::{AnimatedImage, AnimationFrame, ComponentType, FrameDisposal, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, Rect};
+
+/// Signatures of the two GIF versions
+const SIGNATURES: [&[u8]; 2] = [b"GIF87a", b"GIF89a"];
+
+/// LZW codes are at most 12 bits, so the string table holds 4096 entries
+const MAX_CODES: usize = 4096;
+
+/// Row order of interlaced images as (first row, step) per pass
+const INTERLACE_PASSES: [(usize, usize); 4] = [(0, 8), (4, 8), (2, 4), (1, 2)];
+
+fn corrupt(message: &str) -> ImageError {
+    ImageError::Other(format!("Corrupt GIF data: {}", message))
+}
+
+/// Byte cursor over the block structure
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(count)?)?;
+        self.pos += count;
+        Some(bytes)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Read a sequence of data sub-blocks up to its terminator
+    ///
+    /// Returns the sub-blocks, or `None` when the data ends first.
+    fn sub_blocks(&mut self) -> Option<Vec<&'a [u8]>> {
+        let mut blocks = Vec::new();
+        loop {
+            match self.byte()? {
+                0 => return Some(blocks),
+                size => blocks.push(self.bytes(size as usize)?),
+            }
+        }
+    }
+}
+
+/// Graphic control extension values for the next image
+#[derive(Clone, Copy, Default)]
+struct GraphicControl {
+    disposal: FrameDisposal,
+    delay_cs: u16,
+    transparent: Option<u8>,
+}
+
+/// Descriptor and compressed data of one image
+struct FrameData<'a> {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+    interlaced: bool,
+    /// RGB triplets of the local or global color table
+    palette: &'a [u8],
+    min_code_size: u8,
+    /// LZW data, possibly cut short in truncated files
+    data: Vec<&'a [u8]>,
+    control: GraphicControl,
+}
+
+/// Parsed GIF file
+struct Gif<'a> {
+    width: u32,
+    height: u32,
+    frames: Vec<FrameData<'a>>,
+    loop_count: u32,
+}
+
+impl<'a> Gif<'a> {
+    /// Parse the block structure, stopping after `max_frames` images
+    ///
+    /// Files cut short (a common defect of downloaded GIFs) keep the images
+    /// read before the end.
+    fn parse(data: &'a [u8], max_frames: usize) -> ImageResult<Self> {
+        let invalid = |message: &str| ImageError::UnsupportedFormat(format!("Invalid GIF header: {}", message));
+        if !is_gif(data) {
+            return Err(invalid("missing GIF87a/GIF89a signature"));
+        }
+        let mut reader = Reader { data, pos: 6 };
+        let (width, height) = match (reader.u16(), reader.u16()) {
+            (Some(width), Some(height)) => (width as u32, height as u32),
+            _ => return Err(invalid("truncated logical screen descriptor")),
+        };
+        let flags = reader.bytes(3).ok_or_else(|| invalid("truncated logical screen descriptor"))?[0];
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+        let global_palette = if flags & 0x80 != 0 {
+            reader.bytes(3 << ((flags & 7) + 1)).ok_or_else(|| invalid("truncated global color table"))?
+        } else {
+            &[]
+        };
+
+        let mut frames = Vec::new();
+        // Without a NETSCAPE2.0 extension the animation plays once
+        let mut loop_count = 1;
+        let mut control = GraphicControl::default();
+        while frames.len() < max_frames {
+            match reader.byte() {
+                Some(0x21) => {
+                    let Some(label) = reader.byte() else { break };
+                    let Some(blocks) = reader.sub_blocks() else { break };
+                    match (label, &blocks[..]) {
+                        (0xF9, [block, ..]) if block.len() >= 4 => {
+                            control = GraphicControl {
+                                disposal: match (block[0] >> 2) & 7 {
+                                    2 => FrameDisposal::Background,
+                                    3 => FrameDisposal::Previous,
+                                    _ => FrameDisposal::Keep,
+                                },
+                                delay_cs: u16::from_le_bytes([block[1], block[2]]),
+                                transparent: (block[0] & 1 != 0).then_some(block[3]),
+                            };
+                        }
+                        // Looping extension: the stored count is the number of repeats
+                        (0xFF, [b"NETSCAPE2.0" | b"ANIMEXTS1.0", settings, ..]) if settings.len() >= 3 && settings[0] == 1 => {
+                            loop_count = match u16::from_le_bytes([settings[1], settings[2]]) {
+                                0 => 0,
+                                repeats => repeats as u32 + 1,
+                            };
+                        }
+                        _ => {}
+                    }
+                }
+                Some(0x2C) => {
+                    let Some(descriptor) = reader.bytes(9) else { break };
+                    let field = |offset: usize| u16::from_le_bytes([descriptor[offset], descriptor[offset + 1]]) as u32;
+                    let flags = descriptor[8];
+                    let palette = if flags & 0x80 != 0 {
+                        let Some(palette) = reader.bytes(3 << ((flags & 7) + 1)) else { break };
+                        palette
+                    } else {
+                        global_palette
+                    };
+                    if palette.is_empty() {
+                        return Err(corrupt("image without a color table"));
+                    }
+                    let Some(min_code_size) = reader.byte() else { break };
+                    if !(1..=11).contains(&min_code_size) {
+                        return Err(corrupt("invalid LZW code size"));
+                    }
+                    let start = reader.pos;
+                    let (data, complete) = match reader.sub_blocks() {
+                        Some(blocks) => (blocks, true),
+                        // Keep every whole sub-block of a truncated image
+                        None => {
+                            let mut partial = Reader { data, pos: start };
+                            let mut blocks = Vec::new();
+                            while let Some(block) = partial.byte().filter(|&size| size > 0).and_then(|size| partial.bytes(size as usize)) {
+                                blocks.push(block);
+                            }
+                            (blocks, false)
+                        }
+                    };
+                    frames.push(FrameData {
+                        left: field(0),
+                        top: field(2),
+                        width: field(4),
+                        height: field(6),
+                        interlaced: flags & 0x40 != 0,
+                        palette,
+                        min_code_size,
+                        data,
+                        control: std::mem::take(&mut control),
+                    });
+                    if !complete {
+                        break;
+                    }
+                }
+                Some(0x3B) | None => break,
+                Some(_) => return Err(corrupt("unknown block type")),
+            }
+        }
+
+        if frames.is_empty() && max_frames > 0 {
+            return Err(ImageError::Other("GIF file contains no image".to_string()));
+        }
+        Ok(Self { width, height, frames, loop_count })
+    }
+}
+
+/// LSB-first code reader across data sub-blocks
+struct CodeReader<'a> {
+    blocks: std::slice::Iter<'a, &'a [u8]>,
+    block: &'a [u8],
+    value: u32,
+    bits: u32,
+}
+
+impl CodeReader<'_> {
+    /// Read a code, or `None` at the end of the data
+    fn read(&mut self, size: u32) -> Option<usize> {
+        while self.bits < size {
+            while self.block.is_empty() {
+                self.block = self.blocks.next()?;
+            }
+            self.value |= (self.block[0] as u32) << self.bits;
+            self.block = &self.block[1..];
+            self.bits += 8;
+        }
+        let code = self.value & ((1 << size) - 1);
+        self.value >>= size;
+        self.bits -= size;
+        Some(code as usize)
+    }
+}
+
+/// Decode LZW data into palette indices
+///
+/// Returns the number of indices written; data ending early leaves the rest
+/// of `output` untouched.
+fn decode_lzw(blocks: &[&[u8]], min_code_size: u8, output: &mut [u8]) -> ImageResult<usize> {
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    // Each string is its prefix string followed by one byte
+    let mut prefix = [0u16; MAX_CODES];
+    let mut suffix = [0u8; MAX_CODES];
+    let mut first = [0u8; MAX_CODES];
+    let mut length = [0u16; MAX_CODES];
+    for code in 0..clear {
+        suffix[code] = code as u8;
+        first[code] = code as u8;
+        length[code] = 1;
+    }
+
+    let mut reader = CodeReader { blocks: blocks.iter(), block: &[], value: 0, bits: 0 };
+    let mut code_size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    let mut previous: Option<usize> = None;
+    let mut written = 0;
+    while written < output.len() {
+        let Some(code) = reader.read(code_size) else { break };
+        if code == clear {
+            code_size = min_code_size as u32 + 1;
+            next = end + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+
+        match previous {
+            None if code >= clear => return Err(corrupt("invalid LZW code")),
+            None => {}
+            Some(previous) => {
+                if code > next || (code == next && next == MAX_CODES) {
+                    return Err(corrupt("invalid LZW code"));
+                }
+                // Once the table is full, codes keep their size until the next clear code
+                if next < MAX_CODES {
+                    prefix[next] = previous as u16;
+                    suffix[next] = if code < next { first[code] } else { first[previous] };
+                    first[next] = first[previous];
+                    length[next] = length[previous] + 1;
+                    next += 1;
+                    if next == 1 << code_size && code_size < 12 {
+                        code_size += 1;
+                    }
+                }
+            }
+        }
+
+        // Strings are written back to front, clipped at the end of the image
+        let count = length[code] as usize;
+        let mut string = code;
+        for position in (written..written + count).rev() {
+            if let Some(index) = output.get_mut(position) {
+                *index = suffix[string];
+            }
+            string = prefix[string] as usize;
+        }
+        written += count;
+        previous = Some(code);
+    }
+    Ok(written.min(output.len()))
+}
+
+/// Check if data starts with a GIF signature
+pub fn is_gif(data: &[u8]) -> bool {
+    SIGNATURES.iter().any(|signature| data.starts_with(signature))
+}
+
+/// Read GIF header information without decoding pixels
+///
+/// Images are loaded as RGBA U8; `layer_count` is the number of frames.
+pub fn probe_gif(data: &[u8]) -> ImageResult<ImageInfo> {
+    let gif = Gif::parse(data, usize::MAX)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Gif,
+        width: gif.width,
+        height: gif.height,
+        pixel_format: PixelFormat::RGBA,
+        component_type: ComponentType::U8,
+        layer_count: gif.frames.len(),
+        mip_levels: 1,
+    })
+}
+
+/// Decode and composite the frames of a parsed file
+fn composite(gif: Gif, options: &LoadOptions) -> ImageResult<AnimatedImage> {
+    // Every frame is a copy of the canvas, so the limit covers them together
+    options.check_dimensions(gif.width, gif.height)?;
+    let canvas_pixels = gif.width as u64 * gif.height as u64;
+    if canvas_pixels * gif.frames.len() as u64 > options.max_pixels {
+        return Err(ImageError::Other(format!(
+            "GIF animation of {} {}x{} frames exceeds the limit of {} pixels",
+            gif.frames.len(),
+            gif.width,
+            gif.height,
+            options.max_pixels
+        )));
+    }
+
+    let (width, height) = (gif.width as usize, gif.height as usize);
+    let mut canvas = vec![0u8; width * height * 4];
+    let mut frames = Vec::with_capacity(gif.frames.len());
+    let mut indices = Vec::new();
+
+    for frame in &gif.frames {
+        options.check_dimensions(frame.width, frame.height)?;
+        let (frame_width, frame_height) = (frame.width as usize, frame.height as usize);
+        indices.resize(frame_width * frame_height, 0);
+        let decoded = decode_lzw(&frame.data, frame.min_code_size, &mut indices)?;
+
+        let rows: Vec<usize> = if frame.interlaced {
+            INTERLACE_PASSES.iter().flat_map(|&(start, step)| (start..frame_height).step_by(step)).collect()
+        } else {
+            (0..frame_height).collect()
+        };
+        let rect = Rect::new(
+            frame.left.min(gif.width),
+            frame.top.min(gif.height),
+            frame.width.min(gif.width.saturating_sub(frame.left)),
+            frame.height.min(gif.height.saturating_sub(frame.top)),
+        );
+        let previous = (frame.control.disposal == FrameDisposal::Previous).then(|| canvas.clone());
+
+        for (stored_row, &row) in rows.iter().enumerate().take(decoded.div_ceil(frame_width.max(1))) {
+            let y = frame.top as usize + row;
+            if y >= height {
+                continue;
+            }
+            let start = stored_row * frame_width;
+            let row_indices = &indices[start..(start + frame_width).min(decoded)];
+            for (column, &index) in row_indices.iter().enumerate() {
+                let x = frame.left as usize + column;
+                if x >= width || frame.control.transparent == Some(index) {
+                    continue;
+                }
+                // Indices past the color table show as black, like browsers do
+                let color = frame.palette.get(index as usize * 3..index as usize * 3 + 3).unwrap_or(&[0, 0, 0]);
+                let pixel = (y * width + x) * 4;
+                canvas[pixel..pixel + 3].copy_from_slice(color);
+                canvas[pixel + 3] = 255;
+            }
+        }
+
+        let mut image = Image::from_raw(canvas.clone(), gif.width, gif.height, PixelFormat::RGBA, ComponentType::U8);
+        if options.flip_y {
+            image.flip_rows();
+        }
+        frames.push(AnimationFrame {
+            image,
+            delay_ms: frame.control.delay_cs as u32 * 10,
+            disposal: frame.control.disposal,
+            rect,
+        });
+
+        match frame.control.disposal {
+            FrameDisposal::Keep => {}
+            FrameDisposal::Background => {
+                for y in rect.y as usize..(rect.y + rect.height) as usize {
+                    let row = (y * width + rect.x as usize) * 4;
+                    canvas[row..row + rect.width as usize * 4].fill(0);
+                }
+            }
+            FrameDisposal::Previous => canvas = previous.unwrap_or(canvas),
+        }
+    }
+
+    Ok(AnimatedImage { width: gif.width, height: gif.height, frames, loop_count: gif.loop_count })
+}
+
+/// Load the first frame of a GIF file as RGBA U8
+///
+/// Transparent pixels have zero alpha. Canvases and frames larger than
+/// [`LoadOptions::max_pixels`] are rejected before decoding.
+pub fn load_gif(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let animation = composite(Gif::parse(data, 1)?, options)?;
+    Ok(animation.into_images().remove(0))
+}
+
+/// Load every frame of a GIF file, composited onto the full canvas
+///
+/// Frames are RGBA U8 canvases with the disposal of earlier frames applied;
+/// the area outside every frame is transparent (the background color is
+/// ignored, as browsers do). Files cut short keep the frames read so far.
+/// The frames together may not hold more than [`LoadOptions::max_pixels`].
+pub fn load_gif_animation(data: &[u8], options: &LoadOptions) -> ImageResult<AnimatedImage> {
+    composite(Gif::parse(data, usize::MAX)?, options)
+}
@@ -0,0 +1,301 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat};
+use crate::buffer_pool;
+
+/// Program identifiers accepted on the first header line
+const SIGNATURES: [&[u8]; 2] = [b"#?RADIANCE", b"#?RGBE"];
+
+/// Scanline widths written with per-channel run-length encoding
+const RLE_WIDTHS: std::ops::RangeInclusive<usize> = 8..=0x7FFF;
+
+/// Parsed Radiance header
+struct Header {
+    width: u32,
+    height: u32,
+    /// Scanlines are stored bottom-up (`+Y`)
+    bottom_up: bool,
+    /// Pixels are stored right-to-left (`-X`)
+    right_to_left: bool,
+    data_offset: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> ImageResult<Self> {
+        let invalid = |message: &str| ImageError::UnsupportedFormat(format!("Invalid Radiance HDR header: {}", message));
+        if !is_hdr(data) {
+            return Err(invalid("missing #?RADIANCE signature"));
+        }
+
+        // Variable lines up to an empty line, then the resolution string
+        let mut lines = data.split(|&byte| byte == b'\n');
+        let mut data_offset = 0;
+        let mut next_line = || {
+            let line = lines.next()?;
+            data_offset += line.len() + 1;
+            Some(line)
+        };
+        next_line();
+        loop {
+            let line = next_line().ok_or_else(|| invalid("truncated header"))?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(format) = line.strip_prefix(b"FORMAT=") {
+                match format.trim_ascii() {
+                    b"32-bit_rle_rgbe" => {}
+                    b"32-bit_rle_xyze" => {
+                        return Err(ImageError::UnsupportedFormat("XYZE Radiance HDR files are not supported".to_string()));
+                    }
+                    other => return Err(invalid(&format!("unknown format {}", String::from_utf8_lossy(other)))),
+                }
+            }
+        }
+
+        let resolution = next_line().ok_or_else(|| invalid("missing resolution"))?;
+        let resolution = std::str::from_utf8(resolution).map_err(|_| invalid("malformed resolution"))?;
+        let fields: Vec<&str> = resolution.split_ascii_whitespace().collect();
+        let (y_axis, height, x_axis, width) = match fields[..] {
+            [y_axis, height, x_axis, width] => (y_axis, height, x_axis, width),
+            _ => return Err(invalid("malformed resolution")),
+        };
+        let parse = |value: &str| value.parse::<u32>().map_err(|_| invalid("malformed resolution"));
+        let (width, height) = (parse(width)?, parse(height)?);
+        let (bottom_up, right_to_left) = match (y_axis, x_axis) {
+            ("-Y" | "+Y", "+X" | "-X") => (y_axis == "+Y", x_axis == "-X"),
+            ("-X" | "+X", _) => {
+                return Err(ImageError::UnsupportedFormat("Column-major Radiance HDR files are not supported".to_string()));
+            }
+            _ => return Err(invalid("malformed resolution")),
+        };
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+
+        Ok(Self { width, height, bottom_up, right_to_left, data_offset })
+    }
+}
+
+/// Check if data starts with a Radiance HDR signature
+pub fn is_hdr(data: &[u8]) -> bool {
+    SIGNATURES.iter().any(|signature| data.starts_with(signature))
+}
+
+/// Read Radiance HDR header information without decoding pixels
+///
+/// Images are loaded as RGB F32.
+pub fn probe_hdr(data: &[u8]) -> ImageResult<ImageInfo> {
+    let header = Header::parse(data)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Hdr,
+        width: header.width,
+        height: header.height,
+        pixel_format: PixelFormat::RGB,
+        component_type: ComponentType::F32,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Decode one scanline of RGBE pixels (flat, old-style or per-channel RLE)
+///
+/// Returns the position after the scanline.
+fn read_scanline(data: &[u8], mut pos: usize, scanline: &mut [u8]) -> ImageResult<usize> {
+    let truncated = || ImageError::Other("Truncated Radiance HDR pixel data".to_string());
+    let corrupt = || ImageError::Other("Corrupt Radiance HDR run-length data".to_string());
+    let width = scanline.len() / 4;
+
+    let start = data.get(pos..pos + 4).ok_or_else(truncated)?;
+    if RLE_WIDTHS.contains(&width) && start[0] == 2 && start[1] == 2 && start[2] & 0x80 == 0 {
+        if ((start[2] as usize) << 8 | start[3] as usize) != width {
+            return Err(corrupt());
+        }
+        pos += 4;
+        // Each channel is stored separately as runs and literal packets
+        for channel in 0..4 {
+            let mut x = 0;
+            while x < width {
+                let count = *data.get(pos).ok_or_else(truncated)? as usize;
+                pos += 1;
+                let (length, run) = if count > 128 { (count - 128, true) } else { (count, false) };
+                if length == 0 || x + length > width {
+                    return Err(corrupt());
+                }
+                if run {
+                    let value = *data.get(pos).ok_or_else(truncated)?;
+                    pos += 1;
+                    for pixel in x..x + length {
+                        scanline[pixel * 4 + channel] = value;
+                    }
+                } else {
+                    let values = data.get(pos..pos + length).ok_or_else(truncated)?;
+                    pos += length;
+                    for (pixel, &value) in (x..x + length).zip(values) {
+                        scanline[pixel * 4 + channel] = value;
+                    }
+                }
+                x += length;
+            }
+        }
+        return Ok(pos);
+    }
+
+    // Flat pixels, where (1, 1, 1, n) repeats the previous pixel n << shift times
+    let mut x = 0;
+    let mut shift = 0;
+    while x < width {
+        let pixel = data.get(pos..pos + 4).ok_or_else(truncated)?;
+        pos += 4;
+        if pixel[..3] == [1, 1, 1] {
+            let count = (pixel[3] as usize) << shift;
+            if x == 0 || x + count > width {
+                return Err(corrupt());
+            }
+            let previous: [u8; 4] = scanline[(x - 1) * 4..x * 4].try_into().unwrap();
+            for repeated in scanline[x * 4..(x + count) * 4].chunks_exact_mut(4) {
+                repeated.copy_from_slice(&previous);
+            }
+            x += count;
+            shift += 8;
+        } else {
+            scanline[x * 4..x * 4 + 4].copy_from_slice(pixel);
+            x += 1;
+            shift = 0;
+        }
+    }
+    Ok(pos)
+}
+
+/// Convert an RGBE pixel to linear RGB
+fn rgbe_to_rgb(rgbe: &[u8]) -> [f32; 3] {
+    if rgbe[3] == 0 {
+        return [0.0; 3];
+    }
+    // Mantissas are 8-bit fractions of 2^(exponent - 128)
+    let scale = 2f32.powi(rgbe[3] as i32 - 136);
+    [rgbe[0] as f32 * scale, rgbe[1] as f32 * scale, rgbe[2] as f32 * scale]
+}
+
+/// Load a Radiance HDR (RGBE) image as RGB F32
+///
+/// Reads flat, old-style run-length and per-channel run-length scanlines in
+/// any row-major orientation.
+pub fn load_hdr(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let header = Header::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    let (width, height) = (header.width as usize, header.height as usize);
+    let top_down = header.bottom_up == options.flip_y;
+
+    let size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(12))
+        .ok_or(ImageError::InvalidDimensions { width: header.width, height: header.height })?;
+    let mut output = buffer_pool::take_zeroed(size);
+    let mut scanline = vec![0u8; width * 4];
+    let mut pos = header.data_offset;
+    for row in 0..height {
+        pos = read_scanline(data, pos, &mut scanline)?;
+        let y = if top_down { row } else { height - 1 - row };
+        let output_row = &mut output[y * width * 12..(y + 1) * width * 12];
+        for (column, rgbe) in scanline.chunks_exact(4).enumerate() {
+            let x = if header.right_to_left { width - 1 - column } else { column };
+            for (bytes, value) in output_row[x * 12..x * 12 + 12].chunks_exact_mut(4).zip(rgbe_to_rgb(rgbe)) {
+                bytes.copy_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+
+    Ok(Image::from_raw(output, header.width, header.height, PixelFormat::RGB, ComponentType::F32))
+}
+
+/// Convert linear RGB to an RGBE pixel
+///
+/// Negative and NaN components are stored as 0, values beyond the RGBE range
+/// are clamped.
+fn rgb_to_rgbe(rgb: [f32; 3]) -> [u8; 4] {
+    // Largest representable component: mantissa 255 with exponent 127
+    let largest = 255.0 * 2f64.powi(119);
+    let rgb = rgb.map(|value| if value > 0.0 { (value as f64).min(largest) } else { 0.0 });
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    // Values below 2^-128 have no RGBE representation
+    if max < 2f64.powi(-128) {
+        return [0; 4];
+    }
+
+    // Shared exponent so the largest component has a mantissa in 128..256
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max * 2f64.powi(8 - exponent) >= 256.0 {
+        exponent += 1;
+    }
+    let scale = 2f64.powi(8 - exponent);
+    let mantissa = |value: f64| (value * scale).round().min(255.0) as u8;
+    [mantissa(rgb[0]), mantissa(rgb[1]), mantissa(rgb[2]), (exponent + 128) as u8]
+}
+
+/// Append one channel of a scanline as runs (4 or more equal values) and literals
+fn encode_rle_channel(values: &[u8], output: &mut Vec<u8>) {
+    let run_length = |start: usize| values[start..].iter().take(127).take_while(|&&value| value == values[start]).count();
+    let mut start = 0;
+    while start < values.len() {
+        let run = run_length(start);
+        if run >= 4 {
+            output.extend_from_slice(&[128 + run as u8, values[start]]);
+            start += run;
+            continue;
+        }
+
+        // Literal packet up to the next run worth encoding
+        let mut end = start + 1;
+        while end < values.len() && end - start < 128 && run_length(end) < 4 {
+            end += 1;
+        }
+        output.push((end - start) as u8);
+        output.extend_from_slice(&values[start..end]);
+        start = end;
+    }
+}
+
+/// Encode an image as a run-length encoded Radiance HDR file, appending to `buffer`
+///
+/// Supports F16 and F32 component types. Grayscale is replicated to RGB and
+/// alpha is dropped. Empty images are rejected with
+/// [`ImageError::InvalidDimensions`].
+pub fn save_hdr(image: &Image, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    // Radiance HDR only stores floating-point radiance
+    match image.component_type() {
+        ComponentType::F16 | ComponentType::F32 => {}
+        other => {
+            return Err(ImageError::UnsupportedFormat(
+                format!("Radiance HDR does not support {:?} component type, use F16 or F32", other),
+            ));
+        }
+    }
+    if image.width() == 0 || image.height() == 0 {
+        return Err(ImageError::InvalidDimensions { width: image.width(), height: image.height() });
+    }
+    let rgb = image.convert(PixelFormat::RGB, ComponentType::F32)?;
+    let (width, height) = (image.width() as usize, image.height() as usize);
+
+    buffer.extend_from_slice(b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n");
+    buffer.extend_from_slice(format!("-Y {} +X {}\n", height, width).as_bytes());
+
+    let mut scanline = vec![0u8; width * 4];
+    let mut channel = vec![0u8; width];
+    for row in rgb.data().chunks_exact(width * 12).take(height) {
+        for (rgbe, pixel) in scanline.chunks_exact_mut(4).zip(row.chunks_exact(12)) {
+            let component = |index: usize| f32::from_le_bytes(pixel[index * 4..index * 4 + 4].try_into().unwrap());
+            rgbe.copy_from_slice(&rgb_to_rgbe([component(0), component(1), component(2)]));
+        }
+
+        if !RLE_WIDTHS.contains(&width) {
+            buffer.extend_from_slice(&scanline);
+            continue;
+        }
+        buffer.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        for index in 0..4 {
+            for (value, rgbe) in channel.iter_mut().zip(scanline.chunks_exact(4)) {
+                *value = rgbe[index];
+            }
+            encode_rle_channel(&channel, buffer);
+        }
+    }
+    Ok(())
+}
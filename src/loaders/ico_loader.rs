@@ -0,0 +1,239 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat};
+use crate::buffer_pool;
+use crate::loaders::{load_png, probe_png};
+
+/// PNG signature of entries stored as PNG (common for 256x256 icons)
+const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Size of the ICONDIR header and of each ICONDIRENTRY
+const HEADER_SIZE: usize = 6;
+const ENTRY_SIZE: usize = 16;
+
+fn corrupt(message: &str) -> ImageError {
+    ImageError::Other(format!("Corrupt ICO data: {}", message))
+}
+
+/// One resolution stored in the file
+struct Entry<'a> {
+    width: u32,
+    height: u32,
+    bit_count: u16,
+    /// PNG file or headerless BMP (DIB) of the entry
+    payload: &'a [u8],
+}
+
+impl Entry<'_> {
+    fn is_png(&self) -> bool {
+        self.payload.starts_with(&PNG_SIGNATURE)
+    }
+
+    fn info(&self, layer_count: usize) -> ImageInfo {
+        ImageInfo {
+            format: ImageFormat::Ico,
+            width: self.width,
+            height: self.height,
+            pixel_format: PixelFormat::RGBA,
+            component_type: ComponentType::U8,
+            layer_count,
+            mip_levels: 1,
+        }
+    }
+
+    fn decode(&self, options: &LoadOptions) -> ImageResult<Image> {
+        options.check_dimensions(self.width, self.height)?;
+        if self.is_png() {
+            return load_png(self.payload, options)?.convert(PixelFormat::RGBA, ComponentType::U8);
+        }
+        decode_dib(self.payload, self.width, self.height, options.flip_y)
+    }
+}
+
+/// Parse the directory of an ICO or CUR file
+///
+/// Sizes come from the embedded images, since directory entries store 256 as 0
+/// and are sometimes wrong.
+fn parse_entries(data: &[u8]) -> ImageResult<Vec<Entry<'_>>> {
+    if !is_ico(data) {
+        return Err(ImageError::UnsupportedFormat("Invalid ICO header: not an icon or cursor directory".to_string()));
+    }
+    let count = u16::from_le_bytes([data[4], data[5]]) as usize;
+    let mut entries = Vec::with_capacity(count);
+    for index in 0..count {
+        let entry = &data[HEADER_SIZE + index * ENTRY_SIZE..HEADER_SIZE + (index + 1) * ENTRY_SIZE];
+        let size = u32::from_le_bytes(entry[8..12].try_into().unwrap()) as usize;
+        let offset = u32::from_le_bytes(entry[12..16].try_into().unwrap()) as usize;
+        let payload = data
+            .get(offset..offset.saturating_add(size))
+            .ok_or_else(|| ImageError::Other("Truncated ICO file".to_string()))?;
+
+        let (width, height, bit_count) = if payload.starts_with(&PNG_SIGNATURE) {
+            let info = probe_png(payload)?;
+            (info.width, info.height, 32)
+        } else {
+            let header = payload.get(..16).ok_or_else(|| corrupt("truncated bitmap header"))?;
+            let width = i32::from_le_bytes(header[4..8].try_into().unwrap());
+            // The height covers the color bitmap and the AND mask below it
+            let height = i32::from_le_bytes(header[8..12].try_into().unwrap()) / 2;
+            if width <= 0 || height <= 0 {
+                return Err(ImageError::InvalidDimensions { width: width.unsigned_abs(), height: height.unsigned_abs() });
+            }
+            (width as u32, height as u32, u16::from_le_bytes([header[14], header[15]]))
+        };
+        entries.push(Entry { width, height, bit_count, payload });
+    }
+    Ok(entries)
+}
+
+/// Pick the entry with the most pixels, then the highest bit depth
+fn largest<'e, 'a>(entries: &'e [Entry<'a>]) -> &'e Entry<'a> {
+    entries
+        .iter()
+        .max_by_key(|entry| (entry.width as u64 * entry.height as u64, entry.bit_count))
+        .expect("icon directories have at least one entry")
+}
+
+/// Decode a headerless BMP: the color bitmap followed by a 1-bit AND mask
+fn decode_dib(payload: &[u8], width: u32, height: u32, flip_y: bool) -> ImageResult<Image> {
+    let header_size = u32::from_le_bytes(payload[0..4].try_into().unwrap()) as usize;
+    let header = payload.get(..header_size.max(40)).ok_or_else(|| corrupt("truncated bitmap header"))?;
+    let bit_count = u16::from_le_bytes([header[14], header[15]]) as usize;
+    let compression = u32::from_le_bytes(header[16..20].try_into().unwrap());
+    if compression != 0 {
+        return Err(ImageError::UnsupportedFormat(format!("Unsupported ICO bitmap compression {}", compression)));
+    }
+
+    let (width_px, height_px) = (width as usize, height as usize);
+    let palette_len = match bit_count {
+        1 | 4 | 8 => match u32::from_le_bytes(header[32..36].try_into().unwrap()) as usize {
+            0 => 1 << bit_count,
+            used => used.min(1 << bit_count),
+        },
+        16 | 24 | 32 => 0,
+        _ => return Err(ImageError::UnsupportedFormat(format!("Unsupported ICO bit depth {}", bit_count))),
+    };
+    let palette_start = header_size;
+    let palette = payload
+        .get(palette_start..palette_start + palette_len * 4)
+        .ok_or_else(|| corrupt("truncated color table"))?;
+
+    // Rows are padded to 4 bytes and stored bottom-up
+    let color_stride = (width_px * bit_count).div_ceil(32) * 4;
+    let mask_stride = width_px.div_ceil(32) * 4;
+    let color_start = palette_start + palette.len();
+    let colors = payload
+        .get(color_start..color_start + color_stride * height_px)
+        .ok_or_else(|| corrupt("truncated bitmap"))?;
+    // Some writers omit the AND mask of 32-bit entries
+    let mask_start = color_start + colors.len();
+    let mask = payload.get(mask_start..mask_start + mask_stride * height_px);
+
+    let mut output = buffer_pool::take_zeroed(width_px * height_px * 4);
+    for y in 0..height_px {
+        let stored = height_px - 1 - y;
+        let row = &colors[stored * color_stride..(stored + 1) * color_stride];
+        let target = if flip_y { height_px - 1 - y } else { y };
+        let pixels = &mut output[target * width_px * 4..(target + 1) * width_px * 4];
+        for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let bgra = match bit_count {
+                1 | 4 | 8 => {
+                    let bit = x * bit_count;
+                    let index = (row[bit / 8] >> (8 - bit_count - bit % 8)) as usize & ((1 << bit_count) - 1);
+                    // Indices past the color table show as black
+                    let color = palette.get(index * 4..index * 4 + 3).unwrap_or(&[0, 0, 0]);
+                    [color[0], color[1], color[2], 255]
+                }
+                16 => {
+                    let value = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    let channel = |shift: u16| {
+                        let value = ((value >> shift) & 0x1F) as u8;
+                        (value << 3) | (value >> 2)
+                    };
+                    [channel(0), channel(5), channel(10), 255]
+                }
+                24 => [row[x * 3], row[x * 3 + 1], row[x * 3 + 2], 255],
+                _ => [row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]],
+            };
+            pixel.copy_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+        }
+    }
+
+    // The AND mask makes pixels transparent, unless 32-bit entries carry real alpha
+    let has_alpha = bit_count == 32 && output.chunks_exact(4).any(|pixel| pixel[3] != 0);
+    if !has_alpha {
+        for y in 0..height_px {
+            let stored = height_px - 1 - y;
+            let target = if flip_y { height_px - 1 - y } else { y };
+            let row = mask.map(|mask| &mask[stored * mask_stride..(stored + 1) * mask_stride]);
+            for x in 0..width_px {
+                let transparent = row.is_some_and(|row| row[x / 8] & (0x80 >> (x % 8)) != 0);
+                output[(target * width_px + x) * 4 + 3] = if transparent { 0 } else { 255 };
+            }
+        }
+    }
+
+    Ok(Image::from_raw(output, width, height, PixelFormat::RGBA, ComponentType::U8))
+}
+
+/// Check if data starts with a plausible icon (type 1) or cursor (type 2) directory
+///
+/// ICO files have no real magic number, so the first entry must also point
+/// inside the file.
+pub fn is_ico(data: &[u8]) -> bool {
+    if data.len() < HEADER_SIZE + ENTRY_SIZE || data[0..2] != [0, 0] || !matches!(data[2..4], [1, 0] | [2, 0]) {
+        return false;
+    }
+    let count = u16::from_le_bytes([data[4], data[5]]) as usize;
+    let directory_end = HEADER_SIZE + count * ENTRY_SIZE;
+    if count == 0 || data.len() < directory_end {
+        return false;
+    }
+    let entry = &data[HEADER_SIZE..HEADER_SIZE + ENTRY_SIZE];
+    let size = u32::from_le_bytes(entry[8..12].try_into().unwrap()) as u64;
+    let offset = u32::from_le_bytes(entry[12..16].try_into().unwrap()) as u64;
+    size > 0 && offset >= directory_end as u64 && offset + size <= data.len() as u64
+}
+
+/// Read the header of the largest image of an ICO or CUR file
+///
+/// Entries are loaded as RGBA U8; `layer_count` is the number of stored resolutions.
+pub fn probe_ico(data: &[u8]) -> ImageResult<ImageInfo> {
+    let entries = parse_entries(data)?;
+    Ok(largest(&entries).info(entries.len()))
+}
+
+/// Describe every resolution of an ICO or CUR file, in directory order
+pub fn probe_ico_all(data: &[u8]) -> ImageResult<Vec<ImageInfo>> {
+    let entries = parse_entries(data)?;
+    Ok(entries.iter().map(|entry| entry.info(entries.len())).collect())
+}
+
+/// Load the largest image of an ICO or CUR file as RGBA U8
+///
+/// Entries stored as BMP get their transparency from the AND mask (or from
+/// the alpha channel of 32-bit entries); PNG entries are converted to RGBA U8.
+/// Cursor hotspots are ignored.
+pub fn load_ico(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    largest(&parse_entries(data)?).decode(options)
+}
+
+/// Load every resolution of an ICO or CUR file, in directory order
+pub fn load_ico_all(data: &[u8], options: &LoadOptions) -> ImageResult<Vec<Image>> {
+    parse_entries(data)?.iter().map(|entry| entry.decode(options)).collect()
+}
+
+/// Load the resolution of an ICO or CUR file closest to a size
+///
+/// Compares `size` with the largest dimension of each entry. On a tie the
+/// larger entry wins, as downscaling looks better than upscaling, then the
+/// higher bit depth. Only the selected entry is decoded.
+pub fn load_ico_for_size(data: &[u8], size: u32, options: &LoadOptions) -> ImageResult<Image> {
+    let entries = parse_entries(data)?;
+    let entry = entries
+        .iter()
+        .min_by_key(|entry| {
+            let dim = entry.width.max(entry.height);
+            (dim.abs_diff(size), dim < size, std::cmp::Reverse(entry.bit_count))
+        })
+        .expect("icon directories have at least one entry");
+    entry.decode(options)
+}
@@ -1,9 +1,82 @@
-use crate::{Image, ImageError, ImageResult, PixelFormat, ComponentType};
-use std::io::Cursor;
+use crate::{ColorSpace, Image, ImageError, ImageFormat, ImageInfo, ImageMetadata, ImageResult, JpegSubsampling, LoadOptions, Orientation, PixelFormat, ComponentType, SaveOptions};
+use crate::row_view::RowView;
+use super::exif::{orientation_exif, read_orientation};
+use std::io::{Cursor, Read, Write};
 
-pub fn load_jpeg(data: &[u8]) -> ImageResult<Image> {
+/// Map the JPEG decoder pixel format to pixel format and component type
+fn jpeg_layout(
+    pixel_format: jpeg_decoder::PixelFormat,
+) -> ImageResult<(PixelFormat, ComponentType)> {
+    match pixel_format {
+        jpeg_decoder::PixelFormat::L8 => Ok((PixelFormat::R, ComponentType::U8)),
+        jpeg_decoder::PixelFormat::L16 => Ok((PixelFormat::R, ComponentType::U16)),
+        jpeg_decoder::PixelFormat::RGB24 => Ok((PixelFormat::RGB, ComponentType::U8)),
+        jpeg_decoder::PixelFormat::CMYK32 => Err(ImageError::UnsupportedFormat(
+            "JPEG CMYK format not supported".to_string()
+        )),
+    }
+}
+
+/// Read JPEG header information without decoding pixels
+pub fn probe_jpeg(data: &[u8]) -> ImageResult<ImageInfo> {
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(data));
+    decoder.read_info()?;
+    let metadata = decoder.info().ok_or_else(|| {
+        ImageError::Other("Failed to get JPEG metadata".to_string())
+    })?;
+    let (pixel_format, component_type) = jpeg_layout(metadata.pixel_format)?;
+
+    Ok(ImageInfo {
+        format: ImageFormat::Jpeg,
+        width: metadata.width as u32,
+        height: metadata.height as u32,
+        pixel_format,
+        component_type,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+pub fn load_jpeg(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    load_jpeg_from_reader(Cursor::new(data), options)
+}
+
+/// Decode a JPEG image as it is read from `reader`
+pub fn load_jpeg_from_reader<R: Read>(reader: R, options: &LoadOptions) -> ImageResult<Image> {
+    let mut decoder = jpeg_decoder::Decoder::new(reader);
+    decoder.read_info()?;
+    if let Some(info) = decoder.info() {
+        options.check_dimensions(info.width as u32, info.height as u32)?;
+    }
+    let mut image = decode_jpeg(decoder)?;
+    if options.auto_orient {
+        image = image.apply_orientation();
+    }
+
+    // The decoder only produces whole frames, so flip after decoding
+    if options.flip_y {
+        image.flip_rows();
+    }
+
+    Ok(image)
+}
+
+/// Load a JPEG image decoded at a reduced scale
+///
+/// Uses DCT scaling (1/8, 1/4, 1/2 or 1) to produce the smallest image that is
+/// at least the requested size in one dimension, which is much faster than a
+/// full decode followed by a downscale.
+pub fn load_jpeg_scaled(data: &[u8], min_width: u32, min_height: u32) -> ImageResult<Image> {
     let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(data));
-    let pixels = decoder.decode()?;
+    decoder.scale(
+        min_width.min(u16::MAX as u32) as u16,
+        min_height.min(u16::MAX as u32) as u16,
+    )?;
+    decode_jpeg(decoder)
+}
+
+fn decode_jpeg<R: Read>(mut decoder: jpeg_decoder::Decoder<R>) -> ImageResult<Image> {
+    let mut pixels = decoder.decode()?;
     let metadata = decoder.info().ok_or_else(|| {
         ImageError::Other("Failed to get JPEG metadata".to_string())
     })?;
@@ -12,27 +85,36 @@ pub fn load_jpeg(data: &[u8]) -> ImageResult<Image> {
     let height = metadata.height as u32;
 
     // Determine pixel format from JPEG color space
-    let pixel_format = match metadata.pixel_format {
-        jpeg_decoder::PixelFormat::L8 => PixelFormat::R,
-        jpeg_decoder::PixelFormat::L16 => PixelFormat::R,  // 16-bit grayscale
-        jpeg_decoder::PixelFormat::RGB24 => PixelFormat::RGB,
-        jpeg_decoder::PixelFormat::CMYK32 => {
-            return Err(ImageError::UnsupportedFormat(
-                "JPEG CMYK format not supported".to_string()
-            ));
+    let (pixel_format, component_type) = jpeg_layout(metadata.pixel_format)?;
+
+    // 16-bit samples are decoded in native byte order, images store them little-endian
+    if component_type == ComponentType::U16 {
+        for sample in pixels.chunks_exact_mut(2) {
+            let value = u16::from_ne_bytes([sample[0], sample[1]]);
+            sample.copy_from_slice(&value.to_le_bytes());
         }
-    };
+    }
 
-    Ok(Image::from_raw(
+    let orientation = decoder.exif_data().and_then(read_orientation).and_then(Orientation::from_exif);
+
+    let mut image = Image::from_raw(
         pixels,
         width,
         height,
         pixel_format,
-        ComponentType::U8,
-    ))
+        component_type,
+    );
+    image.set_color_space(ColorSpace::Srgb);
+    image.set_metadata(ImageMetadata { orientation: orientation.unwrap_or_default(), ..Default::default() });
+    Ok(image)
 }
 
-pub fn save_jpeg(image: &Image, quality: u8) -> ImageResult<Vec<u8>> {
+/// Encode an image as a JPEG into `writer`
+///
+/// Uses the quality, subsampling and progressive settings of
+/// [`SaveOptions::jpeg`]. A non-upright metadata orientation is
+/// written as an EXIF orientation tag.
+pub fn save_jpeg<W: Write>(image: &Image, options: &SaveOptions, writer: W) -> ImageResult<()> {
     // JPEG only supports U8 component type
     if image.component_type() != ComponentType::U8 {
         return Err(ImageError::UnsupportedFormat(
@@ -40,51 +122,75 @@ pub fn save_jpeg(image: &Image, quality: u8) -> ImageResult<Vec<u8>> {
         ));
     }
 
-    // JPEG supports RGB and grayscale
-    let (data, color_type) = match image.pixel_format() {
-        PixelFormat::R => (image.data().to_vec(), jpeg_encoder::ColorType::Luma),
-        PixelFormat::RGB => (image.data().to_vec(), jpeg_encoder::ColorType::Rgb),
-        PixelFormat::BGR => {
-            // Convert BGR to RGB
-            let mut img = image.clone();
-            img.bgr_to_rgb();
-            (img.into_data(), jpeg_encoder::ColorType::Rgb)
-        }
-        PixelFormat::RGBA | PixelFormat::BGRA => {
-            // Strip alpha channel
-            let mut rgb_data = Vec::with_capacity((image.width() * image.height() * 3) as usize);
-            let channels = image.pixel_format().channel_count();
-            for pixel in image.data().chunks_exact(channels) {
-                if image.pixel_format() == PixelFormat::RGBA {
-                    rgb_data.push(pixel[0]); // R
-                    rgb_data.push(pixel[1]); // G
-                    rgb_data.push(pixel[2]); // B
-                } else {
-                    // BGRA -> RGB
-                    rgb_data.push(pixel[2]); // R
-                    rgb_data.push(pixel[1]); // G
-                    rgb_data.push(pixel[0]); // B
-                }
-            }
-            (rgb_data, jpeg_encoder::ColorType::Rgb)
-        }
+    // The encoder reads BGR and ignores alpha itself, so the image is borrowed as is
+    let color_type = match image.pixel_format() {
+        PixelFormat::R => jpeg_encoder::ColorType::Luma,
+        PixelFormat::RGB => jpeg_encoder::ColorType::Rgb,
+        PixelFormat::BGR => jpeg_encoder::ColorType::Bgr,
+        PixelFormat::RGBA => jpeg_encoder::ColorType::Rgba,
+        PixelFormat::BGRA => jpeg_encoder::ColorType::Bgra,
         PixelFormat::RG => {
-            // Use only the first channel (grayscale)
-            let mut gray_data = Vec::with_capacity((image.width() * image.height()) as usize);
-            for pixel in image.data().chunks_exact(2) {
-                gray_data.push(pixel[0]);
-            }
-            (gray_data, jpeg_encoder::ColorType::Luma)
+            // Gray + alpha has no encoder layout: feed the gray channel row by row
+            let encoder = configured_encoder(image, options, writer)?;
+            return encoder
+                .encode_image(LumaRows(RowView::new(image, &[0], false)))
+                .map_err(|e| ImageError::JpegEncodeError(format!("{:?}", e)));
         }
     };
 
-    // Encode JPEG
-    let mut buffer = Vec::new();
-    let encoder = jpeg_encoder::Encoder::new(&mut buffer, quality);
+    let encoder = configured_encoder(image, options, writer)?;
 
     encoder
-        .encode(&data, image.width() as u16, image.height() as u16, color_type)
+        .encode(image.data(), image.width() as u16, image.height() as u16, color_type)
         .map_err(|e| ImageError::JpegEncodeError(format!("{:?}", e)))?;
 
-    Ok(buffer)
+    Ok(())
+}
+
+/// Create an encoder configured from the save options and the image orientation
+fn configured_encoder<W: Write>(
+    image: &Image,
+    options: &SaveOptions,
+    writer: W,
+) -> ImageResult<jpeg_encoder::Encoder<W>> {
+    let mut encoder = jpeg_encoder::Encoder::new(writer, options.jpeg.quality.clamp(1, 100));
+    // `Auto` keeps the encoder's quality-based choice
+    match options.jpeg.subsampling {
+        JpegSubsampling::Auto => {}
+        JpegSubsampling::Yuv444 => encoder.set_sampling_factor(jpeg_encoder::SamplingFactor::R_4_4_4),
+        JpegSubsampling::Yuv422 => encoder.set_sampling_factor(jpeg_encoder::SamplingFactor::R_4_2_2),
+        JpegSubsampling::Yuv420 => encoder.set_sampling_factor(jpeg_encoder::SamplingFactor::R_4_2_0),
+    }
+    encoder.set_progressive(options.jpeg.progressive);
+
+    let orientation = image.metadata().orientation;
+    if orientation != Orientation::Normal {
+        let exif = [&b"Exif\0\0"[..], &orientation_exif(orientation.to_exif())].concat();
+        encoder
+            .add_app_segment(1, &exif)
+            .map_err(|e| ImageError::JpegEncodeError(format!("{:?}", e)))?;
+    }
+    Ok(encoder)
+}
+
+/// Grayscale rows produced by a row view, for the encoder's row callback
+struct LumaRows<'a>(RowView<'a>);
+
+impl jpeg_encoder::ImageBuffer for LumaRows<'_> {
+    fn get_jpeg_color_type(&self) -> jpeg_encoder::JpegColorType {
+        jpeg_encoder::JpegColorType::Luma
+    }
+
+    fn width(&self) -> u16 {
+        self.0.width() as u16
+    }
+
+    fn height(&self) -> u16 {
+        self.0.height() as u16
+    }
+
+    fn fill_buffers(&self, y: u16, buffers: &mut [Vec<u8>; 4]) {
+        self.0.convert_row(y as u32, &mut buffers[0]);
+    }
 }
+
@@ -0,0 +1,144 @@
+use crate::{Image, ImageError, ImageInfo, ImageResult, LoadOptions};
+#[cfg(feature = "jxl")]
+use crate::{ComponentType, ImageFormat, PixelFormat};
+#[cfg(feature = "jxl")]
+use crate::buffer_pool;
+#[cfg(feature = "jxl")]
+use jxl_oxide::image::BitDepth;
+#[cfg(feature = "jxl")]
+use jxl_oxide::JxlImage;
+
+/// Signature of a bare JPEG XL codestream
+const CODESTREAM_SIGNATURE: [u8; 2] = [0xFF, 0x0A];
+
+/// Signature box of the ISO BMFF-based JPEG XL container
+const CONTAINER_SIGNATURE: [u8; 12] = [0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+/// Check if data starts with a JPEG XL codestream or container signature
+pub fn is_jxl(data: &[u8]) -> bool {
+    data.starts_with(&CODESTREAM_SIGNATURE) || data.starts_with(&CONTAINER_SIGNATURE)
+}
+
+#[cfg(feature = "jxl")]
+fn decode_error(error: impl std::fmt::Display) -> ImageError {
+    ImageError::Other(format!("JPEG XL decoding error: {}", error))
+}
+
+#[cfg(feature = "jxl")]
+fn open(data: &[u8]) -> ImageResult<JxlImage> {
+    if !is_jxl(data) {
+        return Err(ImageError::UnsupportedFormat("Invalid JPEG XL header: missing signature".to_string()));
+    }
+    JxlImage::builder().read(data).map_err(decode_error)
+}
+
+/// Output layout of a decoded image
+///
+/// Float samples (and integers wider than 16 bits) decode to F32, samples
+/// wider than 8 bits to U16, others to U8.
+#[cfg(feature = "jxl")]
+fn layout(image: &JxlImage) -> ImageResult<(PixelFormat, ComponentType)> {
+    let pixel_format = match image.pixel_format() {
+        jxl_oxide::PixelFormat::Gray => PixelFormat::R,
+        jxl_oxide::PixelFormat::Graya => PixelFormat::RG,
+        jxl_oxide::PixelFormat::Rgb => PixelFormat::RGB,
+        jxl_oxide::PixelFormat::Rgba => PixelFormat::RGBA,
+        jxl_oxide::PixelFormat::Cmyk | jxl_oxide::PixelFormat::Cmyka => {
+            return Err(ImageError::UnsupportedFormat("CMYK JPEG XL images are not supported".to_string()));
+        }
+    };
+    let component_type = match image.image_header().metadata.bit_depth {
+        BitDepth::FloatSample { .. } | BitDepth::IntegerSample { bits_per_sample: 17.. } => ComponentType::F32,
+        BitDepth::IntegerSample { bits_per_sample: 9.. } => ComponentType::U16,
+        BitDepth::IntegerSample { .. } => ComponentType::U8,
+    };
+    Ok((pixel_format, component_type))
+}
+
+/// Read JPEG XL header information without decoding pixels
+///
+/// Gray images load as R or RG, color images as RGB or RGBA; see [`load_jxl`]
+/// for the component type.
+#[cfg(feature = "jxl")]
+pub fn probe_jxl(data: &[u8]) -> ImageResult<ImageInfo> {
+    let image = open(data)?;
+    let (pixel_format, component_type) = layout(&image)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Jxl,
+        width: image.width(),
+        height: image.height(),
+        pixel_format,
+        component_type,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Load the first frame of a JPEG XL image (feature `jxl`)
+///
+/// Samples decode to U8 up to 8 bits, U16 up to 16 bits and F32 for float
+/// or wider images, in the color space stored in the file (sRGB for most
+/// images; no ICC transform is applied). The stored orientation is applied.
+/// CMYK images are rejected.
+#[cfg(feature = "jxl")]
+pub fn load_jxl(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let image = open(data)?;
+    options.check_dimensions(image.width(), image.height())?;
+    let (pixel_format, component_type) = layout(&image)?;
+    let render = image.render_frame(0).map_err(decode_error)?;
+    let mut stream = render.stream();
+    let (width, height) = (stream.width(), stream.height());
+    let sample_count = width as usize * height as usize * pixel_format.channel_count();
+    if stream.channels() as usize != pixel_format.channel_count() {
+        return Err(ImageError::Other(format!(
+            "JPEG XL decoding error: {} channels rendered for a {:?} image",
+            stream.channels(),
+            pixel_format
+        )));
+    }
+
+    let output = match component_type {
+        ComponentType::U8 => {
+            let mut output = buffer_pool::take_zeroed(sample_count);
+            stream.write_to_buffer(&mut output[..]);
+            output
+        }
+        ComponentType::U16 => {
+            let mut samples = vec![0u16; sample_count];
+            stream.write_to_buffer(&mut samples[..]);
+            let mut output = buffer_pool::take(sample_count * 2);
+            output.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+            output
+        }
+        _ => {
+            let mut samples = vec![0f32; sample_count];
+            stream.write_to_buffer(&mut samples[..]);
+            let mut output = buffer_pool::take(sample_count * 4);
+            output.extend(samples.iter().flat_map(|sample| sample.to_le_bytes()));
+            output
+        }
+    };
+
+    let mut image = Image::from_raw(output, width, height, pixel_format, component_type);
+    if options.flip_y {
+        image.flip_rows();
+    }
+    Ok(image)
+}
+
+#[cfg(not(feature = "jxl"))]
+fn feature_disabled() -> ImageError {
+    ImageError::UnsupportedFormat("JPEG XL decoding requires the `jxl` feature".to_string())
+}
+
+/// Read JPEG XL header information (requires the `jxl` feature)
+#[cfg(not(feature = "jxl"))]
+pub fn probe_jxl(_data: &[u8]) -> ImageResult<ImageInfo> {
+    Err(feature_disabled())
+}
+
+/// Load a JPEG XL image (requires the `jxl` feature)
+#[cfg(not(feature = "jxl"))]
+pub fn load_jxl(_data: &[u8], _options: &LoadOptions) -> ImageResult<Image> {
+    Err(feature_disabled())
+}
@@ -2,8 +2,33 @@ pub mod png_loader;
 pub mod bmp_loader;
 pub mod jpeg_loader;
 pub mod exr_loader;
+pub mod pbm_loader;
+pub mod tga_loader;
+pub mod dds_loader;
+pub mod hdr_loader;
+pub mod tiff_loader;
+pub mod webp_loader;
+pub mod gif_loader;
+pub mod ico_loader;
+pub mod jxl_loader;
+pub mod avif_loader;
+pub mod psd_loader;
+mod exif;
+mod webp_lossless;
+mod webp_lossy;
 
-pub use png_loader::{load_png, save_png};
-pub use bmp_loader::{load_bmp, save_bmp};
-pub use jpeg_loader::{load_jpeg, save_jpeg};
-pub use exr_loader::{load_exr, save_exr};
+pub use png_loader::{load_png, load_png_from_reader, load_png_mask, probe_png, save_png, save_png_mask};
+pub use bmp_loader::{load_bmp, load_bmp_from_reader, probe_bmp, save_bmp};
+pub use jpeg_loader::{load_jpeg, load_jpeg_from_reader, load_jpeg_scaled, probe_jpeg, save_jpeg};
+pub use exr_loader::{load_exr, load_exr_all, load_exr_channels, load_exr_from_reader, load_exr_layers, load_exr_level_for_size, probe_exr, probe_exr_all, save_exr};
+pub use pbm_loader::{load_pbm_mask, load_pfm, load_pnm, probe_pfm, probe_pnm, save_pbm_mask, save_pfm, save_pnm};
+pub use tga_loader::{load_tga, probe_tga, save_tga};
+pub use dds_loader::{load_dds, load_dds_all, load_dds_level_for_size, probe_dds, probe_dds_all};
+pub use hdr_loader::{load_hdr, probe_hdr, save_hdr};
+pub use tiff_loader::{load_tiff, probe_tiff, save_tiff};
+pub use webp_loader::{load_webp, probe_webp};
+pub use gif_loader::{load_gif, load_gif_animation, probe_gif};
+pub use jxl_loader::{load_jxl, probe_jxl};
+pub use avif_loader::{load_avif, probe_avif};
+pub use psd_loader::{load_psd, probe_psd};
+pub use ico_loader::{load_ico, load_ico_all, load_ico_for_size, probe_ico, probe_ico_all};
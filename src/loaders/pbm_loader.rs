@@ -0,0 +1,419 @@
+use crate::{BitMask, ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, SaveOptions};
+use crate::buffer_pool;
+use crate::row_view::RowView;
+
+/// Longest line of ASCII (plain) PNM rasters recommended by the Netpbm specification
+const ASCII_LINE_LENGTH: usize = 70;
+
+/// Skip whitespace and `#` comments of a PNM header
+fn skip_separators(data: &[u8], mut pos: usize) -> usize {
+    while pos < data.len() {
+        match data[pos] {
+            b'#' => {
+                while pos < data.len() && data[pos] != b'\n' && data[pos] != b'\r' {
+                    pos += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => pos += 1,
+            _ => break,
+        }
+    }
+    pos
+}
+
+/// Parse an unsigned decimal header field
+fn parse_field(data: &[u8], pos: &mut usize) -> ImageResult<u32> {
+    *pos = skip_separators(data, *pos);
+    let start = *pos;
+    while *pos < data.len() && data[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    std::str::from_utf8(&data[start..*pos])
+        .ok()
+        .and_then(|text| text.parse().ok())
+        .ok_or_else(|| ImageError::Other("Invalid PNM header".to_string()))
+}
+
+/// Decode a binary (`P4`) or ASCII (`P1`) portable bitmap
+///
+/// PBM stores 1 for black; set mask pixels are the white ones, so masks look
+/// the same as 1-bit PNG masks.
+pub fn load_pbm_mask(data: &[u8]) -> ImageResult<BitMask> {
+    let binary = match data.get(..2) {
+        Some(b"P4") => true,
+        Some(b"P1") => false,
+        _ => return Err(ImageError::UnsupportedFormat("Not a PBM (P1/P4) file".to_string())),
+    };
+    let mut pos = 2;
+    let width = parse_field(data, &mut pos)?;
+    let height = parse_field(data, &mut pos)?;
+    if width == 0 || height == 0 {
+        return Err(ImageError::InvalidDimensions { width, height });
+    }
+
+    let mut mask = BitMask::new(width, height);
+    if binary {
+        // A single whitespace byte separates the header from the raster
+        let start = pos + 1;
+        let size = mask.row_bytes() * height as usize;
+        let raster = data
+            .get(start..start + size)
+            .ok_or_else(|| ImageError::Other("Truncated PBM raster".to_string()))?;
+        return BitMask::from_raw(raster.iter().map(|byte| !byte).collect(), width, height);
+    }
+
+    let mut bits = data[pos..].iter().filter(|byte| !byte.is_ascii_whitespace());
+    for y in 0..height {
+        for x in 0..width {
+            match bits.next() {
+                Some(b'0') => mask.set(x, y, true),
+                Some(b'1') => {}
+                _ => return Err(ImageError::Other("Invalid or truncated PBM raster".to_string())),
+            }
+        }
+    }
+    Ok(mask)
+}
+
+/// Encode a mask as a binary (`P4`) portable bitmap
+pub fn save_pbm_mask(mask: &BitMask) -> Vec<u8> {
+    let mut bytes = format!("P4\n{} {}\n", mask.width(), mask.height()).into_bytes();
+    let mut inverted = mask.clone();
+    inverted.invert();
+    bytes.extend_from_slice(inverted.data());
+    bytes
+}
+
+/// Image kind of a PNM file, from its magic number
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PnmKind {
+    /// PBM, 1 bit per pixel
+    Bitmap,
+    /// PGM, one gray sample per pixel
+    Graymap,
+    /// PPM, RGB samples
+    Pixmap,
+}
+
+/// Parsed PNM header
+struct PnmHeader {
+    kind: PnmKind,
+    binary: bool,
+    width: u32,
+    height: u32,
+    max_value: u32,
+    /// Offset of the first raster byte (or of the separators before it in ASCII files)
+    raster_start: usize,
+}
+
+impl PnmHeader {
+    fn parse(data: &[u8]) -> ImageResult<Self> {
+        let (kind, binary) = match data.get(..2) {
+            Some(b"P1") => (PnmKind::Bitmap, false),
+            Some(b"P2") => (PnmKind::Graymap, false),
+            Some(b"P3") => (PnmKind::Pixmap, false),
+            Some(b"P4") => (PnmKind::Bitmap, true),
+            Some(b"P5") => (PnmKind::Graymap, true),
+            Some(b"P6") => (PnmKind::Pixmap, true),
+            _ => return Err(ImageError::UnsupportedFormat("Not a PNM (P1-P6) file".to_string())),
+        };
+        let mut pos = 2;
+        let width = parse_field(data, &mut pos)?;
+        let height = parse_field(data, &mut pos)?;
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+        let max_value = if kind == PnmKind::Bitmap { 1 } else { parse_field(data, &mut pos)? };
+        if !(1..=65535).contains(&max_value) {
+            return Err(ImageError::Other(format!("Invalid PNM maximum value {}", max_value)));
+        }
+        // A single whitespace byte separates the header from a binary raster
+        let raster_start = if binary { pos + 1 } else { pos };
+        Ok(Self { kind, binary, width, height, max_value, raster_start })
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        if self.kind == PnmKind::Pixmap { PixelFormat::RGB } else { PixelFormat::R }
+    }
+
+    fn component_type(&self) -> ComponentType {
+        if self.max_value > 255 { ComponentType::U16 } else { ComponentType::U8 }
+    }
+}
+
+/// Check if data starts with a PNM magic number (`P1` to `P6`) and a separator
+pub fn is_pnm(data: &[u8]) -> bool {
+    data.len() >= 3 && data[0] == b'P' && (b'1'..=b'6').contains(&data[1]) && data[2].is_ascii_whitespace()
+}
+
+/// Read PNM header information without decoding pixels
+///
+/// PBM and PGM files load as R, PPM files as RGB; U8 up to a maximum value of
+/// 255, U16 above.
+pub fn probe_pnm(data: &[u8]) -> ImageResult<ImageInfo> {
+    let header = PnmHeader::parse(data)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Pnm,
+        width: header.width,
+        height: header.height,
+        pixel_format: header.pixel_format(),
+        component_type: header.component_type(),
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Load a PBM, PGM or PPM image, ASCII or binary
+///
+/// PBM black pixels load as 0 and white ones as 255. Samples with a maximum
+/// value other than 255 or 65535 are rescaled to the full component range.
+pub fn load_pnm(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let header = PnmHeader::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    let (width, height) = (header.width as usize, header.height as usize);
+    let pixel_format = header.pixel_format();
+    let component_type = header.component_type();
+    let sample_count = width * height * pixel_format.channel_count();
+    let truncated = || ImageError::Other("Truncated PNM raster".to_string());
+    let invalid = || ImageError::Other("Invalid or truncated PNM raster".to_string());
+
+    let full = if component_type == ComponentType::U16 { 65535 } else { 255 };
+    let mut output = buffer_pool::take(sample_count * component_type.size_bytes());
+    let mut push = |value: u32| {
+        let value = (value.min(header.max_value) * full + header.max_value / 2) / header.max_value;
+        match component_type {
+            ComponentType::U8 => output.push(value as u8),
+            _ => output.extend_from_slice(&(value as u16).to_le_bytes()),
+        }
+    };
+    match (header.kind, header.binary) {
+        (PnmKind::Bitmap, true) => {
+            let row_bytes = width.div_ceil(8);
+            let raster = data.get(header.raster_start..header.raster_start + row_bytes * height).ok_or_else(truncated)?;
+            for row in raster.chunks_exact(row_bytes) {
+                (0..width).for_each(|x| push(if row[x / 8] & (0x80 >> (x % 8)) != 0 { 0 } else { 1 }));
+            }
+        }
+        (PnmKind::Bitmap, false) => {
+            // Plain PBM digits need no separators
+            let mut digits = data[header.raster_start..].iter().filter(|byte| !byte.is_ascii_whitespace());
+            for _ in 0..sample_count {
+                match digits.next() {
+                    Some(b'0') => push(1),
+                    Some(b'1') => push(0),
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        (_, true) => {
+            let size = component_type.size_bytes();
+            let raster = data
+                .get(header.raster_start..header.raster_start + sample_count * size)
+                .ok_or_else(truncated)?;
+            match size {
+                1 => raster.iter().for_each(|&value| push(value as u32)),
+                _ => raster.chunks_exact(2).for_each(|value| push(u16::from_be_bytes([value[0], value[1]]) as u32)),
+            }
+        }
+        (_, false) => {
+            let mut pos = header.raster_start;
+            for _ in 0..sample_count {
+                push(parse_field(data, &mut pos).map_err(|_| invalid())?);
+            }
+        }
+    }
+
+
+    let mut image = Image::from_raw(output, header.width, header.height, pixel_format, component_type);
+    if options.flip_y {
+        image.flip_rows();
+    }
+    Ok(image)
+}
+
+/// Encode an image as a PGM (gray) or PPM (color) file, appending to `buffer`
+///
+/// Binary (`P5`/`P6`) unless `options.pnm.ascii` is set. U8 images are written
+/// with a maximum value of 255, U16 images with 65535. Alpha is dropped and RG
+/// images keep their first channel. Empty images are rejected with
+/// [`ImageError::InvalidDimensions`].
+pub fn save_pnm(image: &Image, options: &SaveOptions, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    let component_type = image.component_type();
+    let max_value = match component_type {
+        ComponentType::U8 => 255,
+        ComponentType::U16 => 65535,
+        _ => return Err(ImageError::UnsupportedFormat("PNM only supports U8 and U16 component types".to_string())),
+    };
+    if image.width() == 0 || image.height() == 0 {
+        return Err(ImageError::InvalidDimensions { width: image.width(), height: image.height() });
+    }
+
+    let (channels, magic): (&[usize], u8) = match image.pixel_format() {
+        PixelFormat::R | PixelFormat::RG => (&[0], 2),
+        PixelFormat::RGB | PixelFormat::RGBA => (&[0, 1, 2], 3),
+        PixelFormat::BGR | PixelFormat::BGRA => (&[2, 1, 0], 3),
+    };
+    let magic = if options.pnm.ascii { magic } else { magic + 3 };
+    buffer.extend_from_slice(format!("P{}\n{} {}\n{}\n", magic, image.width(), image.height(), max_value).as_bytes());
+
+    // Binary samples are big-endian; ASCII rows are built from the little-endian layout
+    let mut rows = RowView::new(image, channels, !options.pnm.ascii);
+    for y in 0..image.height() {
+        let row = rows.row(y);
+        if !options.pnm.ascii {
+            buffer.extend_from_slice(row);
+            continue;
+        }
+        let mut line_length = 0;
+        for value in row.chunks_exact(component_type.size_bytes()) {
+            let value = match value {
+                [byte] => *byte as u16,
+                _ => u16::from_le_bytes([value[0], value[1]]),
+            };
+            let text = value.to_string();
+            if line_length > 0 && line_length + 1 + text.len() > ASCII_LINE_LENGTH {
+                buffer.push(b'\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                buffer.push(b' ');
+                line_length += 1;
+            }
+            buffer.extend_from_slice(text.as_bytes());
+            line_length += text.len();
+        }
+        buffer.push(b'\n');
+    }
+    Ok(())
+}
+
+/// Parsed PFM header
+struct PfmHeader {
+    color: bool,
+    little_endian: bool,
+    width: u32,
+    height: u32,
+    raster_start: usize,
+}
+
+impl PfmHeader {
+    fn parse(data: &[u8]) -> ImageResult<Self> {
+        let color = match data.get(..2) {
+            Some(b"PF") => true,
+            Some(b"Pf") => false,
+            _ => return Err(ImageError::UnsupportedFormat("Not a PFM (PF/Pf) file".to_string())),
+        };
+        let mut pos = 2;
+        let width = parse_field(data, &mut pos)?;
+        let height = parse_field(data, &mut pos)?;
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+
+        // The scale factor only tells the byte order: negative for little-endian
+        pos = skip_separators(data, pos);
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let scale: f32 = std::str::from_utf8(&data[start..pos])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .filter(|scale: &f32| scale.is_finite() && *scale != 0.0)
+            .ok_or_else(|| ImageError::Other("Invalid PFM scale factor".to_string()))?;
+        Ok(Self { color, little_endian: scale < 0.0, width, height, raster_start: pos + 1 })
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        if self.color { PixelFormat::RGB } else { PixelFormat::R }
+    }
+}
+
+/// Check if data starts with a PFM magic number (`PF` or `Pf`) and a separator
+pub fn is_pfm(data: &[u8]) -> bool {
+    data.len() >= 3 && (data.starts_with(b"PF") || data.starts_with(b"Pf")) && data[2].is_ascii_whitespace()
+}
+
+/// Read PFM header information without decoding pixels
+///
+/// Color files load as RGB F32, grayscale files as R F32.
+pub fn probe_pfm(data: &[u8]) -> ImageResult<ImageInfo> {
+    let header = PfmHeader::parse(data)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Pfm,
+        width: header.width,
+        height: header.height,
+        pixel_format: header.pixel_format(),
+        component_type: ComponentType::F32,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Load a PFM (portable float map) image as RGB or R F32
+///
+/// Rows are stored bottom-to-top and returned top-to-bottom (or as stored
+/// with `flip_y`). Samples are kept as is, including NaN and infinity unless
+/// `options.reject_non_finite` is set.
+pub fn load_pfm(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let header = PfmHeader::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    let pixel_format = header.pixel_format();
+    let row_bytes = header.width as usize * pixel_format.channel_count() * 4;
+    let raster = data
+        .get(header.raster_start..header.raster_start + row_bytes * header.height as usize)
+        .ok_or_else(|| ImageError::Other("Truncated PFM raster".to_string()))?;
+
+    let mut output = buffer_pool::take(raster.len());
+    let rows = raster.chunks_exact(row_bytes);
+    let rows: Box<dyn Iterator<Item = &[u8]>> = if options.flip_y { Box::new(rows) } else { Box::new(rows.rev()) };
+    for row in rows {
+        if header.little_endian {
+            output.extend_from_slice(row);
+        } else {
+            output.extend(row.chunks_exact(4).flat_map(|value| [value[3], value[2], value[1], value[0]]));
+        }
+    }
+
+    let image = Image::from_raw(output, header.width, header.height, pixel_format, ComponentType::F32);
+    if options.reject_non_finite {
+        let report = image.find_non_finite();
+        if let Some((x, y)) = report.pixels.first() {
+            return Err(ImageError::Other(format!(
+                "PFM image contains {} NaN and {} infinite samples (first at ({}, {}))",
+                report.nan_count, report.infinite_count, x, y
+            )));
+        }
+    }
+    Ok(image)
+}
+
+/// Encode an F16/F32 image as a little-endian PFM file, appending to `buffer`
+///
+/// Gray images are written as `Pf` (RG keeps the first channel), color images
+/// as `PF` without alpha. Empty images are rejected with
+/// [`ImageError::InvalidDimensions`].
+pub fn save_pfm(image: &Image, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    match image.component_type() {
+        ComponentType::F16 | ComponentType::F32 => {}
+        other => {
+            return Err(ImageError::UnsupportedFormat(
+                format!("PFM does not support {:?} component type, use F16 or F32", other),
+            ));
+        }
+    }
+    if image.width() == 0 || image.height() == 0 {
+        return Err(ImageError::InvalidDimensions { width: image.width(), height: image.height() });
+    }
+    let (pixel_format, magic) = match image.pixel_format() {
+        PixelFormat::R | PixelFormat::RG => (PixelFormat::R, "Pf"),
+        _ => (PixelFormat::RGB, "PF"),
+    };
+    let converted = image.convert(pixel_format, ComponentType::F32)?;
+
+    buffer.extend_from_slice(format!("{}\n{} {}\n-1.0\n", magic, image.width(), image.height()).as_bytes());
+    let row_bytes = image.width() as usize * pixel_format.channel_count() * 4;
+    for row in converted.data().chunks_exact(row_bytes).rev() {
+        buffer.extend_from_slice(row);
+    }
+    Ok(())
+}
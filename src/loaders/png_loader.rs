@@ -1,16 +1,16 @@
-use crate::{Image, ImageError, ImageResult, PixelFormat, ComponentType};
-use std::io::Cursor;
-
-pub fn load_png(data: &[u8]) -> ImageResult<Image> {
-    let decoder = png::Decoder::new(Cursor::new(data));
-    let mut reader = decoder.read_info()?;
-
-    let info = reader.info();
-    let width = info.width;
-    let height = info.height;
-    let color_type = info.color_type;
-    let bit_depth = info.bit_depth;
+use crate::{BitMask, Image, ImageError, ImageFormat, ImageInfo, ImageMetadata, ImageResult, LoadOptions, PixelFormat, ComponentType, SaveOptions};
+use crate::{PngCompression, PngFilter, PngSaveOptions};
+use crate::buffer_pool;
+use crate::row_view::{rgb_format, rgb_order, RowView};
+use flate2::write::ZlibEncoder;
+use std::borrow::Cow;
+use std::io::{Cursor, Read, Write};
 
+/// Map PNG color type and bit depth to pixel format and component type
+fn png_layout(
+    color_type: png::ColorType,
+    bit_depth: png::BitDepth,
+) -> ImageResult<(PixelFormat, ComponentType)> {
     // Determine pixel format
     let pixel_format = match color_type {
         png::ColorType::Grayscale => PixelFormat::R,
@@ -24,9 +24,12 @@ pub fn load_png(data: &[u8]) -> ImageResult<Image> {
         }
     };
 
-    // Determine component type
+    // Determine component type (1, 2 and 4-bit grayscale are expanded to 8 bits)
     let component_type = match bit_depth {
         png::BitDepth::Eight => ComponentType::U8,
+        png::BitDepth::One | png::BitDepth::Two | png::BitDepth::Four if pixel_format == PixelFormat::R => {
+            ComponentType::U8
+        }
         png::BitDepth::Sixteen => ComponentType::U16,
         _ => {
             return Err(ImageError::UnsupportedFormat(
@@ -35,21 +38,108 @@ pub fn load_png(data: &[u8]) -> ImageResult<Image> {
         }
     };
 
+    Ok((pixel_format, component_type))
+}
+
+/// Read PNG header information without decoding pixels
+pub fn probe_png(data: &[u8]) -> ImageResult<ImageInfo> {
+    let decoder = png::Decoder::new(Cursor::new(data));
+    let reader = decoder.read_info()?;
+    let info = reader.info();
+    let (pixel_format, component_type) = png_layout(info.color_type, info.bit_depth)?;
+
+    Ok(ImageInfo {
+        format: ImageFormat::Png,
+        width: info.width,
+        height: info.height,
+        pixel_format,
+        component_type,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+pub fn load_png(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    load_png_from_reader(Cursor::new(data), options)
+}
+
+/// Decode a PNG image as it is read from `reader`
+pub fn load_png_from_reader<R: Read>(reader: R, options: &LoadOptions) -> ImageResult<Image> {
+    let decoder = png::Decoder::new(reader);
+    let mut reader = decoder.read_info()?;
+
+    let info = reader.info();
+    let width = info.width;
+    let height = info.height;
+    options.check_dimensions(width, height)?;
+    let color_type = info.color_type;
+    let bit_depth = info.bit_depth;
+
+    let (pixel_format, component_type) = png_layout(color_type, bit_depth)?;
+
+    let interlaced = info.interlaced;
+    let metadata = ImageMetadata {
+        icc_profile: info.icc_profile.as_ref().map(|profile| profile.to_vec()),
+        gamma: info.gama_chunk.map(|gamma| gamma.into_value()),
+        srgb: info.srgb.is_some(),
+        ..Default::default()
+    };
+
     // Allocate buffer
-    let mut buffer = vec![0u8; reader.output_buffer_size()];
-    let info = reader.next_frame(&mut buffer)?;
-    let bytes = &buffer[..info.buffer_size()];
+    let mut buffer = buffer_pool::take_zeroed(reader.output_buffer_size());
+    if options.flip_y && !interlaced {
+        // Write rows bottom-to-top as they are decoded
+        let row_bytes = reader.output_line_size(width);
+        let mut y = height as usize;
+        while let Some(row) = reader.next_row()? {
+            y -= 1;
+            buffer[y * row_bytes..(y + 1) * row_bytes].copy_from_slice(row.data());
+        }
+        buffer.truncate(row_bytes * height as usize);
+    } else {
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+    }
+
+    // PNG stores 16-bit samples big-endian, images store them little-endian
+    if component_type == ComponentType::U16 {
+        swap_u16_bytes(&mut buffer);
+    }
 
-    Ok(Image::from_raw(
-        bytes.to_vec(),
+    let bits = bit_depth as u8;
+    if bits < 8 {
+        buffer = expand_packed_gray(&buffer, width as usize, height as usize, bits);
+    }
+
+    let mut image = Image::from_raw(
+        buffer,
         width,
         height,
         pixel_format,
         component_type,
-    ))
+    );
+
+    // Interlaced rows arrive in passes, flip them once the frame is complete
+    if options.flip_y && interlaced {
+        image.flip_rows();
+    }
+
+    if let Some(color_space) = metadata.color_space() {
+        image.set_color_space(color_space);
+    }
+    image.set_metadata(metadata);
+    if options.convert_to_srgb {
+        image.convert_to_srgb();
+    }
+
+    Ok(image)
 }
 
-pub fn save_png(image: &Image) -> ImageResult<Vec<u8>> {
+/// Encode an image as a PNG into `writer`
+///
+/// Compression, filter and interlacing follow [`SaveOptions::png`]. Rows are
+/// written as they are compressed, appending to a `Vec` or streaming to a file.
+pub fn save_png<W: Write>(image: &Image, options: &SaveOptions, writer: W) -> ImageResult<()> {
     // PNG only supports U8 and U16 component types
     if image.component_type() != ComponentType::U8 && image.component_type() != ComponentType::U16 {
         return Err(ImageError::UnsupportedFormat(
@@ -57,47 +147,250 @@ pub fn save_png(image: &Image) -> ImageResult<Vec<u8>> {
         ));
     }
 
-    let mut buffer = Vec::new();
     {
-        let mut encoder = png::Encoder::new(
-            &mut buffer,
-            image.width(),
-            image.height(),
-        );
-
-        // Set color type
-        let color_type = match image.pixel_format() {
+        let mut info = png::Info::with_size(image.width(), image.height());
+
+        // BGR/BGRA are written through a row view in RGB order
+        info.color_type = match rgb_format(image.pixel_format()) {
             PixelFormat::R => png::ColorType::Grayscale,
             PixelFormat::RG => png::ColorType::GrayscaleAlpha,
             PixelFormat::RGB => png::ColorType::Rgb,
-            PixelFormat::RGBA => png::ColorType::Rgba,
-            PixelFormat::BGR => {
-                // Convert BGR to RGB for PNG
-                let mut img_copy = image.clone();
-                img_copy.bgr_to_rgb();
-                return save_png(&img_copy);
-            }
-            PixelFormat::BGRA => {
-                // Convert BGRA to RGBA for PNG
-                let mut img_copy = image.clone();
-                img_copy.bgr_to_rgb();
-                return save_png(&img_copy);
-            }
+            _ => png::ColorType::Rgba,
         };
 
-        encoder.set_color(color_type);
-
         // Set bit depth
-        let bit_depth = match image.component_type() {
+        info.bit_depth = match image.component_type() {
             ComponentType::U8 => png::BitDepth::Eight,
             ComponentType::U16 => png::BitDepth::Sixteen,
             _ => unreachable!(),
         };
-        encoder.set_depth(bit_depth);
+        info.interlaced = options.png.interlaced;
+
+        // Color metadata, an sRGB chunk replaces the ICC profile
+        let metadata = image.metadata();
+        info.icc_profile = metadata.icc_profile.as_deref().map(Cow::Borrowed);
+        info.source_gamma = metadata.gamma.map(png::ScaledFloat::new);
+        info.srgb = metadata.srgb.then_some(png::SrgbRenderingIntent::Perceptual);
+
+        let mut encoder = png::Encoder::with_info(writer, info)?;
+        set_encoder_options(&mut encoder, &options.png);
 
+        // PNG stores 16-bit samples big-endian
+        let mut rows = RowView::new(image, rgb_order(image.pixel_format()), true);
         let mut writer = encoder.write_header()?;
-        writer.write_image_data(image.data())?;
+        if options.png.interlaced {
+            // The encoder only filters progressive images, so the passes are encoded here
+            let data = if rows.needs_conversion() {
+                let mut data = Vec::with_capacity(image.size_bytes());
+                for y in 0..image.height() {
+                    data.extend_from_slice(rows.row(y));
+                }
+                Cow::Owned(data)
+            } else {
+                Cow::Borrowed(image.data())
+            };
+            let (width, height) = (image.width() as usize, image.height() as usize);
+            let idat = encode_interlaced(&data, width, height, image.bytes_per_pixel(), &options.png)?;
+            writer.write_chunk(png::chunk::IDAT, &idat)?;
+        } else if rows.needs_conversion() {
+            let mut stream = writer.stream_writer()?;
+            for y in 0..image.height() {
+                stream.write_all(rows.row(y))?;
+            }
+            stream.finish()?;
+        } else {
+            writer.write_image_data(image.data())?;
+        }
+        writer.finish()?;
+    }
+
+    Ok(())
+}
+
+/// Apply the compression and filter options to a PNG encoder
+fn set_encoder_options<W: Write>(encoder: &mut png::Encoder<W>, options: &PngSaveOptions) {
+    encoder.set_compression(match options.compression {
+        PngCompression::Fast => png::Compression::Fast,
+        PngCompression::Default => png::Compression::Default,
+        PngCompression::Best => png::Compression::Best,
+    });
+    let (filter, adaptive) = match options.filter {
+        PngFilter::None => (png::FilterType::NoFilter, false),
+        PngFilter::Sub => (png::FilterType::Sub, false),
+        PngFilter::Up => (png::FilterType::Up, false),
+        PngFilter::Average => (png::FilterType::Avg, false),
+        PngFilter::Paeth => (png::FilterType::Paeth, false),
+        PngFilter::Adaptive => (png::FilterType::Sub, true),
+    };
+    encoder.set_filter(filter);
+    encoder.set_adaptive_filter(if adaptive {
+        png::AdaptiveFilterType::Adaptive
+    } else {
+        png::AdaptiveFilterType::NonAdaptive
+    });
+}
+
+/// Adam7 passes: first column, first row, column step, row step
+const ADAM7_PASSES: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// Interlace, filter and deflate top-to-bottom rows into the content of an IDAT chunk
+fn encode_interlaced(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    options: &PngSaveOptions,
+) -> ImageResult<Vec<u8>> {
+    let row_bytes = width * bytes_per_pixel;
+    let level = match options.compression {
+        PngCompression::Fast => flate2::Compression::fast(),
+        PngCompression::Default => flate2::Compression::default(),
+        PngCompression::Best => flate2::Compression::best(),
+    };
+    let mut encoder = ZlibEncoder::new(Vec::with_capacity(data.len() / 2), level);
+
+    let mut previous = Vec::new();
+    let mut current = Vec::new();
+    let mut filtered = Vec::new();
+    for (x0, y0, dx, dy) in ADAM7_PASSES {
+        // Empty passes have no rows, not even filter bytes
+        if width <= x0 || height <= y0 {
+            continue;
+        }
+        let pass_bytes = (width - x0).div_ceil(dx) * bytes_per_pixel;
+        previous.clear();
+        previous.resize(pass_bytes, 0);
+        for y in (y0..height).step_by(dy) {
+            let row = &data[y * row_bytes..(y + 1) * row_bytes];
+            current.clear();
+            for x in (x0..width).step_by(dx) {
+                current.extend_from_slice(&row[x * bytes_per_pixel..(x + 1) * bytes_per_pixel]);
+            }
+            filter_row(options.filter, bytes_per_pixel, &previous, &current, &mut filtered);
+            encoder.write_all(&filtered)?;
+            std::mem::swap(&mut previous, &mut current);
+        }
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Filter one row into `output`, prefixed with its filter type byte
+fn filter_row(filter: PngFilter, bytes_per_pixel: usize, previous: &[u8], row: &[u8], output: &mut Vec<u8>) {
+    let filters: &[u8] = match filter {
+        PngFilter::None => &[0],
+        PngFilter::Sub => &[1],
+        PngFilter::Up => &[2],
+        PngFilter::Average => &[3],
+        PngFilter::Paeth => &[4],
+        PngFilter::Adaptive => &[0, 1, 2, 3, 4],
+    };
+
+    // Adaptive filtering keeps the filter with the smallest sum of signed bytes
+    let mut best_cost = u64::MAX;
+    let mut candidate = Vec::with_capacity(row.len() + 1);
+    for &kind in filters {
+        candidate.clear();
+        candidate.push(kind);
+        for i in 0..row.len() {
+            let left = if i >= bytes_per_pixel { row[i - bytes_per_pixel] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= bytes_per_pixel { previous[i - bytes_per_pixel] } else { 0 };
+            let predicted = match kind {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                _ => paeth(left, up, up_left),
+            };
+            candidate.push(row[i].wrapping_sub(predicted));
+        }
+        let cost = if filters.len() == 1 {
+            0
+        } else {
+            candidate[1..].iter().map(|&b| (b as i8).unsigned_abs() as u64).sum()
+        };
+        if cost < best_cost {
+            best_cost = cost;
+            std::mem::swap(output, &mut candidate);
+        }
+    }
+}
+
+/// Paeth predictor of the PNG specification
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Decode a PNG as a bit mask
+///
+/// 1-bit grayscale files are read as is; other files are decoded and their
+/// first channel thresholded at 0.5.
+pub fn load_png_mask(data: &[u8]) -> ImageResult<BitMask> {
+    let decoder = png::Decoder::new(Cursor::new(data));
+    let mut reader = decoder.read_info()?;
+    let info = reader.info();
+    if info.color_type != png::ColorType::Grayscale || info.bit_depth != png::BitDepth::One {
+        return BitMask::from_channel(&load_png(data, &LoadOptions::default())?, 0, 0.5);
     }
 
+    let (width, height) = (info.width, info.height);
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+    buffer.truncate(frame.buffer_size());
+    BitMask::from_raw(buffer, width, height)
+}
+
+/// Encode a bit mask as a 1-bit grayscale PNG
+pub fn save_png_mask(mask: &BitMask, options: &SaveOptions) -> ImageResult<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(mask.data().len() / 4);
+    {
+        let mut encoder = png::Encoder::new(&mut buffer, mask.width(), mask.height());
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+        set_encoder_options(&mut encoder, &options.png);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(mask.data())?;
+    }
     Ok(buffer)
 }
+
+/// Expand packed 1, 2 or 4-bit grayscale rows to one byte per pixel, scaled to 0..=255
+fn expand_packed_gray(packed: &[u8], width: usize, height: usize, bits: u8) -> Vec<u8> {
+    let row_bytes = (width * bits as usize).div_ceil(8);
+    let max = (1u16 << bits) - 1;
+    let per_byte = 8 / bits as usize;
+    let mut expanded = Vec::with_capacity(width * height);
+    for row in packed.chunks_exact(row_bytes).take(height) {
+        expanded.extend((0..width).map(|x| {
+            let shift = 8 - bits as usize * (x % per_byte + 1);
+            let value = (row[x / per_byte] >> shift) as u16 & max;
+            (value * 255 / max) as u8
+        }));
+    }
+    expanded
+}
+
+/// Swap the byte order of every 16-bit sample in place
+fn swap_u16_bytes(data: &mut [u8]) {
+    for sample in data.chunks_exact_mut(2) {
+        sample.swap(0, 1);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat};
+use crate::buffer_pool;
+
+/// File signature, followed by the version: 1 for PSD, 2 for PSB (large documents)
+const SIGNATURE: [u8; 4] = *b"8BPS";
+const HEADER_SIZE: usize = 26;
+
+/// Compression methods of the image data section
+const COMPRESSION_RAW: u16 = 0;
+const COMPRESSION_RLE: u16 = 1;
+
+fn truncated() -> ImageError {
+    ImageError::Other("Truncated PSD file".to_string())
+}
+
+fn corrupt(message: &str) -> ImageError {
+    ImageError::Other(format!("Corrupt PSD data: {}", message))
+}
+
+fn read_bytes<const N: usize>(data: &[u8], pos: usize) -> ImageResult<[u8; N]> {
+    data.get(pos..pos.checked_add(N).ok_or_else(truncated)?)
+        .map(|bytes| bytes.try_into().unwrap())
+        .ok_or_else(truncated)
+}
+
+fn read_u32(data: &[u8], pos: usize) -> ImageResult<u32> {
+    read_bytes(data, pos).map(u32::from_be_bytes)
+}
+
+/// Header of a PSD or PSB file and location of its composite image
+struct Document<'a> {
+    width: u32,
+    height: u32,
+    /// Stored channels: the color channels, then alpha and spot channels
+    channels: usize,
+    /// 1 for grayscale, 3 for RGB
+    color_channels: usize,
+    /// 8 or 16 bits per sample
+    depth: u16,
+    /// The first channel after the colors holds the transparency of the composite
+    has_alpha: bool,
+    /// PSB files use 32-bit RLE row lengths
+    large: bool,
+    compression: u16,
+    /// Image data section, after the compression method
+    image_data: &'a [u8],
+}
+
+impl<'a> Document<'a> {
+    fn parse(data: &'a [u8]) -> ImageResult<Self> {
+        if !is_psd(data) {
+            return Err(ImageError::UnsupportedFormat("Invalid PSD header: missing 8BPS signature".to_string()));
+        }
+        let large = data[5] == 2;
+        let channels = u16::from_be_bytes([data[12], data[13]]) as usize;
+        let height = read_u32(data, 14)?;
+        let width = read_u32(data, 18)?;
+        let depth = u16::from_be_bytes([data[22], data[23]]);
+        let color_channels = match u16::from_be_bytes([data[24], data[25]]) {
+            1 => 1,
+            3 => 3,
+            mode => {
+                let name = match mode {
+                    0 => "bitmap",
+                    2 => "indexed",
+                    4 => "CMYK",
+                    7 => "multichannel",
+                    8 => "duotone",
+                    9 => "Lab",
+                    _ => "unknown",
+                };
+                return Err(ImageError::UnsupportedFormat(format!("Unsupported PSD color mode: {} ({})", name, mode)));
+            }
+        };
+        if !matches!(depth, 8 | 16) {
+            return Err(ImageError::UnsupportedFormat(format!("Unsupported PSD bit depth {}", depth)));
+        }
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+        if channels < color_channels {
+            return Err(corrupt("fewer channels than the color mode needs"));
+        }
+
+        // Color mode data and image resources, skipped
+        let mut pos = HEADER_SIZE;
+        for _ in 0..2 {
+            pos = pos.checked_add(4 + read_u32(data, pos)? as usize).ok_or_else(truncated)?;
+        }
+
+        // Layer and mask information: a negative layer count means the first
+        // alpha channel holds the transparency of the composite
+        let (section_length, length_size) = if large {
+            (u64::from_be_bytes(read_bytes(data, pos)?), 8)
+        } else {
+            (read_u32(data, pos)? as u64, 4)
+        };
+        let section_start = pos + length_size;
+        let image_start = usize::try_from(section_length)
+            .ok()
+            .and_then(|length| section_start.checked_add(length))
+            .ok_or_else(truncated)?;
+        let layers = data.get(section_start..image_start).ok_or_else(truncated)?;
+        let has_alpha = layer_count(layers, large) < 0 && channels > color_channels;
+
+        let section = data.get(image_start..).filter(|section| section.len() >= 2).ok_or_else(truncated)?;
+        Ok(Document {
+            width,
+            height,
+            channels,
+            color_channels,
+            depth,
+            has_alpha,
+            large,
+            compression: u16::from_be_bytes([section[0], section[1]]),
+            image_data: &section[2..],
+        })
+    }
+
+    fn layout(&self) -> (PixelFormat, ComponentType) {
+        let pixel_format = match (self.color_channels, self.has_alpha) {
+            (1, false) => PixelFormat::R,
+            (1, true) => PixelFormat::RG,
+            (_, false) => PixelFormat::RGB,
+            (_, true) => PixelFormat::RGBA,
+        };
+        let component_type = if self.depth == 16 { ComponentType::U16 } else { ComponentType::U8 };
+        (pixel_format, component_type)
+    }
+
+    /// Decode the planes of the first `count` channels, each `height` rows of `row_size` bytes
+    fn planes(&self, count: usize) -> ImageResult<Vec<Vec<u8>>> {
+        let row_size = self.width as usize * (self.depth / 8) as usize;
+        let height = self.height as usize;
+        let plane_size = row_size * height;
+        match self.compression {
+            COMPRESSION_RAW => (0..count)
+                .map(|channel| {
+                    self.image_data
+                        .get(channel * plane_size..(channel + 1) * plane_size)
+                        .map(<[u8]>::to_vec)
+                        .ok_or_else(truncated)
+                })
+                .collect(),
+            COMPRESSION_RLE => {
+                // Byte counts of every row of every channel, then the rows
+                let count_size = if self.large { 4 } else { 2 };
+                let table = self
+                    .image_data
+                    .get(..self.channels * height * count_size)
+                    .ok_or_else(truncated)?;
+                let row_lengths: Vec<usize> = table
+                    .chunks_exact(count_size)
+                    .map(|bytes| match bytes {
+                        [high, low] => u16::from_be_bytes([*high, *low]) as usize,
+                        _ => u32::from_be_bytes(bytes.try_into().unwrap()) as usize,
+                    })
+                    .collect();
+
+                let mut pos = table.len();
+                let mut planes = Vec::with_capacity(count);
+                for lengths in row_lengths.chunks_exact(height).take(count) {
+                    // A PackBits byte pair expands to at most 128 bytes
+                    if lengths.iter().any(|&length| length * 64 < row_size) {
+                        return Err(corrupt("RLE row too short for the image width"));
+                    }
+                    if lengths.iter().sum::<usize>() > self.image_data.len() - pos {
+                        return Err(truncated());
+                    }
+                    let mut plane = buffer_pool::take(plane_size);
+                    for &length in lengths {
+                        let row = self.image_data.get(pos..pos + length).ok_or_else(truncated)?;
+                        decode_packbits_row(row, row_size, &mut plane)?;
+                        pos += length;
+                    }
+                    planes.push(plane);
+                }
+                Ok(planes)
+            }
+            compression => Err(ImageError::UnsupportedFormat(format!(
+                "Unsupported PSD composite compression {}",
+                compression
+            ))),
+        }
+    }
+}
+
+/// Read the layer count from the layer and mask information section
+///
+/// 16 and 32-bit documents leave the layer info empty and store their layers
+/// in an `Lr16` or `Lr32` block after the global layer mask info.
+fn layer_count(section: &[u8], large: bool) -> i16 {
+    let read_length = |pos: usize, wide: bool| -> Option<(usize, usize)> {
+        let length = if wide {
+            usize::try_from(u64::from_be_bytes(read_bytes(section, pos).ok()?)).ok()?
+        } else {
+            read_u32(section, pos).ok()? as usize
+        };
+        let start = pos + if wide { 8 } else { 4 };
+        Some((start, start.checked_add(length)?))
+    };
+    let count_at = |pos: usize| read_bytes(section, pos).map(i16::from_be_bytes).unwrap_or(0);
+
+    let Some((layer_info, global_mask)) = read_length(0, large) else { return 0 };
+    if global_mask > layer_info {
+        return count_at(layer_info);
+    }
+    let Some((_, mut pos)) = read_length(global_mask, false) else { return 0 };
+
+    // Tagged blocks: '8BIM' or '8B64', a key, a length and data padded to 4 bytes
+    while let Ok(header) = read_bytes::<8>(section, pos) {
+        if !matches!(&header[..4], b"8BIM" | b"8B64") {
+            break;
+        }
+        let key = &header[4..];
+        let wide = large && matches!(key, b"LMsk" | b"Lr16" | b"Lr32" | b"Layr" | b"Mt16" | b"Mt32" | b"Mtrn" | b"Alph" | b"FMsk" | b"lnk2" | b"FEid" | b"FXid" | b"PxSD");
+        let Some((start, end)) = read_length(pos + 8, wide) else { break };
+        if matches!(key, b"Lr16" | b"Lr32" | b"Layr") {
+            return count_at(start);
+        }
+        let Some(next) = end.checked_next_multiple_of(4) else { break };
+        pos = next;
+    }
+    0
+}
+
+/// Decompress one PackBits row of `row_size` bytes, appending it to `output`
+fn decode_packbits_row(data: &[u8], row_size: usize, output: &mut Vec<u8>) -> ImageResult<()> {
+    let end = output.len() + row_size;
+    let mut pos = 0;
+    while output.len() < end {
+        let header = *data.get(pos).ok_or_else(|| corrupt("short RLE row"))? as i8;
+        pos += 1;
+        match header {
+            0..=127 => {
+                let length = header as usize + 1;
+                output.extend_from_slice(data.get(pos..pos + length).ok_or_else(|| corrupt("short RLE row"))?);
+                pos += length;
+            }
+            -127..=-1 => {
+                let value = *data.get(pos).ok_or_else(|| corrupt("short RLE row"))?;
+                output.resize(output.len() + (1 - header as isize) as usize, value);
+                pos += 1;
+            }
+            -128 => {}
+        }
+    }
+    if output.len() > end {
+        return Err(corrupt("RLE row longer than the image"));
+    }
+    Ok(())
+}
+
+/// Check if data starts with a PSD (version 1) or PSB (version 2) header
+pub fn is_psd(data: &[u8]) -> bool {
+    data.len() >= HEADER_SIZE && data[0..4] == SIGNATURE && matches!(data[4..6], [0, 1] | [0, 2])
+}
+
+/// Read PSD header information without decoding the composite image
+pub fn probe_psd(data: &[u8]) -> ImageResult<ImageInfo> {
+    let document = Document::parse(data)?;
+    let (pixel_format, component_type) = document.layout();
+    Ok(ImageInfo {
+        format: ImageFormat::Psd,
+        width: document.width,
+        height: document.height,
+        pixel_format,
+        component_type,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Load the flattened composite image of a PSD or PSB file
+///
+/// RGB documents load as RGB or RGBA and grayscale ones as R or RG, U8 for
+/// 8-bit files and U16 for 16-bit ones. Alpha comes from the transparency of
+/// the composite, whose colors are stored blended with white and are restored
+/// here; other alpha and spot channels are ignored. Layers are not
+/// composited, so files saved without Photoshop's "Maximize Compatibility"
+/// option may not hold a usable composite. Raw and RLE composites are
+/// supported; bitmap, indexed, CMYK, Lab and 32-bit documents are rejected.
+pub fn load_psd(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let document = Document::parse(data)?;
+    options.check_dimensions(document.width, document.height)?;
+    let (pixel_format, component_type) = document.layout();
+    let channels = pixel_format.channel_count();
+    let planes = document.planes(channels)?;
+
+    let (width, height) = (document.width as usize, document.height as usize);
+    let sample_size = component_type.size_bytes();
+    let mut output = buffer_pool::take_zeroed(width * height * channels * sample_size);
+    for (index, pixel) in output.chunks_exact_mut(channels * sample_size).enumerate() {
+        let source_y = index / width;
+        let y = if options.flip_y { height - 1 - source_y } else { source_y };
+        let offset = (y * width + index % width) * sample_size;
+        for (channel, plane) in planes.iter().enumerate() {
+            let sample = &plane[offset..offset + sample_size];
+            let target = &mut pixel[channel * sample_size..(channel + 1) * sample_size];
+            // Samples are stored big-endian
+            if sample_size == 2 {
+                target.copy_from_slice(&[sample[1], sample[0]]);
+            } else {
+                target[0] = sample[0];
+            }
+        }
+    }
+
+    if document.has_alpha {
+        remove_white_matte(&mut output, channels, component_type);
+    }
+    Ok(Image::from_raw(output, width as u32, height as u32, pixel_format, component_type))
+}
+
+/// Undo the blending of transparent composite pixels with white
+fn remove_white_matte(output: &mut [u8], channels: usize, component_type: ComponentType) {
+    let unmatte = |color: f32, alpha: f32| if alpha == 0.0 { 0.0 } else { ((color - 1.0 + alpha) / alpha).clamp(0.0, 1.0) };
+    if component_type == ComponentType::U16 {
+        for pixel in output.chunks_exact_mut(channels * 2) {
+            let alpha = u16::from_le_bytes([pixel[channels * 2 - 2], pixel[channels * 2 - 1]]) as f32 / 65535.0;
+            for sample in pixel[..(channels - 1) * 2].chunks_exact_mut(2) {
+                let color = u16::from_le_bytes([sample[0], sample[1]]) as f32 / 65535.0;
+                sample.copy_from_slice(&((unmatte(color, alpha) * 65535.0 + 0.5) as u16).to_le_bytes());
+            }
+        }
+    } else {
+        for pixel in output.chunks_exact_mut(channels) {
+            let alpha = pixel[channels - 1] as f32 / 255.0;
+            for sample in &mut pixel[..channels - 1] {
+                *sample = (unmatte(*sample as f32 / 255.0, alpha) * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, SaveOptions};
+use crate::buffer_pool;
+use crate::row_view::RowView;
+
+const HEADER_SIZE: usize = 18;
+
+/// TGA 2.0 footer signature, the last 18 bytes of the file
+const FOOTER_SIGNATURE: &[u8; 18] = b"TRUEVISION-XFILE.\0";
+
+/// Parsed TGA header
+struct Header {
+    id_length: usize,
+    has_color_map: bool,
+    image_type: u8,
+    color_map_first: usize,
+    color_map_length: usize,
+    color_map_bits: u8,
+    width: u32,
+    height: u32,
+    pixel_bits: u8,
+    descriptor: u8,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> ImageResult<Self> {
+        let invalid = || ImageError::UnsupportedFormat("Invalid TGA header".to_string());
+        let header = data.get(..HEADER_SIZE).ok_or_else(invalid)?;
+        let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+        let parsed = Self {
+            id_length: header[0] as usize,
+            has_color_map: header[1] == 1,
+            image_type: header[2],
+            color_map_first: u16_at(3) as usize,
+            color_map_length: u16_at(5) as usize,
+            color_map_bits: header[7],
+            width: u16_at(12) as u32,
+            height: u16_at(14) as u32,
+            pixel_bits: header[16],
+            descriptor: header[17],
+        };
+
+        let valid_layout = match parsed.image_type & !8 {
+            1 => parsed.has_color_map && matches!(parsed.pixel_bits, 8 | 16) && matches!(parsed.color_map_bits, 15 | 16 | 24 | 32),
+            2 => matches!(parsed.pixel_bits, 15 | 16 | 24 | 32),
+            3 => matches!(parsed.pixel_bits, 8 | 16),
+            _ => false,
+        };
+        // Bits 6-7 of the descriptor are reserved (interleaving in TGA 1.0)
+        if header[1] > 1 || !valid_layout || parsed.descriptor & 0xC0 != 0 {
+            return Err(invalid());
+        }
+        if parsed.width == 0 || parsed.height == 0 {
+            return Err(ImageError::InvalidDimensions { width: parsed.width, height: parsed.height });
+        }
+        Ok(parsed)
+    }
+
+    fn is_rle(&self) -> bool {
+        self.image_type & 8 != 0
+    }
+
+    fn is_gray(&self) -> bool {
+        self.image_type & !8 == 3
+    }
+
+    fn is_color_mapped(&self) -> bool {
+        self.image_type & !8 == 1
+    }
+
+    /// Bits per color value, read from the color map for color-mapped images
+    fn color_bits(&self) -> u8 {
+        if self.is_color_mapped() { self.color_map_bits } else { self.pixel_bits }
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        let has_alpha = self.descriptor & 0x0F != 0;
+        match (self.is_gray(), self.color_bits()) {
+            (true, 16) => PixelFormat::RG,
+            (true, _) => PixelFormat::R,
+            (false, 16 | 32) if has_alpha => PixelFormat::RGBA,
+            (false, _) => PixelFormat::RGB,
+        }
+    }
+}
+
+/// Check if data looks like a TGA file
+///
+/// TGA has no magic number: files with the TGA 2.0 footer are accepted, others
+/// must have a consistent header.
+pub fn is_tga(data: &[u8]) -> bool {
+    data.ends_with(FOOTER_SIGNATURE) || Header::parse(data).is_ok()
+}
+
+/// Read TGA header information without decoding pixels
+///
+/// Color images are loaded as RGB/RGBA U8, grayscale as R or RG U8.
+pub fn probe_tga(data: &[u8]) -> ImageResult<ImageInfo> {
+    let header = Header::parse(data)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Tga,
+        width: header.width,
+        height: header.height,
+        pixel_format: header.pixel_format(),
+        component_type: ComponentType::U8,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Expand a stored color (15/16/24/32-bit BGR(A) or 8/16-bit gray) to output channels
+fn expand_color(bytes: &[u8], bits: u8, gray: bool, output: &mut [u8]) {
+    if gray {
+        output.copy_from_slice(&bytes[..output.len()]);
+        return;
+    }
+    match bits {
+        15 | 16 => {
+            // 5 bits per channel, X1R5G5B5 or A1R5G5B5
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+            let expand = |shift: u16| {
+                let v = ((value >> shift) & 0x1F) as u8;
+                (v << 3) | (v >> 2)
+            };
+            output[..3].copy_from_slice(&[expand(10), expand(5), expand(0)]);
+            if let Some(alpha) = output.get_mut(3) {
+                *alpha = if value & 0x8000 != 0 { u8::MAX } else { 0 };
+            }
+        }
+        _ => {
+            output[..3].copy_from_slice(&[bytes[2], bytes[1], bytes[0]]);
+            if let Some(alpha) = output.get_mut(3) {
+                *alpha = bytes[3];
+            }
+        }
+    }
+}
+
+/// Expand RLE packets to `count` stored pixels (packets may span rows)
+fn decode_rle(data: &[u8], count: usize, pixel_bytes: usize) -> ImageResult<Vec<u8>> {
+    let truncated = || ImageError::Other("Truncated TGA RLE data".to_string());
+    // Each packet of at least 1 + pixel_bytes bytes expands to at most 128 pixels
+    let max_pixels = data.len().div_ceil(1 + pixel_bytes).saturating_mul(128);
+    let mut output = Vec::with_capacity(count.min(max_pixels) * pixel_bytes);
+    let mut pos = 0;
+    while output.len() < count * pixel_bytes {
+        let packet = *data.get(pos).ok_or_else(truncated)?;
+        pos += 1;
+        let length = (packet & 0x7F) as usize + 1;
+        if packet & 0x80 != 0 {
+            let pixel = data.get(pos..pos + pixel_bytes).ok_or_else(truncated)?;
+            for _ in 0..length {
+                output.extend_from_slice(pixel);
+            }
+            pos += pixel_bytes;
+        } else {
+            let pixels = data.get(pos..pos + length * pixel_bytes).ok_or_else(truncated)?;
+            output.extend_from_slice(pixels);
+            pos += length * pixel_bytes;
+        }
+    }
+    // A final packet may run past the last pixel
+    output.truncate(count * pixel_bytes);
+    Ok(output)
+}
+
+pub fn load_tga(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let header = Header::parse(data)?;
+    options.check_dimensions(header.width, header.height)?;
+    let (width, height) = (header.width as usize, header.height as usize);
+    let pixel_format = header.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let pixel_bytes = (header.pixel_bits as usize).div_ceil(8);
+    let color_bytes = (header.color_bits() as usize).div_ceil(8);
+
+    // The color map follows the image ID and is present even for true-color images
+    let color_map_start = HEADER_SIZE + header.id_length;
+    let color_map_size = if header.has_color_map {
+        header.color_map_length * (header.color_map_bits as usize).div_ceil(8)
+    } else {
+        0
+    };
+    let color_map = data
+        .get(color_map_start..color_map_start + color_map_size)
+        .ok_or_else(|| ImageError::Other("Truncated TGA color map".to_string()))?;
+    let pixel_data = &data[color_map_start + color_map_size..];
+
+    let decoded;
+    let stored = if header.is_rle() {
+        decoded = decode_rle(pixel_data, width * height, pixel_bytes)?;
+        &decoded[..]
+    } else {
+        pixel_data
+            .get(..width * height * pixel_bytes)
+            .ok_or_else(|| ImageError::Other("Truncated TGA pixel data".to_string()))?
+    };
+
+    // Rows are stored bottom-up unless bit 5 is set, right-to-left if bit 4 is set
+    let top_down = (header.descriptor & 0x20 != 0) != options.flip_y;
+    let right_to_left = header.descriptor & 0x10 != 0;
+
+    let mut output = buffer_pool::take_zeroed(width * height * channel_count);
+    for (row, stored_row) in stored.chunks_exact(width * pixel_bytes).enumerate() {
+        let y = if top_down { row } else { height - 1 - row };
+        for (column, pixel) in stored_row.chunks_exact(pixel_bytes).enumerate() {
+            let x = if right_to_left { width - 1 - column } else { column };
+            let start = (y * width + x) * channel_count;
+            let target = &mut output[start..start + channel_count];
+
+            if header.is_color_mapped() {
+                let index = if pixel_bytes == 2 { u16::from_le_bytes([pixel[0], pixel[1]]) as usize } else { pixel[0] as usize };
+                let entry = index
+                    .checked_sub(header.color_map_first)
+                    .filter(|&entry| entry < header.color_map_length)
+                    .ok_or_else(|| ImageError::Other(format!("TGA color index {} outside the color map", index)))?;
+                expand_color(&color_map[entry * color_bytes..(entry + 1) * color_bytes], header.color_map_bits, false, target);
+            } else {
+                expand_color(pixel, header.pixel_bits, header.is_gray(), target);
+            }
+        }
+    }
+
+    Ok(Image::from_raw(output, header.width, header.height, pixel_format, ComponentType::U8))
+}
+
+/// Append one row of stored pixels as RLE packets, without crossing rows
+fn encode_rle_row(row: &[u8], pixel_bytes: usize, output: &mut Vec<u8>) {
+    let pixels: Vec<&[u8]> = row.chunks_exact(pixel_bytes).collect();
+    let mut start = 0;
+    while start < pixels.len() {
+        let run = pixels[start..].iter().take(128).take_while(|&&pixel| pixel == pixels[start]).count();
+        if run >= 2 {
+            output.push(0x80 | (run - 1) as u8);
+            output.extend_from_slice(pixels[start]);
+            start += run;
+            continue;
+        }
+
+        // Raw packet up to the next run of two identical pixels
+        let mut end = start + 1;
+        while end < pixels.len() && end - start < 128 && !(end + 1 < pixels.len() && pixels[end] == pixels[end + 1]) {
+            end += 1;
+        }
+        output.push((end - start - 1) as u8);
+        for pixel in &pixels[start..end] {
+            output.extend_from_slice(pixel);
+        }
+        start = end;
+    }
+}
+
+/// Encode an image as a true-color or grayscale TGA, appending to `buffer`
+///
+/// Rows are written top-down. RLE compression follows `options.tga.rle`.
+/// Empty images and sides over 65535 pixels are rejected with
+/// [`ImageError::InvalidDimensions`].
+pub fn save_tga(image: &Image, options: &SaveOptions, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    // TGA only supports U8 component type
+    if image.component_type() != ComponentType::U8 {
+        return Err(ImageError::UnsupportedFormat(
+            "TGA only supports U8 component type".to_string()
+        ));
+    }
+    let (width, height) = (image.width(), image.height());
+    if width == 0 || height == 0 || width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(ImageError::InvalidDimensions { width, height });
+    }
+
+    // TGA stores color as BGR(A) and gray + alpha as two bytes
+    let (channels, gray, alpha_bits): (&[usize], bool, u8) = match image.pixel_format() {
+        PixelFormat::R => (&[0], true, 0),
+        PixelFormat::RG => (&[0, 1], true, 8),
+        PixelFormat::RGB => (&[2, 1, 0], false, 0),
+        PixelFormat::RGBA => (&[2, 1, 0, 3], false, 8),
+        PixelFormat::BGR => (&[0, 1, 2], false, 0),
+        PixelFormat::BGRA => (&[0, 1, 2, 3], false, 8),
+    };
+    let image_type = match (gray, options.tga.rle) {
+        (true, false) => 3,
+        (true, true) => 11,
+        (false, false) => 2,
+        (false, true) => 10,
+    };
+
+    buffer.extend_from_slice(&[0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    buffer.extend_from_slice(&(width as u16).to_le_bytes());
+    buffer.extend_from_slice(&(height as u16).to_le_bytes());
+    buffer.push((channels.len() * 8) as u8);
+    buffer.push(0x20 | alpha_bits);
+
+    let mut rows = RowView::new(image, channels, false);
+    for y in 0..height {
+        if options.tga.rle {
+            encode_rle_row(rows.row(y), channels.len(), buffer);
+        } else {
+            buffer.extend_from_slice(rows.row(y));
+        }
+    }
+
+    // TGA 2.0 footer without extension or developer areas
+    buffer.extend_from_slice(&[0; 8]);
+    buffer.extend_from_slice(FOOTER_SIGNATURE);
+    Ok(())
+}
@@ -0,0 +1,816 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat, SaveOptions, TiffCompression};
+use crate::buffer_pool;
+use crate::row_view::{rgb_format, rgb_order, RowView};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+const TAG_IMAGE_WIDTH: u16 = 256;
+const TAG_IMAGE_LENGTH: u16 = 257;
+const TAG_BITS_PER_SAMPLE: u16 = 258;
+const TAG_COMPRESSION: u16 = 259;
+const TAG_PHOTOMETRIC: u16 = 262;
+const TAG_STRIP_OFFSETS: u16 = 273;
+const TAG_SAMPLES_PER_PIXEL: u16 = 277;
+const TAG_ROWS_PER_STRIP: u16 = 278;
+const TAG_STRIP_BYTE_COUNTS: u16 = 279;
+const TAG_X_RESOLUTION: u16 = 282;
+const TAG_Y_RESOLUTION: u16 = 283;
+const TAG_PLANAR_CONFIGURATION: u16 = 284;
+const TAG_RESOLUTION_UNIT: u16 = 296;
+const TAG_PREDICTOR: u16 = 317;
+const TAG_COLOR_MAP: u16 = 320;
+const TAG_TILE_WIDTH: u16 = 322;
+const TAG_EXTRA_SAMPLES: u16 = 338;
+const TAG_SAMPLE_FORMAT: u16 = 339;
+
+const TYPE_BYTE: u16 = 1;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+
+const COMPRESSION_NONE: u32 = 1;
+const COMPRESSION_LZW: u32 = 5;
+const COMPRESSION_DEFLATE: u32 = 8;
+const COMPRESSION_DEFLATE_OLD: u32 = 32946;
+const COMPRESSION_PACKBITS: u32 = 32773;
+
+/// Samples per pixel beyond the declared extra samples (RGBA)
+const MAX_COLOR_SAMPLES: usize = 4;
+
+const PHOTOMETRIC_WHITE_IS_ZERO: u32 = 0;
+const PHOTOMETRIC_BLACK_IS_ZERO: u32 = 1;
+const PHOTOMETRIC_RGB: u32 = 2;
+const PHOTOMETRIC_PALETTE: u32 = 3;
+
+const PREDICTOR_HORIZONTAL: u32 = 2;
+const PREDICTOR_FLOATING_POINT: u32 = 3;
+
+const LZW_CLEAR: u16 = 256;
+const LZW_END: u16 = 257;
+
+/// Target size of written strips
+const STRIP_BYTES: usize = 64 * 1024;
+
+/// Bounds-checked reads in the byte order of the file
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&self, offset: usize, length: usize) -> ImageResult<&'a [u8]> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| ImageError::Other("Truncated TIFF file".to_string()))
+    }
+
+    fn u16(&self, offset: usize) -> ImageResult<u16> {
+        let bytes = self.bytes(offset, 2)?;
+        let bytes = [bytes[0], bytes[1]];
+        Ok(if self.big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    }
+
+    fn u32(&self, offset: usize) -> ImageResult<u32> {
+        let bytes = self.bytes(offset, 4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Ok(if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    }
+}
+
+/// One IFD entry; values of up to four bytes are stored inline
+struct Entry {
+    field_type: u16,
+    count: usize,
+    /// Offset of the value or of the inline value field
+    value_offset: usize,
+}
+
+/// Tags of the first image file directory
+struct Directory<'a> {
+    reader: Reader<'a>,
+    entries: HashMap<u16, Entry>,
+}
+
+impl<'a> Directory<'a> {
+    fn read(data: &'a [u8]) -> ImageResult<Self> {
+        let big_endian = match data.get(..4) {
+            Some([b'I', b'I', 42, 0]) => false,
+            Some([b'M', b'M', 0, 42]) => true,
+            _ => return Err(ImageError::UnsupportedFormat("Invalid TIFF header".to_string())),
+        };
+        let reader = Reader { data, big_endian };
+        let ifd = reader.u32(4)? as usize;
+        let count = reader.u16(ifd)? as usize;
+
+        let mut entries = HashMap::with_capacity(count);
+        for index in 0..count {
+            let offset = ifd + 2 + index * 12;
+            let field_type = reader.u16(offset + 2)?;
+            let entry_count = reader.u32(offset + 4)? as usize;
+            let size = match field_type {
+                TYPE_BYTE | 2 | 6 | 7 => 1,
+                TYPE_SHORT | 8 => 2,
+                TYPE_LONG | 9 | 11 => 4,
+                _ => 8,
+            };
+            let value_offset = if entry_count.saturating_mul(size) <= 4 { offset + 8 } else { reader.u32(offset + 8)? as usize };
+            entries.insert(reader.u16(offset)?, Entry { field_type, count: entry_count, value_offset });
+        }
+        Ok(Self { reader, entries })
+    }
+
+    /// Read the integer values of a tag (BYTE, SHORT or LONG)
+    fn values(&self, tag: u16) -> ImageResult<Option<Vec<u32>>> {
+        let Some(entry) = self.entries.get(&tag) else {
+            return Ok(None);
+        };
+        let reader = self.reader;
+        (0..entry.count)
+            .map(|index| match entry.field_type {
+                TYPE_BYTE => reader.bytes(entry.value_offset + index, 1).map(|byte| byte[0] as u32),
+                TYPE_SHORT => reader.u16(entry.value_offset + index * 2).map(u32::from),
+                TYPE_LONG => reader.u32(entry.value_offset + index * 4),
+                other => Err(ImageError::Other(format!("Unexpected type {} for TIFF tag {}", other, tag))),
+            })
+            .collect::<ImageResult<Vec<u32>>>()
+            .map(Some)
+    }
+
+    fn value(&self, tag: u16) -> ImageResult<Option<u32>> {
+        Ok(self.values(tag)?.and_then(|values| values.first().copied()))
+    }
+
+    fn required(&self, tag: u16, name: &str) -> ImageResult<Vec<u32>> {
+        self.values(tag)?
+            .filter(|values| !values.is_empty())
+            .ok_or_else(|| ImageError::Other(format!("TIFF file has no {} tag", name)))
+    }
+}
+
+/// Image layout described by the first directory
+struct Layout {
+    width: u32,
+    height: u32,
+    samples_per_pixel: usize,
+    /// Bytes per stored sample (1, 2, 4 or 8)
+    sample_bytes: usize,
+    /// Stored sample type, before F64 to F32 conversion
+    sample_type: SampleType,
+    photometric: u32,
+    planar: bool,
+    compression: u32,
+    predictor: u32,
+    rows_per_strip: usize,
+    pixel_format: PixelFormat,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SampleType {
+    Unsigned,
+    Signed,
+    Float,
+}
+
+impl Layout {
+    fn parse(directory: &Directory) -> ImageResult<Self> {
+        let unsupported = |message: String| ImageError::UnsupportedFormat(message);
+        let width = directory.required(TAG_IMAGE_WIDTH, "ImageWidth")?[0];
+        let height = directory.required(TAG_IMAGE_LENGTH, "ImageLength")?[0];
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions { width, height });
+        }
+        if directory.entries.contains_key(&TAG_TILE_WIDTH) {
+            return Err(unsupported("Tiled TIFF files are not supported".to_string()));
+        }
+
+        let samples_per_pixel = directory.value(TAG_SAMPLES_PER_PIXEL)?.unwrap_or(1) as usize;
+        let bits = directory.values(TAG_BITS_PER_SAMPLE)?.unwrap_or_else(|| vec![1]);
+        let bits_per_sample = bits[0];
+        if samples_per_pixel == 0 || bits.iter().any(|&b| b != bits_per_sample) {
+            return Err(unsupported(format!("Unsupported TIFF bits per sample {:?}", bits)));
+        }
+        let extra_samples = directory.values(TAG_EXTRA_SAMPLES)?.map_or(0, |values| values.len());
+        if samples_per_pixel > MAX_COLOR_SAMPLES + extra_samples {
+            return Err(unsupported(format!(
+                "Unsupported TIFF layout: {} samples per pixel with {} extra samples",
+                samples_per_pixel, extra_samples
+            )));
+        }
+        let sample_type = match directory.value(TAG_SAMPLE_FORMAT)?.unwrap_or(1) {
+            1 => SampleType::Unsigned,
+            2 => SampleType::Signed,
+            3 => SampleType::Float,
+            other => return Err(unsupported(format!("Unsupported TIFF sample format {}", other))),
+        };
+        match (sample_type, bits_per_sample) {
+            (SampleType::Unsigned | SampleType::Signed, 8 | 16) | (SampleType::Float, 16 | 32 | 64) => {}
+            _ => {
+                let kind = match sample_type {
+                    SampleType::Unsigned => "unsigned",
+                    SampleType::Signed => "signed",
+                    SampleType::Float => "float",
+                };
+                return Err(unsupported(format!("Unsupported TIFF sample type: {}-bit {}", bits_per_sample, kind)));
+            }
+        }
+
+        let photometric = directory
+            .value(TAG_PHOTOMETRIC)?
+            .ok_or_else(|| ImageError::Other("TIFF file has no PhotometricInterpretation tag".to_string()))?;
+        // The first extra sample, if any, is treated as alpha
+        let pixel_format = match (photometric, samples_per_pixel) {
+            (PHOTOMETRIC_WHITE_IS_ZERO | PHOTOMETRIC_BLACK_IS_ZERO, 1) => PixelFormat::R,
+            (PHOTOMETRIC_WHITE_IS_ZERO | PHOTOMETRIC_BLACK_IS_ZERO, _) => PixelFormat::RG,
+            (PHOTOMETRIC_RGB, 3) => PixelFormat::RGB,
+            (PHOTOMETRIC_RGB, 4..) => PixelFormat::RGBA,
+            (PHOTOMETRIC_PALETTE, 1) if sample_type == SampleType::Unsigned => PixelFormat::RGB,
+            _ => {
+                return Err(unsupported(format!(
+                    "Unsupported TIFF photometric interpretation {} with {} samples",
+                    photometric, samples_per_pixel
+                )));
+            }
+        };
+
+        let compression = directory.value(TAG_COMPRESSION)?.unwrap_or(COMPRESSION_NONE);
+        if !matches!(compression, COMPRESSION_NONE | COMPRESSION_LZW | COMPRESSION_DEFLATE | COMPRESSION_DEFLATE_OLD | COMPRESSION_PACKBITS) {
+            return Err(unsupported(format!("Unsupported TIFF compression {}", compression)));
+        }
+        let predictor = directory.value(TAG_PREDICTOR)?.unwrap_or(1);
+        let valid_predictor = match predictor {
+            1 => true,
+            PREDICTOR_HORIZONTAL => bits_per_sample <= 16,
+            PREDICTOR_FLOATING_POINT => sample_type == SampleType::Float,
+            _ => false,
+        };
+        if !valid_predictor {
+            return Err(unsupported(format!("Unsupported TIFF predictor {}", predictor)));
+        }
+
+        let rows_per_strip = directory.value(TAG_ROWS_PER_STRIP)?.unwrap_or(u32::MAX).clamp(1, height) as usize;
+        Ok(Self {
+            width,
+            height,
+            samples_per_pixel,
+            sample_bytes: bits_per_sample as usize / 8,
+            sample_type,
+            photometric,
+            planar: directory.value(TAG_PLANAR_CONFIGURATION)?.unwrap_or(1) == 2,
+            compression,
+            predictor,
+            rows_per_strip,
+            pixel_format,
+        })
+    }
+
+    fn component_type(&self) -> ComponentType {
+        match (self.sample_type, self.sample_bytes) {
+            _ if self.photometric == PHOTOMETRIC_PALETTE => ComponentType::U8,
+            (SampleType::Unsigned, 1) => ComponentType::U8,
+            (SampleType::Unsigned, _) => ComponentType::U16,
+            (SampleType::Signed, 1) => ComponentType::I8,
+            (SampleType::Signed, _) => ComponentType::I16,
+            (SampleType::Float, 2) => ComponentType::F16,
+            (SampleType::Float, _) => ComponentType::F32,
+        }
+    }
+}
+
+/// Check if data starts with a TIFF header (either byte order)
+pub fn is_tiff(data: &[u8]) -> bool {
+    data.starts_with(b"II*\0") || data.starts_with(b"MM\0*")
+}
+
+/// Read TIFF header information without decoding pixels
+///
+/// Describes the first image of the file, as returned by [`load_tiff`].
+pub fn probe_tiff(data: &[u8]) -> ImageResult<ImageInfo> {
+    let layout = Layout::parse(&Directory::read(data)?)?;
+    Ok(ImageInfo {
+        format: ImageFormat::Tiff,
+        width: layout.width,
+        height: layout.height,
+        pixel_format: layout.pixel_format,
+        component_type: layout.component_type(),
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Read MSB-first variable-width codes
+struct CodeReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl CodeReader<'_> {
+    fn read(&mut self, width: usize) -> Option<u16> {
+        if self.position + width > self.data.len() * 8 {
+            return None;
+        }
+        let mut code = 0u32;
+        for _ in 0..width {
+            let bit = (self.data[self.position / 8] >> (7 - self.position % 8)) & 1;
+            code = (code << 1) | bit as u32;
+            self.position += 1;
+        }
+        Some(code as u16)
+    }
+}
+
+/// Decompress a TIFF LZW strip (MSB-first codes with early change)
+fn decode_lzw(data: &[u8], expected: usize) -> ImageResult<Vec<u8>> {
+    let corrupt = || ImageError::Other("Corrupt TIFF LZW data".to_string());
+    let mut output = Vec::with_capacity(expected);
+    // Dictionary entries as (offset, length) into the output
+    let mut table: Vec<(usize, usize)> = Vec::with_capacity(4096);
+    let mut reader = CodeReader { data, position: 0 };
+    let mut width = 9;
+    let mut previous: Option<(usize, usize)> = None;
+
+    let reset = |table: &mut Vec<(usize, usize)>| {
+        table.clear();
+        table.extend((0..258).map(|_| (0, 0)));
+    };
+    reset(&mut table);
+
+    while let Some(code) = reader.read(width) {
+        if code == LZW_END {
+            break;
+        }
+        if code == LZW_CLEAR {
+            reset(&mut table);
+            width = 9;
+            previous = None;
+            continue;
+        }
+
+        let start = output.len();
+        let code = code as usize;
+        if code < 256 {
+            output.push(code as u8);
+        } else if code < table.len() {
+            let (offset, length) = table[code];
+            output.extend_from_within(offset..offset + length);
+        } else if code == table.len() {
+            // The code being defined: previous string plus its first byte
+            let (offset, length) = previous.ok_or_else(corrupt)?;
+            output.extend_from_within(offset..offset + length);
+            output.push(output[offset]);
+        } else {
+            return Err(corrupt());
+        }
+
+        if let Some((offset, length)) = previous {
+            if table.len() < 4096 {
+                table.push((offset, length + 1));
+            }
+        }
+        previous = Some((start, output.len() - start));
+        if table.len() + 1 >= 1 << width && width < 12 {
+            width += 1;
+        }
+        if output.len() >= expected {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/// Decompress a PackBits strip
+fn decode_packbits(data: &[u8], expected: usize) -> ImageResult<Vec<u8>> {
+    let truncated = || ImageError::Other("Truncated TIFF PackBits data".to_string());
+    let mut output = Vec::with_capacity(expected);
+    let mut pos = 0;
+    while output.len() < expected && pos < data.len() {
+        let header = data[pos] as i8;
+        pos += 1;
+        match header {
+            0..=127 => {
+                let length = header as usize + 1;
+                output.extend_from_slice(data.get(pos..pos + length).ok_or_else(truncated)?);
+                pos += length;
+            }
+            -127..=-1 => {
+                let value = *data.get(pos).ok_or_else(truncated)?;
+                output.resize(output.len() + (1 - header as isize) as usize, value);
+                pos += 1;
+            }
+            -128 => {}
+        }
+    }
+    Ok(output)
+}
+
+/// Decompress one strip to `expected` bytes
+/// Largest number of decompressed bytes one stored byte can produce
+///
+/// An LZW code has at least 9 bits and a string at most 4096 bytes, Deflate
+/// peaks at 1032:1 and a 2-byte PackBits run repeats a byte 128 times.
+fn max_expansion(compression: u32) -> usize {
+    match compression {
+        COMPRESSION_NONE => 1,
+        COMPRESSION_LZW => 4096 * 8 / 9 + 1,
+        COMPRESSION_PACKBITS => 64,
+        _ => 1032,
+    }
+}
+
+fn decompress(data: &[u8], compression: u32, expected: usize) -> ImageResult<Vec<u8>> {
+    let mut output = match compression {
+        COMPRESSION_NONE => data.to_vec(),
+        COMPRESSION_LZW => decode_lzw(data, expected)?,
+        COMPRESSION_PACKBITS => decode_packbits(data, expected)?,
+        _ => {
+            let mut output = Vec::with_capacity(expected);
+            ZlibDecoder::new(data)
+                .take(expected as u64)
+                .read_to_end(&mut output)
+                .map_err(|error| ImageError::Other(format!("Corrupt TIFF Deflate data: {}", error)))?;
+            output
+        }
+    };
+    if output.len() < expected {
+        return Err(ImageError::Other(format!(
+            "TIFF strip holds {} bytes, expected {}",
+            output.len(),
+            expected
+        )));
+    }
+    output.truncate(expected);
+    Ok(output)
+}
+
+/// Convert one decompressed row to little-endian samples, undoing predictors
+fn decode_row(row: &mut [u8], layout: &Layout, big_endian: bool, samples_per_pixel: usize) {
+    let size = layout.sample_bytes;
+    if layout.predictor == PREDICTOR_FLOATING_POINT {
+        // Bytes were differenced across the row, after splitting samples into
+        // byte planes from most to least significant
+        for index in samples_per_pixel..row.len() {
+            row[index] = row[index].wrapping_add(row[index - samples_per_pixel]);
+        }
+        let planes = row.to_vec();
+        let count = row.len() / size;
+        for (sample, bytes) in row.chunks_exact_mut(size).enumerate() {
+            for (byte, value) in bytes.iter_mut().enumerate() {
+                *value = planes[(size - 1 - byte) * count + sample];
+            }
+        }
+        return;
+    }
+
+    if big_endian && size > 1 {
+        for sample in row.chunks_exact_mut(size) {
+            sample.reverse();
+        }
+    }
+    if layout.predictor == PREDICTOR_HORIZONTAL {
+        let stride = samples_per_pixel * size;
+        match size {
+            1 => {
+                for index in stride..row.len() {
+                    row[index] = row[index].wrapping_add(row[index - stride]);
+                }
+            }
+            _ => {
+                for index in (stride..row.len()).step_by(2) {
+                    let previous = u16::from_le_bytes([row[index - stride], row[index - stride + 1]]);
+                    let current = u16::from_le_bytes([row[index], row[index + 1]]);
+                    row[index..index + 2].copy_from_slice(&current.wrapping_add(previous).to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// Load the first image of a TIFF file
+///
+/// Reads strip-based files in either byte order, uncompressed or compressed
+/// with LZW, Deflate or PackBits, with horizontal or floating-point
+/// predictors, chunky or planar. 8/16-bit unsigned samples load as U8/U16,
+/// signed as I8/I16, floats as F16/F32 (64-bit floats are converted to F32).
+/// Grayscale loads as R (RG with an extra sample), RGB with an extra sample
+/// as RGBA, palette images as RGB U8. Further samples are dropped. Images
+/// larger than [`LoadOptions::max_pixels`], or with more samples than RGBA
+/// plus their extra samples, are rejected before decoding.
+pub fn load_tiff(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let directory = Directory::read(data)?;
+    let layout = Layout::parse(&directory)?;
+    options.check_dimensions(layout.width, layout.height)?;
+    let (width, height) = (layout.width as usize, layout.height as usize);
+    let spp = layout.samples_per_pixel;
+    let size = layout.sample_bytes;
+
+    let offsets = directory.required(TAG_STRIP_OFFSETS, "StripOffsets")?;
+    let byte_counts = directory.required(TAG_STRIP_BYTE_COUNTS, "StripByteCounts")?;
+    let strips_per_plane = height.div_ceil(layout.rows_per_strip);
+    let planes = if layout.planar { spp } else { 1 };
+    let row_samples = if layout.planar { width } else { width * spp };
+    if offsets.len() < strips_per_plane * planes || byte_counts.len() < offsets.len() {
+        return Err(ImageError::Other("TIFF file has fewer strips than rows".to_string()));
+    }
+
+    // Decode every strip into chunky little-endian samples
+    let image_bytes = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(spp * size))
+        .ok_or(ImageError::InvalidDimensions { width: layout.width, height: layout.height })?;
+    let strip_rows = |strip: usize| layout.rows_per_strip.min(height - strip * layout.rows_per_strip);
+    let strips = (0..strips_per_plane * planes)
+        .map(|index| directory.reader.bytes(offsets[index] as usize, byte_counts[index] as usize))
+        .collect::<ImageResult<Vec<_>>>()?;
+    // Strips must be able to hold their rows, checked before allocating the image
+    options.check_bytes(image_bytes as u64)?;
+    let expansion = max_expansion(layout.compression);
+    for (index, stored) in strips.iter().enumerate() {
+        let expected = strip_rows(index % strips_per_plane) * row_samples * size;
+        if stored.len().saturating_mul(expansion) < expected {
+            return Err(ImageError::Other(format!("TIFF strip holds {} bytes, expected {}", stored.len(), expected)));
+        }
+    }
+
+    let mut samples = buffer_pool::take_zeroed(image_bytes);
+    for plane in 0..planes {
+        for strip in 0..strips_per_plane {
+            let index = plane * strips_per_plane + strip;
+            let first_row = strip * layout.rows_per_strip;
+            let rows = strip_rows(strip);
+            let mut decoded = decompress(strips[index], layout.compression, rows * row_samples * size)?;
+
+            for (row, stored_row) in decoded.chunks_exact_mut(row_samples * size).enumerate() {
+                decode_row(stored_row, &layout, directory.reader.big_endian, if layout.planar { 1 } else { spp });
+                let start = (first_row + row) * width * spp * size;
+                let target = &mut samples[start..start + width * spp * size];
+                if layout.planar {
+                    for (pixel, sample) in target.chunks_exact_mut(spp * size).zip(stored_row.chunks_exact(size)) {
+                        pixel[plane * size..(plane + 1) * size].copy_from_slice(sample);
+                    }
+                } else {
+                    target.copy_from_slice(stored_row);
+                }
+            }
+        }
+    }
+
+    let pixel_format = layout.pixel_format;
+    let component_type = layout.component_type();
+    let channels = pixel_format.channel_count();
+    let output_size = component_type.size_bytes();
+    let color_map = if layout.photometric == PHOTOMETRIC_PALETTE {
+        let entries = 1usize << (size * 8);
+        let map = directory.required(TAG_COLOR_MAP, "ColorMap")?;
+        if map.len() < entries * 3 {
+            return Err(ImageError::Other("TIFF color map is too short".to_string()));
+        }
+        Some((map, entries))
+    } else {
+        None
+    };
+
+    let mut output = buffer_pool::take_zeroed(width * height * channels * output_size);
+    let output_row_bytes = width * channels * output_size;
+    for (y, row) in samples.chunks_exact(width * spp * size).enumerate() {
+        let target_y = if options.flip_y { height - 1 - y } else { y };
+        let target = &mut output[target_y * output_row_bytes..(target_y + 1) * output_row_bytes];
+        for (pixel, output_pixel) in row.chunks_exact(spp * size).zip(target.chunks_exact_mut(channels * output_size)) {
+            if let Some((map, entries)) = &color_map {
+                let index = if size == 1 { pixel[0] as usize } else { u16::from_le_bytes([pixel[0], pixel[1]]) as usize };
+                for channel in 0..3 {
+                    output_pixel[channel] = (map[channel * entries + index] >> 8) as u8;
+                }
+                continue;
+            }
+            for channel in 0..channels {
+                let sample = &pixel[channel * size..(channel + 1) * size];
+                let output_sample = &mut output_pixel[channel * output_size..(channel + 1) * output_size];
+                if size == 8 {
+                    let value = f64::from_le_bytes(sample.try_into().unwrap()) as f32;
+                    output_sample.copy_from_slice(&value.to_le_bytes());
+                } else {
+                    output_sample.copy_from_slice(sample);
+                }
+                // WhiteIsZero stores inverted gray in unsigned images
+                let invert = layout.photometric == PHOTOMETRIC_WHITE_IS_ZERO && channel == 0 && layout.sample_type == SampleType::Unsigned;
+                if invert {
+                    output_sample.iter_mut().for_each(|byte| *byte = !*byte);
+                }
+            }
+        }
+    }
+    buffer_pool::recycle(samples);
+
+    Ok(Image::from_raw(output, layout.width, layout.height, pixel_format, component_type))
+}
+
+/// Write MSB-first variable-width codes
+struct CodeWriter<'a> {
+    output: &'a mut Vec<u8>,
+    bits: u32,
+    count: usize,
+}
+
+impl CodeWriter<'_> {
+    fn write(&mut self, code: u16, width: usize) {
+        self.bits = (self.bits << width) | code as u32;
+        self.count += width;
+        while self.count >= 8 {
+            self.count -= 8;
+            self.output.push((self.bits >> self.count) as u8);
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.count > 0 {
+            self.output.push((self.bits << (8 - self.count)) as u8);
+            self.count = 0;
+        }
+    }
+}
+
+/// Compress data with TIFF LZW, appending to `output`
+fn encode_lzw(data: &[u8], output: &mut Vec<u8>) {
+    let mut writer = CodeWriter { output, bits: 0, count: 0 };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = 258u16;
+    let mut width = 9;
+    writer.write(LZW_CLEAR, width);
+
+    let Some((&first, rest)) = data.split_first() else {
+        writer.write(LZW_END, width);
+        writer.finish();
+        return;
+    };
+    let mut prefix = first as u16;
+    for &byte in rest {
+        if let Some(&code) = table.get(&(prefix, byte)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, width);
+        table.insert((prefix, byte), next_code);
+        next_code += 1;
+        // Decoders widen codes one entry early; restart before 12-bit codes run out
+        if next_code == 4094 {
+            writer.write(LZW_CLEAR, width);
+            table.clear();
+            next_code = 258;
+            width = 9;
+        } else if next_code as usize == 1 << width {
+            width += 1;
+        }
+        prefix = byte as u16;
+    }
+    writer.write(prefix, width);
+    // The decoder adds one more entry before reading the end code
+    if next_code as usize + 1 == 1 << width && width < 12 {
+        width += 1;
+    }
+    writer.write(LZW_END, width);
+    writer.finish();
+}
+
+/// Encode an image as a strip-based little-endian TIFF, appending to `buffer`
+///
+/// Every pixel format and component type is stored as is (BGR orders are
+/// written as RGB). Compression follows `options.tiff.compression`; integer
+/// samples use the horizontal predictor when compressed.
+pub fn save_tiff(image: &Image, options: &SaveOptions, buffer: &mut Vec<u8>) -> ImageResult<()> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    if width == 0 || height == 0 {
+        return Err(ImageError::InvalidDimensions { width: image.width(), height: image.height() });
+    }
+    let pixel_format = rgb_format(image.pixel_format());
+    let component_type = image.component_type();
+    let channels = pixel_format.channel_count();
+    let size = component_type.size_bytes();
+    let row_bytes = width * channels * size;
+    let rows_per_strip = (STRIP_BYTES / row_bytes).clamp(1, height);
+
+    let (compression, use_predictor) = match options.tiff.compression {
+        TiffCompression::None => (COMPRESSION_NONE, false),
+        TiffCompression::Lzw => (COMPRESSION_LZW, true),
+        TiffCompression::Deflate => (COMPRESSION_DEFLATE, true),
+    };
+    let predictor = use_predictor && matches!(size, 1 | 2) && !matches!(component_type, ComponentType::F16);
+    let sample_format: u16 = match component_type {
+        ComponentType::U8 | ComponentType::U16 => 1,
+        ComponentType::I8 | ComponentType::I16 => 2,
+        ComponentType::F16 | ComponentType::F32 => 3,
+    };
+
+    let start = buffer.len();
+    buffer.extend_from_slice(b"II*\0\0\0\0\0");
+
+    // Strips first, then out-of-line tag values, then the directory
+    let mut rows = RowView::new(image, rgb_order(image.pixel_format()), false);
+    let mut strip = Vec::with_capacity(rows_per_strip * row_bytes);
+    let mut strip_offsets = Vec::new();
+    let mut strip_byte_counts = Vec::new();
+    for first_row in (0..height).step_by(rows_per_strip) {
+        strip.clear();
+        for y in first_row..(first_row + rows_per_strip).min(height) {
+            let row_start = strip.len();
+            strip.extend_from_slice(rows.row(y as u32));
+            if predictor {
+                encode_predictor(&mut strip[row_start..], channels, size);
+            }
+        }
+
+        strip_offsets.push((buffer.len() - start) as u32);
+        let strip_start = buffer.len();
+        match options.tiff.compression {
+            TiffCompression::None => buffer.extend_from_slice(&strip),
+            TiffCompression::Lzw => encode_lzw(&strip, buffer),
+            TiffCompression::Deflate => {
+                let mut encoder = ZlibEncoder::new(std::mem::take(buffer), flate2::Compression::default());
+                encoder.write_all(&strip)?;
+                *buffer = encoder.finish()?;
+            }
+        }
+        strip_byte_counts.push((buffer.len() - strip_start) as u32);
+        if buffer.len() - start > u32::MAX as usize {
+            return Err(ImageError::Other("TIFF files are limited to 4 GiB".to_string()));
+        }
+    }
+
+    let shorts = |values: &[u16]| values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+    let longs = |values: &[u32]| values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+    let photometric = if channels <= 2 { PHOTOMETRIC_BLACK_IS_ZERO } else { PHOTOMETRIC_RGB } as u16;
+    let mut entries: Vec<(u16, u16, usize, Vec<u8>)> = vec![
+        (TAG_IMAGE_WIDTH, TYPE_LONG, 1, longs(&[width as u32])),
+        (TAG_IMAGE_LENGTH, TYPE_LONG, 1, longs(&[height as u32])),
+        (TAG_BITS_PER_SAMPLE, TYPE_SHORT, channels, shorts(&vec![(size * 8) as u16; channels])),
+        (TAG_COMPRESSION, TYPE_SHORT, 1, shorts(&[compression as u16])),
+        (TAG_PHOTOMETRIC, TYPE_SHORT, 1, shorts(&[photometric])),
+        (TAG_STRIP_OFFSETS, TYPE_LONG, strip_offsets.len(), longs(&strip_offsets)),
+        (TAG_SAMPLES_PER_PIXEL, TYPE_SHORT, 1, shorts(&[channels as u16])),
+        (TAG_ROWS_PER_STRIP, TYPE_LONG, 1, longs(&[rows_per_strip as u32])),
+        (TAG_STRIP_BYTE_COUNTS, TYPE_LONG, strip_byte_counts.len(), longs(&strip_byte_counts)),
+        (TAG_X_RESOLUTION, TYPE_RATIONAL, 1, longs(&[72, 1])),
+        (TAG_Y_RESOLUTION, TYPE_RATIONAL, 1, longs(&[72, 1])),
+        (TAG_PLANAR_CONFIGURATION, TYPE_SHORT, 1, shorts(&[1])),
+        (TAG_RESOLUTION_UNIT, TYPE_SHORT, 1, shorts(&[2])),
+    ];
+    if predictor {
+        entries.push((TAG_PREDICTOR, TYPE_SHORT, 1, shorts(&[PREDICTOR_HORIZONTAL as u16])));
+    }
+    if pixel_format.has_alpha() {
+        // Unassociated (straight) alpha
+        entries.push((TAG_EXTRA_SAMPLES, TYPE_SHORT, 1, shorts(&[2])));
+    }
+    entries.push((TAG_SAMPLE_FORMAT, TYPE_SHORT, channels, shorts(&vec![sample_format; channels])));
+
+    // Values larger than four bytes are stored before the directory, word-aligned
+    let mut value_fields = Vec::with_capacity(entries.len());
+    for (_, _, _, value) in &entries {
+        if value.len() <= 4 {
+            let mut inline = [0u8; 4];
+            inline[..value.len()].copy_from_slice(value);
+            value_fields.push(inline);
+        } else {
+            if (buffer.len() - start) % 2 == 1 {
+                buffer.push(0);
+            }
+            value_fields.push(((buffer.len() - start) as u32).to_le_bytes());
+            buffer.extend_from_slice(value);
+        }
+    }
+    if (buffer.len() - start) % 2 == 1 {
+        buffer.push(0);
+    }
+    let directory_offset = (buffer.len() - start) as u32;
+    buffer.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for ((tag, field_type, count, _), value_field) in entries.iter().zip(&value_fields) {
+        buffer.extend_from_slice(&tag.to_le_bytes());
+        buffer.extend_from_slice(&field_type.to_le_bytes());
+        buffer.extend_from_slice(&(*count as u32).to_le_bytes());
+        buffer.extend_from_slice(value_field);
+    }
+    buffer.extend_from_slice(&0u32.to_le_bytes());
+    buffer[start + 4..start + 8].copy_from_slice(&directory_offset.to_le_bytes());
+    Ok(())
+}
+
+/// Replace samples of a little-endian row with differences to the previous pixel
+fn encode_predictor(row: &mut [u8], channels: usize, size: usize) {
+    let stride = channels * size;
+    match size {
+        1 => {
+            for index in (stride..row.len()).rev() {
+                row[index] = row[index].wrapping_sub(row[index - stride]);
+            }
+        }
+        _ => {
+            for index in (stride..row.len()).step_by(2).rev() {
+                let previous = u16::from_le_bytes([row[index - stride], row[index - stride + 1]]);
+                let current = u16::from_le_bytes([row[index], row[index + 1]]);
+                row[index..index + 2].copy_from_slice(&current.wrapping_sub(previous).to_le_bytes());
+            }
+        }
+    }
+}
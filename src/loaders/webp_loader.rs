@@ -0,0 +1,221 @@
+use crate::{ComponentType, Image, ImageError, ImageFormat, ImageInfo, ImageResult, LoadOptions, PixelFormat};
+use crate::buffer_pool;
+use crate::loaders::{webp_lossless, webp_lossy};
+
+/// VP8X flag: the image has an alpha channel
+const FLAG_ALPHA: u8 = 0x10;
+
+/// VP8X flag: the file holds an animation
+const FLAG_ANIMATION: u8 = 0x02;
+
+/// Compressed image data of a WebP file
+enum Bitstream<'a> {
+    /// VP8 key frame, with an optional ALPH chunk payload
+    Lossy { frame: &'a [u8], alpha: Option<&'a [u8]> },
+    /// VP8L bitstream
+    Lossless(&'a [u8]),
+}
+
+/// Parsed RIFF container
+struct Container<'a> {
+    width: u32,
+    height: u32,
+    has_alpha: bool,
+    bitstream: Bitstream<'a>,
+}
+
+impl<'a> Container<'a> {
+    fn parse(data: &'a [u8]) -> ImageResult<Self> {
+        let invalid = |message: &str| ImageError::UnsupportedFormat(format!("Invalid WebP header: {}", message));
+        if !is_webp(data) {
+            return Err(invalid("missing RIFF/WEBP signature"));
+        }
+        let riff_size = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+        if riff_size < 4 {
+            return Err(invalid("RIFF size too small"));
+        }
+        let end = riff_size.saturating_add(8);
+        if end > data.len() {
+            return Err(ImageError::Other("Truncated WebP file".to_string()));
+        }
+
+        // Chunks are a FourCC and a payload size, padded to an even length
+        let mut chunks = Vec::new();
+        let mut pos = 12;
+        while pos + 8 <= end {
+            let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize;
+            let payload = data
+                .get(pos + 8..(pos + 8).saturating_add(size))
+                .filter(|_| pos + 8 + size <= end)
+                .ok_or_else(|| ImageError::Other("Truncated WebP chunk".to_string()))?;
+            chunks.push((&data[pos..pos + 4], payload));
+            pos += 8 + size + (size & 1);
+        }
+
+        let (extended, mut chunks) = match chunks.split_first() {
+            Some(((b"VP8X", payload), rest)) => (Some(*payload), rest.iter()),
+            _ => (None, chunks.iter()),
+        };
+        let mut alpha = None;
+        let bitstream = loop {
+            match chunks.next() {
+                Some((b"VP8 ", payload)) => break Bitstream::Lossy { frame: payload, alpha },
+                Some((b"VP8L", payload)) => break Bitstream::Lossless(payload),
+                Some((b"ALPH", payload)) => alpha = alpha.or(Some(*payload)),
+                Some((b"ANIM" | b"ANMF", _)) => {
+                    return Err(ImageError::UnsupportedFormat("Animated WebP files are not supported".to_string()));
+                }
+                Some(_) if extended.is_some() => {}
+                _ => return Err(invalid("missing VP8 or VP8L chunk")),
+            }
+        };
+
+        let (width, height, lossless_alpha) = match bitstream {
+            Bitstream::Lossy { frame, .. } => {
+                let (width, height) = webp_lossy::read_header(frame)?;
+                (width, height, false)
+            }
+            Bitstream::Lossless(stream) => webp_lossless::read_header(stream)?,
+        };
+
+        let has_alpha = match extended {
+            Some(header) => {
+                if header.len() < 10 {
+                    return Err(invalid("truncated VP8X chunk"));
+                }
+                if header[0] & FLAG_ANIMATION != 0 {
+                    return Err(ImageError::UnsupportedFormat("Animated WebP files are not supported".to_string()));
+                }
+                let canvas = |offset: usize| u32::from_le_bytes([header[offset], header[offset + 1], header[offset + 2], 0]) + 1;
+                if (canvas(4), canvas(7)) != (width, height) {
+                    return Err(invalid("canvas size does not match the image"));
+                }
+                header[0] & FLAG_ALPHA != 0 || alpha.is_some()
+            }
+            None => lossless_alpha,
+        };
+
+        Ok(Self { width, height, has_alpha, bitstream })
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        if self.has_alpha { PixelFormat::RGBA } else { PixelFormat::RGB }
+    }
+}
+
+/// Check if data starts with a RIFF header of WebP form type
+pub fn is_webp(data: &[u8]) -> bool {
+    data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP"
+}
+
+/// Read WebP header information without decoding pixels
+///
+/// Images are loaded as RGB U8, or RGBA U8 when they have an alpha channel.
+pub fn probe_webp(data: &[u8]) -> ImageResult<ImageInfo> {
+    let container = Container::parse(data)?;
+    Ok(ImageInfo {
+        format: ImageFormat::WebP,
+        width: container.width,
+        height: container.height,
+        pixel_format: container.pixel_format(),
+        component_type: ComponentType::U8,
+        layer_count: 1,
+        mip_levels: 1,
+    })
+}
+
+/// Decode an ALPH chunk payload into one alpha value per pixel
+fn decode_alpha(payload: &[u8], width: usize, height: usize) -> ImageResult<Vec<u8>> {
+    let corrupt = |message: &str| ImageError::Other(format!("Corrupt WebP alpha data: {}", message));
+    let (&header, data) = payload.split_first().ok_or_else(|| corrupt("empty chunk"))?;
+    let (compression, filter) = (header & 3, (header >> 2) & 3);
+    if compression > 1 || header >> 6 != 0 {
+        return Err(corrupt("unknown compression"));
+    }
+
+    let mut alpha = if compression == 0 {
+        data.get(..width * height).ok_or_else(|| corrupt("truncated raw alpha"))?.to_vec()
+    } else {
+        // Alpha is stored in the green channel of a headerless lossless stream
+        webp_lossless::decode_stream(data, width as u32, height as u32)?
+            .into_iter()
+            .map(|argb| (argb >> 8) as u8)
+            .collect()
+    };
+
+    // Undo the prediction filter; the first row always predicts from the left
+    for y in 0..height {
+        let (previous, rest) = alpha.split_at_mut(y * width);
+        let row = &mut rest[..width];
+        let above = previous.len().checked_sub(width).map(|start| &previous[start..]);
+        match (filter, above) {
+            (0, _) => {}
+            (1, above) | (_, above @ None) => {
+                let mut left = above.map_or(0, |above| above[0]);
+                for value in row.iter_mut() {
+                    *value = value.wrapping_add(left);
+                    left = *value;
+                }
+            }
+            (2, Some(above)) => {
+                for (value, &top) in row.iter_mut().zip(above) {
+                    *value = value.wrapping_add(top);
+                }
+            }
+            (_, Some(above)) => {
+                let (mut left, mut top_left) = (above[0], above[0]);
+                for (value, &top) in row.iter_mut().zip(above) {
+                    let prediction = (left as i32 + top as i32 - top_left as i32).clamp(0, 255) as u8;
+                    *value = value.wrapping_add(prediction);
+                    (left, top_left) = (*value, top);
+                }
+            }
+        }
+    }
+    Ok(alpha)
+}
+
+/// Load a still WebP image (lossy or lossless) as RGB or RGBA U8
+///
+/// Lossy images are converted from YUV 4:2:0 with the same chroma upsampling
+/// as libwebp, so pixels match its RGB output. Animated files are rejected.
+pub fn load_webp(data: &[u8], options: &LoadOptions) -> ImageResult<Image> {
+    let container = Container::parse(data)?;
+    options.check_dimensions(container.width, container.height)?;
+    let (width, height) = (container.width as usize, container.height as usize);
+    let pixel_format = container.pixel_format();
+    let channels = pixel_format.channel_count();
+    let mut output = buffer_pool::take_zeroed(width * height * channels);
+
+    match container.bitstream {
+        Bitstream::Lossy { frame, alpha } => {
+            let frame = webp_lossy::decode(frame)?;
+            frame.write_rgb(&mut output, channels);
+            if channels == 4 {
+                if let Some(payload) = alpha {
+                    let alpha = decode_alpha(payload, width, height)?;
+                    for (pixel, value) in output.chunks_exact_mut(4).zip(alpha) {
+                        pixel[3] = value;
+                    }
+                }
+            }
+        }
+        Bitstream::Lossless(stream) => {
+            let (_, _, pixels) = webp_lossless::decode(stream)?;
+            for (pixel, argb) in output.chunks_exact_mut(channels).zip(pixels) {
+                let [blue, green, red, alpha] = argb.to_le_bytes();
+                pixel[..3].copy_from_slice(&[red, green, blue]);
+                if channels == 4 {
+                    pixel[3] = alpha;
+                }
+            }
+        }
+    }
+
+    let mut image = Image::from_raw(output, container.width, container.height, pixel_format, ComponentType::U8);
+    // The decoders produce whole frames, so flip after decoding
+    if options.flip_y {
+        image.flip_rows();
+    }
+    Ok(image)
+}
@@ -0,0 +1,555 @@
+use crate::{ImageError, ImageResult};
+
+/// First byte of a VP8L bitstream
+const SIGNATURE: u8 = 0x2F;
+
+/// Alphabet sizes of a prefix code group: green + length prefixes (+ color
+/// cache), red, blue, alpha and distance prefixes
+const ALPHABET_SIZES: [usize; 5] = [256 + 24, 256, 256, 256, 40];
+
+/// Order in which code length code lengths are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [17, 18, 0, 1, 2, 3, 4, 5, 16, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+/// Distance codes 1-120 as (dy << 4) | (8 - dx) neighbourhood offsets
+const DISTANCE_MAP: [u8; 120] = [
+    0x18, 0x07, 0x17, 0x19, 0x28, 0x06, 0x27, 0x29, 0x16, 0x1A, 0x26, 0x2A, 0x38, 0x05, 0x37, 0x39,
+    0x15, 0x1B, 0x36, 0x3A, 0x25, 0x2B, 0x48, 0x04, 0x47, 0x49, 0x14, 0x1C, 0x35, 0x3B, 0x46, 0x4A,
+    0x24, 0x2C, 0x58, 0x45, 0x4B, 0x34, 0x3C, 0x03, 0x57, 0x59, 0x13, 0x1D, 0x56, 0x5A, 0x23, 0x2D,
+    0x44, 0x4C, 0x55, 0x5B, 0x33, 0x3D, 0x68, 0x02, 0x67, 0x69, 0x12, 0x1E, 0x66, 0x6A, 0x22, 0x2E,
+    0x54, 0x5C, 0x43, 0x4D, 0x65, 0x6B, 0x32, 0x3E, 0x78, 0x01, 0x77, 0x79, 0x53, 0x5D, 0x11, 0x1F,
+    0x64, 0x6C, 0x42, 0x4E, 0x76, 0x7A, 0x21, 0x2F, 0x75, 0x7B, 0x31, 0x3F, 0x63, 0x6D, 0x52, 0x5E,
+    0x00, 0x74, 0x7C, 0x41, 0x4F, 0x10, 0x20, 0x62, 0x6E, 0x30, 0x73, 0x7D, 0x51, 0x5F, 0x40, 0x72,
+    0x7E, 0x61, 0x6F, 0x50, 0x71, 0x7F, 0x60, 0x70,
+];
+
+/// Bits resolved by the prefix code lookup table
+const TABLE_BITS: u32 = 8;
+
+const MAX_CODE_LENGTH: usize = 15;
+
+fn corrupt(message: &str) -> ImageError {
+    ImageError::Other(format!("Corrupt WebP lossless data: {}", message))
+}
+
+/// LSB-first bit reader; bytes past the end read as zero and are reported by `overrun`
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    value: u64,
+    bits: u32,
+    consumed: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0, value: 0, bits: 0, consumed: 0 }
+    }
+
+    fn peek(&mut self, count: u32) -> u32 {
+        while self.bits <= 56 {
+            let byte = self.data.get(self.pos).copied().unwrap_or(0);
+            self.value |= (byte as u64) << self.bits;
+            self.bits += 8;
+            self.pos += 1;
+        }
+        (self.value & ((1u64 << count) - 1)) as u32
+    }
+
+    fn skip(&mut self, count: u32) {
+        self.value >>= count;
+        self.bits -= count;
+        self.consumed += count as usize;
+    }
+
+    fn read(&mut self, count: u32) -> u32 {
+        let value = self.peek(count);
+        self.skip(count);
+        value
+    }
+
+    fn overrun(&self) -> bool {
+        self.consumed > self.data.len() * 8
+    }
+}
+
+/// Canonical prefix code
+struct PrefixCode {
+    /// (symbol, length) for the next `TABLE_BITS` bits, length 0 for longer codes
+    table: Vec<(u16, u8)>,
+    /// Codes per length, for codes longer than the table
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    /// Symbols ordered by code
+    symbols: Vec<u16>,
+    /// Symbol of a code with a single (zero-length) entry
+    single: Option<u16>,
+}
+
+impl PrefixCode {
+    fn from_lengths(lengths: &[u8]) -> ImageResult<Self> {
+        let mut counts = [0u16; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let used: usize = counts.iter().map(|&count| count as usize).sum();
+        if used == 0 {
+            return Err(corrupt("empty prefix code"));
+        }
+        if used == 1 {
+            let symbol = lengths.iter().position(|&length| length != 0).unwrap() as u16;
+            return Ok(Self { table: Vec::new(), counts, symbols: Vec::new(), single: Some(symbol) });
+        }
+
+        // Codes must exactly fill the code space
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(corrupt("over-subscribed prefix code"));
+            }
+        }
+        if left != 0 {
+            return Err(corrupt("incomplete prefix code"));
+        }
+
+        let mut offsets = [0usize; MAX_CODE_LENGTH + 2];
+        for length in 1..=MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length] as usize;
+        }
+        let mut symbols = vec![0u16; used];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize]] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        // Codes are stored MSB first, so table indices are bit-reversed codes
+        let mut table = vec![(0u16, 0u8); 1 << TABLE_BITS];
+        let mut code = 0u32;
+        let mut index = 0;
+        for (length, &count) in counts.iter().enumerate().skip(1) {
+            for _ in 0..count {
+                if length as u32 <= TABLE_BITS {
+                    let reversed = code.reverse_bits() >> (32 - length);
+                    for fill in (reversed as usize..1 << TABLE_BITS).step_by(1 << length) {
+                        table[fill] = (symbols[index], length as u8);
+                    }
+                }
+                code += 1;
+                index += 1;
+            }
+            code <<= 1;
+        }
+        Ok(Self { table, counts, symbols, single: None })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> u16 {
+        if let Some(symbol) = self.single {
+            return symbol;
+        }
+        let (symbol, length) = self.table[reader.peek(TABLE_BITS) as usize];
+        if length != 0 {
+            reader.skip(length as u32);
+            return symbol;
+        }
+
+        // Long code: walk the canonical code one bit at a time
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..=MAX_CODE_LENGTH {
+            code |= reader.read(1) as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return self.symbols[(index + code - first) as usize];
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        // Unreachable for complete codes
+        0
+    }
+}
+
+/// Read one prefix code (simple or normal form)
+fn read_prefix_code(reader: &mut BitReader, alphabet_size: usize) -> ImageResult<PrefixCode> {
+    let mut lengths = vec![0u8; alphabet_size];
+    if reader.read(1) == 1 {
+        // One or two symbols of up to 8 bits
+        let count = reader.read(1) + 1;
+        let first_bits = if reader.read(1) == 1 { 8 } else { 1 };
+        let symbols = [reader.read(first_bits) as usize, if count == 2 { reader.read(8) as usize } else { usize::MAX }];
+        for symbol in symbols.into_iter().take(count as usize) {
+            *lengths.get_mut(symbol).ok_or_else(|| corrupt("symbol outside alphabet"))? = 1;
+        }
+        return PrefixCode::from_lengths(&lengths);
+    }
+
+    let mut code_length_lengths = [0u8; 19];
+    let stored = 4 + reader.read(4) as usize;
+    for &symbol in &CODE_LENGTH_ORDER[..stored] {
+        code_length_lengths[symbol] = reader.read(3) as u8;
+    }
+    let code_length_code = PrefixCode::from_lengths(&code_length_lengths)?;
+
+    let mut max_symbol = if reader.read(1) == 1 {
+        let length_bits = 2 + 2 * reader.read(3);
+        let max_symbol = 2 + reader.read(length_bits) as usize;
+        if max_symbol > alphabet_size {
+            return Err(corrupt("code lengths exceed alphabet"));
+        }
+        max_symbol
+    } else {
+        alphabet_size
+    };
+
+    let mut symbol = 0;
+    let mut previous_length = 8;
+    while symbol < alphabet_size && max_symbol > 0 {
+        max_symbol -= 1;
+        let length = code_length_code.decode(reader);
+        if length < 16 {
+            lengths[symbol] = length as u8;
+            symbol += 1;
+            if length != 0 {
+                previous_length = length as u8;
+            }
+            continue;
+        }
+        let (extra_bits, offset, value) = match length {
+            16 => (2, 3, previous_length),
+            17 => (3, 3, 0),
+            _ => (7, 11, 0),
+        };
+        let repeat = reader.read(extra_bits) as usize + offset;
+        if symbol + repeat > alphabet_size {
+            return Err(corrupt("code length repeat past alphabet"));
+        }
+        lengths[symbol..symbol + repeat].fill(value);
+        symbol += repeat;
+    }
+    PrefixCode::from_lengths(&lengths)
+}
+
+fn subsample(size: usize, bits: u32) -> usize {
+    (size + (1 << bits) - 1) >> bits
+}
+
+/// Length or distance from a prefix symbol and its extra bits
+fn copy_value(reader: &mut BitReader, symbol: u16) -> usize {
+    let symbol = symbol as usize;
+    if symbol < 4 {
+        return symbol + 1;
+    }
+    let extra_bits = (symbol - 2) >> 1;
+    let offset = (2 + (symbol & 1)) << extra_bits;
+    offset + reader.read(extra_bits as u32) as usize + 1
+}
+
+/// Convert a distance code to a pixel distance in an image of `width`
+fn plane_distance(width: usize, code: usize) -> usize {
+    if code > 120 {
+        return code - 120;
+    }
+    let offset = DISTANCE_MAP[code - 1] as isize;
+    let distance = (offset >> 4) * width as isize + 8 - (offset & 0xF);
+    distance.max(1) as usize
+}
+
+/// Decode entropy-coded ARGB pixels, with meta prefix codes for the main image
+fn decode_pixels(reader: &mut BitReader, width: usize, height: usize, main_image: bool) -> ImageResult<Vec<u32>> {
+    let cache_bits = if reader.read(1) == 1 {
+        let bits = reader.read(4);
+        if !(1..=11).contains(&bits) {
+            return Err(corrupt("invalid color cache size"));
+        }
+        bits
+    } else {
+        0
+    };
+
+    // Entropy image mapping blocks to prefix code groups
+    let (group_bits, group_map) = if main_image && reader.read(1) == 1 {
+        let bits = reader.read(3) + 2;
+        let map = decode_pixels(reader, subsample(width, bits), subsample(height, bits), false)?;
+        (bits, map.into_iter().map(|pixel| ((pixel >> 8) & 0xFFFF) as usize).collect())
+    } else {
+        (0, Vec::new())
+    };
+    let group_count = group_map.iter().max().map_or(1, |&max| max + 1);
+
+    // Groups that no block uses are read but not kept
+    let mut used = vec![group_map.is_empty(); group_count];
+    for &group in &group_map {
+        used[group] = true;
+    }
+    let cache_size = if cache_bits > 0 { 1 << cache_bits } else { 0 };
+    let mut groups = Vec::with_capacity(group_count);
+    let mut group_index = vec![0usize; group_count];
+    for (group, &is_used) in used.iter().enumerate() {
+        let mut codes = Vec::with_capacity(5);
+        for (index, &size) in ALPHABET_SIZES.iter().enumerate() {
+            let size = if index == 0 { size + cache_size } else { size };
+            codes.push(read_prefix_code(reader, size)?);
+        }
+        if is_used {
+            group_index[group] = groups.len();
+            groups.push(codes);
+        }
+    }
+    if reader.overrun() {
+        return Err(ImageError::Other("Truncated WebP lossless data".to_string()));
+    }
+
+    let map_width = subsample(width, group_bits);
+    let total = width * height;
+    let mut pixels = vec![0u32; total];
+    let mut cache = vec![0u32; cache_size];
+    let cache_shift = 32 - cache_bits;
+    let mut cached = 0;
+    let mut pos = 0;
+    while pos < total {
+        let codes = if group_bits == 0 {
+            &groups[0]
+        } else {
+            let (x, y) = (pos % width, pos / width);
+            &groups[group_index[group_map[(y >> group_bits) * map_width + (x >> group_bits)]]]
+        };
+
+        let green = codes[0].decode(reader);
+        if green < 256 {
+            let red = codes[1].decode(reader) as u32;
+            let blue = codes[2].decode(reader) as u32;
+            let alpha = codes[3].decode(reader) as u32;
+            pixels[pos] = (alpha << 24) | (red << 16) | ((green as u32) << 8) | blue;
+            pos += 1;
+        } else if green < 256 + 24 {
+            let length = copy_value(reader, green - 256);
+            let distance_symbol = codes[4].decode(reader);
+            let distance = plane_distance(width, copy_value(reader, distance_symbol));
+            if distance > pos || length > total - pos {
+                return Err(corrupt("backward reference out of bounds"));
+            }
+            for index in pos..pos + length {
+                pixels[index] = pixels[index - distance];
+            }
+            pos += length;
+        } else {
+            let index = (green - 256 - 24) as usize;
+            // Every pixel before this one enters the cache in order
+            while cached < pos {
+                let pixel = pixels[cached];
+                cache[(pixel.wrapping_mul(0x1E35A7BD) >> cache_shift) as usize] = pixel;
+                cached += 1;
+            }
+            pixels[pos] = *cache.get(index).ok_or_else(|| corrupt("color cache index out of range"))?;
+            pos += 1;
+        }
+        if reader.overrun() {
+            return Err(ImageError::Other("Truncated WebP lossless data".to_string()));
+        }
+    }
+    Ok(pixels)
+}
+
+enum Transform {
+    Predictor { bits: u32, width: usize, modes: Vec<u32> },
+    CrossColor { bits: u32, width: usize, multipliers: Vec<u32> },
+    SubtractGreen,
+    ColorIndexing { bits: u32, width: usize, palette: Vec<u32> },
+}
+
+/// Add two ARGB pixels channel by channel
+fn add_pixels(a: u32, b: u32) -> u32 {
+    let alpha_green = (a & 0xFF00FF00).wrapping_add(b & 0xFF00FF00);
+    let red_blue = (a & 0x00FF00FF).wrapping_add(b & 0x00FF00FF);
+    (alpha_green & 0xFF00FF00) | (red_blue & 0x00FF00FF)
+}
+
+fn average2(a: u32, b: u32) -> u32 {
+    (((a ^ b) & 0xFEFEFEFE) >> 1) + (a & b)
+}
+
+fn channels(pixel: u32) -> [i32; 4] {
+    [(pixel >> 24) as i32, ((pixel >> 16) & 0xFF) as i32, ((pixel >> 8) & 0xFF) as i32, (pixel & 0xFF) as i32]
+}
+
+fn from_channels(values: [i32; 4]) -> u32 {
+    values.iter().fold(0, |pixel, &value| (pixel << 8) | value.clamp(0, 255) as u32)
+}
+
+fn select(left: u32, top: u32, top_left: u32) -> u32 {
+    let (l, t, tl) = (channels(left), channels(top), channels(top_left));
+    // Manhattan distances of the gradient estimate to the left and top pixels
+    let to_left: i32 = (0..4).map(|i| (t[i] - tl[i]).abs()).sum();
+    let to_top: i32 = (0..4).map(|i| (l[i] - tl[i]).abs()).sum();
+    if to_left < to_top { left } else { top }
+}
+
+fn predict(mode: u32, left: u32, top: u32, top_right: u32, top_left: u32) -> u32 {
+    match mode {
+        1 => left,
+        2 => top,
+        3 => top_right,
+        4 => top_left,
+        5 => average2(average2(left, top_right), top),
+        6 => average2(left, top_left),
+        7 => average2(left, top),
+        8 => average2(top_left, top),
+        9 => average2(top, top_right),
+        10 => average2(average2(left, top_left), average2(top, top_right)),
+        11 => select(left, top, top_left),
+        12 => {
+            let (l, t, tl) = (channels(left), channels(top), channels(top_left));
+            from_channels([0, 1, 2, 3].map(|i| l[i] + t[i] - tl[i]))
+        }
+        13 => {
+            let (average, tl) = (channels(average2(left, top)), channels(top_left));
+            from_channels([0, 1, 2, 3].map(|i| average[i] + (average[i] - tl[i]) / 2))
+        }
+        _ => 0xFF000000,
+    }
+}
+
+/// Signed 3.5 fixed-point color transform term
+fn color_delta(multiplier: u32, color: u32) -> i32 {
+    ((multiplier as u8 as i8 as i32) * (color as u8 as i8 as i32)) >> 5
+}
+
+impl Transform {
+    fn invert(&self, pixels: Vec<u32>, height: usize) -> Vec<u32> {
+        match self {
+            Transform::Predictor { bits, width, modes } => {
+                let mut pixels = pixels;
+                let width = *width;
+                let mode_width = subsample(width, *bits);
+                for y in 0..height {
+                    for x in 0..width {
+                        let pos = y * width + x;
+                        let prediction = match (x, y) {
+                            (0, 0) => 0xFF000000,
+                            (_, 0) => pixels[pos - 1],
+                            (0, _) => pixels[pos - width],
+                            _ => {
+                                let mode = (modes[(y >> bits) * mode_width + (x >> bits)] >> 8) & 0xF;
+                                // The top-right of the last column is the first pixel of the row
+                                predict(mode, pixels[pos - 1], pixels[pos - width], pixels[pos - width + 1], pixels[pos - width - 1])
+                            }
+                        };
+                        pixels[pos] = add_pixels(pixels[pos], prediction);
+                    }
+                }
+                pixels
+            }
+            Transform::CrossColor { bits, width, multipliers } => {
+                let mut pixels = pixels;
+                let multiplier_width = subsample(*width, *bits);
+                for (pos, pixel) in pixels.iter_mut().enumerate() {
+                    let (x, y) = (pos % width, pos / width);
+                    let code = multipliers[(y >> bits) * multiplier_width + (x >> bits)];
+                    let (green_to_red, green_to_blue, red_to_blue) = (code, code >> 8, code >> 16);
+                    let green = *pixel >> 8;
+                    let red = ((*pixel >> 16) as i32 + color_delta(green_to_red, green)) as u32 & 0xFF;
+                    let blue = (*pixel as i32 + color_delta(green_to_blue, green) + color_delta(red_to_blue, red)) as u32 & 0xFF;
+                    *pixel = (*pixel & 0xFF00FF00) | (red << 16) | blue;
+                }
+                pixels
+            }
+            Transform::SubtractGreen => {
+                let mut pixels = pixels;
+                for pixel in &mut pixels {
+                    let green = (*pixel >> 8) & 0xFF;
+                    *pixel = add_pixels(*pixel, (green << 16) | green);
+                }
+                pixels
+            }
+            Transform::ColorIndexing { bits, width, palette } => {
+                let packed_width = subsample(*width, *bits);
+                let index_bits = 8 >> bits;
+                let index_mask = (1 << index_bits) - 1;
+                let mut output = Vec::with_capacity(width * height);
+                for row in pixels.chunks_exact(packed_width) {
+                    for x in 0..*width {
+                        let packed = (row[x >> bits] >> 8) & 0xFF;
+                        let index = (packed >> (index_bits * (x as u32 & ((1 << bits) - 1)))) & index_mask;
+                        // Indices past the palette are transparent black
+                        output.push(palette.get(index as usize).copied().unwrap_or(0));
+                    }
+                }
+                output
+            }
+        }
+    }
+}
+
+/// Decode an image stream: transforms followed by the entropy-coded image
+fn decode_image_stream(reader: &mut BitReader, width: usize, height: usize) -> ImageResult<Vec<u32>> {
+    let mut transforms = Vec::new();
+    let mut seen = [false; 4];
+    let mut coded_width = width;
+    while reader.read(1) == 1 {
+        let kind = reader.read(2) as usize;
+        if std::mem::replace(&mut seen[kind], true) {
+            return Err(corrupt("repeated transform"));
+        }
+        transforms.push(match kind {
+            0 | 1 => {
+                let bits = reader.read(3) + 2;
+                let data = decode_pixels(reader, subsample(coded_width, bits), subsample(height, bits), false)?;
+                if kind == 0 {
+                    Transform::Predictor { bits, width: coded_width, modes: data }
+                } else {
+                    Transform::CrossColor { bits, width: coded_width, multipliers: data }
+                }
+            }
+            2 => Transform::SubtractGreen,
+            _ => {
+                let size = reader.read(8) as usize + 1;
+                let mut palette = decode_pixels(reader, size, 1, false)?;
+                for index in 1..size {
+                    palette[index] = add_pixels(palette[index], palette[index - 1]);
+                }
+                let bits = match size {
+                    0..=2 => 3,
+                    3..=4 => 2,
+                    5..=16 => 1,
+                    _ => 0,
+                };
+                let transform = Transform::ColorIndexing { bits, width: coded_width, palette };
+                coded_width = subsample(coded_width, bits);
+                transform
+            }
+        });
+    }
+
+    let mut pixels = decode_pixels(reader, coded_width, height, true)?;
+    for transform in transforms.iter().rev() {
+        pixels = transform.invert(pixels, height);
+    }
+    Ok(pixels)
+}
+
+/// Read the dimensions and alpha hint of a VP8L bitstream
+pub(crate) fn read_header(data: &[u8]) -> ImageResult<(u32, u32, bool)> {
+    if data.len() < 5 || data[0] != SIGNATURE {
+        return Err(ImageError::UnsupportedFormat("Invalid WebP lossless header".to_string()));
+    }
+    let bits = u32::from_le_bytes([data[1], data[2], data[3], data[4]]);
+    if bits >> 29 != 0 {
+        return Err(ImageError::UnsupportedFormat("Unsupported WebP lossless version".to_string()));
+    }
+    Ok(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1, (bits >> 28) & 1 == 1))
+}
+
+/// Decode a VP8L bitstream to ARGB pixels
+pub(crate) fn decode(data: &[u8]) -> ImageResult<(u32, u32, Vec<u32>)> {
+    let (width, height, _) = read_header(data)?;
+    let mut reader = BitReader::new(&data[5..]);
+    let pixels = decode_image_stream(&mut reader, width as usize, height as usize)?;
+    Ok((width, height, pixels))
+}
+
+/// Decode a headerless VP8L image stream (lossless ALPH chunk) of known size
+pub(crate) fn decode_stream(data: &[u8], width: u32, height: u32) -> ImageResult<Vec<u32>> {
+    decode_image_stream(&mut BitReader::new(data), width as usize, height as usize)
+}
@@ -0,0 +1,1258 @@
+use crate::{ImageError, ImageResult};
+
+/// Key frame start code following the frame tag
+const START_CODE: [u8; 3] = [0x9D, 0x01, 0x2A];
+
+/// Coefficient band of each position in a block (the last entry is a sentinel)
+const BANDS: [usize; 17] = [0, 1, 2, 3, 6, 4, 5, 6, 6, 6, 6, 6, 6, 6, 6, 7, 0];
+
+/// Raster position of each coefficient in zigzag order
+const ZIGZAG: [usize; 16] = [0, 1, 4, 8, 5, 2, 3, 6, 9, 12, 13, 10, 7, 11, 14, 15];
+
+/// Probabilities of the extra bits of DCT_CAT3 to DCT_CAT6 tokens
+const CATEGORY_PROBS: [&[u8]; 4] = [
+    &[173, 148, 140],
+    &[176, 155, 140, 135],
+    &[180, 157, 141, 134, 130],
+    &[254, 254, 243, 230, 196, 177, 153, 140, 133, 130, 129],
+];
+
+// Prediction modes, in the order used by the probability tables
+const B_DC_PRED: u8 = 0;
+const B_TM_PRED: u8 = 1;
+const B_VE_PRED: u8 = 2;
+const B_HE_PRED: u8 = 3;
+const B_RD_PRED: u8 = 4;
+const B_VR_PRED: u8 = 5;
+const B_LD_PRED: u8 = 6;
+const B_VL_PRED: u8 = 7;
+const B_HD_PRED: u8 = 8;
+const B_HU_PRED: u8 = 9;
+
+/// Sub-block mode tree: positive entries are node indices, others negated modes
+const BMODE_TREE: [i8; 18] = [
+    -(B_DC_PRED as i8), 1,
+    -(B_TM_PRED as i8), 2,
+    -(B_VE_PRED as i8), 3,
+    4, 6,
+    -(B_HE_PRED as i8), 5,
+    -(B_RD_PRED as i8), -(B_VR_PRED as i8),
+    -(B_LD_PRED as i8), 7,
+    -(B_VL_PRED as i8), 8,
+    -(B_HD_PRED as i8), -(B_HU_PRED as i8),
+];
+
+/// DC dequantization factor of each quantizer index
+const DC_QUANT: [u8; 128] = [
+    4, 5, 6, 7, 8, 9, 10, 10, 11, 12, 13, 14, 15, 16, 17, 17,
+    18, 19, 20, 20, 21, 21, 22, 22, 23, 23, 24, 25, 25, 26, 27, 28,
+    29, 30, 31, 32, 33, 34, 35, 36, 37, 37, 38, 39, 40, 41, 42, 43,
+    44, 45, 46, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58,
+    59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74,
+    75, 76, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89,
+    91, 93, 95, 96, 98, 100, 101, 102, 104, 106, 108, 110, 112, 114, 116, 118,
+    122, 124, 126, 128, 130, 132, 134, 136, 138, 140, 143, 145, 148, 151, 154, 157,
+];
+
+/// AC dequantization factor of each quantizer index
+const AC_QUANT: [u16; 128] = [
+    4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+    20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35,
+    36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51,
+    52, 53, 54, 55, 56, 57, 58, 60, 62, 64, 66, 68, 70, 72, 74, 76,
+    78, 80, 82, 84, 86, 88, 90, 92, 94, 96, 98, 100, 102, 104, 106, 108,
+    110, 112, 114, 116, 119, 122, 125, 128, 131, 134, 137, 140, 143, 146, 149, 152,
+    155, 158, 161, 164, 167, 170, 173, 177, 181, 185, 189, 193, 197, 201, 205, 209,
+    213, 217, 221, 225, 229, 234, 239, 245, 249, 254, 259, 264, 269, 274, 279, 284,
+];
+
+/// Default coefficient probabilities by block type, band, context and token node
+const COEFF_PROBS: [[[[u8; 11]; 3]; 8]; 4] = [
+    [
+        [[128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128], [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128], [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128]],
+        [[253, 136, 254, 255, 228, 219, 128, 128, 128, 128, 128], [189, 129, 242, 255, 227, 213, 255, 219, 128, 128, 128], [106, 126, 227, 252, 214, 209, 255, 255, 128, 128, 128]],
+        [[1, 98, 248, 255, 236, 226, 255, 255, 128, 128, 128], [181, 133, 238, 254, 221, 234, 255, 154, 128, 128, 128], [78, 134, 202, 247, 198, 180, 255, 219, 128, 128, 128]],
+        [[1, 185, 249, 255, 243, 255, 128, 128, 128, 128, 128], [184, 150, 247, 255, 236, 224, 128, 128, 128, 128, 128], [77, 110, 216, 255, 236, 230, 128, 128, 128, 128, 128]],
+        [[1, 101, 251, 255, 241, 255, 128, 128, 128, 128, 128], [170, 139, 241, 252, 236, 209, 255, 255, 128, 128, 128], [37, 116, 196, 243, 228, 255, 255, 255, 128, 128, 128]],
+        [[1, 204, 254, 255, 245, 255, 128, 128, 128, 128, 128], [207, 160, 250, 255, 238, 128, 128, 128, 128, 128, 128], [102, 103, 231, 255, 211, 171, 128, 128, 128, 128, 128]],
+        [[1, 152, 252, 255, 240, 255, 128, 128, 128, 128, 128], [177, 135, 243, 255, 234, 225, 128, 128, 128, 128, 128], [80, 129, 211, 255, 194, 224, 128, 128, 128, 128, 128]],
+        [[1, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128], [246, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128], [255, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128]],
+    ],
+    [
+        [[198, 35, 237, 223, 193, 187, 162, 160, 145, 155, 62], [131, 45, 198, 221, 172, 176, 220, 157, 252, 221, 1], [68, 47, 146, 208, 149, 167, 221, 162, 255, 223, 128]],
+        [[1, 149, 241, 255, 221, 224, 255, 255, 128, 128, 128], [184, 141, 234, 253, 222, 220, 255, 199, 128, 128, 128], [81, 99, 181, 242, 176, 190, 249, 202, 255, 255, 128]],
+        [[1, 129, 232, 253, 214, 197, 242, 196, 255, 255, 128], [99, 121, 210, 250, 201, 198, 255, 202, 128, 128, 128], [23, 91, 163, 242, 170, 187, 247, 210, 255, 255, 128]],
+        [[1, 200, 246, 255, 234, 255, 128, 128, 128, 128, 128], [109, 178, 241, 255, 231, 245, 255, 255, 128, 128, 128], [44, 130, 201, 253, 205, 192, 255, 255, 128, 128, 128]],
+        [[1, 132, 239, 251, 219, 209, 255, 165, 128, 128, 128], [94, 136, 225, 251, 218, 190, 255, 255, 128, 128, 128], [22, 100, 174, 245, 186, 161, 255, 199, 128, 128, 128]],
+        [[1, 182, 249, 255, 232, 235, 128, 128, 128, 128, 128], [124, 143, 241, 255, 227, 234, 128, 128, 128, 128, 128], [35, 77, 181, 251, 193, 211, 255, 205, 128, 128, 128]],
+        [[1, 157, 247, 255, 236, 231, 255, 255, 128, 128, 128], [121, 141, 235, 255, 225, 227, 255, 255, 128, 128, 128], [45, 99, 188, 251, 195, 217, 255, 224, 128, 128, 128]],
+        [[1, 1, 251, 255, 213, 255, 128, 128, 128, 128, 128], [203, 1, 248, 255, 255, 128, 128, 128, 128, 128, 128], [137, 1, 177, 255, 224, 255, 128, 128, 128, 128, 128]],
+    ],
+    [
+        [[253, 9, 248, 251, 207, 208, 255, 192, 128, 128, 128], [175, 13, 224, 243, 193, 185, 249, 198, 255, 255, 128], [73, 17, 171, 221, 161, 179, 236, 167, 255, 234, 128]],
+        [[1, 95, 247, 253, 212, 183, 255, 255, 128, 128, 128], [239, 90, 244, 250, 211, 209, 255, 255, 128, 128, 128], [155, 77, 195, 248, 188, 195, 255, 255, 128, 128, 128]],
+        [[1, 24, 239, 251, 218, 219, 255, 205, 128, 128, 128], [201, 51, 219, 255, 196, 186, 128, 128, 128, 128, 128], [69, 46, 190, 239, 201, 218, 255, 228, 128, 128, 128]],
+        [[1, 191, 251, 255, 255, 128, 128, 128, 128, 128, 128], [223, 165, 249, 255, 213, 255, 128, 128, 128, 128, 128], [141, 124, 248, 255, 255, 128, 128, 128, 128, 128, 128]],
+        [[1, 16, 248, 255, 255, 128, 128, 128, 128, 128, 128], [190, 36, 230, 255, 236, 255, 128, 128, 128, 128, 128], [149, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128]],
+        [[1, 226, 255, 128, 128, 128, 128, 128, 128, 128, 128], [247, 192, 255, 128, 128, 128, 128, 128, 128, 128, 128], [240, 128, 255, 128, 128, 128, 128, 128, 128, 128, 128]],
+        [[1, 134, 252, 255, 255, 128, 128, 128, 128, 128, 128], [213, 62, 250, 255, 255, 128, 128, 128, 128, 128, 128], [55, 93, 255, 128, 128, 128, 128, 128, 128, 128, 128]],
+        [[128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128], [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128], [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128]],
+    ],
+    [
+        [[202, 24, 213, 235, 186, 191, 220, 160, 240, 175, 255], [126, 38, 182, 232, 169, 184, 228, 174, 255, 187, 128], [61, 46, 138, 219, 151, 178, 240, 170, 255, 216, 128]],
+        [[1, 112, 230, 250, 199, 191, 247, 159, 255, 255, 128], [166, 109, 228, 252, 211, 215, 255, 174, 128, 128, 128], [39, 77, 162, 232, 172, 180, 245, 178, 255, 255, 128]],
+        [[1, 52, 220, 246, 198, 199, 249, 220, 255, 255, 128], [124, 74, 191, 243, 183, 193, 250, 221, 255, 255, 128], [24, 71, 130, 219, 154, 170, 243, 182, 255, 255, 128]],
+        [[1, 182, 225, 249, 219, 240, 255, 224, 128, 128, 128], [149, 150, 226, 252, 216, 205, 255, 171, 128, 128, 128], [28, 108, 170, 242, 183, 194, 254, 223, 255, 255, 128]],
+        [[1, 81, 230, 252, 204, 203, 255, 192, 128, 128, 128], [123, 102, 209, 247, 188, 196, 255, 233, 128, 128, 128], [20, 95, 153, 243, 164, 173, 255, 203, 128, 128, 128]],
+        [[1, 222, 248, 255, 216, 213, 128, 128, 128, 128, 128], [168, 175, 246, 252, 235, 205, 255, 255, 128, 128, 128], [47, 116, 215, 255, 211, 212, 255, 255, 128, 128, 128]],
+        [[1, 121, 236, 253, 212, 214, 255, 255, 128, 128, 128], [141, 84, 213, 252, 201, 202, 255, 219, 128, 128, 128], [42, 80, 160, 240, 162, 185, 255, 205, 128, 128, 128]],
+        [[1, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128], [244, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128], [238, 1, 255, 128, 128, 128, 128, 128, 128, 128, 128]],
+    ],
+];
+
+/// Probabilities of the frame header updating each coefficient probability
+const COEFF_UPDATE_PROBS: [[[[u8; 11]; 3]; 8]; 4] = [
+    [
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[176, 246, 255, 255, 255, 255, 255, 255, 255, 255, 255], [223, 241, 252, 255, 255, 255, 255, 255, 255, 255, 255], [249, 253, 253, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 244, 252, 255, 255, 255, 255, 255, 255, 255, 255], [234, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [253, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 246, 254, 255, 255, 255, 255, 255, 255, 255, 255], [239, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 254, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 248, 254, 255, 255, 255, 255, 255, 255, 255, 255], [251, 255, 254, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [251, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 254, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 253, 255, 254, 255, 255, 255, 255, 255, 255], [250, 255, 254, 255, 254, 255, 255, 255, 255, 255, 255], [254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+    ],
+    [
+        [[217, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [225, 252, 241, 253, 255, 255, 254, 255, 255, 255, 255], [234, 250, 241, 250, 253, 255, 253, 254, 255, 255, 255]],
+        [[255, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255], [223, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [238, 253, 254, 254, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 248, 254, 255, 255, 255, 255, 255, 255, 255, 255], [249, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 253, 255, 255, 255, 255, 255, 255, 255, 255, 255], [247, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [252, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [253, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 253, 255, 255, 255, 255, 255, 255, 255, 255], [250, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+    ],
+    [
+        [[186, 251, 250, 255, 255, 255, 255, 255, 255, 255, 255], [234, 251, 244, 254, 255, 255, 255, 255, 255, 255, 255], [251, 251, 243, 253, 254, 255, 254, 255, 255, 255, 255]],
+        [[255, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [236, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [251, 253, 253, 254, 254, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [254, 254, 254, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255], [254, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+    ],
+    [
+        [[248, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [250, 254, 252, 254, 255, 255, 255, 255, 255, 255, 255], [248, 254, 249, 253, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 253, 253, 255, 255, 255, 255, 255, 255, 255, 255], [246, 253, 253, 255, 255, 255, 255, 255, 255, 255, 255], [252, 254, 251, 254, 254, 255, 255, 255, 255, 255, 255]],
+        [[255, 254, 252, 255, 255, 255, 255, 255, 255, 255, 255], [248, 254, 253, 255, 255, 255, 255, 255, 255, 255, 255], [253, 255, 254, 254, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 251, 254, 255, 255, 255, 255, 255, 255, 255, 255], [245, 251, 254, 255, 255, 255, 255, 255, 255, 255, 255], [253, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 251, 253, 255, 255, 255, 255, 255, 255, 255, 255], [252, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255], [255, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 252, 255, 255, 255, 255, 255, 255, 255, 255, 255], [249, 255, 254, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 254, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 253, 255, 255, 255, 255, 255, 255, 255, 255], [250, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+        [[255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255], [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255]],
+    ],
+];
+
+/// Sub-block mode probabilities by the modes of the blocks above and to the left
+const BMODE_PROBS: [[[u8; 9]; 10]; 10] = [
+    [
+        [231, 120, 48, 89, 115, 113, 120, 152, 112],
+        [152, 179, 64, 126, 170, 118, 46, 70, 95],
+        [175, 69, 143, 80, 85, 82, 72, 155, 103],
+        [56, 58, 10, 171, 218, 189, 17, 13, 152],
+        [114, 26, 17, 163, 44, 195, 21, 10, 173],
+        [121, 24, 80, 195, 26, 62, 44, 64, 85],
+        [144, 71, 10, 38, 171, 213, 144, 34, 26],
+        [170, 46, 55, 19, 136, 160, 33, 206, 71],
+        [63, 20, 8, 114, 114, 208, 12, 9, 226],
+        [81, 40, 11, 96, 182, 84, 29, 16, 36],
+    ],
+    [
+        [134, 183, 89, 137, 98, 101, 106, 165, 148],
+        [72, 187, 100, 130, 157, 111, 32, 75, 80],
+        [66, 102, 167, 99, 74, 62, 40, 234, 128],
+        [41, 53, 9, 178, 241, 141, 26, 8, 107],
+        [74, 43, 26, 146, 73, 166, 49, 23, 157],
+        [65, 38, 105, 160, 51, 52, 31, 115, 128],
+        [104, 79, 12, 27, 217, 255, 87, 17, 7],
+        [87, 68, 71, 44, 114, 51, 15, 186, 23],
+        [47, 41, 14, 110, 182, 183, 21, 17, 194],
+        [66, 45, 25, 102, 197, 189, 23, 18, 22],
+    ],
+    [
+        [88, 88, 147, 150, 42, 46, 45, 196, 205],
+        [43, 97, 183, 117, 85, 38, 35, 179, 61],
+        [39, 53, 200, 87, 26, 21, 43, 232, 171],
+        [56, 34, 51, 104, 114, 102, 29, 93, 77],
+        [39, 28, 85, 171, 58, 165, 90, 98, 64],
+        [34, 22, 116, 206, 23, 34, 43, 166, 73],
+        [107, 54, 32, 26, 51, 1, 81, 43, 31],
+        [68, 25, 106, 22, 64, 171, 36, 225, 114],
+        [34, 19, 21, 102, 132, 188, 16, 76, 124],
+        [62, 18, 78, 95, 85, 57, 50, 48, 51],
+    ],
+    [
+        [193, 101, 35, 159, 215, 111, 89, 46, 111],
+        [60, 148, 31, 172, 219, 228, 21, 18, 111],
+        [112, 113, 77, 85, 179, 255, 38, 120, 114],
+        [40, 42, 1, 196, 245, 209, 10, 25, 109],
+        [88, 43, 29, 140, 166, 213, 37, 43, 154],
+        [61, 63, 30, 155, 67, 45, 68, 1, 209],
+        [100, 80, 8, 43, 154, 1, 51, 26, 71],
+        [142, 78, 78, 16, 255, 128, 34, 197, 171],
+        [41, 40, 5, 102, 211, 183, 4, 1, 221],
+        [51, 50, 17, 168, 209, 192, 23, 25, 82],
+    ],
+    [
+        [138, 31, 36, 171, 27, 166, 38, 44, 229],
+        [67, 87, 58, 169, 82, 115, 26, 59, 179],
+        [63, 59, 90, 180, 59, 166, 93, 73, 154],
+        [40, 40, 21, 116, 143, 209, 34, 39, 175],
+        [47, 15, 16, 183, 34, 223, 49, 45, 183],
+        [46, 17, 33, 183, 6, 98, 15, 32, 183],
+        [57, 46, 22, 24, 128, 1, 54, 17, 37],
+        [65, 32, 73, 115, 28, 128, 23, 128, 205],
+        [40, 3, 9, 115, 51, 192, 18, 6, 223],
+        [87, 37, 9, 115, 59, 77, 64, 21, 47],
+    ],
+    [
+        [104, 55, 44, 218, 9, 54, 53, 130, 226],
+        [64, 90, 70, 205, 40, 41, 23, 26, 57],
+        [54, 57, 112, 184, 5, 41, 38, 166, 213],
+        [30, 34, 26, 133, 152, 116, 10, 32, 134],
+        [39, 19, 53, 221, 26, 114, 32, 73, 255],
+        [31, 9, 65, 234, 2, 15, 1, 118, 73],
+        [75, 32, 12, 51, 192, 255, 160, 43, 51],
+        [88, 31, 35, 67, 102, 85, 55, 186, 85],
+        [56, 21, 23, 111, 59, 205, 45, 37, 192],
+        [55, 38, 70, 124, 73, 102, 1, 34, 98],
+    ],
+    [
+        [125, 98, 42, 88, 104, 85, 117, 175, 82],
+        [95, 84, 53, 89, 128, 100, 113, 101, 45],
+        [75, 79, 123, 47, 51, 128, 81, 171, 1],
+        [57, 17, 5, 71, 102, 57, 53, 41, 49],
+        [38, 33, 13, 121, 57, 73, 26, 1, 85],
+        [41, 10, 67, 138, 77, 110, 90, 47, 114],
+        [115, 21, 2, 10, 102, 255, 166, 23, 6],
+        [101, 29, 16, 10, 85, 128, 101, 196, 26],
+        [57, 18, 10, 102, 102, 213, 34, 20, 43],
+        [117, 20, 15, 36, 163, 128, 68, 1, 26],
+    ],
+    [
+        [102, 61, 71, 37, 34, 53, 31, 243, 192],
+        [69, 60, 71, 38, 73, 119, 28, 222, 37],
+        [68, 45, 128, 34, 1, 47, 11, 245, 171],
+        [62, 17, 19, 70, 146, 85, 55, 62, 70],
+        [37, 43, 37, 154, 100, 163, 85, 160, 1],
+        [63, 9, 92, 136, 28, 64, 32, 201, 85],
+        [75, 15, 9, 9, 64, 255, 184, 119, 16],
+        [86, 6, 28, 5, 64, 255, 25, 248, 1],
+        [56, 8, 17, 132, 137, 255, 55, 116, 128],
+        [58, 15, 20, 82, 135, 57, 26, 121, 40],
+    ],
+    [
+        [164, 50, 31, 137, 154, 133, 25, 35, 218],
+        [51, 103, 44, 131, 131, 123, 31, 6, 158],
+        [86, 40, 64, 135, 148, 224, 45, 183, 128],
+        [22, 26, 17, 131, 240, 154, 14, 1, 209],
+        [45, 16, 21, 91, 64, 222, 7, 1, 197],
+        [56, 21, 39, 155, 60, 138, 23, 102, 213],
+        [83, 12, 13, 54, 192, 255, 68, 47, 28],
+        [85, 26, 85, 85, 128, 128, 32, 146, 171],
+        [18, 11, 7, 63, 144, 171, 4, 4, 246],
+        [35, 27, 10, 146, 174, 171, 12, 26, 128],
+    ],
+    [
+        [190, 80, 35, 99, 180, 80, 126, 54, 45],
+        [85, 126, 47, 87, 176, 51, 41, 20, 32],
+        [101, 75, 128, 139, 118, 146, 116, 128, 85],
+        [56, 41, 15, 176, 236, 85, 37, 9, 62],
+        [71, 30, 17, 119, 118, 255, 17, 18, 138],
+        [101, 38, 60, 138, 55, 70, 43, 26, 142],
+        [146, 36, 19, 30, 171, 255, 97, 27, 20],
+        [138, 45, 61, 62, 219, 1, 81, 188, 64],
+        [32, 41, 20, 117, 151, 142, 20, 21, 163],
+        [112, 19, 12, 61, 195, 128, 48, 4, 24],
+    ],
+];
+
+const NUM_SEGMENTS: usize = 4;
+
+fn truncated() -> ImageError {
+    ImageError::Other("Truncated WebP lossy data".to_string())
+}
+
+/// Boolean entropy decoder (RFC 6386 section 7); bytes past the end read as zero
+struct BoolDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    value: u32,
+    range: u32,
+    bit_count: u32,
+}
+
+impl<'a> BoolDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        let mut decoder = Self { data, pos: 0, value: 0, range: 255, bit_count: 0 };
+        decoder.value = (decoder.next_byte() << 8) | decoder.next_byte();
+        decoder
+    }
+
+    fn next_byte(&mut self) -> u32 {
+        let byte = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        byte as u32
+    }
+
+    /// Read a bit that is 0 with probability `probability / 256`
+    fn read(&mut self, probability: u8) -> bool {
+        let split = 1 + (((self.range - 1) * probability as u32) >> 8);
+        let big_split = split << 8;
+        let bit = if self.value >= big_split {
+            self.range -= split;
+            self.value -= big_split;
+            true
+        } else {
+            self.range = split;
+            false
+        };
+        while self.range < 128 {
+            self.value <<= 1;
+            self.range <<= 1;
+            self.bit_count += 1;
+            if self.bit_count == 8 {
+                self.bit_count = 0;
+                self.value |= self.next_byte();
+            }
+        }
+        bit
+    }
+
+    fn read_flag(&mut self) -> bool {
+        self.read(128)
+    }
+
+    fn read_literal(&mut self, bits: u32) -> u32 {
+        (0..bits).fold(0, |value, _| (value << 1) | self.read_flag() as u32)
+    }
+
+    fn read_signed(&mut self, bits: u32) -> i32 {
+        let value = self.read_literal(bits) as i32;
+        if self.read_flag() { -value } else { value }
+    }
+
+    /// Read a flag-prefixed signed value, 0 when the flag is clear
+    fn read_optional_signed(&mut self, bits: u32) -> i32 {
+        if self.read_flag() { self.read_signed(bits) } else { 0 }
+    }
+
+    /// Consumed more bytes than available (with the two bytes of lookahead)
+    fn overrun(&self) -> bool {
+        self.pos > self.data.len() + 2
+    }
+}
+
+/// Dequantization factors (DC, AC) of one segment
+#[derive(Clone, Copy, Default)]
+struct Quantizer {
+    y1: [i32; 2],
+    y2: [i32; 2],
+    uv: [i32; 2],
+}
+
+/// Loop filter parameters of one segment and macroblock type
+#[derive(Clone, Copy, Default)]
+struct FilterStrength {
+    /// Edge limit, 0 disables filtering
+    limit: i32,
+    interior_limit: i32,
+    hev_threshold: i32,
+}
+
+/// Loop filter parameters of one macroblock
+#[derive(Clone, Copy)]
+struct MacroblockFilter {
+    strength: FilterStrength,
+    /// Filter inner edges (4x4 prediction or non-zero coefficients)
+    inner: bool,
+}
+
+/// Whether the neighbouring blocks had non-zero coefficients
+#[derive(Clone, Copy, Default)]
+struct NonZero {
+    y: [bool; 4],
+    u: [bool; 2],
+    v: [bool; 2],
+    dc: bool,
+}
+
+/// Decoded YUV 4:2:0 planes, padded to whole macroblocks
+pub(crate) struct Frame {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    y: Vec<u8>,
+    u: Vec<u8>,
+    v: Vec<u8>,
+    y_stride: usize,
+    uv_stride: usize,
+}
+
+/// Read the dimensions of a VP8 key frame
+pub(crate) fn read_header(data: &[u8]) -> ImageResult<(u32, u32)> {
+    let invalid = |message: &str| ImageError::UnsupportedFormat(format!("Invalid WebP lossy header: {}", message));
+    if data.len() < 10 {
+        return Err(invalid("truncated frame header"));
+    }
+    if data[0] & 1 != 0 {
+        return Err(invalid("not a key frame"));
+    }
+    if data[3..6] != START_CODE {
+        return Err(invalid("missing start code"));
+    }
+    let width = u16::from_le_bytes([data[6], data[7]]) as u32 & 0x3FFF;
+    let height = u16::from_le_bytes([data[8], data[9]]) as u32 & 0x3FFF;
+    if width == 0 || height == 0 {
+        return Err(ImageError::InvalidDimensions { width, height });
+    }
+    Ok((width, height))
+}
+
+/// Read the coefficients of one block in zigzag order, starting at `first`
+///
+/// Returns the position after the last non-zero coefficient.
+fn read_coefficients(
+    decoder: &mut BoolDecoder,
+    probs: &[[[u8; 11]; 3]; 8],
+    context: usize,
+    dequant: [i32; 2],
+    first: usize,
+    output: &mut [i16],
+) -> usize {
+    let mut n = first;
+    let mut p = &probs[BANDS[n]][context];
+    while n < 16 {
+        if !decoder.read(p[0]) {
+            return n;
+        }
+        while !decoder.read(p[1]) {
+            n += 1;
+            if n == 16 {
+                return 16;
+            }
+            p = &probs[BANDS[n]][0];
+        }
+
+        let (value, next_context) = if !decoder.read(p[2]) {
+            (1, 1)
+        } else {
+            (read_large_value(decoder, p), 2)
+        };
+        let value = if decoder.read_flag() { -value } else { value };
+        output[ZIGZAG[n]] = (value * dequant[(n > 0) as usize]) as i16;
+        n += 1;
+        p = &probs[BANDS[n]][next_context];
+    }
+    16
+}
+
+/// Read a token value of 2 or more (DCT_CAT tokens include extra bits)
+fn read_large_value(decoder: &mut BoolDecoder, p: &[u8; 11]) -> i32 {
+    if !decoder.read(p[3]) {
+        if !decoder.read(p[4]) {
+            return 2;
+        }
+        return 3 + decoder.read(p[5]) as i32;
+    }
+    if !decoder.read(p[6]) {
+        if !decoder.read(p[7]) {
+            return 5 + decoder.read(159) as i32;
+        }
+        return 7 + 2 * decoder.read(165) as i32 + decoder.read(145) as i32;
+    }
+    let high = decoder.read(p[8]) as usize;
+    let low = decoder.read(p[9 + high]) as usize;
+    let category = 2 * high + low;
+    let extra = CATEGORY_PROBS[category].iter().fold(0, |value, &prob| 2 * value + decoder.read(prob) as i32);
+    extra + 3 + (8 << category)
+}
+
+/// Inverse Walsh-Hadamard transform of the Y2 block into the DC of each luma block
+fn inverse_wht(input: &[i16; 16], coefficients: &mut [i16]) {
+    let mut temp = [0i32; 16];
+    for i in 0..4 {
+        let a0 = input[i] as i32 + input[12 + i] as i32;
+        let a1 = input[4 + i] as i32 + input[8 + i] as i32;
+        let a2 = input[4 + i] as i32 - input[8 + i] as i32;
+        let a3 = input[i] as i32 - input[12 + i] as i32;
+        temp[i] = a0 + a1;
+        temp[8 + i] = a0 - a1;
+        temp[4 + i] = a3 + a2;
+        temp[12 + i] = a3 - a2;
+    }
+    for i in 0..4 {
+        let dc = temp[i * 4] + 3;
+        let a0 = dc + temp[3 + i * 4];
+        let a1 = temp[1 + i * 4] + temp[2 + i * 4];
+        let a2 = temp[1 + i * 4] - temp[2 + i * 4];
+        let a3 = dc - temp[3 + i * 4];
+        for (block, value) in [(a0 + a1) >> 3, (a3 + a2) >> 3, (a0 - a1) >> 3, (a3 - a2) >> 3].into_iter().enumerate() {
+            coefficients[(i * 4 + block) * 16] = value as i16;
+        }
+    }
+}
+
+// Second-pass inputs of corrupt files exceed what fits an i32 product
+fn mul1(a: i32) -> i32 {
+    ((a as i64 * 20091) >> 16) as i32 + a
+}
+
+fn mul2(a: i32) -> i32 {
+    ((a as i64 * 35468) >> 16) as i32
+}
+
+fn clip_pixel(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+/// Inverse DCT of a 4x4 block added to the prediction at `pos`
+fn add_inverse_dct(input: &[i16], buffer: &mut [u8], pos: usize, stride: usize) {
+    if input[..16].iter().all(|&value| value == 0) {
+        return;
+    }
+    let mut temp = [0i32; 16];
+    for i in 0..4 {
+        let a = input[i] as i32 + input[8 + i] as i32;
+        let b = input[i] as i32 - input[8 + i] as i32;
+        let c = mul2(input[4 + i] as i32) - mul1(input[12 + i] as i32);
+        let d = mul1(input[4 + i] as i32) + mul2(input[12 + i] as i32);
+        temp[i * 4] = a + d;
+        temp[i * 4 + 1] = b + c;
+        temp[i * 4 + 2] = b - c;
+        temp[i * 4 + 3] = a - d;
+    }
+    for i in 0..4 {
+        let dc = temp[i] + 4;
+        let a = dc + temp[8 + i];
+        let b = dc - temp[8 + i];
+        let c = mul2(temp[4 + i]) - mul1(temp[12 + i]);
+        let d = mul1(temp[4 + i]) + mul2(temp[12 + i]);
+        let row = pos + i * stride;
+        for (x, value) in [a + d, b + c, b - c, a - d].into_iter().enumerate() {
+            buffer[row + x] = clip_pixel(buffer[row + x] as i32 + (value >> 3));
+        }
+    }
+}
+
+/// Prediction of a 16x16 luma or 8x8 chroma block from its top row and left column
+fn predict_block(buffer: &mut [u8], stride: usize, pos: usize, size: usize, mode: u8, has_top: bool, has_left: bool) {
+    let top = pos - stride;
+    match mode {
+        B_DC_PRED => {
+            let shift = size.trailing_zeros();
+            let top_sum: u32 = (0..size).map(|x| buffer[top + x] as u32).sum();
+            let left_sum: u32 = (0..size).map(|y| buffer[pos + y * stride - 1] as u32).sum();
+            let value = match (has_top, has_left) {
+                (true, true) => (top_sum + left_sum + size as u32) >> (shift + 1),
+                (true, false) => (top_sum + size as u32 / 2) >> shift,
+                (false, true) => (left_sum + size as u32 / 2) >> shift,
+                (false, false) => 128,
+            };
+            for y in 0..size {
+                buffer[pos + y * stride..pos + y * stride + size].fill(value as u8);
+            }
+        }
+        B_TM_PRED => {
+            let top_left = buffer[top - 1] as i32;
+            for y in 0..size {
+                let left = buffer[pos + y * stride - 1] as i32;
+                for x in 0..size {
+                    buffer[pos + y * stride + x] = clip_pixel(buffer[top + x] as i32 + left - top_left);
+                }
+            }
+        }
+        B_VE_PRED => {
+            for y in 0..size {
+                buffer.copy_within(top..top + size, pos + y * stride);
+            }
+        }
+        _ => {
+            for y in 0..size {
+                let left = buffer[pos + y * stride - 1];
+                buffer[pos + y * stride..pos + y * stride + size].fill(left);
+            }
+        }
+    }
+}
+
+fn avg2(a: u8, b: u8) -> u8 {
+    ((a as u32 + b as u32 + 1) >> 1) as u8
+}
+
+fn avg3(a: u8, b: u8, c: u8) -> u8 {
+    ((a as u32 + 2 * b as u32 + c as u32 + 2) >> 2) as u8
+}
+
+/// Prediction of a 4x4 luma sub-block
+fn predict_subblock(buffer: &mut [u8], stride: usize, pos: usize, mode: u8) {
+    let top = pos - stride;
+    // Edge pixels: A-H above (E-H above-right), I-L to the left, X above-left
+    let [a, b, c, d, e, f, g, h] = [0, 1, 2, 3, 4, 5, 6, 7].map(|x| buffer[top + x]);
+    let [i, j, k, l] = [0, 1, 2, 3].map(|y| buffer[pos + y * stride - 1]);
+    let x = buffer[top - 1];
+
+    let mut block = [[0u8; 4]; 4];
+    match mode {
+        B_DC_PRED => {
+            let sum: u32 = [a, b, c, d, i, j, k, l].iter().map(|&value| value as u32).sum();
+            block = [[((sum + 4) >> 3) as u8; 4]; 4];
+        }
+        B_TM_PRED => {
+            for (row, &left) in block.iter_mut().zip(&[i, j, k, l]) {
+                for (value, &above) in row.iter_mut().zip(&[a, b, c, d]) {
+                    *value = clip_pixel(above as i32 + left as i32 - x as i32);
+                }
+            }
+        }
+        B_VE_PRED => {
+            block = [[avg3(x, a, b), avg3(a, b, c), avg3(b, c, d), avg3(c, d, e)]; 4];
+        }
+        B_HE_PRED => {
+            block = [[avg3(x, i, j); 4], [avg3(i, j, k); 4], [avg3(j, k, l); 4], [avg3(k, l, l); 4]];
+        }
+        B_RD_PRED => {
+            let edge = [avg3(j, k, l), avg3(i, j, k), avg3(x, i, j), avg3(a, x, i), avg3(b, a, x), avg3(c, b, a), avg3(d, c, b)];
+            for (y, row) in block.iter_mut().enumerate() {
+                for (column, value) in row.iter_mut().enumerate() {
+                    *value = edge[3 - y + column];
+                }
+            }
+        }
+        B_LD_PRED => {
+            let edge = [avg3(a, b, c), avg3(b, c, d), avg3(c, d, e), avg3(d, e, f), avg3(e, f, g), avg3(f, g, h), avg3(g, h, h)];
+            for (y, row) in block.iter_mut().enumerate() {
+                for (column, value) in row.iter_mut().enumerate() {
+                    *value = edge[y + column];
+                }
+            }
+        }
+        B_VR_PRED => {
+            block = [
+                [avg2(x, a), avg2(a, b), avg2(b, c), avg2(c, d)],
+                [avg3(i, x, a), avg3(x, a, b), avg3(a, b, c), avg3(b, c, d)],
+                [avg3(j, i, x), avg2(x, a), avg2(a, b), avg2(b, c)],
+                [avg3(k, j, i), avg3(i, x, a), avg3(x, a, b), avg3(a, b, c)],
+            ];
+        }
+        B_VL_PRED => {
+            block = [
+                [avg2(a, b), avg2(b, c), avg2(c, d), avg2(d, e)],
+                [avg3(a, b, c), avg3(b, c, d), avg3(c, d, e), avg3(d, e, f)],
+                [avg2(b, c), avg2(c, d), avg2(d, e), avg3(e, f, g)],
+                [avg3(b, c, d), avg3(c, d, e), avg3(d, e, f), avg3(f, g, h)],
+            ];
+        }
+        B_HD_PRED => {
+            block = [
+                [avg2(i, x), avg3(i, x, a), avg3(x, a, b), avg3(a, b, c)],
+                [avg2(j, i), avg3(j, i, x), avg2(i, x), avg3(i, x, a)],
+                [avg2(k, j), avg3(k, j, i), avg2(j, i), avg3(j, i, x)],
+                [avg2(l, k), avg3(l, k, j), avg2(k, j), avg3(k, j, i)],
+            ];
+        }
+        _ => {
+            block = [
+                [avg2(i, j), avg3(i, j, k), avg2(j, k), avg3(j, k, l)],
+                [avg2(j, k), avg3(j, k, l), avg2(k, l), avg3(k, l, l)],
+                [avg2(k, l), avg3(k, l, l), l, l],
+                [l, l, l, l],
+            ];
+        }
+    }
+    for (y, row) in block.iter().enumerate() {
+        buffer[pos + y * stride..pos + y * stride + 4].copy_from_slice(row);
+    }
+}
+
+/// Loop filter check of the 4 pixels across an edge
+fn needs_filter(p: &[u8], pos: usize, step: usize, threshold: i32) -> bool {
+    let (p1, p0, q0, q1) = (p[pos - 2 * step] as i32, p[pos - step] as i32, p[pos] as i32, p[pos + step] as i32);
+    4 * (p0 - q0).abs() + (p1 - q1).abs() <= threshold
+}
+
+/// Normal loop filter check, including the interior differences
+fn needs_filter_normal(p: &[u8], pos: usize, step: usize, threshold: i32, interior: i32) -> bool {
+    let at = |offset: isize| p[(pos as isize + offset * step as isize) as usize] as i32;
+    let (p3, p2, p1, p0, q0, q1, q2, q3) = (at(-4), at(-3), at(-2), at(-1), at(0), at(1), at(2), at(3));
+    4 * (p0 - q0).abs() + (p1 - q1).abs() <= threshold
+        && [p3 - p2, p2 - p1, p1 - p0, q3 - q2, q2 - q1, q1 - q0].iter().all(|difference| difference.abs() <= interior)
+}
+
+/// High edge variance check
+fn high_variance(p: &[u8], pos: usize, step: usize, threshold: i32) -> bool {
+    let (p1, p0, q0, q1) = (p[pos - 2 * step] as i32, p[pos - step] as i32, p[pos] as i32, p[pos + step] as i32);
+    (p1 - p0).abs() > threshold || (q1 - q0).abs() > threshold
+}
+
+fn clamp_signed(value: i32, limit: i32) -> i32 {
+    value.clamp(-limit, limit - 1)
+}
+
+/// Adjust the two pixels closest to the edge
+fn filter2(p: &mut [u8], pos: usize, step: usize) {
+    let (p1, p0, q0, q1) = (p[pos - 2 * step] as i32, p[pos - step] as i32, p[pos] as i32, p[pos + step] as i32);
+    let a = 3 * (q0 - p0) + clamp_signed(p1 - q1, 128);
+    let a1 = clamp_signed((a + 4) >> 3, 16);
+    let a2 = clamp_signed((a + 3) >> 3, 16);
+    p[pos - step] = clip_pixel(p0 + a2);
+    p[pos] = clip_pixel(q0 - a1);
+}
+
+/// Adjust four pixels across an inner edge
+fn filter4(p: &mut [u8], pos: usize, step: usize) {
+    let (p1, p0, q0, q1) = (p[pos - 2 * step] as i32, p[pos - step] as i32, p[pos] as i32, p[pos + step] as i32);
+    let a = 3 * (q0 - p0);
+    let a1 = clamp_signed((a + 4) >> 3, 16);
+    let a2 = clamp_signed((a + 3) >> 3, 16);
+    let a3 = (a1 + 1) >> 1;
+    p[pos - 2 * step] = clip_pixel(p1 + a3);
+    p[pos - step] = clip_pixel(p0 + a2);
+    p[pos] = clip_pixel(q0 - a1);
+    p[pos + step] = clip_pixel(q1 - a3);
+}
+
+/// Adjust six pixels across a macroblock edge
+fn filter6(p: &mut [u8], pos: usize, step: usize) {
+    let (p2, p1, p0) = (p[pos - 3 * step] as i32, p[pos - 2 * step] as i32, p[pos - step] as i32);
+    let (q0, q1, q2) = (p[pos] as i32, p[pos + step] as i32, p[pos + 2 * step] as i32);
+    let a = clamp_signed(3 * (q0 - p0) + clamp_signed(p1 - q1, 128), 128);
+    let a1 = (27 * a + 63) >> 7;
+    let a2 = (18 * a + 63) >> 7;
+    let a3 = (9 * a + 63) >> 7;
+    p[pos - 3 * step] = clip_pixel(p2 + a3);
+    p[pos - 2 * step] = clip_pixel(p1 + a2);
+    p[pos - step] = clip_pixel(p0 + a1);
+    p[pos] = clip_pixel(q0 - a1);
+    p[pos + step] = clip_pixel(q1 - a2);
+    p[pos + 2 * step] = clip_pixel(q2 - a3);
+}
+
+/// Filter `count` edge positions starting at `pos`
+///
+/// `step` crosses the edge and `advance` moves along it.
+#[allow(clippy::too_many_arguments)]
+fn filter_edge(p: &mut [u8], pos: usize, step: usize, advance: usize, count: usize, strength: &FilterStrength, limit: i32, macroblock_edge: bool) {
+    let threshold = 2 * limit + 1;
+    for index in 0..count {
+        let pos = pos + index * advance;
+        if needs_filter_normal(p, pos, step, threshold, strength.interior_limit) {
+            if high_variance(p, pos, step, strength.hev_threshold) {
+                filter2(p, pos, step);
+            } else if macroblock_edge {
+                filter6(p, pos, step);
+            } else {
+                filter4(p, pos, step);
+            }
+        }
+    }
+}
+
+/// Simple loop filter along `count` edge positions (luma only)
+fn filter_edge_simple(p: &mut [u8], pos: usize, step: usize, advance: usize, limit: i32) {
+    let threshold = 2 * limit + 1;
+    for index in 0..16 {
+        let pos = pos + index * advance;
+        if needs_filter(p, pos, step, threshold) {
+            filter2(p, pos, step);
+        }
+    }
+}
+
+/// Parse the residual coefficients of a macroblock
+///
+/// Returns whether any block has non-zero coefficients.
+fn read_residuals(
+    decoder: &mut BoolDecoder,
+    probs: &[[[[u8; 11]; 3]; 8]; 4],
+    quantizer: &Quantizer,
+    is_4x4: bool,
+    top: &mut NonZero,
+    left: &mut NonZero,
+    coefficients: &mut [i16; 384],
+) -> bool {
+    let mut non_zero = false;
+    let (first, luma_type) = if is_4x4 {
+        (0, 3)
+    } else {
+        let mut dc = [0i16; 16];
+        let context = top.dc as usize + left.dc as usize;
+        let count = read_coefficients(decoder, &probs[1], context, quantizer.y2, 0, &mut dc);
+        top.dc = count > 0;
+        left.dc = count > 0;
+        if count > 1 {
+            inverse_wht(&dc, coefficients);
+        } else {
+            let value = ((dc[0] as i32 + 3) >> 3) as i16;
+            for block in 0..16 {
+                coefficients[block * 16] = value;
+            }
+        }
+        (1, 0)
+    };
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let block = &mut coefficients[(y * 4 + x) * 16..(y * 4 + x + 1) * 16];
+            let context = top.y[x] as usize + left.y[y] as usize;
+            let count = read_coefficients(decoder, &probs[luma_type], context, quantizer.y1, first, block);
+            top.y[x] = count > first;
+            left.y[y] = count > first;
+            non_zero |= count > 1 || block[0] != 0;
+        }
+    }
+    for (plane, (top_flags, left_flags)) in [(&mut top.u, &mut left.u), (&mut top.v, &mut left.v)].into_iter().enumerate() {
+        for (y, left_flag) in left_flags.iter_mut().enumerate() {
+            for (x, top_flag) in top_flags.iter_mut().enumerate() {
+                let start = (16 + plane * 4 + y * 2 + x) * 16;
+                let block = &mut coefficients[start..start + 16];
+                let count = read_coefficients(decoder, &probs[2], *top_flag as usize + *left_flag as usize, quantizer.uv, 0, block);
+                *top_flag = count > 0;
+                *left_flag = count > 0;
+                non_zero |= count > 1 || block[0] != 0;
+            }
+        }
+    }
+    non_zero
+}
+
+/// Decode a VP8 key frame
+pub(crate) fn decode(data: &[u8]) -> ImageResult<Frame> {
+    let (width, height) = read_header(data)?;
+    let tag = data[0] as usize | (data[1] as usize) << 8 | (data[2] as usize) << 16;
+    if (tag >> 1) & 7 > 3 {
+        return Err(ImageError::UnsupportedFormat("Unsupported WebP lossy profile".to_string()));
+    }
+    if (tag >> 4) & 1 == 0 {
+        return Err(ImageError::UnsupportedFormat("WebP lossy frame is not displayable".to_string()));
+    }
+    let first_size = tag >> 5;
+    let data = &data[10..];
+    if first_size > data.len() {
+        return Err(truncated());
+    }
+    let mut decoder = BoolDecoder::new(&data[..first_size]);
+    // Color space and clamping type do not change decoding
+    decoder.read_flag();
+    decoder.read_flag();
+
+    // Segments
+    let use_segments = decoder.read_flag();
+    let mut update_map = false;
+    let mut absolute_values = true;
+    let mut segment_quantizer = [0i32; NUM_SEGMENTS];
+    let mut segment_filter_level = [0i32; NUM_SEGMENTS];
+    let mut segment_probs = [255u8; 3];
+    if use_segments {
+        update_map = decoder.read_flag();
+        if decoder.read_flag() {
+            absolute_values = decoder.read_flag();
+            segment_quantizer = [(); NUM_SEGMENTS].map(|_| decoder.read_optional_signed(7));
+            segment_filter_level = [(); NUM_SEGMENTS].map(|_| decoder.read_optional_signed(6));
+        }
+        if update_map {
+            segment_probs = [(); 3].map(|_| if decoder.read_flag() { decoder.read_literal(8) as u8 } else { 255 });
+        }
+    }
+
+    // Loop filter
+    let simple_filter = decoder.read_flag();
+    let filter_level = decoder.read_literal(6) as i32;
+    let sharpness = decoder.read_literal(3) as i32;
+    let mut reference_delta = 0;
+    let mut mode_delta = 0;
+    let use_deltas = decoder.read_flag();
+    if use_deltas && decoder.read_flag() {
+        // Only the intra frame and B_PRED deltas apply to key frames
+        let deltas: Vec<Option<i32>> = (0..8).map(|_| decoder.read_flag().then(|| decoder.read_signed(6))).collect();
+        reference_delta = deltas[0].unwrap_or(0);
+        mode_delta = deltas[4].unwrap_or(0);
+    }
+
+    // Token partitions follow the first partition, preceded by their sizes
+    let partition_count = 1usize << decoder.read_literal(2);
+    let sizes_length = 3 * (partition_count - 1);
+    let rest = &data[first_size..];
+    if rest.len() < sizes_length {
+        return Err(truncated());
+    }
+    let mut partitions = Vec::with_capacity(partition_count);
+    let mut start = sizes_length;
+    for index in 0..partition_count {
+        let size = if index + 1 < partition_count {
+            let bytes = &rest[index * 3..index * 3 + 3];
+            (bytes[0] as usize | (bytes[1] as usize) << 8 | (bytes[2] as usize) << 16).min(rest.len() - start)
+        } else {
+            rest.len() - start
+        };
+        partitions.push(BoolDecoder::new(&rest[start..start + size]));
+        start += size;
+    }
+    if partitions.last().is_some_and(|partition| partition.data.is_empty()) {
+        return Err(truncated());
+    }
+
+    // Quantizers
+    let base_index = decoder.read_literal(7) as i32;
+    let [y1_dc, y2_dc, y2_ac, uv_dc, uv_ac] = [(); 5].map(|_| decoder.read_optional_signed(4));
+    let mut quantizers = [Quantizer::default(); NUM_SEGMENTS];
+    for (segment, quantizer) in quantizers.iter_mut().enumerate() {
+        let index = match (use_segments, absolute_values) {
+            (false, _) => base_index,
+            (true, true) => segment_quantizer[segment],
+            (true, false) => segment_quantizer[segment] + base_index,
+        };
+        let dc = |delta: i32, max: i32| DC_QUANT[(index + delta).clamp(0, max) as usize] as i32;
+        let ac = |delta: i32| AC_QUANT[(index + delta).clamp(0, 127) as usize] as i32;
+        *quantizer = Quantizer {
+            y1: [dc(y1_dc, 127), ac(0)],
+            // The Y2 AC factor is scaled by 155 / 100
+            y2: [dc(y2_dc, 127) * 2, ((ac(y2_ac) * 101581) >> 16).max(8)],
+            uv: [dc(uv_dc, 117), ac(uv_ac)],
+        };
+    }
+
+    // Refreshing entropy probabilities does not matter for a single frame
+    decoder.read_flag();
+    let mut probs = COEFF_PROBS;
+    for (t, bands) in probs.iter_mut().enumerate() {
+        for (b, contexts) in bands.iter_mut().enumerate() {
+            for (c, context) in contexts.iter_mut().enumerate() {
+                for (p, prob) in context.iter_mut().enumerate() {
+                    if decoder.read(COEFF_UPDATE_PROBS[t][b][c][p]) {
+                        *prob = decoder.read_literal(8) as u8;
+                    }
+                }
+            }
+        }
+    }
+    let skip_prob = decoder.read_flag().then(|| decoder.read_literal(8) as u8);
+
+    // Filter strengths per segment, for 16x16 and 4x4 predicted macroblocks
+    let mut strengths = [[FilterStrength::default(); 2]; NUM_SEGMENTS];
+    for (segment, segment_strengths) in strengths.iter_mut().enumerate() {
+        let base_level = match (use_segments, absolute_values) {
+            (false, _) => filter_level,
+            (true, true) => segment_filter_level[segment],
+            (true, false) => segment_filter_level[segment] + filter_level,
+        };
+        for (is_4x4, strength) in segment_strengths.iter_mut().enumerate() {
+            let mut level = base_level;
+            if use_deltas {
+                level += reference_delta;
+                if is_4x4 == 1 {
+                    level += mode_delta;
+                }
+            }
+            let level = level.clamp(0, 63);
+            if level == 0 {
+                continue;
+            }
+            let mut interior_limit = level;
+            if sharpness > 0 {
+                interior_limit >>= if sharpness > 4 { 2 } else { 1 };
+                interior_limit = interior_limit.min(9 - sharpness);
+            }
+            let interior_limit = interior_limit.max(1);
+            *strength = FilterStrength {
+                limit: 2 * level + interior_limit,
+                interior_limit,
+                hev_threshold: if level >= 40 { 2 } else if level >= 15 { 1 } else { 0 },
+            };
+        }
+    }
+
+    let mb_width = (width as usize).div_ceil(16);
+    let mb_height = (height as usize).div_ceil(16);
+    let y_stride = mb_width * 16;
+    let uv_stride = mb_width * 8;
+    let mut y_plane = vec![0u8; y_stride * mb_height * 16];
+    let mut u_plane = vec![0u8; uv_stride * mb_height * 8];
+    let mut v_plane = vec![0u8; uv_stride * mb_height * 8];
+
+    let mut top_modes = vec![B_DC_PRED; mb_width * 4];
+    let mut top_non_zero = vec![NonZero::default(); mb_width];
+    let mut filters = Vec::with_capacity(mb_width * mb_height);
+    let mut coefficients = [0i16; 384];
+    // Work buffers with the top row and left column of the macroblock; luma
+    // also holds 4 pixels above-right
+    const Y_STRIDE: usize = 1 + 16 + 4;
+    const UV_STRIDE: usize = 1 + 8;
+    let mut y_work = [0u8; Y_STRIDE * 17];
+    let mut u_work = [0u8; UV_STRIDE * 9];
+    let mut v_work = [0u8; UV_STRIDE * 9];
+
+    for mb_y in 0..mb_height {
+        let mut left_modes = [B_DC_PRED; 4];
+        let mut left_non_zero = NonZero::default();
+        let partition = &mut partitions[mb_y % partition_count];
+        for mb_x in 0..mb_width {
+            // Macroblock header
+            let segment = if update_map {
+                if !decoder.read(segment_probs[0]) {
+                    decoder.read(segment_probs[1]) as usize
+                } else {
+                    2 + decoder.read(segment_probs[2]) as usize
+                }
+            } else {
+                0
+            };
+            let skip = skip_prob.is_some_and(|prob| decoder.read(prob));
+            let is_4x4 = !decoder.read(145);
+            let mut modes = [B_DC_PRED; 16];
+            let top = &mut top_modes[mb_x * 4..mb_x * 4 + 4];
+            if !is_4x4 {
+                let mode = if decoder.read(156) {
+                    if decoder.read(128) { B_TM_PRED } else { B_HE_PRED }
+                } else if decoder.read(163) {
+                    B_VE_PRED
+                } else {
+                    B_DC_PRED
+                };
+                modes[0] = mode;
+                top.fill(mode);
+                left_modes = [mode; 4];
+            } else {
+                for (y, left) in left_modes.iter_mut().enumerate() {
+                    for (x, above) in top.iter_mut().enumerate() {
+                        let probs = &BMODE_PROBS[*above as usize][*left as usize];
+                        let mut node = BMODE_TREE[decoder.read(probs[0]) as usize];
+                        while node > 0 {
+                            node = BMODE_TREE[2 * node as usize + decoder.read(probs[node as usize]) as usize];
+                        }
+                        let mode = (-node) as u8;
+                        modes[y * 4 + x] = mode;
+                        *above = mode;
+                        *left = mode;
+                    }
+                }
+            }
+            let uv_mode = if !decoder.read(142) {
+                B_DC_PRED
+            } else if !decoder.read(114) {
+                B_VE_PRED
+            } else if decoder.read(183) {
+                B_TM_PRED
+            } else {
+                B_HE_PRED
+            };
+
+            // Residuals
+            coefficients.fill(0);
+            let top_flags = &mut top_non_zero[mb_x];
+            let has_coefficients = if skip {
+                let keep_dc = (top_flags.dc, left_non_zero.dc);
+                *top_flags = NonZero::default();
+                left_non_zero = NonZero::default();
+                if is_4x4 {
+                    (top_flags.dc, left_non_zero.dc) = keep_dc;
+                }
+                false
+            } else {
+                read_residuals(partition, &probs, &quantizers[segment], is_4x4, top_flags, &mut left_non_zero, &mut coefficients)
+            };
+            filters.push(MacroblockFilter { strength: strengths[segment][is_4x4 as usize], inner: is_4x4 || has_coefficients });
+
+            // Edges from the unfiltered neighbouring macroblocks: 127 above the
+            // image, 129 left of it
+            let (px, py) = (mb_x * 16, mb_y * 16);
+            for (work, plane, plane_stride, stride, size) in [
+                (&mut y_work[..], &y_plane, y_stride, Y_STRIDE, 16),
+                (&mut u_work[..], &u_plane, uv_stride, UV_STRIDE, 8),
+                (&mut v_work[..], &v_plane, uv_stride, UV_STRIDE, 8),
+            ] {
+                let (px, py) = (px * size / 16, py * size / 16);
+                let top_row = (py.max(1) - 1) * plane_stride;
+                for x in 0..stride {
+                    work[x] = match (mb_y, x) {
+                        (0, _) => 127,
+                        (_, 0) if mb_x == 0 => 129,
+                        (_, 0) => plane[top_row + px - 1],
+                        // Above-right pixels of the last column repeat the last top pixel
+                        (_, _) if x > size && mb_x + 1 == mb_width => plane[top_row + px + size - 1],
+                        (_, _) => plane[top_row + px + x - 1],
+                    };
+                }
+                for y in 0..size {
+                    work[(y + 1) * stride] = if mb_x == 0 { 129 } else { plane[(py + y) * plane_stride + px - 1] };
+                }
+            }
+            // Sub-blocks on the right use the above-right pixels of the macroblock
+            for row in [4, 8, 12] {
+                y_work.copy_within(17..21, row * Y_STRIDE + 17);
+            }
+
+            // Prediction and residuals
+            let (has_top, has_left) = (mb_y > 0, mb_x > 0);
+            if is_4x4 {
+                for (block, &mode) in modes.iter().enumerate() {
+                    let pos = (1 + (block / 4) * 4) * Y_STRIDE + 1 + (block % 4) * 4;
+                    predict_subblock(&mut y_work, Y_STRIDE, pos, mode);
+                    add_inverse_dct(&coefficients[block * 16..], &mut y_work, pos, Y_STRIDE);
+                }
+            } else {
+                predict_block(&mut y_work, Y_STRIDE, Y_STRIDE + 1, 16, modes[0], has_top, has_left);
+                for block in 0..16 {
+                    let pos = (1 + (block / 4) * 4) * Y_STRIDE + 1 + (block % 4) * 4;
+                    add_inverse_dct(&coefficients[block * 16..], &mut y_work, pos, Y_STRIDE);
+                }
+            }
+            for (plane, work) in [&mut u_work, &mut v_work].into_iter().enumerate() {
+                predict_block(work, UV_STRIDE, UV_STRIDE + 1, 8, uv_mode, has_top, has_left);
+                for block in 0..4 {
+                    let pos = (1 + (block / 2) * 4) * UV_STRIDE + 1 + (block % 2) * 4;
+                    add_inverse_dct(&coefficients[(16 + plane * 4 + block) * 16..], work, pos, UV_STRIDE);
+                }
+            }
+
+            for y in 0..16 {
+                let row = (py + y) * y_stride + px;
+                y_plane[row..row + 16].copy_from_slice(&y_work[(y + 1) * Y_STRIDE + 1..(y + 1) * Y_STRIDE + 17]);
+            }
+            for y in 0..8 {
+                let row = (py / 2 + y) * uv_stride + px / 2;
+                u_plane[row..row + 8].copy_from_slice(&u_work[(y + 1) * UV_STRIDE + 1..(y + 2) * UV_STRIDE]);
+                v_plane[row..row + 8].copy_from_slice(&v_work[(y + 1) * UV_STRIDE + 1..(y + 2) * UV_STRIDE]);
+            }
+        }
+        if decoder.overrun() || partition.overrun() {
+            return Err(truncated());
+        }
+    }
+
+    // Loop filter over the whole frame, in macroblock order
+    if filter_level != 0 {
+        for mb_y in 0..mb_height {
+            for mb_x in 0..mb_width {
+                let filter = &filters[mb_y * mb_width + mb_x];
+                let strength = &filter.strength;
+                if strength.limit == 0 {
+                    continue;
+                }
+                let limit = strength.limit;
+                let y_pos = mb_y * 16 * y_stride + mb_x * 16;
+                let uv_pos = mb_y * 8 * uv_stride + mb_x * 8;
+                if simple_filter {
+                    if mb_x > 0 {
+                        filter_edge_simple(&mut y_plane, y_pos, 1, y_stride, limit + 4);
+                    }
+                    if filter.inner {
+                        for x in [4, 8, 12] {
+                            filter_edge_simple(&mut y_plane, y_pos + x, 1, y_stride, limit);
+                        }
+                    }
+                    if mb_y > 0 {
+                        filter_edge_simple(&mut y_plane, y_pos, y_stride, 1, limit + 4);
+                    }
+                    if filter.inner {
+                        for y in [4, 8, 12] {
+                            filter_edge_simple(&mut y_plane, y_pos + y * y_stride, y_stride, 1, limit);
+                        }
+                    }
+                    continue;
+                }
+
+                if mb_x > 0 {
+                    filter_edge(&mut y_plane, y_pos, 1, y_stride, 16, strength, limit + 4, true);
+                    filter_edge(&mut u_plane, uv_pos, 1, uv_stride, 8, strength, limit + 4, true);
+                    filter_edge(&mut v_plane, uv_pos, 1, uv_stride, 8, strength, limit + 4, true);
+                }
+                if filter.inner {
+                    for x in [4, 8, 12] {
+                        filter_edge(&mut y_plane, y_pos + x, 1, y_stride, 16, strength, limit, false);
+                    }
+                    filter_edge(&mut u_plane, uv_pos + 4, 1, uv_stride, 8, strength, limit, false);
+                    filter_edge(&mut v_plane, uv_pos + 4, 1, uv_stride, 8, strength, limit, false);
+                }
+                if mb_y > 0 {
+                    filter_edge(&mut y_plane, y_pos, y_stride, 1, 16, strength, limit + 4, true);
+                    filter_edge(&mut u_plane, uv_pos, uv_stride, 1, 8, strength, limit + 4, true);
+                    filter_edge(&mut v_plane, uv_pos, uv_stride, 1, 8, strength, limit + 4, true);
+                }
+                if filter.inner {
+                    for y in [4, 8, 12] {
+                        filter_edge(&mut y_plane, y_pos + y * y_stride, y_stride, 1, 16, strength, limit, false);
+                    }
+                    filter_edge(&mut u_plane, uv_pos + 4 * uv_stride, uv_stride, 1, 8, strength, limit, false);
+                    filter_edge(&mut v_plane, uv_pos + 4 * uv_stride, uv_stride, 1, 8, strength, limit, false);
+                }
+            }
+        }
+    }
+
+    Ok(Frame { width, height, y: y_plane, u: u_plane, v: v_plane, y_stride, uv_stride })
+}
+
+/// Fixed-point BT.601 conversion (14-bit precision, as libwebp)
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let mult = |value: u8, coefficient: i32| (value as i32 * coefficient) >> 8;
+    let clip = |value: i32| (value >> 6).clamp(0, 255) as u8;
+    let luma = mult(y, 19077);
+    [
+        clip(luma + mult(v, 26149) - 14234),
+        clip(luma - mult(u, 6419) - mult(v, 13320) + 8708),
+        clip(luma + mult(u, 33050) - 17685),
+    ]
+}
+
+impl Frame {
+    /// Convert to RGB (3 channels) or RGBA with opaque alpha (4 channels), upsampling chroma
+    ///
+    /// Chroma is interpolated with 9-3-3-1 weights between the nearest
+    /// samples ("fancy upsampling"), matching libwebp.
+    pub(crate) fn write_rgb(&self, output: &mut [u8], channels: usize) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let chroma_width = width.div_ceil(2);
+        let last_chroma_row = (height - 1) / 2;
+        let mut row_u = vec![0u8; width];
+        let mut row_v = vec![0u8; width];
+
+        for y in 0..height {
+            // Odd rows lean on the chroma row above, even rows on the one below
+            let (near, far) = match y {
+                0 => (0, 0),
+                _ if y % 2 == 1 => (y / 2, (y / 2 + 1).min(last_chroma_row)),
+                _ => (y / 2, y / 2 - 1),
+            };
+            let row = |chroma_y: usize| chroma_y * self.uv_stride..chroma_y * self.uv_stride + chroma_width;
+            upsample_row(&self.u[row(near)], &self.u[row(far)], &mut row_u);
+            upsample_row(&self.v[row(near)], &self.v[row(far)], &mut row_v);
+
+            let luma = &self.y[y * self.y_stride..y * self.y_stride + width];
+            let target = &mut output[y * width * channels..(y + 1) * width * channels];
+            for (x, pixel) in target.chunks_exact_mut(channels).enumerate() {
+                pixel[..3].copy_from_slice(&yuv_to_rgb(luma[x], row_u[x], row_v[x]));
+                if channels == 4 {
+                    pixel[3] = 255;
+                }
+            }
+        }
+    }
+}
+
+/// Upsample one chroma row to `output.len()` pixels, weighting `near` 3:1 over `far`
+fn upsample_row(near: &[u8], far: &[u8], output: &mut [u8]) {
+    let width = output.len();
+    let (mut left_near, mut left_far) = (near[0] as u32, far[0] as u32);
+    output[0] = ((3 * left_near + left_far + 2) >> 2) as u8;
+    for x in 1..=(width - 1) / 2 {
+        let (right_near, right_far) = (near[x] as u32, far[x] as u32);
+        let sum = left_near + right_near + left_far + right_far + 8;
+        output[2 * x - 1] = ((((sum + 2 * (right_near + left_far)) >> 3) + left_near) >> 1) as u8;
+        output[2 * x] = ((((sum + 2 * (left_near + right_far)) >> 3) + right_near) >> 1) as u8;
+        (left_near, left_far) = (right_near, right_far);
+    }
+    if width.is_multiple_of(2) {
+        output[width - 1] = ((3 * left_near + left_far + 2) >> 2) as u8;
+    }
+}
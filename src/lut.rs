@@ -0,0 +1,290 @@
+/// 3D color lookup tables (.cube)
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{Image, ImageError, ImageResult, PixelFormat};
+use std::fs;
+use std::path::Path;
+
+/// Interpolation between the entries of a [`Lut3d`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LutInterpolation {
+    /// Nearest entry, for LUTs that map to discrete colors
+    Nearest,
+
+    /// Blend of the 8 surrounding entries
+    Trilinear,
+
+    /// Blend of the 4 entries of the enclosing tetrahedron
+    ///
+    /// Smoother along the gray axis than trilinear, and what grading tools use.
+    #[default]
+    Tetrahedral,
+}
+
+/// 3D color lookup table
+///
+/// Holds `size³` RGB entries with red varying fastest, as in Adobe/Resolve
+/// `.cube` files. Input colors are clamped to the domain (`0.0..=1.0` unless the
+/// file says otherwise) and mapped onto the lattice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+    title: Option<String>,
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    table: Vec<[f32; 3]>,
+}
+
+/// Error of a malformed .cube file
+fn cube_error(line: usize, message: &str) -> ImageError {
+    ImageError::Other(format!("invalid .cube LUT (line {}): {}", line, message))
+}
+
+/// Parse `N` floats from the words of a line
+fn parse_numbers<'a, const N: usize>(mut words: impl Iterator<Item = &'a str>, line: usize) -> ImageResult<[f32; N]> {
+    let mut numbers = [0f32; N];
+    for value in &mut numbers {
+        *value = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(|| cube_error(line, &format!("expected {} numbers", N)))?;
+    }
+    Ok(numbers)
+}
+
+impl Lut3d {
+    /// Create a LUT from `size³` entries, red varying fastest
+    ///
+    /// Returns an error if `size` is below 2 or the entry count does not match.
+    pub fn from_table(size: usize, table: Vec<[f32; 3]>) -> ImageResult<Self> {
+        if size < 2 || table.len() != size * size * size {
+            return Err(ImageError::Other(format!(
+                "{} entries do not form a 3D LUT of size {}",
+                table.len(), size
+            )));
+        }
+        Ok(Self { title: None, size, domain_min: [0.0; 3], domain_max: [1.0; 3], table })
+    }
+
+    /// Create a LUT that leaves colors unchanged
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is below 2.
+    pub fn identity(size: usize) -> Self {
+        assert!(size >= 2, "3D LUT size must be at least 2");
+        let scale = (size - 1) as f32;
+        let table = (0..size * size * size)
+            .map(|index| {
+                let (r, g, b) = (index % size, index / size % size, index / (size * size));
+                [r as f32 / scale, g as f32 / scale, b as f32 / scale]
+            })
+            .collect();
+        Self { title: None, size, domain_min: [0.0; 3], domain_max: [1.0; 3], table }
+    }
+
+    /// Parse the text of a `.cube` file
+    ///
+    /// Supports `TITLE`, `LUT_3D_SIZE`, `DOMAIN_MIN`, `DOMAIN_MAX` and
+    /// `LUT_3D_INPUT_RANGE`. 1D LUTs are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::Lut3d;
+    ///
+    /// let cube = "LUT_3D_SIZE 2\n0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+    /// let lut = Lut3d::from_cube(cube).unwrap();
+    /// assert_eq!(lut, Lut3d::identity(2));
+    /// ```
+    pub fn from_cube(text: &str) -> ImageResult<Self> {
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0f32; 3];
+        let mut domain_max = [1f32; 3];
+        let mut table = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            match keyword {
+                "TITLE" => {
+                    title = Some(line["TITLE".len()..].trim().trim_matches('"').to_string());
+                }
+                "LUT_3D_SIZE" => {
+                    let value = words.next().and_then(|word| word.parse::<usize>().ok());
+                    let Some(value) = value.filter(|value| (2..=256).contains(value)) else {
+                        return Err(cube_error(number, "LUT_3D_SIZE must be between 2 and 256"));
+                    };
+                    size = Some(value);
+                    table.reserve(value.pow(3));
+                }
+                "LUT_1D_SIZE" => {
+                    return Err(ImageError::UnsupportedFormat("1D .cube LUTs are not supported".to_string()));
+                }
+                "DOMAIN_MIN" => domain_min = parse_numbers(words, number)?,
+                "DOMAIN_MAX" => domain_max = parse_numbers(words, number)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let [min, max] = parse_numbers(words, number)?;
+                    (domain_min, domain_max) = ([min; 3], [max; 3]);
+                }
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+' || c == '.') => {
+                    if size.is_none() {
+                        return Err(cube_error(number, "table data before LUT_3D_SIZE"));
+                    }
+                    table.push(parse_numbers(line.split_whitespace(), number)?);
+                }
+                // Unknown keywords (e.g. vendor extensions) are ignored
+                _ => {}
+            }
+        }
+
+        let size = size.ok_or_else(|| cube_error(text.lines().count(), "missing LUT_3D_SIZE"))?;
+        if table.len() != size * size * size {
+            return Err(ImageError::Other(format!(
+                "invalid .cube LUT: {} entries for size {} (expected {})",
+                table.len(), size, size * size * size
+            )));
+        }
+        if (0..3).any(|c| domain_max[c] <= domain_min[c]) {
+            return Err(ImageError::Other("invalid .cube LUT: empty domain".to_string()));
+        }
+        Ok(Self { title, size, domain_min, domain_max, table })
+    }
+
+    /// Load a `.cube` file
+    pub fn load_cube<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        Self::from_cube(&fs::read_to_string(path)?)
+    }
+
+    /// Lattice size along each axis
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Title given in the file, if any
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Entries, red varying fastest
+    pub fn table(&self) -> &[[f32; 3]] {
+        &self.table
+    }
+
+    fn entry(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.table[(b * self.size + g) * self.size + r]
+    }
+
+    /// Map one RGB color through the LUT
+    pub fn lookup(&self, rgb: [f32; 3], interpolation: LutInterpolation) -> [f32; 3] {
+        let scale = (self.size - 1) as f32;
+        let mut base = [0usize; 3];
+        let mut fraction = [0f32; 3];
+        for c in 0..3 {
+            let t = (rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]);
+            // NaN clamps to the domain start
+            let t = (if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) }) * scale;
+            base[c] = (t as usize).min(self.size - 2);
+            fraction[c] = t - base[c] as f32;
+        }
+        let [r, g, b] = base;
+        let [fr, fg, fb] = fraction;
+        let corner = |dr: usize, dg: usize, db: usize| self.entry(r + dr, g + dg, b + db);
+        let blend = |weights: &[(f32, [f32; 3])]| {
+            let mut out = [0f32; 3];
+            for (weight, value) in weights {
+                for c in 0..3 {
+                    out[c] += weight * value[c];
+                }
+            }
+            out
+        };
+
+        match interpolation {
+            LutInterpolation::Nearest => {
+                let round = |f: f32| usize::from(f >= 0.5);
+                corner(round(fr), round(fg), round(fb))
+            }
+            LutInterpolation::Trilinear => {
+                let mut weights = [(0f32, [0f32; 3]); 8];
+                for (index, weight) in weights.iter_mut().enumerate() {
+                    let (dr, dg, db) = (index & 1, (index >> 1) & 1, index >> 2);
+                    let w = [1.0 - fr, fr][dr] * [1.0 - fg, fg][dg] * [1.0 - fb, fb][db];
+                    *weight = (w, corner(dr, dg, db));
+                }
+                blend(&weights)
+            }
+            LutInterpolation::Tetrahedral => {
+                // The cube is split into 6 tetrahedra along its gray diagonal
+                let c000 = corner(0, 0, 0);
+                let c111 = corner(1, 1, 1);
+                if fr >= fg && fg >= fb {
+                    blend(&[(1.0 - fr, c000), (fr - fg, corner(1, 0, 0)), (fg - fb, corner(1, 1, 0)), (fb, c111)])
+                } else if fr >= fb && fb >= fg {
+                    blend(&[(1.0 - fr, c000), (fr - fb, corner(1, 0, 0)), (fb - fg, corner(1, 0, 1)), (fg, c111)])
+                } else if fb >= fr && fr >= fg {
+                    blend(&[(1.0 - fb, c000), (fb - fr, corner(0, 0, 1)), (fr - fg, corner(1, 0, 1)), (fg, c111)])
+                } else if fg >= fr && fr >= fb {
+                    blend(&[(1.0 - fg, c000), (fg - fr, corner(0, 1, 0)), (fr - fb, corner(1, 1, 0)), (fb, c111)])
+                } else if fg >= fb && fb >= fr {
+                    blend(&[(1.0 - fg, c000), (fg - fb, corner(0, 1, 0)), (fb - fr, corner(0, 1, 1)), (fr, c111)])
+                } else {
+                    blend(&[(1.0 - fb, c000), (fb - fg, corner(0, 0, 1)), (fg - fr, corner(0, 1, 1)), (fr, c111)])
+                }
+            }
+        }
+    }
+}
+
+impl Image {
+    /// Map the colors of the image through a 3D LUT
+    ///
+    /// The LUT is applied to the stored values, so it must expect the encoding
+    /// of the image (usually sRGB for 8 and 16-bit images); float images are
+    /// mapped as stored, clamped to the LUT domain. Alpha is kept and grayscale
+    /// images are left unchanged.
+    ///
+    /// # Arguments
+    /// * `lut` - Lookup table
+    /// * `interpolation` - Interpolation between LUT entries
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, Lut3d, LutInterpolation};
+    ///
+    /// let look = Lut3d::load_cube("looks/warm_film.cube")?;
+    /// let mut capture = GalaxyImage::load_from_file("env_capture.png")?;
+    /// capture.apply_lut3d(&look, LutInterpolation::Tetrahedral);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn apply_lut3d(&mut self, lut: &Lut3d, interpolation: LutInterpolation) {
+        let (red, blue) = match self.pixel_format() {
+            PixelFormat::R | PixelFormat::RG => return,
+            PixelFormat::RGB | PixelFormat::RGBA => (0, 2),
+            PixelFormat::BGR | PixelFormat::BGRA => (2, 0),
+        };
+
+        let component_type = self.component_type();
+        let channel_count = self.pixel_format().channel_count();
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return;
+        }
+
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data_mut().chunks_exact_mut(row_bytes) {
+            read_normalized_slice(row, component_type, &mut values);
+            for pixel in values.chunks_exact_mut(channel_count) {
+                let [r, g, b] = lut.lookup([pixel[red], pixel[1], pixel[blue]], interpolation);
+                (pixel[red], pixel[1], pixel[blue]) = (r, g, b);
+            }
+            write_normalized_slice(row, component_type, &values);
+        }
+    }
+}
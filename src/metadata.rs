@@ -0,0 +1,271 @@
+/// Color and orientation metadata of decoded images, conversion to sRGB
+use crate::color::{linear_to_srgb, ColorSpace};
+use crate::icc::{parse_icc_profile, IccTransform};
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{Image, PixelFormat};
+
+/// Orientation of stored pixels, from the EXIF orientation tag
+///
+/// Each variant names the transform that displays the image upright, see
+/// [`Image::apply_orientation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Orientation {
+    /// Stored upright (EXIF 1)
+    #[default]
+    Normal,
+
+    /// Mirrored left to right (EXIF 2)
+    FlipHorizontal,
+
+    /// Upside down (EXIF 3)
+    Rotate180,
+
+    /// Mirrored top to bottom (EXIF 4)
+    FlipVertical,
+
+    /// Rows and columns swapped (EXIF 5)
+    Transpose,
+
+    /// Needs a 90 degree clockwise rotation, typical of portrait photos (EXIF 6)
+    Rotate90,
+
+    /// Rows and columns swapped along the other diagonal (EXIF 7)
+    Transverse,
+
+    /// Needs a 90 degree counter-clockwise rotation (EXIF 8)
+    Rotate270,
+}
+
+impl Orientation {
+    /// Orientation of an EXIF orientation tag value, `None` outside `1..=8`
+    pub fn from_exif(value: u16) -> Option<Orientation> {
+        match value {
+            1 => Some(Orientation::Normal),
+            2 => Some(Orientation::FlipHorizontal),
+            3 => Some(Orientation::Rotate180),
+            4 => Some(Orientation::FlipVertical),
+            5 => Some(Orientation::Transpose),
+            6 => Some(Orientation::Rotate90),
+            7 => Some(Orientation::Transverse),
+            8 => Some(Orientation::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// EXIF orientation tag value
+    pub fn to_exif(self) -> u16 {
+        match self {
+            Orientation::Normal => 1,
+            Orientation::FlipHorizontal => 2,
+            Orientation::Rotate180 => 3,
+            Orientation::FlipVertical => 4,
+            Orientation::Transpose => 5,
+            Orientation::Rotate90 => 6,
+            Orientation::Transverse => 7,
+            Orientation::Rotate270 => 8,
+        }
+    }
+
+    /// Check if displaying the image swaps its width and height
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self, Orientation::Transpose | Orientation::Rotate90 | Orientation::Transverse | Orientation::Rotate270)
+    }
+}
+
+/// Color and orientation metadata read from the file an image was decoded from
+///
+/// Filled by the PNG loader from the `iCCP`, `gAMA` and `sRGB` chunks and by
+/// the JPEG loader from the EXIF orientation, and written back when saving to
+/// the same format. Clones, in-place edits, crops, resizes, flips, rotations,
+/// mips and conversions keep it (conversions between gray and color drop the
+/// ICC profile). Images without color metadata are assumed to be sRGB.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImageMetadata {
+    /// Embedded ICC profile, uncompressed
+    pub icc_profile: Option<Vec<u8>>,
+
+    /// Encoding gamma: stored values are `linear^gamma` (0.45455 for a 2.2 display gamma)
+    pub gamma: Option<f32>,
+
+    /// Whether the file declares its colors as sRGB
+    pub srgb: bool,
+
+    /// Orientation of the stored pixels (JPEG EXIF)
+    pub orientation: Orientation,
+}
+
+// A NaN gamma is meaningless and never produced by the loaders
+impl Eq for ImageMetadata {}
+
+impl ImageMetadata {
+    /// Check if no metadata is set
+    pub fn is_empty(&self) -> bool {
+        *self == ImageMetadata::default()
+    }
+
+    /// Metadata of an image converted to another channel layout
+    ///
+    /// ICC profiles describe either gray or color data, so the profile is
+    /// dropped when the conversion crosses between the two.
+    pub(crate) fn converted(&self, from: PixelFormat, to: PixelFormat) -> ImageMetadata {
+        let is_gray = |pixel_format| matches!(pixel_format, PixelFormat::R | PixelFormat::RG);
+        let mut metadata = self.clone();
+        if is_gray(from) != is_gray(to) {
+            metadata.icc_profile = None;
+        }
+        metadata
+    }
+
+    /// Closest color space tag, linear for a gamma of 1.0 and sRGB for other color metadata
+    pub(crate) fn color_space(&self) -> Option<ColorSpace> {
+        if self.srgb || self.icc_profile.is_some() {
+            return Some(ColorSpace::Srgb);
+        }
+        self.gamma.map(|gamma| {
+            if (gamma - 1.0).abs() <= SRGB_GAMMA_TOLERANCE {
+                ColorSpace::Linear
+            } else {
+                ColorSpace::Srgb
+            }
+        })
+    }
+}
+
+/// Gammas this close to 1/2.2 are treated as sRGB, as most tools write them for sRGB images
+const SRGB_GAMMA_TOLERANCE: f32 = 0.01;
+
+/// Decoding of stored color values to linear Rec.709
+enum Decoding {
+    Icc(IccTransform),
+    Gamma(f32),
+}
+
+impl Image {
+    /// Convert colors described by an ICC profile or gamma to sRGB
+    ///
+    /// Decodes the color channels with the embedded ICC profile (matrix/TRC RGB
+    /// and gray profiles, as written by image editors) or the gamma, converts
+    /// them to Rec.709 primaries and encodes them with the sRGB transfer
+    /// function; alpha is kept. The color metadata is then replaced by
+    /// [`ImageMetadata::srgb`] and the image is tagged [`ColorSpace::Srgb`].
+    /// Colors outside the sRGB gamut are clamped in 8
+    /// and 16-bit images. A gamma of 1/2.2 is considered sRGB already.
+    ///
+    /// Returns false and leaves the image unchanged if the ICC profile is not
+    /// supported (LUT-based or CMYK profiles, or a gray profile on a color
+    /// image and the reverse). Images without metadata or already tagged sRGB
+    /// are left as is.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// // Artist export with an embedded Display P3 profile
+    /// let mut albedo = GalaxyImage::load_from_file("albedo_p3.png")?;
+    /// if albedo.metadata().icc_profile.is_some() && !albedo.convert_to_srgb() {
+    ///     eprintln!("unsupported ICC profile, colors may be off");
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert_to_srgb(&mut self) -> bool {
+        let metadata = self.metadata();
+        if metadata.srgb || (metadata.icc_profile.is_none() && metadata.gamma.is_none()) {
+            return true;
+        }
+
+        let decoding = match &metadata.icc_profile {
+            Some(profile) => {
+                let gray_image = matches!(self.pixel_format(), PixelFormat::R | PixelFormat::RG);
+                match parse_icc_profile(profile) {
+                    Some(transform @ IccTransform::Gray(_)) if gray_image => Some(Decoding::Icc(transform)),
+                    Some(transform @ IccTransform::Rgb { .. }) if !gray_image => Some(Decoding::Icc(transform)),
+                    _ => return false,
+                }
+            }
+            None => metadata
+                .gamma
+                .filter(|gamma| (gamma * 2.2 - 1.0).abs() > SRGB_GAMMA_TOLERANCE)
+                .map(Decoding::Gamma),
+        };
+
+        if let Some(decoding) = decoding {
+            self.apply_decoding(&decoding);
+        }
+        let orientation = self.metadata().orientation;
+        self.set_metadata(ImageMetadata { srgb: true, orientation, ..Default::default() });
+        self.set_color_space(ColorSpace::Srgb);
+        true
+    }
+
+    /// Rotate and mirror the pixels upright according to the metadata orientation
+    ///
+    /// Returns an image with [`Orientation::Normal`] and the rest of the
+    /// metadata kept; width and height are swapped for the orientations of
+    /// [`Orientation::swaps_dimensions`]. Returns a clone for upright images.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// // Portrait photo stored landscape with EXIF orientation 6
+    /// let photo = GalaxyImage::load_from_file("reference.jpg")?;
+    /// let upright = photo.apply_orientation();
+    /// assert_eq!((upright.width(), upright.height()), (photo.height(), photo.width()));
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn apply_orientation(&self) -> Image {
+        let mut output = match self.metadata().orientation {
+            Orientation::Normal => return self.clone(),
+            Orientation::FlipHorizontal => self.flip_horizontal(),
+            Orientation::Rotate180 => self.rotate_180(),
+            Orientation::FlipVertical => self.flip_vertical(),
+            Orientation::Transpose => self.transpose(),
+            Orientation::Rotate90 => self.rotate_90(),
+            Orientation::Transverse => self.transpose().rotate_180(),
+            Orientation::Rotate270 => self.rotate_270(),
+        };
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(ImageMetadata { orientation: Orientation::Normal, ..self.metadata().clone() });
+        output
+    }
+
+    /// Decode the color channels to linear Rec.709 and encode them as sRGB
+    fn apply_decoding(&mut self, decoding: &Decoding) {
+        let (red, blue) = match self.pixel_format() {
+            PixelFormat::R | PixelFormat::RG => (0, 0),
+            PixelFormat::RGB | PixelFormat::RGBA => (0, 2),
+            PixelFormat::BGR | PixelFormat::BGRA => (2, 0),
+        };
+        let component_type = self.component_type();
+        let channel_count = self.pixel_format().channel_count();
+        let color_channels = if self.pixel_format().has_alpha() { channel_count - 1 } else { channel_count };
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return;
+        }
+
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data_mut().chunks_exact_mut(row_bytes) {
+            read_normalized_slice(row, component_type, &mut values);
+            for pixel in values.chunks_exact_mut(channel_count) {
+                match decoding {
+                    Decoding::Gamma(gamma) => {
+                        for value in &mut pixel[..color_channels] {
+                            *value = linear_to_srgb(value.clamp(0.0, 1.0).powf(1.0 / gamma));
+                        }
+                    }
+                    Decoding::Icc(IccTransform::Gray(curve)) => {
+                        pixel[0] = linear_to_srgb(curve.eval(pixel[0]));
+                    }
+                    Decoding::Icc(IccTransform::Rgb { curves, matrix }) => {
+                        let linear = [curves[0].eval(pixel[red]), curves[1].eval(pixel[1]), curves[2].eval(pixel[blue])];
+                        let [r, g, b] = matrix.map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]);
+                        (pixel[red], pixel[1], pixel[blue]) = (linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b));
+                    }
+                }
+            }
+            write_normalized_slice(row, component_type, &values);
+        }
+    }
+}
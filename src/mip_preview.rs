@@ -0,0 +1,136 @@
+/// Debug rendering of mip chains in a single annotated image
+use crate::contact_sheet::{blend_onto, to_preview};
+use crate::font::{draw_text, text_width, GLYPH_HEIGHT};
+use crate::{ComponentType, Image, PixelFormat, ResizeFilter};
+
+/// Tint colors of successive mip levels (cycled for long chains)
+const LEVEL_TINTS: [[u8; 3]; 8] = [
+    [255, 64, 64],
+    [255, 160, 32],
+    [255, 240, 32],
+    [64, 220, 64],
+    [32, 220, 220],
+    [64, 96, 255],
+    [160, 64, 255],
+    [255, 64, 220],
+];
+
+/// Fraction of the tint color mixed into tinted levels, out of 255
+const TINT_WEIGHT: u32 = 96;
+
+/// Options of [`GalaxyImage::mip_chain_preview`](crate::GalaxyImage::mip_chain_preview)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MipPreviewOptions {
+    /// Mix a distinct color into every level, to see which level the GPU samples (default: false)
+    pub tint: bool,
+
+    /// Add a row showing the alpha channel of every level as grayscale (default: false)
+    pub show_alpha: bool,
+
+    /// Scale every level up to the base size with nearest filtering (default: false)
+    ///
+    /// Shows the filtering of small levels texel by texel.
+    pub magnify: bool,
+
+    /// Integer scale of the level labels (`index: width x height`), 0 to hide them (default: 1)
+    pub label_scale: u32,
+
+    /// Gap between levels and around the image in pixels (default: 8)
+    pub spacing: u32,
+
+    /// Background color, RGBA (default: dark gray)
+    pub background: [u8; 4],
+}
+
+impl Default for MipPreviewOptions {
+    fn default() -> Self {
+        Self {
+            tint: false,
+            show_alpha: false,
+            magnify: false,
+            label_scale: 1,
+            spacing: 8,
+            background: [32, 32, 32, 255],
+        }
+    }
+}
+
+/// Mix a tint color into the color channels of an RGBA/U8 image
+fn tint(image: &mut Image, color: [u8; 3]) {
+    for pixel in image.data_mut().chunks_exact_mut(4) {
+        for c in 0..3 {
+            pixel[c] = ((pixel[c] as u32 * (255 - TINT_WEIGHT) + color[c] as u32 * TINT_WEIGHT + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Opaque grayscale RGBA/U8 image of the alpha channel of an RGBA/U8 image
+fn alpha_view(image: &Image) -> Image {
+    let mut view = image.clone();
+    for pixel in view.data_mut().chunks_exact_mut(4) {
+        let alpha = pixel[3];
+        pixel.copy_from_slice(&[alpha, alpha, alpha, 255]);
+    }
+    view
+}
+
+/// Render mip levels side by side
+pub(crate) fn mip_chain_preview(levels: &[Image], options: &MipPreviewOptions) -> Image {
+    let (base_width, base_height) = levels.first().map_or((0, 0), |base| (base.width(), base.height()));
+    let mut previews: Vec<Image> = levels
+        .iter()
+        .map(|level| {
+            let level = if options.magnify {
+                level.resize(base_width, base_height, ResizeFilter::Nearest)
+            } else {
+                level.clone()
+            };
+            to_preview(&level)
+        })
+        .collect();
+
+    let scale = options.label_scale;
+    let labels: Vec<String> = levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| format!("{}: {}x{}", index, level.width(), level.height()))
+        .collect();
+    let label_height = if scale > 0 { (GLYPH_HEIGHT + 2) * scale } else { 0 };
+
+    // Columns are wide enough for their label, rows as tall as the base level
+    let column_widths: Vec<u32> = previews
+        .iter()
+        .zip(&labels)
+        .map(|(preview, label)| if scale > 0 { preview.width().max(text_width(label, scale)) } else { preview.width() })
+        .collect();
+    let row_height = previews.iter().map(Image::height).max().unwrap_or(0);
+    let row_count = if options.show_alpha { 2 } else { 1 };
+    let spacing = options.spacing;
+    let width = column_widths.iter().sum::<u32>() + (column_widths.len() as u32 + 1) * spacing;
+    let height = row_count * row_height + label_height + (row_count + 1) * spacing;
+
+    let mut output = Image::new(width, height, PixelFormat::RGBA, ComponentType::U8);
+    for pixel in output.data_mut().chunks_exact_mut(4) {
+        pixel.copy_from_slice(&options.background);
+    }
+
+    let label_color = [220, 220, 220, 255];
+    let mut x = spacing;
+    for (index, preview) in previews.iter_mut().enumerate() {
+        if options.show_alpha {
+            // Alpha as stored, before tinting
+            let alpha = alpha_view(preview);
+            blend_onto(&mut output, x, 2 * spacing + row_height, &alpha);
+        }
+        if options.tint {
+            tint(preview, LEVEL_TINTS[index % LEVEL_TINTS.len()]);
+        }
+        blend_onto(&mut output, x, spacing, preview);
+        if scale > 0 {
+            let y = row_count * (row_height + spacing) + spacing + scale;
+            draw_text(&mut output, x, y, &labels[index], scale, label_color);
+        }
+        x += column_widths[index] + spacing;
+    }
+    output
+}
@@ -0,0 +1,175 @@
+/// Mip level generation
+use crate::color::ColorSpace;
+use crate::resample::box_downscale;
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, ResizeFilter};
+
+/// Size of the next mip level (halved, rounded down, at least 1)
+pub(crate) fn next_mip_size(width: u32, height: u32) -> (u32, u32) {
+    ((width / 2).max(1), (height / 2).max(1))
+}
+
+/// Number of levels in a full mip chain down to 1x1
+pub(crate) fn mip_count(width: u32, height: u32) -> usize {
+    32 - width.max(height).max(1).leading_zeros() as usize
+}
+
+/// Apply a transfer function to the color channels of normalized values
+fn map_color_channels(values: &mut [f32], channel_count: usize, has_alpha: bool, f: impl Fn(f32) -> f32) {
+    let color_channels = if has_alpha { channel_count - 1 } else { channel_count };
+    for pixel in values.chunks_exact_mut(channel_count) {
+        for value in &mut pixel[..color_channels] {
+            *value = f(*value);
+        }
+    }
+}
+
+/// Generate the next mip level with a box filter
+///
+/// sRGB, PQ and HLG samples are averaged in linear space so mips do not darken.
+/// Images without pixels give a zeroed level, as [`Image::generate_mipmaps`] does.
+pub(crate) fn next_mip(image: &Image, color_space: ColorSpace) -> Image {
+    if image.width() == 0 || image.height() == 0 {
+        let (width, height) = next_mip_size(image.width(), image.height());
+        let mut output = Image::new(width, height, image.pixel_format(), image.component_type());
+        output.set_alpha_mode(image.alpha_mode());
+        output.set_color_space(image.color_space());
+        output.set_metadata(image.metadata().clone());
+        return output;
+    }
+    downscale_linear(image, color_space, box_downscale)
+}
+
+/// Generate the next mip level with `downscale`, filtering color channels in linear space
+fn downscale_linear(image: &Image, color_space: ColorSpace, downscale: impl Fn(&Image, u32, u32) -> Image) -> Image {
+    let (width, height) = next_mip_size(image.width(), image.height());
+    if color_space == ColorSpace::Linear {
+        return downscale(image, width, height);
+    }
+
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let has_alpha = pixel_format.has_alpha();
+
+    let mut values = vec![0f32; image.size_bytes() / image.component_type().size_bytes()];
+    read_normalized_slice(image.data(), image.component_type(), &mut values);
+    map_color_channels(&mut values, channel_count, has_alpha, |value| color_space.decode(value));
+
+    let mut linear = Image::new(image.width(), image.height(), pixel_format, ComponentType::F32);
+    write_normalized_slice(linear.data_mut(), ComponentType::F32, &values);
+    let linear = downscale(&linear, width, height);
+
+    let mut values = vec![0f32; linear.size_bytes() / 4];
+    read_normalized_slice(linear.data(), ComponentType::F32, &mut values);
+    map_color_channels(&mut values, channel_count, has_alpha, |value| color_space.encode(value));
+
+    let mut output = Image::new(width, height, pixel_format, image.component_type());
+    output.set_alpha_mode(image.alpha_mode());
+    output.set_color_space(image.color_space());
+    output.set_metadata(image.metadata().clone());
+    write_normalized_slice(output.data_mut(), image.component_type(), &values);
+    output
+}
+
+/// Fraction of pixels whose alpha passes an alpha test (`alpha >= threshold`)
+pub(crate) fn alpha_coverage(image: &Image, threshold: f32) -> f32 {
+    let channel_count = image.pixel_format().channel_count();
+    let mut values = vec![0f32; image.size_bytes() / image.component_type().size_bytes()];
+    read_normalized_slice(image.data(), image.component_type(), &mut values);
+    coverage_of(&values, channel_count, threshold)
+}
+
+fn coverage_of(values: &[f32], channel_count: usize, threshold: f32) -> f32 {
+    let pixel_count = values.len() / channel_count;
+    if pixel_count == 0 {
+        return 0.0;
+    }
+    let passing = values.chunks_exact(channel_count).filter(|pixel| pixel[channel_count - 1] >= threshold).count();
+    passing as f32 / pixel_count as f32
+}
+
+/// Scale the alpha of a mip level so its alpha-test coverage matches `coverage`
+///
+/// Searches the test threshold that gives the wanted coverage on the level,
+/// then scales alpha so that threshold maps to the real one (Castaño's method).
+/// Images without alpha, or a coverage of zero, leave the level unchanged.
+pub(crate) fn preserve_alpha_coverage(image: &mut Image, threshold: f32, coverage: f32) {
+    let pixel_format = image.pixel_format();
+    if !pixel_format.has_alpha() || coverage <= 0.0 {
+        return;
+    }
+    let channel_count = pixel_format.channel_count();
+    let component_type = image.component_type();
+    let mut values = vec![0f32; image.size_bytes() / component_type.size_bytes()];
+    read_normalized_slice(image.data(), component_type, &mut values);
+
+    // Coverage decreases as the test threshold rises
+    let (mut low, mut high) = (0f32, 1f32);
+    for _ in 0..16 {
+        let middle = (low + high) / 2.0;
+        if coverage_of(&values, channel_count, middle) > coverage {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    // The low end still reaches the coverage, so texels on the boundary keep passing after rounding
+    let scale = threshold / low.max(f32::EPSILON);
+
+    for pixel in values.chunks_exact_mut(channel_count) {
+        let alpha = &mut pixel[channel_count - 1];
+        *alpha = (*alpha * scale).min(1.0);
+    }
+    write_normalized_slice(image.data_mut(), component_type, &values);
+}
+
+impl Image {
+    /// Generate a full mip chain down to 1x1
+    ///
+    /// Returns the base level (a copy of the image) followed by every smaller
+    /// level. Each level halves the previous one, rounding down, so
+    /// non-power-of-two images follow the GPU convention (e.g. 300x100, 150x50,
+    /// 75x25, 37x12, ... 1x1), and every level is filtered from the one above
+    /// it. [`ResizeFilter::Area`] averages odd sizes exactly, where the 2x2 box
+    /// of a power-of-two chain would skip the last row or column.
+    ///
+    /// Color channels of images tagged sRGB, PQ or HLG (see
+    /// [`Image::color_space`]) are decoded to linear light before filtering and
+    /// encoded back, so mips do not darken; alpha and [`ColorSpace::Linear`]
+    /// images are filtered as stored. Tag data textures loaded from 8-bit files
+    /// as linear first.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter used to shrink each level
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ColorSpace, GalaxyImage, ResizeFilter};
+    ///
+    /// let albedo = GalaxyImage::load_from_file("rock_albedo.png")?;
+    /// let mips = albedo.generate_mipmaps(ResizeFilter::Area);
+    /// for (index, level) in mips.iter().enumerate() {
+    ///     println!("mip {}: {}x{}", index, level.width(), level.height());
+    /// }
+    ///
+    /// let mut roughness = GalaxyImage::load_from_file("rock_roughness.png")?;
+    /// roughness.set_color_space(ColorSpace::Linear);
+    /// let roughness_mips = roughness.generate_mipmaps(ResizeFilter::Lanczos3);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn generate_mipmaps(&self, filter: ResizeFilter) -> Vec<Image> {
+        let color_space = self.color_space();
+        let level_count = mip_count(self.width(), self.height());
+        let mut levels = Vec::with_capacity(level_count);
+        levels.push(self.clone());
+        for _ in 1..level_count {
+            let level = downscale_linear(&levels[levels.len() - 1], color_space, |image, width, height| {
+                image.resize(width, height, filter)
+            });
+            levels.push(level);
+        }
+        levels
+    }
+}
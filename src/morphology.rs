@@ -0,0 +1,151 @@
+/// Morphological operations on single-channel mask images
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{Image, ImageError, ImageResult, PixelFormat};
+use std::collections::VecDeque;
+
+/// Shape of the neighborhood used by morphological operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MorphShape {
+    /// `(2r + 1) x (2r + 1)` square
+    Square,
+
+    /// Disk of radius `r` (round growth, no diagonal bias)
+    #[default]
+    Disk,
+}
+
+/// Sliding minimum or maximum over `[i - radius, i + radius]`, ignoring samples outside
+fn sliding_extreme(input: &[f32], radius: usize, output: &mut [f32], take_max: bool) {
+    let better = |a: f32, b: f32| if take_max { a >= b } else { a <= b };
+    let mut window: VecDeque<usize> = VecDeque::new();
+    let len = input.len();
+    let mut next = 0;
+
+    for (i, out) in output.iter_mut().enumerate().take(len) {
+        // Extend the window to i + radius
+        while next < len && next <= i + radius {
+            while window.back().is_some_and(|&j| better(input[next], input[j])) {
+                window.pop_back();
+            }
+            window.push_back(next);
+            next += 1;
+        }
+        // Drop samples left of i - radius
+        while window.front().is_some_and(|&j| j + radius < i) {
+            window.pop_front();
+        }
+        *out = input[window[0]];
+    }
+}
+
+/// Erode (minimum) or dilate (maximum) normalized samples
+fn morph(values: &[f32], width: usize, height: usize, radius: usize, shape: MorphShape, take_max: bool) -> Vec<f32> {
+    let identity = if take_max { f32::NEG_INFINITY } else { f32::INFINITY };
+    let combine = |a: f32, b: f32| if take_max { a.max(b) } else { a.min(b) };
+
+    // Half-width of the shape on each row offset
+    let half_widths: Vec<usize> = (0..=radius)
+        .map(|dy| match shape {
+            MorphShape::Square => radius,
+            MorphShape::Disk => ((radius * radius - dy * dy) as f64).sqrt().floor() as usize,
+        })
+        .collect();
+
+    // Horizontal pass for every distinct half-width, then combine rows vertically
+    let mut distinct: Vec<usize> = half_widths.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    let mut horizontal: Vec<Vec<f32>> = Vec::with_capacity(distinct.len());
+    for &half_width in &distinct {
+        let mut pass = vec![0f32; values.len()];
+        for (row, out) in values.chunks_exact(width).zip(pass.chunks_exact_mut(width)) {
+            sliding_extreme(row, half_width, out, take_max);
+        }
+        horizontal.push(pass);
+    }
+    let pass_for = |dy: usize| &horizontal[distinct.binary_search(&half_widths[dy]).unwrap()];
+
+    let mut output = vec![identity; values.len()];
+    for y in 0..height {
+        let out_row = &mut output[y * width..(y + 1) * width];
+        for dy in 0..=radius {
+            let pass = pass_for(dy);
+            let rows = [y.checked_sub(dy), (dy > 0).then_some(y + dy).filter(|&sy| sy < height)];
+            for sy in rows.into_iter().flatten() {
+                for (out, &value) in out_row.iter_mut().zip(&pass[sy * width..(sy + 1) * width]) {
+                    *out = combine(*out, value);
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Apply a sequence of erode (`false`) / dilate (`true`) steps to a mask image
+fn apply(image: &Image, radius: u32, shape: MorphShape, steps: &[bool]) -> ImageResult<Image> {
+    if image.pixel_format() != PixelFormat::R {
+        return Err(ImageError::InvalidPixelFormat(format!(
+            "Morphology requires a single-channel (R) mask image, got {:?}",
+            image.pixel_format()
+        )));
+    }
+
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+    let mut values = vec![0f32; width * height];
+    read_normalized_slice(image.data(), image.component_type(), &mut values);
+    if radius > 0 && !values.is_empty() {
+        for &take_max in steps {
+            values = morph(&values, width, height, radius as usize, shape, take_max);
+        }
+    }
+
+    let mut output = Image::new(image.width(), image.height(), PixelFormat::R, image.component_type());
+    write_normalized_slice(output.data_mut(), image.component_type(), &values);
+    Ok(output)
+}
+
+impl Image {
+    /// Shrink the bright regions of a mask (minimum over the neighborhood)
+    ///
+    /// Works on single-channel (R) images of any component type. Pixels outside
+    /// the image are ignored, so regions touching the border do not shrink from it.
+    ///
+    /// # Arguments
+    /// * `radius` - Neighborhood radius in pixels (0 returns a copy)
+    /// * `shape` - Neighborhood shape
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, MorphShape};
+    ///
+    /// let mask = GalaxyImage::load_from_file("splat_mask.png")?;
+    /// let shrunk = mask.erode(3, MorphShape::Disk)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn erode(&self, radius: u32, shape: MorphShape) -> ImageResult<Image> {
+        apply(self, radius, shape, &[false])
+    }
+
+    /// Grow the bright regions of a mask (maximum over the neighborhood)
+    ///
+    /// See [`Image::erode`] for the supported images.
+    pub fn dilate(&self, radius: u32, shape: MorphShape) -> ImageResult<Image> {
+        apply(self, radius, shape, &[true])
+    }
+
+    /// Remove bright specks smaller than the neighborhood (erode, then dilate)
+    ///
+    /// See [`Image::erode`] for the supported images.
+    pub fn open(&self, radius: u32, shape: MorphShape) -> ImageResult<Image> {
+        apply(self, radius, shape, &[false, true])
+    }
+
+    /// Fill dark holes smaller than the neighborhood (dilate, then erode)
+    ///
+    /// See [`Image::erode`] for the supported images.
+    pub fn close(&self, radius: u32, shape: MorphShape) -> ImageResult<Image> {
+        apply(self, radius, shape, &[true, false])
+    }
+}
@@ -0,0 +1,78 @@
+/// Conversions to and from `ndarray` arrays (feature `ndarray`)
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+use ndarray::{Array3, ArrayView3};
+
+impl Image {
+    /// Convert the image to a height x width x channels array of normalized floats
+    ///
+    /// Integer components are mapped to `0.0..=1.0`, float components are copied
+    /// as stored. Channels are in storage order (e.g. B, G, R for BGR images).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let image = GalaxyImage::load_from_file("heightmap.png").unwrap();
+    /// let array = image.to_ndarray();
+    /// let mean_height = array.mean().unwrap();
+    /// ```
+    pub fn to_ndarray(&self) -> Array3<f32> {
+        let shape = (
+            self.height() as usize,
+            self.width() as usize,
+            self.pixel_format().channel_count(),
+        );
+        let mut values = vec![0f32; shape.0 * shape.1 * shape.2];
+        read_normalized_slice(self.data(), self.component_type(), &mut values);
+        Array3::from_shape_vec(shape, values).expect("pixel data matches image dimensions")
+    }
+
+    /// Create an image from a height x width x channels array of normalized floats
+    ///
+    /// Values are converted to `component_type` (integer components are clamped
+    /// to `0.0..=1.0` and rounded). Accepts any memory layout, including views.
+    ///
+    /// # Arguments
+    ///
+    /// * `array` - Pixel values, channels in storage order of `pixel_format`
+    /// * `pixel_format` - Channel layout; must match the channel axis length
+    /// * `component_type` - Storage type of the created image
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, GalaxyImage, Image, ImageFormat, PixelFormat};
+    ///
+    /// let image = GalaxyImage::load_from_file("heightmap.png").unwrap();
+    /// let slope = image.to_ndarray().mapv(|h| h * 0.5);
+    /// let result = Image::from_ndarray(slope.view(), PixelFormat::R, ComponentType::F32).unwrap();
+    /// GalaxyImage::save_to_file(&result, "slope.exr", ImageFormat::Exr).unwrap();
+    /// ```
+    pub fn from_ndarray(
+        array: ArrayView3<'_, f32>,
+        pixel_format: PixelFormat,
+        component_type: ComponentType,
+    ) -> ImageResult<Image> {
+        let (height, width, channels) = array.dim();
+        if channels != pixel_format.channel_count() {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "array has {} channels, {:?} needs {}",
+                channels,
+                pixel_format,
+                pixel_format.channel_count()
+            )));
+        }
+        let (width, height) = match (u32::try_from(width), u32::try_from(height)) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(ImageError::Other("Array dimensions exceed u32 range".to_string())),
+        };
+
+        let values = array.as_standard_layout();
+        let values = values.as_slice().expect("standard layout arrays are contiguous");
+        let mut image = Image::new(width, height, pixel_format, component_type);
+        write_normalized_slice(image.data_mut(), component_type, values);
+        Ok(image)
+    }
+}
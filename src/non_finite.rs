@@ -0,0 +1,109 @@
+/// Detection and replacement of NaN and infinite samples
+use crate::sample::{read_normalized, write_normalized};
+use crate::{ComponentType, Image};
+
+/// NaN and infinite samples found in an image
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NonFiniteReport {
+    /// Number of NaN samples
+    pub nan_count: usize,
+
+    /// Number of positive or negative infinite samples
+    pub infinite_count: usize,
+
+    /// Pixels with at least one non-finite sample, in row-major order
+    pub pixels: Vec<(u32, u32)>,
+}
+
+impl NonFiniteReport {
+    /// Check if no non-finite sample was found
+    pub fn is_clean(&self) -> bool {
+        self.nan_count == 0 && self.infinite_count == 0
+    }
+}
+
+impl Image {
+    /// Find NaN and infinite samples
+    ///
+    /// Renders occasionally produce them, and a single one spreads through
+    /// filtering, mip generation and block compression. Integer images never
+    /// contain any and return an empty report.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let render = GalaxyImage::load_from_file("render.exr")?;
+    /// let report = render.find_non_finite();
+    /// if let Some((x, y)) = report.pixels.first() {
+    ///     println!("{} NaN, {} Inf, first at ({}, {})", report.nan_count, report.infinite_count, x, y);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn find_non_finite(&self) -> NonFiniteReport {
+        let mut report = NonFiniteReport::default();
+        let component_type = self.component_type();
+        if !matches!(component_type, ComponentType::F16 | ComponentType::F32) {
+            return report;
+        }
+
+        let size = component_type.size_bytes();
+        let width = self.width().max(1) as usize;
+        for (index, pixel) in self.data().chunks_exact(self.bytes_per_pixel()).enumerate() {
+            let mut found = false;
+            for sample in pixel.chunks_exact(size) {
+                let value = read_normalized(sample, component_type);
+                if value.is_nan() {
+                    report.nan_count += 1;
+                    found = true;
+                } else if value.is_infinite() {
+                    report.infinite_count += 1;
+                    found = true;
+                }
+            }
+            if found {
+                report.pixels.push(((index % width) as u32, (index / width) as u32));
+            }
+        }
+        report
+    }
+
+    /// Replace NaN and infinite samples with a value
+    ///
+    /// Returns the number of replaced samples. Integer images are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacement` - Value written in place of every non-finite sample (usually 0.0)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut render = GalaxyImage::load_from_file("render.exr")?;
+    /// let fixed = render.sanitize_non_finite(0.0);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn sanitize_non_finite(&mut self, replacement: f32) -> usize {
+        let component_type = self.component_type();
+        if !matches!(component_type, ComponentType::F16 | ComponentType::F32) {
+            return 0;
+        }
+        // Scan first so clean images are not copied out of shared storage
+        if self.find_non_finite().is_clean() {
+            return 0;
+        }
+
+        let size = component_type.size_bytes();
+        let mut replaced = 0;
+        for sample in self.data_mut().chunks_exact_mut(size) {
+            if !read_normalized(sample, component_type).is_finite() {
+                write_normalized(sample, component_type, replacement);
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+}
@@ -0,0 +1,334 @@
+/// Pixel and row accessors by coordinates
+use crate::sample::{read_normalized, write_normalized};
+use crate::typed::Component;
+use crate::{ComponentType, Image, PixelFormat};
+use half::f16;
+
+/// One pixel in RGBA order with components of type `T`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rgba<T> {
+    /// Red (or gray)
+    pub r: T,
+    /// Green
+    pub g: T,
+    /// Blue
+    pub b: T,
+    /// Alpha
+    pub a: T,
+}
+
+impl<T> Rgba<T> {
+    /// Create a pixel from its components
+    pub fn new(r: T, g: T, b: T, a: T) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+/// Pixel read in the storage type of an image
+///
+/// Returned by [`Image::get_pixel`]. Values are stored as is (no
+/// normalization); use [`Pixel::to_f32`] for normalized floats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pixel {
+    /// 8-bit unsigned components
+    U8(Rgba<u8>),
+    /// 16-bit unsigned components
+    U16(Rgba<u16>),
+    /// Half-float components
+    F16(Rgba<f16>),
+    /// Float components
+    F32(Rgba<f32>),
+    /// 8-bit signed-normalized components
+    I8(Rgba<i8>),
+    /// 16-bit signed-normalized components
+    I16(Rgba<i16>),
+}
+
+macro_rules! pixel_from_rgba {
+    ($($component:ty => $variant:ident),*) => {$(
+        impl From<Rgba<$component>> for Pixel {
+            fn from(rgba: Rgba<$component>) -> Self {
+                Pixel::$variant(rgba)
+            }
+        }
+    )*};
+}
+
+pixel_from_rgba!(u8 => U8, u16 => U16, f16 => F16, f32 => F32, i8 => I8, i16 => I16);
+
+/// Normalize one component like the storage readers do
+fn normalized<T: Component>(value: T) -> f32 {
+    let mut bytes = [0u8; 4];
+    value.write_le(&mut bytes);
+    read_normalized(&bytes, T::COMPONENT_TYPE)
+}
+
+impl Pixel {
+    /// Component type of the values
+    pub fn component_type(&self) -> ComponentType {
+        match self {
+            Pixel::U8(_) => ComponentType::U8,
+            Pixel::U16(_) => ComponentType::U16,
+            Pixel::F16(_) => ComponentType::F16,
+            Pixel::F32(_) => ComponentType::F32,
+            Pixel::I8(_) => ComponentType::I8,
+            Pixel::I16(_) => ComponentType::I16,
+        }
+    }
+
+    /// Convert to normalized floats, as [`Image::read_pixel_f32`] returns them
+    pub fn to_f32(&self) -> Rgba<f32> {
+        fn convert<T: Component>(rgba: &Rgba<T>) -> Rgba<f32> {
+            Rgba::new(normalized(rgba.r), normalized(rgba.g), normalized(rgba.b), normalized(rgba.a))
+        }
+        match self {
+            Pixel::U8(rgba) => convert(rgba),
+            Pixel::U16(rgba) => convert(rgba),
+            Pixel::F16(rgba) => convert(rgba),
+            Pixel::F32(rgba) => convert(rgba),
+            Pixel::I8(rgba) => convert(rgba),
+            Pixel::I16(rgba) => convert(rgba),
+        }
+    }
+}
+
+/// Read a pixel in RGBA order from its stored components
+fn read_rgba<T: Component>(pixel: &[u8], pixel_format: PixelFormat) -> Rgba<T> {
+    let size = T::COMPONENT_TYPE.size_bytes();
+    let c = |channel: usize| T::from_le(&pixel[channel * size..]);
+    match pixel_format {
+        PixelFormat::R => Rgba::new(c(0), c(0), c(0), T::OPAQUE),
+        PixelFormat::RG => Rgba::new(c(0), c(0), c(0), c(1)),
+        PixelFormat::RGB => Rgba::new(c(0), c(1), c(2), T::OPAQUE),
+        PixelFormat::RGBA => Rgba::new(c(0), c(1), c(2), c(3)),
+        PixelFormat::BGR => Rgba::new(c(2), c(1), c(0), T::OPAQUE),
+        PixelFormat::BGRA => Rgba::new(c(2), c(1), c(0), c(3)),
+    }
+}
+
+/// Store a pixel given in RGBA order, dropping the channels the format lacks
+fn write_rgba<T: Component>(pixel: &mut [u8], pixel_format: PixelFormat, rgba: Rgba<T>) {
+    let size = T::COMPONENT_TYPE.size_bytes();
+    let Rgba { r, g, b, a } = rgba;
+    let values: &[T] = match pixel_format {
+        PixelFormat::R => &[r],
+        PixelFormat::RG => &[r, a],
+        PixelFormat::RGB => &[r, g, b],
+        PixelFormat::RGBA => &[r, g, b, a],
+        PixelFormat::BGR => &[b, g, r],
+        PixelFormat::BGRA => &[b, g, r, a],
+    };
+    for (component, value) in pixel.chunks_exact_mut(size).zip(values) {
+        value.write_le(component);
+    }
+}
+
+impl Image {
+    /// Byte offset of a pixel, panicking on out-of-bounds coordinates
+    fn pixel_offset(&self, x: u32, y: u32) -> usize {
+        assert!(
+            x < self.width() && y < self.height(),
+            "pixel ({}, {}) out of bounds for {}x{} image",
+            x,
+            y,
+            self.width(),
+            self.height()
+        );
+        (y as usize * self.width() as usize + x as usize) * self.bytes_per_pixel()
+    }
+
+    /// Read a pixel as normalized RGBA floats
+    ///
+    /// Integer components are mapped to `0.0..=1.0`, float components are returned
+    /// as stored. Channels are always returned in RGBA order: BGR(A) is swapped,
+    /// grayscale is replicated to R, G and B, and alpha is 1.0 for formats
+    /// without alpha.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let image = Image::new(16, 16, PixelFormat::BGR, ComponentType::U8);
+    /// let [r, g, b, a] = image.read_pixel_f32(3, 4);
+    /// ```
+    pub fn read_pixel_f32(&self, x: u32, y: u32) -> [f32; 4] {
+        let offset = self.pixel_offset(x, y);
+        let component_type = self.component_type();
+        let size = component_type.size_bytes();
+        let pixel = &self.data()[offset..offset + self.bytes_per_pixel()];
+        let c = |channel: usize| read_normalized(&pixel[channel * size..], component_type);
+
+        match self.pixel_format() {
+            PixelFormat::R => [c(0), c(0), c(0), 1.0],
+            PixelFormat::RG => [c(0), c(0), c(0), c(1)],
+            PixelFormat::RGB => [c(0), c(1), c(2), 1.0],
+            PixelFormat::RGBA => [c(0), c(1), c(2), c(3)],
+            PixelFormat::BGR => [c(2), c(1), c(0), 1.0],
+            PixelFormat::BGRA => [c(2), c(1), c(0), c(3)],
+        }
+    }
+
+    /// Write a pixel from normalized RGBA floats
+    ///
+    /// Values are converted to the storage type (integer components are clamped to
+    /// `0.0..=1.0` and rounded). Channels missing from the pixel format are
+    /// ignored; grayscale formats store the red value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let mut image = Image::new(16, 16, PixelFormat::RGBA, ComponentType::U16);
+    /// image.write_pixel_f32(3, 4, [1.0, 0.5, 0.0, 1.0]);
+    /// ```
+    pub fn write_pixel_f32(&mut self, x: u32, y: u32, rgba: [f32; 4]) {
+        let offset = self.pixel_offset(x, y);
+        let component_type = self.component_type();
+        let size = component_type.size_bytes();
+        let [r, g, b, a] = rgba;
+        let values: &[f32] = match self.pixel_format() {
+            PixelFormat::R => &[r],
+            PixelFormat::RG => &[r, a],
+            PixelFormat::RGB => &[r, g, b],
+            PixelFormat::RGBA => &[r, g, b, a],
+            PixelFormat::BGR => &[b, g, r],
+            PixelFormat::BGRA => &[b, g, r, a],
+        };
+
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let pixel = &mut self.data_mut()[offset..offset + bytes_per_pixel];
+        for (component, &value) in pixel.chunks_exact_mut(size).zip(values) {
+            write_normalized(component, component_type, value);
+        }
+    }
+
+    /// Read a pixel in the storage type of the image
+    ///
+    /// Channels are returned in RGBA order like [`Image::read_pixel_f32`]:
+    /// BGR(A) is swapped, grayscale is replicated to R, G and B, and alpha is
+    /// opaque (the maximum of integer types, 1.0 for floats) for formats
+    /// without alpha.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, Pixel, PixelFormat, Rgba};
+    ///
+    /// let mut image = Image::new(16, 16, PixelFormat::BGRA, ComponentType::U16);
+    /// image.set_pixel(3, 4, Rgba::new(65535u16, 32768, 0, 65535));
+    /// match image.get_pixel(3, 4) {
+    ///     Pixel::U16(Rgba { r, g, .. }) => assert_eq!((r, g), (65535, 32768)),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn get_pixel(&self, x: u32, y: u32) -> Pixel {
+        let offset = self.pixel_offset(x, y);
+        let pixel = &self.data()[offset..offset + self.bytes_per_pixel()];
+        let pixel_format = self.pixel_format();
+        match self.component_type() {
+            ComponentType::U8 => Pixel::U8(read_rgba(pixel, pixel_format)),
+            ComponentType::U16 => Pixel::U16(read_rgba(pixel, pixel_format)),
+            ComponentType::F16 => Pixel::F16(read_rgba(pixel, pixel_format)),
+            ComponentType::F32 => Pixel::F32(read_rgba(pixel, pixel_format)),
+            ComponentType::I8 => Pixel::I8(read_rgba(pixel, pixel_format)),
+            ComponentType::I16 => Pixel::I16(read_rgba(pixel, pixel_format)),
+        }
+    }
+
+    /// Write a pixel given in RGBA order
+    ///
+    /// Values of the image component type are stored as is; other types go
+    /// through normalized floats like [`Image::write_pixel_f32`] (e.g. a U8
+    /// 255 becomes a U16 65535). Channels missing from the pixel format are
+    /// ignored; grayscale formats store the red value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    pub fn set_pixel(&mut self, x: u32, y: u32, pixel: impl Into<Pixel>) {
+        let pixel = pixel.into();
+        if pixel.component_type() != self.component_type() {
+            let Rgba { r, g, b, a } = pixel.to_f32();
+            return self.write_pixel_f32(x, y, [r, g, b, a]);
+        }
+
+        let offset = self.pixel_offset(x, y);
+        let pixel_format = self.pixel_format();
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let target = &mut self.data_mut()[offset..offset + bytes_per_pixel];
+        match pixel {
+            Pixel::U8(rgba) => write_rgba(target, pixel_format, rgba),
+            Pixel::U16(rgba) => write_rgba(target, pixel_format, rgba),
+            Pixel::F16(rgba) => write_rgba(target, pixel_format, rgba),
+            Pixel::F32(rgba) => write_rgba(target, pixel_format, rgba),
+            Pixel::I8(rgba) => write_rgba(target, pixel_format, rgba),
+            Pixel::I16(rgba) => write_rgba(target, pixel_format, rgba),
+        }
+    }
+
+    /// Get the bytes of one row
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside the image.
+    pub fn row(&self, y: u32) -> &[u8] {
+        assert!(y < self.height(), "row {} out of bounds for height {}", y, self.height());
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        &self.data()[y as usize * row_bytes..(y as usize + 1) * row_bytes]
+    }
+
+    /// Get the bytes of one row mutably
+    ///
+    /// Copies the pixel data first if it is shared with another image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside the image.
+    pub fn row_mut(&mut self, y: u32) -> &mut [u8] {
+        assert!(y < self.height(), "row {} out of bounds for height {}", y, self.height());
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        &mut self.data_mut()[y as usize * row_bytes..(y as usize + 1) * row_bytes]
+    }
+
+    /// Iterate over the bytes of every row, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        let row_bytes = (self.width() as usize * self.bytes_per_pixel()).max(1);
+        self.data().chunks_exact(row_bytes).take(self.height() as usize)
+    }
+
+    /// Iterate over the pixels of one row, as [`Image::get_pixel`] returns them
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside the image.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let image = GalaxyImage::load_from_file("strip.png")?;
+    /// for pixel in image.row_pixels(0) {
+    ///     println!("{:?}", pixel.to_f32());
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn row_pixels(&self, y: u32) -> impl Iterator<Item = Pixel> + '_ {
+        assert!(y < self.height(), "row {} out of bounds for height {}", y, self.height());
+        (0..self.width()).map(move |x| self.get_pixel(x, y))
+    }
+}
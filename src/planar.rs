@@ -0,0 +1,169 @@
+/// Planar (per-channel contiguous) pixel storage
+use crate::buffer_pool;
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+
+/// Image with each channel stored as a separate contiguous plane
+///
+/// Planes follow the channel storage order of the pixel format (e.g. B, G, R
+/// for BGR) and are stored back to back in one buffer. Components use the same
+/// little-endian encoding as [`Image`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanarImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    pixel_format: PixelFormat,
+    component_type: ComponentType,
+}
+
+impl PlanarImage {
+    /// Create a planar image from raw plane data
+    ///
+    /// Returns an error if the buffer size does not match the dimensions and format.
+    pub fn from_raw(
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        component_type: ComponentType,
+    ) -> ImageResult<Self> {
+        let expected = width as usize
+            * height as usize
+            * pixel_format.channel_count()
+            * component_type.size_bytes();
+        if data.len() != expected {
+            return Err(ImageError::Other(format!(
+                "Planar data is {} bytes, expected {} for {}x{} {:?} {:?}",
+                data.len(), expected, width, height, pixel_format, component_type
+            )));
+        }
+
+        Ok(Self { data, width, height, pixel_format, component_type })
+    }
+
+    /// Get image width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get image height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get pixel format
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Get component type
+    pub fn component_type(&self) -> ComponentType {
+        self.component_type
+    }
+
+    /// Size in bytes of one plane
+    pub fn plane_size(&self) -> usize {
+        self.width as usize * self.height as usize * self.component_type.size_bytes()
+    }
+
+    /// Get one plane in channel storage order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not below the channel count.
+    pub fn plane(&self, channel: usize) -> &[u8] {
+        let size = self.plane_size();
+        &self.data[channel * size..(channel + 1) * size]
+    }
+
+    /// Get one plane mutably
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not below the channel count.
+    pub fn plane_mut(&mut self, channel: usize) -> &mut [u8] {
+        let size = self.plane_size();
+        &mut self.data[channel * size..(channel + 1) * size]
+    }
+
+    /// Iterate over all planes in channel storage order
+    pub fn planes(&self) -> impl Iterator<Item = &[u8]> {
+        // max(1) keeps chunks_exact valid for empty images
+        self.data.chunks_exact(self.plane_size().max(1))
+    }
+
+    /// Get all planes as one buffer
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the plane buffer as owned Vec
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Interleave the planes into an [`Image`]
+    pub fn to_interleaved(&self) -> Image {
+        let size = self.component_type.size_bytes();
+        let channel_count = self.pixel_format.channel_count();
+        let mut image = Image::new(self.width, self.height, self.pixel_format, self.component_type);
+        let pixel_bytes = channel_count * size;
+
+        let output = image.data_mut();
+        for (channel, plane) in self.planes().enumerate() {
+            for (pixel, sample) in output.chunks_exact_mut(pixel_bytes).zip(plane.chunks_exact(size)) {
+                pixel[channel * size..(channel + 1) * size].copy_from_slice(sample);
+            }
+        }
+        image
+    }
+}
+
+impl Image {
+    /// Convert to planar storage (one contiguous plane per channel)
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let image = GalaxyImage::load_from_file("frame.png")?;
+    /// let planar = image.to_planar();
+    /// let red = planar.plane(0);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn to_planar(&self) -> PlanarImage {
+        let size = self.component_type().size_bytes();
+        let channel_count = self.pixel_format().channel_count();
+        let pixel_bytes = channel_count * size;
+        let plane_size = self.width() as usize * self.height() as usize * size;
+
+        let mut data = buffer_pool::take_zeroed(plane_size * channel_count);
+        if plane_size > 0 {
+            for (channel, plane) in data.chunks_exact_mut(plane_size).enumerate() {
+                for (sample, pixel) in plane.chunks_exact_mut(size).zip(self.data().chunks_exact(pixel_bytes)) {
+                    sample.copy_from_slice(&pixel[channel * size..(channel + 1) * size]);
+                }
+            }
+        }
+
+        PlanarImage {
+            data,
+            width: self.width(),
+            height: self.height(),
+            pixel_format: self.pixel_format(),
+            component_type: self.component_type(),
+        }
+    }
+}
+
+impl From<&PlanarImage> for Image {
+    fn from(planar: &PlanarImage) -> Self {
+        planar.to_interleaved()
+    }
+}
+
+impl From<&Image> for PlanarImage {
+    fn from(image: &Image) -> Self {
+        image.to_planar()
+    }
+}
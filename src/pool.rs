@@ -0,0 +1,135 @@
+/// Background decode worker pool
+use crate::{GalaxyImage, Image, ImageError, ImageResult};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Pending result of a background decode
+///
+/// Returned by [`GalaxyImage::load_async_pooled`].
+#[derive(Debug)]
+pub struct ImageFuture {
+    path: PathBuf,
+    receiver: Receiver<ImageResult<Image>>,
+    taken: bool,
+}
+
+impl ImageFuture {
+    /// Get the path being decoded
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Block until the image is decoded
+    pub fn wait(self) -> ImageResult<Image> {
+        self.receiver.recv().unwrap_or_else(|_| Err(worker_stopped()))
+    }
+
+    /// Get the result if the decode has finished, without blocking
+    ///
+    /// Returns `None` while the decode is pending. The result is returned once;
+    /// later calls return `None`.
+    pub fn try_wait(&mut self) -> Option<ImageResult<Image>> {
+        if self.taken {
+            return None;
+        }
+        let result = match self.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(worker_stopped()),
+        };
+        self.taken = true;
+        Some(result)
+    }
+}
+
+fn worker_stopped() -> ImageError {
+    ImageError::Other("Decode worker stopped before finishing".to_string())
+}
+
+/// Fixed set of worker threads consuming a shared job queue
+struct WorkerPool {
+    sender: Sender<Job>,
+    size: usize,
+}
+
+impl WorkerPool {
+    fn new(size: usize) -> Self {
+        let size = size.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..size {
+            let receiver = Arc::clone(&receiver);
+            // Workers exit once the pool is replaced and the queue is drained
+            let spawned = thread::Builder::new()
+                .name(format!("galaxy_image-decode-{}", index))
+                .spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    match job {
+                        // A panicking decode must not take the worker down with it
+                        Ok(job) => {
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => break,
+                    }
+                });
+            if spawned.is_err() {
+                break;
+            }
+        }
+
+        Self { sender, size }
+    }
+}
+
+fn default_worker_count() -> usize {
+    thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+/// Pool shared by the `GalaxyImage` manager, started on first use
+fn global_pool() -> &'static Mutex<Option<WorkerPool>> {
+    static POOL: OnceLock<Mutex<Option<WorkerPool>>> = OnceLock::new();
+    POOL.get_or_init(|| Mutex::new(None))
+}
+
+fn lock_global_pool() -> MutexGuard<'static, Option<WorkerPool>> {
+    global_pool().lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn load_async_pooled(path: &Path) -> ImageFuture {
+    let (result_tx, result_rx) = mpsc::channel();
+    let job_path = path.to_path_buf();
+    let job: Job = Box::new(move || {
+        let _ = result_tx.send(GalaxyImage::load_from_file(&job_path));
+    });
+
+    let mut pool = lock_global_pool();
+    let pool = pool.get_or_insert_with(|| WorkerPool::new(default_worker_count()));
+    // If every worker failed to start, the job is dropped and the future reports it
+    let _ = pool.sender.send(job);
+
+    ImageFuture {
+        path: path.to_path_buf(),
+        receiver: result_rx,
+        taken: false,
+    }
+}
+
+pub(crate) fn set_decode_threads(count: usize) {
+    // Previous workers finish the jobs already queued, then exit
+    *lock_global_pool() = Some(WorkerPool::new(count));
+}
+
+pub(crate) fn decode_threads() -> usize {
+    lock_global_pool()
+        .as_ref()
+        .map_or_else(default_worker_count, |pool| pool.size)
+}
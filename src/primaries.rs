@@ -0,0 +1,181 @@
+/// Color primaries and gamut conversion between wide-gamut color spaces
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ColorSpace, Image, PixelFormat};
+
+type Matrix = [[f64; 3]; 3];
+
+/// Bradford cone response matrix for chromatic adaptation
+const BRADFORD: Matrix = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// RGB primaries and white point of a color space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Primaries {
+    /// ITU-R BT.709, shared by sRGB (D65 white)
+    #[default]
+    Rec709,
+
+    /// ITU-R BT.2020, used by HDR10 and HLG (D65 white)
+    Rec2020,
+
+    /// DCI-P3 digital cinema (DCI white)
+    DciP3,
+
+    /// ACEScg working space, ACES AP1 primaries (ACES white, about D60)
+    AcesCg,
+}
+
+impl Primaries {
+    /// xy chromaticities of red, green, blue and white
+    fn chromaticities(self) -> [[f64; 2]; 4] {
+        match self {
+            Primaries::Rec709 => [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06], [0.3127, 0.3290]],
+            Primaries::Rec2020 => [[0.708, 0.292], [0.170, 0.797], [0.131, 0.046], [0.3127, 0.3290]],
+            Primaries::DciP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060], [0.314, 0.351]],
+            Primaries::AcesCg => [[0.713, 0.293], [0.165, 0.830], [0.128, 0.044], [0.32168, 0.33767]],
+        }
+    }
+
+    /// Matrix from linear RGB in these primaries to CIE XYZ
+    fn to_xyz(self) -> Matrix {
+        let [red, green, blue, white] = self.chromaticities();
+        let xyz = |[x, y]: [f64; 2]| [x / y, 1.0, (1.0 - x - y) / y];
+        let (r, g, b) = (xyz(red), xyz(green), xyz(blue));
+        let columns = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+        // Scale the primaries so RGB (1, 1, 1) maps to the white point
+        let scale = multiply_vector(&invert(&columns), xyz(white));
+        std::array::from_fn(|row| std::array::from_fn(|column| columns[row][column] * scale[column]))
+    }
+
+    /// Matrix converting linear RGB from `from` primaries to `to` primaries
+    ///
+    /// White points are adapted with the Bradford transform, so white stays
+    /// `(1, 1, 1)`. Multiply column vectors: `rgb_to = matrix * rgb_from`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::Primaries;
+    ///
+    /// let matrix = Primaries::conversion_matrix(Primaries::Rec709, Primaries::Rec2020);
+    /// assert!((matrix[0][0] - 0.6274).abs() < 1e-4);
+    /// ```
+    pub fn conversion_matrix(from: Primaries, to: Primaries) -> [[f32; 3]; 3] {
+        let source_white = multiply_vector(&from.to_xyz(), [1.0; 3]);
+        let target_white = multiply_vector(&to.to_xyz(), [1.0; 3]);
+        let adaptation = bradford_adaptation(source_white, target_white);
+
+        let matrix = multiply(&invert(&to.to_xyz()), &multiply(&adaptation, &from.to_xyz()));
+        matrix.map(|row| row.map(|value| value as f32))
+    }
+}
+
+/// D50 white of the ICC profile connection space
+const PCS_WHITE: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// Matrix from linear RGB with the given D50-adapted XYZ colorants (the
+/// columns of an ICC matrix profile) to linear Rec.709 (D65)
+pub(crate) fn pcs_to_rec709(colorants: [[f64; 3]; 3]) -> [[f32; 3]; 3] {
+    let rec709 = Primaries::Rec709.to_xyz();
+    let adaptation = bradford_adaptation(PCS_WHITE, multiply_vector(&rec709, [1.0; 3]));
+    let matrix = multiply(&invert(&rec709), &multiply(&adaptation, &colorants));
+    matrix.map(|row| row.map(|value| value as f32))
+}
+
+/// Chromatic adaptation of XYZ values from one white point to another
+fn bradford_adaptation(source_white: [f64; 3], target_white: [f64; 3]) -> Matrix {
+    // Scale cone responses from the source white to the target white
+    let source_cone = multiply_vector(&BRADFORD, source_white);
+    let target_cone = multiply_vector(&BRADFORD, target_white);
+    let mut scale = [[0.0; 3]; 3];
+    for c in 0..3 {
+        scale[c][c] = target_cone[c] / source_cone[c];
+    }
+    multiply(&invert(&BRADFORD), &multiply(&scale, &BRADFORD))
+}
+
+impl ColorSpace {
+    /// Primaries of the color space (Rec.709 for sRGB and linear, Rec.2020 for PQ and HLG)
+    pub fn primaries(self) -> Primaries {
+        match self {
+            ColorSpace::Srgb | ColorSpace::Linear => Primaries::Rec709,
+            ColorSpace::Pq | ColorSpace::Hlg => Primaries::Rec2020,
+        }
+    }
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    std::array::from_fn(|row| std::array::from_fn(|column| (0..3).map(|k| a[row][k] * b[k][column]).sum()))
+}
+
+fn multiply_vector(matrix: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    matrix.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let adjugate = [
+        [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+        [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+        [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+    ];
+    let determinant = m[0][0] * adjugate[0][0] + m[0][1] * adjugate[1][0] + m[0][2] * adjugate[2][0];
+    adjugate.map(|row| row.map(|value| value / determinant))
+}
+
+impl Image {
+    /// Convert linear color values from one set of primaries to another
+    ///
+    /// Applies [`Primaries::conversion_matrix`] to every pixel; alpha is kept
+    /// and grayscale images are left unchanged. Values must be linear: decode
+    /// them first with [`Image::convert_transfer`] if needed. Colors outside the
+    /// target gamut get negative components, which 8 and 16-bit images clamp to 0.
+    ///
+    /// # Arguments
+    /// * `from` - Primaries the values are expressed in
+    /// * `to` - Primaries to express them in
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{ColorSpace, GalaxyImage, ImageFormat, LdrConversion, Primaries, SaveOptions};
+    ///
+    /// // ACEScg render to an sRGB texture
+    /// let mut render = GalaxyImage::load_from_file("render_acescg.exr")?;
+    /// render.convert_color_space(Primaries::AcesCg, Primaries::Rec709);
+    /// let options = SaveOptions { ldr_conversion: LdrConversion::Tonemap, ..Default::default() };
+    /// GalaxyImage::save_to_file_with_options(&render, "render.png", ImageFormat::Png, &options)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn convert_color_space(&mut self, from: Primaries, to: Primaries) {
+        let (red, blue) = match self.pixel_format() {
+            PixelFormat::R | PixelFormat::RG => return,
+            PixelFormat::RGB | PixelFormat::RGBA => (0, 2),
+            PixelFormat::BGR | PixelFormat::BGRA => (2, 0),
+        };
+        if from == to {
+            return;
+        }
+
+        let matrix = Primaries::conversion_matrix(from, to);
+        let component_type = self.component_type();
+        let channel_count = self.pixel_format().channel_count();
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        if row_bytes == 0 {
+            return;
+        }
+
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data_mut().chunks_exact_mut(row_bytes) {
+            read_normalized_slice(row, component_type, &mut values);
+            for pixel in values.chunks_exact_mut(channel_count) {
+                let rgb = [pixel[red], pixel[1], pixel[blue]];
+                let [r, g, b] = matrix.map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+                (pixel[red], pixel[1], pixel[blue]) = (r, g, b);
+            }
+            write_normalized_slice(row, component_type, &values);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+/// Multi-resolution tile pyramids for virtual texturing
+use crate::resample::box_downscale;
+use crate::{atomic_write, GalaxyImage, Image, ImageError, ImageFormat, ImageResult, SaveOptions};
+use std::fs;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of a packed pyramid file
+pub const PYRAMID_MAGIC: [u8; 4] = *b"GXPY";
+
+/// Version of the packed pyramid layout
+pub const PYRAMID_VERSION: u32 = 1;
+
+/// Size of one tile entry in the packed pyramid index
+const INDEX_ENTRY_SIZE: usize = 4 * 3 + 8 * 2;
+
+/// Options controlling how a tile pyramid is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyramidOptions {
+    /// Size of the tile content in pixels (tiles are square)
+    pub tile_size: u32,
+
+    /// Border width added on every side of a tile, for filtering across tiles
+    pub overlap: u32,
+
+    /// Format used to encode tiles when writing them
+    pub format: ImageFormat,
+
+    /// Encoding options used when writing tiles
+    pub save_options: SaveOptions,
+}
+
+impl Default for PyramidOptions {
+    fn default() -> Self {
+        Self {
+            tile_size: 256,
+            overlap: 1,
+            format: ImageFormat::Png,
+            save_options: SaveOptions::default(),
+        }
+    }
+}
+
+/// Position of a tile in a pyramid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileCoord {
+    /// Level index (0 is full resolution, each next level halves the size)
+    pub level: u32,
+
+    /// Tile column
+    pub x: u32,
+
+    /// Tile row
+    pub y: u32,
+}
+
+/// One tile of a pyramid
+#[derive(Debug, Clone)]
+pub struct PyramidTile {
+    /// Position of the tile
+    pub coord: TileCoord,
+
+    /// Tile pixels, `tile_size + 2 * overlap` pixels square
+    pub image: Image,
+}
+
+/// Compute the size of every pyramid level
+///
+/// Levels halve (rounding up) until the level fits in a single tile.
+pub fn pyramid_levels(width: u32, height: u32, tile_size: u32) -> Vec<(u32, u32)> {
+    let tile_size = tile_size.max(1);
+    let mut levels = vec![(width, height)];
+    let (mut w, mut h) = (width, height);
+    while w > tile_size || h > tile_size {
+        w = w.div_ceil(2);
+        h = h.div_ceil(2);
+        levels.push((w, h));
+    }
+    levels
+}
+
+fn validate(image: &Image, options: &PyramidOptions) -> ImageResult<()> {
+    if options.tile_size == 0 {
+        return Err(ImageError::Other("Pyramid tile size must be greater than zero".to_string()));
+    }
+    if image.width() == 0 || image.height() == 0 {
+        return Err(ImageError::InvalidDimensions { width: image.width(), height: image.height() });
+    }
+    Ok(())
+}
+
+/// Copy a region into a new image, replicating edge pixels outside the source
+fn copy_region_clamped(image: &Image, x0: i64, y0: i64, width: u32, height: u32) -> Image {
+    let bytes_per_pixel = image.bytes_per_pixel();
+    let src_width = image.width() as i64;
+    let src_height = image.height() as i64;
+    let src = image.data();
+
+    let mut output = Image::new(width, height, image.pixel_format(), image.component_type());
+    let row_bytes = width as usize * bytes_per_pixel;
+    for (dy, row) in output.data_mut().chunks_exact_mut(row_bytes).enumerate() {
+        let sy = (y0 + dy as i64).clamp(0, src_height - 1) as usize;
+        let src_row = &src[sy * src_width as usize * bytes_per_pixel..][..src_width as usize * bytes_per_pixel];
+        for (dx, pixel) in row.chunks_exact_mut(bytes_per_pixel).enumerate() {
+            let sx = (x0 + dx as i64).clamp(0, src_width - 1) as usize;
+            pixel.copy_from_slice(&src_row[sx * bytes_per_pixel..(sx + 1) * bytes_per_pixel]);
+        }
+    }
+    output
+}
+
+pub(crate) fn build_pyramid<F>(image: &Image, options: &PyramidOptions, mut callback: F) -> ImageResult<()>
+where
+    F: FnMut(PyramidTile) -> ImageResult<()>,
+{
+    validate(image, options)?;
+
+    let tile_size = options.tile_size;
+    let overlap = options.overlap as i64;
+    let padded = tile_size + 2 * options.overlap;
+    let levels = pyramid_levels(image.width(), image.height(), tile_size);
+
+    // Each level is downscaled from the previous one, not from the source
+    let mut current: Option<Image> = None;
+    for (level, &(width, height)) in levels.iter().enumerate() {
+        let level_image = match current.take() {
+            None => image.clone(),
+            Some(previous) => box_downscale(&previous, width, height),
+        };
+
+        for y in 0..height.div_ceil(tile_size) {
+            for x in 0..width.div_ceil(tile_size) {
+                let x0 = (x * tile_size) as i64 - overlap;
+                let y0 = (y * tile_size) as i64 - overlap;
+                callback(PyramidTile {
+                    coord: TileCoord { level: level as u32, x, y },
+                    image: copy_region_clamped(&level_image, x0, y0, padded, padded),
+                })?;
+            }
+        }
+
+        current = Some(level_image);
+    }
+    Ok(())
+}
+
+pub(crate) fn export_pyramid<F>(image: &Image, options: &PyramidOptions, mut naming: F) -> ImageResult<Vec<PathBuf>>
+where
+    F: FnMut(TileCoord) -> PathBuf,
+{
+    let mut paths = Vec::new();
+    build_pyramid(image, options, |tile| {
+        let path = naming(tile.coord);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = GalaxyImage::save_to_bytes_with_options(&tile.image, options.format, &options.save_options)?;
+        atomic_write::write_file(&path, &bytes, options.save_options.atomic)?;
+        paths.push(path);
+        Ok(())
+    })?;
+    Ok(paths)
+}
+
+/// Format identifier stored in packed pyramid headers
+fn format_id(format: ImageFormat) -> u32 {
+    match format {
+        ImageFormat::Png => 0,
+        ImageFormat::Bmp => 1,
+        ImageFormat::Jpeg => 2,
+        ImageFormat::Exr => 3,
+        ImageFormat::Tga => 4,
+        ImageFormat::Dds => 5,
+        ImageFormat::Hdr => 6,
+        ImageFormat::Tiff => 7,
+        ImageFormat::WebP => 8,
+        ImageFormat::Gif => 9,
+        ImageFormat::Ico => 10,
+        ImageFormat::Pnm => 11,
+        ImageFormat::Pfm => 12,
+        ImageFormat::Jxl => 13,
+        ImageFormat::Avif => 14,
+        ImageFormat::Psd => 15,
+        ImageFormat::Unknown => u32::MAX,
+    }
+}
+
+pub(crate) fn export_pyramid_packed(image: &Image, path: &Path, options: &PyramidOptions) -> ImageResult<()> {
+    validate(image, options)?;
+
+    let levels = pyramid_levels(image.width(), image.height(), options.tile_size);
+    let tile_count: usize = levels
+        .iter()
+        .map(|&(w, h)| (w.div_ceil(options.tile_size) * h.div_ceil(options.tile_size)) as usize)
+        .sum();
+
+    atomic_write::write_file_with(path, options.save_options.atomic, |file| {
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&PYRAMID_MAGIC)?;
+        for value in [
+            PYRAMID_VERSION,
+            image.width(),
+            image.height(),
+            options.tile_size,
+            options.overlap,
+            format_id(options.format),
+            levels.len() as u32,
+            tile_count as u32,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        // Reserve the index, then stream tiles so encoded data is never held in memory
+        let index_start = writer.stream_position()?;
+        writer.write_all(&vec![0u8; INDEX_ENTRY_SIZE * tile_count])?;
+
+        let mut offset = index_start + (INDEX_ENTRY_SIZE * tile_count) as u64;
+        let mut index = Vec::with_capacity(INDEX_ENTRY_SIZE * tile_count);
+        build_pyramid(image, options, |tile| {
+            let bytes = GalaxyImage::save_to_bytes_with_options(&tile.image, options.format, &options.save_options)?;
+            writer.write_all(&bytes)?;
+
+            index.extend_from_slice(&tile.coord.level.to_le_bytes());
+            index.extend_from_slice(&tile.coord.x.to_le_bytes());
+            index.extend_from_slice(&tile.coord.y.to_le_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            offset += bytes.len() as u64;
+            Ok(())
+        })?;
+
+        writer.seek(SeekFrom::Start(index_start))?;
+        writer.write_all(&index)?;
+        writer.flush()?;
+        Ok(())
+    })
+}
@@ -0,0 +1,256 @@
+/// Flood fill and connected-component labeling
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+
+/// Pixel neighborhood used to connect regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Connectivity {
+    /// Horizontal and vertical neighbors
+    #[default]
+    Four,
+
+    /// Horizontal, vertical and diagonal neighbors
+    Eight,
+}
+
+/// Statistics of one connected component
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComponentStats {
+    /// Label of the component in [`ComponentLabels::labels`] (starting at 1)
+    pub label: u32,
+
+    /// Number of pixels
+    pub pixel_count: usize,
+
+    /// Smallest x coordinate
+    pub min_x: u32,
+
+    /// Smallest y coordinate
+    pub min_y: u32,
+
+    /// Largest x coordinate (inclusive)
+    pub max_x: u32,
+
+    /// Largest y coordinate (inclusive)
+    pub max_y: u32,
+
+    /// Mean pixel position (x, y)
+    pub centroid: (f32, f32),
+}
+
+/// Result of [`Image::connected_components`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentLabels {
+    /// Width of the labeled image
+    pub width: u32,
+
+    /// Height of the labeled image
+    pub height: u32,
+
+    /// Label of every pixel in row order (0 for background)
+    pub labels: Vec<u32>,
+
+    /// Statistics of every component, in label order
+    pub components: Vec<ComponentStats>,
+}
+
+impl ComponentLabels {
+    /// Get the label of a pixel (0 for background)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    pub fn label_at(&self, x: u32, y: u32) -> u32 {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) out of bounds", x, y);
+        self.labels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Convert the labels to an R/F32 image (exact for up to 2^24 labels)
+    pub fn to_image(&self) -> Image {
+        let data = self.labels.iter().flat_map(|&label| (label as f32).to_le_bytes()).collect();
+        Image::from_raw(data, self.width, self.height, PixelFormat::R, ComponentType::F32)
+    }
+}
+
+impl Image {
+    /// Fill the region connected to a seed pixel with a color
+    ///
+    /// Fills the 4-connected pixels whose normalized RGBA values (as returned by
+    /// [`Image::read_pixel_f32`]) differ from the seed pixel by at most
+    /// `tolerance` in every channel. Returns the number of filled pixels.
+    ///
+    /// # Arguments
+    /// * `x`, `y` - Seed pixel
+    /// * `color` - Fill color as normalized RGBA
+    /// * `tolerance` - Maximum per-channel difference to the seed (0.0 for exact matches)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is outside the image.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let mut image = GalaxyImage::load_from_file("lineart.png")?;
+    /// let filled = image.flood_fill(10, 10, [1.0, 0.0, 0.0, 1.0], 0.05);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn flood_fill(&mut self, x: u32, y: u32, color: [f32; 4], tolerance: f32) -> usize {
+        let seed = self.read_pixel_f32(x, y);
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+        let matches = |image: &Image, px: usize, py: usize| {
+            let pixel = image.read_pixel_f32(px as u32, py as u32);
+            pixel.iter().zip(&seed).all(|(a, b)| (a - b).abs() <= tolerance)
+        };
+
+        // Encode the fill color once
+        let mut encoded = Image::new(1, 1, self.pixel_format(), self.component_type());
+        encoded.write_pixel_f32(0, 0, color);
+        let fill = encoded.data().to_vec();
+        let bytes_per_pixel = self.bytes_per_pixel();
+
+        // Scanline fill: find the run of each popped pixel, queue the rows around it
+        let mut visited = vec![false; width * height];
+        let mut stack = vec![(x as usize, y as usize)];
+        let mut filled = 0;
+        while let Some((sx, sy)) = stack.pop() {
+            if visited[sy * width + sx] || !matches(self, sx, sy) {
+                continue;
+            }
+
+            let mut left = sx;
+            while left > 0 && !visited[sy * width + left - 1] && matches(self, left - 1, sy) {
+                left -= 1;
+            }
+            let mut right = sx;
+            while right + 1 < width && !visited[sy * width + right + 1] && matches(self, right + 1, sy) {
+                right += 1;
+            }
+
+            let data = self.data_mut();
+            for px in left..=right {
+                visited[sy * width + px] = true;
+                let offset = (sy * width + px) * bytes_per_pixel;
+                data[offset..offset + bytes_per_pixel].copy_from_slice(&fill);
+            }
+            filled += right - left + 1;
+
+            for ny in [sy.checked_sub(1), Some(sy + 1).filter(|&ny| ny < height)].into_iter().flatten() {
+                for px in left..=right {
+                    if !visited[ny * width + px] {
+                        stack.push((px, ny));
+                    }
+                }
+            }
+        }
+        filled
+    }
+
+    /// Label the connected regions of a mask
+    ///
+    /// Works on single-channel (R) images; nonzero pixels are foreground. Labels
+    /// start at 1 in the order components are first met scanning rows top to
+    /// bottom, 0 is background.
+    ///
+    /// # Arguments
+    /// * `connectivity` - Whether diagonal neighbors are connected
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{Connectivity, GalaxyImage};
+    ///
+    /// let coverage = GalaxyImage::load_from_file("lightmap_uv_mask.png")?;
+    /// let islands = coverage.connected_components(Connectivity::Eight)?;
+    /// for island in &islands.components {
+    ///     println!("island {}: {} px, bbox {}..={} x {}..={}",
+    ///         island.label, island.pixel_count, island.min_x, island.max_x, island.min_y, island.max_y);
+    /// }
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn connected_components(&self, connectivity: Connectivity) -> ImageResult<ComponentLabels> {
+        if self.pixel_format() != PixelFormat::R {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "Connected components require a single-channel (R) mask image, got {:?}",
+                self.pixel_format()
+            )));
+        }
+
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+        let size = self.component_type().size_bytes();
+        let foreground: Vec<bool> = self
+            .data()
+            .chunks_exact(size)
+            .map(|sample| match self.component_type() {
+                ComponentType::F16 => half::f16::from_le_bytes([sample[0], sample[1]]).to_f32() != 0.0,
+                ComponentType::F32 => f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]) != 0.0,
+                ComponentType::U8 | ComponentType::U16 | ComponentType::I8 | ComponentType::I16 => {
+                    sample.iter().any(|&b| b != 0)
+                }
+            })
+            .collect();
+
+        let offsets: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        };
+
+        let mut labels = vec![0u32; width * height];
+        let mut components = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..labels.len() {
+            if !foreground[start] || labels[start] != 0 {
+                continue;
+            }
+
+            let label = components.len() as u32 + 1;
+            let mut stats = ComponentStats {
+                label,
+                pixel_count: 0,
+                min_x: u32::MAX,
+                min_y: u32::MAX,
+                max_x: 0,
+                max_y: 0,
+                centroid: (0.0, 0.0),
+            };
+            let (mut sum_x, mut sum_y) = (0f64, 0f64);
+
+            labels[start] = label;
+            stack.push(start);
+            while let Some(index) = stack.pop() {
+                let (px, py) = (index % width, index / width);
+                stats.pixel_count += 1;
+                stats.min_x = stats.min_x.min(px as u32);
+                stats.min_y = stats.min_y.min(py as u32);
+                stats.max_x = stats.max_x.max(px as u32);
+                stats.max_y = stats.max_y.max(py as u32);
+                sum_x += px as f64;
+                sum_y += py as f64;
+
+                for &(dx, dy) in offsets {
+                    let (nx, ny) = (px as isize + dx, py as isize + dy);
+                    if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                        continue;
+                    }
+                    let neighbor = ny as usize * width + nx as usize;
+                    if foreground[neighbor] && labels[neighbor] == 0 {
+                        labels[neighbor] = label;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            let count = stats.pixel_count as f64;
+            stats.centroid = ((sum_x / count) as f32, (sum_y / count) as f32);
+            components.push(stats);
+        }
+
+        Ok(ComponentLabels {
+            width: self.width(),
+            height: self.height(),
+            labels,
+            components,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+/// Internal resampling helpers shared by thumbnails and downscaling operations
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::instrument;
+use crate::Image;
+
+/// Downscale an image by averaging the source pixels covered by each output pixel
+///
+/// Each output pixel averages the integer block of source pixels it maps to.
+/// Works for every component type; samples are accumulated as normalized f64
+/// values and never pass through an 8-bit intermediate, so U16, F16 and F32
+/// images keep their precision and float images keep values outside `0.0..=1.0`.
+pub(crate) fn box_downscale(image: &Image, width: u32, height: u32) -> Image {
+    instrument::convert("box_downscale", image, || box_downscale_impl(image, width, height))
+}
+
+fn box_downscale_impl(image: &Image, width: u32, height: u32) -> Image {
+    let width = width.clamp(1, image.width().max(1));
+    let height = height.clamp(1, image.height().max(1));
+
+    let component_type = image.component_type();
+    let channel_count = image.pixel_format().channel_count();
+    let src_width = image.width() as usize;
+    let src_height = image.height() as usize;
+    let src_row_bytes = src_width * image.bytes_per_pixel();
+    let src = image.data();
+
+    let mut output = Image::new(width, height, image.pixel_format(), component_type);
+    output.set_alpha_mode(image.alpha_mode());
+    output.set_color_space(image.color_space());
+    output.set_metadata(image.metadata().clone());
+    let dst_width = width as usize;
+    let dst_height = height as usize;
+    let dst_row_bytes = dst_width * image.bytes_per_pixel();
+
+    // Rows are converted to normalized floats in one batch, then accumulated in f64
+    let mut src_row = vec![0f32; src_width * channel_count];
+    let mut sums = vec![0f64; dst_width * channel_count];
+    let mut averages = vec![0f32; dst_width * channel_count];
+    let dst = output.data_mut();
+
+    for dy in 0..dst_height {
+        let y0 = dy * src_height / dst_height;
+        let y1 = ((dy + 1) * src_height / dst_height).max(y0 + 1);
+
+        sums.iter_mut().for_each(|s| *s = 0.0);
+        for sy in y0..y1 {
+            read_normalized_slice(&src[sy * src_row_bytes..(sy + 1) * src_row_bytes], component_type, &mut src_row);
+            for dx in 0..dst_width {
+                let x0 = dx * src_width / dst_width;
+                let x1 = ((dx + 1) * src_width / dst_width).max(x0 + 1);
+                let sum = &mut sums[dx * channel_count..(dx + 1) * channel_count];
+                for pixel in src_row[x0 * channel_count..x1 * channel_count].chunks_exact(channel_count) {
+                    for (sum, value) in sum.iter_mut().zip(pixel) {
+                        *sum += *value as f64;
+                    }
+                }
+            }
+        }
+
+        for dx in 0..dst_width {
+            let x0 = dx * src_width / dst_width;
+            let x1 = ((dx + 1) * src_width / dst_width).max(x0 + 1);
+            let count = ((y1 - y0) * (x1 - x0)) as f64;
+            let range = dx * channel_count..(dx + 1) * channel_count;
+            for (average, sum) in averages[range.clone()].iter_mut().zip(&sums[range]) {
+                *average = (*sum / count) as f32;
+            }
+        }
+        write_normalized_slice(&mut dst[dy * dst_row_bytes..(dy + 1) * dst_row_bytes], component_type, &averages);
+    }
+
+    output
+}
@@ -0,0 +1,165 @@
+/// Typed pixel views and conversions with the `rgb` and `imgref` crates (feature `rgb`)
+use crate::{ComponentType, Image, PixelFormat};
+use bytemuck::Pod;
+use imgref::{ImgRef, ImgVec};
+use rgb::{Bgr, Bgra, Gray, GrayAlpha, Rgb, Rgba};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Pixel type of the `rgb` crate matching an image layout
+///
+/// Implemented for `Rgb`, `Rgba`, `Bgr`, `Bgra`, `Gray` and `GrayAlpha` with
+/// `u8`, `u16` and `f32` components (e.g. `rgb::RGBA8`, `rgb::RGB16`).
+pub trait RgbPixel: Pod + sealed::Sealed {
+    /// Matching pixel format
+    const PIXEL_FORMAT: PixelFormat;
+
+    /// Matching component type
+    const COMPONENT_TYPE: ComponentType;
+}
+
+macro_rules! rgb_pixels {
+    ($($component:ty => $component_type:ident;)*) => {$(
+        rgb_pixels!(@pixel Rgb<$component>, RGB, $component_type);
+        rgb_pixels!(@pixel Rgba<$component>, RGBA, $component_type);
+        rgb_pixels!(@pixel Bgr<$component>, BGR, $component_type);
+        rgb_pixels!(@pixel Bgra<$component>, BGRA, $component_type);
+        rgb_pixels!(@pixel Gray<$component>, R, $component_type);
+        rgb_pixels!(@pixel GrayAlpha<$component>, RG, $component_type);
+    )*};
+    (@pixel $pixel:ty, $pixel_format:ident, $component_type:ident) => {
+        impl sealed::Sealed for $pixel {}
+        impl RgbPixel for $pixel {
+            const PIXEL_FORMAT: PixelFormat = PixelFormat::$pixel_format;
+            const COMPONENT_TYPE: ComponentType = ComponentType::$component_type;
+        }
+    };
+}
+
+rgb_pixels! {
+    u8 => U8;
+    u16 => U16;
+    f32 => F32;
+}
+
+/// Convert little-endian component bytes to native order in place (no-op on little-endian targets)
+fn le_to_native(bytes: &mut [u8], component_size: usize) {
+    if cfg!(target_endian = "big") && component_size > 1 {
+        bytes.chunks_exact_mut(component_size).for_each(|c| c.reverse());
+    }
+}
+
+impl Image {
+    fn matches<P: RgbPixel>(&self) -> bool {
+        self.pixel_format() == P::PIXEL_FORMAT && self.component_type() == P::COMPONENT_TYPE
+    }
+
+    /// Create an image from typed pixels
+    ///
+    /// U8 pixels are moved without copying; wider components are copied to the
+    /// little-endian storage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels.len()` is not `width * height`.
+    pub fn from_pixels<P: RgbPixel>(width: u32, height: u32, pixels: Vec<P>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize,
+            "pixel count does not match {}x{}",
+            width,
+            height
+        );
+        let mut data = match bytemuck::allocation::try_cast_vec::<P, u8>(pixels) {
+            Ok(data) => data,
+            Err((_, pixels)) => bytemuck::cast_slice::<P, u8>(&pixels).to_vec(),
+        };
+        le_to_native(&mut data, P::COMPONENT_TYPE.size_bytes());
+        Image::from_raw(data, width, height, P::PIXEL_FORMAT, P::COMPONENT_TYPE)
+    }
+
+    /// Borrow the pixel data as typed pixels without copying
+    ///
+    /// Returns `None` if the pixel format or component type does not match `P`,
+    /// or if the buffer cannot be viewed in place (multi-byte components on a
+    /// misaligned buffer or a big-endian target). U8 images can always be viewed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    /// use rgb::RGBA8;
+    ///
+    /// let image = GalaxyImage::load_from_file("sprite.png").unwrap();
+    /// if let Some(pixels) = image.as_pixels::<RGBA8>() {
+    ///     let opaque = pixels.iter().filter(|p| p.a == 255).count();
+    /// }
+    /// ```
+    pub fn as_pixels<P: RgbPixel>(&self) -> Option<&[P]> {
+        if !self.matches::<P>() || (cfg!(target_endian = "big") && P::COMPONENT_TYPE.size_bytes() > 1) {
+            return None;
+        }
+        bytemuck::try_cast_slice(self.data()).ok()
+    }
+
+    /// Borrow the pixel data mutably as typed pixels without copying
+    ///
+    /// Same conditions as [`Image::as_pixels`]. Copies the pixel data first if it
+    /// is shared with another image.
+    pub fn as_pixels_mut<P: RgbPixel>(&mut self) -> Option<&mut [P]> {
+        if !self.matches::<P>() || (cfg!(target_endian = "big") && P::COMPONENT_TYPE.size_bytes() > 1) {
+            return None;
+        }
+        bytemuck::try_cast_slice_mut(self.data_mut()).ok()
+    }
+
+    /// Copy the pixel data into typed pixels
+    ///
+    /// Returns `None` if the pixel format or component type does not match `P`.
+    /// Works for every component type regardless of buffer alignment.
+    pub fn to_pixels<P: RgbPixel>(&self) -> Option<Vec<P>> {
+        if !self.matches::<P>() {
+            return None;
+        }
+        let mut pixels = vec![P::zeroed(); self.width() as usize * self.height() as usize];
+        let bytes = bytemuck::cast_slice_mut::<P, u8>(&mut pixels);
+        bytes.copy_from_slice(self.data());
+        le_to_native(bytes, P::COMPONENT_TYPE.size_bytes());
+        Some(pixels)
+    }
+
+    /// Borrow the image as an `imgref` image without copying
+    ///
+    /// Same conditions as [`Image::as_pixels`].
+    pub fn as_imgref<P: RgbPixel>(&self) -> Option<ImgRef<'_, P>> {
+        let pixels = self.as_pixels::<P>()?;
+        Some(ImgRef::new(pixels, self.width() as usize, self.height() as usize))
+    }
+
+    /// Copy the image into an `imgref` image
+    ///
+    /// Same conditions as [`Image::to_pixels`].
+    pub fn to_imgvec<P: RgbPixel>(&self) -> Option<ImgVec<P>> {
+        let pixels = self.to_pixels::<P>()?;
+        Some(ImgVec::new(pixels, self.width() as usize, self.height() as usize))
+    }
+}
+
+/// Convert from an `imgref` image, compacting rows in place if the stride is padded
+impl<P: RgbPixel> From<ImgVec<P>> for Image {
+    fn from(img: ImgVec<P>) -> Self {
+        let (mut pixels, width, height) = img.into_contiguous_buf();
+        pixels.truncate(width * height);
+        Image::from_pixels(width as u32, height as u32, pixels)
+    }
+}
+
+/// Convert from a borrowed `imgref` image (rows are copied, stride is removed)
+impl<P: RgbPixel> From<ImgRef<'_, P>> for Image {
+    fn from(img: ImgRef<'_, P>) -> Self {
+        let pixels: Vec<P> = img.rows().flat_map(|row| row.iter().copied()).collect();
+        Image::from_pixels(img.width() as u32, img.height() as u32, pixels)
+    }
+}
@@ -0,0 +1,184 @@
+/// Region-of-interest processing
+use crate::sample::write_normalized;
+use crate::{Image, ImageError, ImageResult, PixelFormat};
+
+/// Axis-aligned rectangle in pixel coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    /// Left column
+    pub x: u32,
+
+    /// Top row
+    pub y: u32,
+
+    /// Width in pixels
+    pub width: u32,
+
+    /// Height in pixels
+    pub height: u32,
+}
+
+impl Rect {
+    /// Create a rectangle
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Rectangle covering a whole image
+    pub fn of_image(image: &Image) -> Self {
+        Self::new(0, 0, image.width(), image.height())
+    }
+
+    /// Check if the rectangle has no pixels
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Check if the rectangle lies inside a `width` x `height` image
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        self.x.checked_add(self.width).is_some_and(|end| end <= width)
+            && self.y.checked_add(self.height).is_some_and(|end| end <= height)
+    }
+
+    /// Overlap of two rectangles, or `None` if they do not overlap
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x as u64 + self.width as u64).min(other.x as u64 + other.width as u64);
+        let y1 = (self.y as u64 + self.height as u64).min(other.y as u64 + other.height as u64);
+        (x1 > x0 as u64 && y1 > y0 as u64)
+            .then(|| Rect::new(x0, y0, (x1 - x0 as u64) as u32, (y1 - y0 as u64) as u32))
+    }
+}
+
+impl Image {
+    fn check_rect(&self, rect: Rect) -> ImageResult<()> {
+        if !rect.fits(self.width(), self.height()) {
+            return Err(ImageError::Other(format!(
+                "region {}x{} at ({}, {}) out of bounds for {}x{} image",
+                rect.width, rect.height, rect.x, rect.y, self.width(), self.height()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Copy the pixels of a rectangle into a new image
+    pub(crate) fn copy_rect(&self, rect: Rect) -> ImageResult<Image> {
+        self.check_rect(rect)?;
+
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = self.width() as usize * bytes_per_pixel;
+        let rect_row_bytes = rect.width as usize * bytes_per_pixel;
+        let mut output = Image::new(rect.width, rect.height, self.pixel_format(), self.component_type());
+
+        if rect_row_bytes > 0 {
+            for (row, output_row) in output.data_mut().chunks_exact_mut(rect_row_bytes).enumerate() {
+                let start = (rect.y as usize + row) * row_bytes + rect.x as usize * bytes_per_pixel;
+                output_row.copy_from_slice(&self.data()[start..start + rect_row_bytes]);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Copy an image of the same layout into the pixels at (x, y)
+    pub(crate) fn write_rect(&mut self, x: u32, y: u32, source: &Image) -> ImageResult<()> {
+        self.check_rect(Rect::new(x, y, source.width(), source.height()))?;
+
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = self.width() as usize * bytes_per_pixel;
+        let source_row_bytes = source.width() as usize * bytes_per_pixel;
+        if source_row_bytes == 0 {
+            return Ok(());
+        }
+
+        let data = self.data_mut();
+        for (row, source_row) in source.data().chunks_exact(source_row_bytes).enumerate() {
+            let start = (y as usize + row) * row_bytes + x as usize * bytes_per_pixel;
+            data[start..start + source_row_bytes].copy_from_slice(source_row);
+        }
+        Ok(())
+    }
+
+    /// Apply an operation to a rectangle of the image only
+    ///
+    /// The operation receives the pixels of `rect` as an image of the same
+    /// layout; whatever it leaves there is written back, and the rest of the
+    /// image is untouched. Works with any in-place operation (adjustments,
+    /// posterize, flood fill, pixel writes, ...).
+    ///
+    /// Returns an error if `rect` does not fit in the image, or if the operation
+    /// changes the dimensions, pixel format or component type of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - Region to process
+    /// * `operation` - Operation applied to the region
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::{GalaxyImage, Rect};
+    ///
+    /// let mut photo = GalaxyImage::load_from_file("photo.jpg")?;
+    /// // Warm up only the face area
+    /// photo.apply_roi(Rect::new(320, 180, 256, 256), |face| {
+    ///     face.adjust_white_balance(5000.0, 0.0);
+    /// })?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn apply_roi(&mut self, rect: Rect, operation: impl FnOnce(&mut Image)) -> ImageResult<()> {
+        let mut region = self.copy_rect(rect)?;
+        operation(&mut region);
+
+        if region.width() != rect.width || region.height() != rect.height {
+            return Err(ImageError::InvalidDimensions {
+                width: region.width(),
+                height: region.height(),
+            });
+        }
+        if region.pixel_format() != self.pixel_format() || region.component_type() != self.component_type() {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "region operation changed the layout to {:?} {:?}",
+                region.pixel_format(), region.component_type()
+            )));
+        }
+
+        self.write_rect(rect.x, rect.y, &region)
+    }
+
+    /// Fill a rectangle with a color given as normalized RGBA floats
+    ///
+    /// The color is converted like [`Image::write_pixel_f32`]. Returns an error
+    /// if `rect` does not fit in the image.
+    pub fn fill_rect(&mut self, rect: Rect, rgba: [f32; 4]) -> ImageResult<()> {
+        self.check_rect(rect)?;
+
+        let component_type = self.component_type();
+        let size = component_type.size_bytes();
+        let [r, g, b, a] = rgba;
+        let values: &[f32] = match self.pixel_format() {
+            PixelFormat::R => &[r],
+            PixelFormat::RG => &[r, a],
+            PixelFormat::RGB => &[r, g, b],
+            PixelFormat::RGBA => &[r, g, b, a],
+            PixelFormat::BGR => &[b, g, r],
+            PixelFormat::BGRA => &[b, g, r, a],
+        };
+        let mut pixel = vec![0u8; values.len() * size];
+        for (bytes, &value) in pixel.chunks_exact_mut(size).zip(values) {
+            write_normalized(bytes, component_type, value);
+        }
+
+        let bytes_per_pixel = pixel.len();
+        let row_bytes = self.width() as usize * bytes_per_pixel;
+        let data = self.data_mut();
+        for row in rect.y..rect.y + rect.height {
+            let start = row as usize * row_bytes + rect.x as usize * bytes_per_pixel;
+            let end = start + rect.width as usize * bytes_per_pixel;
+            for target in data[start..end].chunks_exact_mut(bytes_per_pixel) {
+                target.copy_from_slice(&pixel);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+/// Borrowed row-by-row channel conversion for encoders
+use crate::buffer_pool;
+use crate::{Image, PixelFormat};
+
+/// Borrowed view of an image whose rows are converted on demand
+///
+/// Each output pixel is built from a list of source channel indices, so
+/// reordering (BGR to RGB), dropping (alpha strip) and duplicating (gray to
+/// RGB) channels all go through the same path. Rows that need no conversion are
+/// returned straight from the image; otherwise one reusable row buffer is used.
+pub(crate) struct RowView<'a> {
+    image: &'a Image,
+    channels: &'a [usize],
+    big_endian: bool,
+    row: Vec<u8>,
+}
+
+impl<'a> RowView<'a> {
+    /// Create a view producing `channels` (source channel indices) per pixel
+    ///
+    /// With `big_endian`, multi-byte components are written in big-endian order.
+    pub(crate) fn new(image: &'a Image, channels: &'a [usize], big_endian: bool) -> Self {
+        Self { image, channels, big_endian, row: Vec::new() }
+    }
+
+    /// Get image width
+    pub(crate) fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    /// Get image height
+    pub(crate) fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// Check if rows differ from the image storage
+    pub(crate) fn needs_conversion(&self) -> bool {
+        let identity = self.channels.len() == self.image.pixel_format().channel_count()
+            && self.channels.iter().enumerate().all(|(i, &channel)| i == channel);
+        !identity || (self.big_endian && self.image.component_type().size_bytes() > 1)
+    }
+
+    /// Append converted row `y` to `output`
+    pub(crate) fn convert_row(&self, y: u32, output: &mut Vec<u8>) {
+        let size = self.image.component_type().size_bytes();
+        let pixel_bytes = self.image.bytes_per_pixel();
+        let row_bytes = self.image.width() as usize * pixel_bytes;
+        let start = y as usize * row_bytes;
+        let source = &self.image.data()[start..start + row_bytes];
+
+        output.reserve(self.image.width() as usize * self.channels.len() * size);
+        for pixel in source.chunks_exact(pixel_bytes) {
+            for &channel in self.channels {
+                let component = &pixel[channel * size..(channel + 1) * size];
+                if self.big_endian {
+                    output.extend(component.iter().rev());
+                } else {
+                    output.extend_from_slice(component);
+                }
+            }
+        }
+    }
+
+    /// Get converted row `y`
+    ///
+    /// Borrows the image row directly when no conversion is needed.
+    pub(crate) fn row(&mut self, y: u32) -> &[u8] {
+        if !self.needs_conversion() {
+            let row_bytes = self.image.width() as usize * self.image.bytes_per_pixel();
+            let start = y as usize * row_bytes;
+            return &self.image.data()[start..start + row_bytes];
+        }
+
+        let mut row = std::mem::take(&mut self.row);
+        if row.capacity() == 0 {
+            row = buffer_pool::take(self.image.width() as usize * self.channels.len() * self.image.component_type().size_bytes());
+        }
+        row.clear();
+        self.convert_row(y, &mut row);
+        self.row = row;
+        &self.row
+    }
+}
+
+impl Drop for RowView<'_> {
+    fn drop(&mut self) {
+        buffer_pool::recycle(std::mem::take(&mut self.row));
+    }
+}
+
+/// Source channel indices giving RGB(A) order for a pixel format
+pub(crate) fn rgb_order(pixel_format: PixelFormat) -> &'static [usize] {
+    match pixel_format {
+        PixelFormat::R => &[0],
+        PixelFormat::RG => &[0, 1],
+        PixelFormat::RGB => &[0, 1, 2],
+        PixelFormat::RGBA => &[0, 1, 2, 3],
+        PixelFormat::BGR => &[2, 1, 0],
+        PixelFormat::BGRA => &[2, 1, 0, 3],
+    }
+}
+
+/// Pixel format matching the channels produced by [`rgb_order`]
+pub(crate) fn rgb_format(pixel_format: PixelFormat) -> PixelFormat {
+    match pixel_format {
+        PixelFormat::BGR => PixelFormat::RGB,
+        PixelFormat::BGRA => PixelFormat::RGBA,
+        other => other,
+    }
+}
+
@@ -0,0 +1,78 @@
+/// Component sample decoding and encoding helpers
+///
+/// Multi-byte components are stored little-endian. Unsigned integer components
+/// are normalized to `0.0..=1.0`, signed ones to `-1.0..=1.0` (the most negative
+/// value also maps to -1.0), float components are returned as-is.
+use crate::half_float::{f16_bytes_to_f32, f32_to_f16_bytes};
+use crate::ComponentType;
+use half::f16;
+
+/// Read one component sample as a normalized f32
+pub(crate) fn read_normalized(bytes: &[u8], component_type: ComponentType) -> f32 {
+    match component_type {
+        ComponentType::U8 => bytes[0] as f32 / 255.0,
+        ComponentType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+        ComponentType::F16 => f16::from_le_bytes([bytes[0], bytes[1]]).to_f32(),
+        ComponentType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        ComponentType::I8 => (bytes[0] as i8 as f32 / 127.0).max(-1.0),
+        ComponentType::I16 => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767.0).max(-1.0),
+    }
+}
+
+/// Write one component sample from a normalized f32
+///
+/// Integer components are clamped to their range and rounded to nearest.
+pub(crate) fn write_normalized(bytes: &mut [u8], component_type: ComponentType, value: f32) {
+    match component_type {
+        ComponentType::U8 => {
+            bytes[0] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        ComponentType::U16 => {
+            let v = (value.clamp(0.0, 1.0) * 65535.0).round() as u16;
+            bytes[..2].copy_from_slice(&v.to_le_bytes());
+        }
+        ComponentType::F16 => {
+            bytes[..2].copy_from_slice(&f16::from_f32(value).to_le_bytes());
+        }
+        ComponentType::F32 => {
+            bytes[..4].copy_from_slice(&value.to_le_bytes());
+        }
+        ComponentType::I8 => {
+            bytes[0] = (value.clamp(-1.0, 1.0) * 127.0).round() as i8 as u8;
+        }
+        ComponentType::I16 => {
+            let v = (value.clamp(-1.0, 1.0) * 32767.0).round() as i16;
+            bytes[..2].copy_from_slice(&v.to_le_bytes());
+        }
+    }
+}
+
+/// Read consecutive component samples as normalized f32 values
+///
+/// `bytes` holds `values.len()` samples. F16 samples are converted in batches.
+pub(crate) fn read_normalized_slice(bytes: &[u8], component_type: ComponentType, values: &mut [f32]) {
+    match component_type {
+        ComponentType::F16 => f16_bytes_to_f32(bytes, values),
+        _ => {
+            let size = component_type.size_bytes();
+            for (value, sample) in values.iter_mut().zip(bytes.chunks_exact(size)) {
+                *value = read_normalized(sample, component_type);
+            }
+        }
+    }
+}
+
+/// Write consecutive component samples from normalized f32 values
+///
+/// `bytes` holds `values.len()` samples. F16 samples are converted in batches.
+pub(crate) fn write_normalized_slice(bytes: &mut [u8], component_type: ComponentType, values: &[f32]) {
+    match component_type {
+        ComponentType::F16 => f32_to_f16_bytes(values, bytes),
+        _ => {
+            let size = component_type.size_bytes();
+            for (value, sample) in values.iter().zip(bytes.chunks_exact_mut(size)) {
+                write_normalized(sample, component_type, *value);
+            }
+        }
+    }
+}
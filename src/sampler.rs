@@ -0,0 +1,173 @@
+/// Texture-style filtered sampling at normalized coordinates
+use crate::Image;
+
+/// Filter used by [`Sampler`] between texels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SamplerFilter {
+    /// Texel containing the sample point (masks, ID maps)
+    Nearest,
+
+    /// Linear interpolation between the 2x2 texels around the sample point
+    #[default]
+    Bilinear,
+}
+
+/// How [`Sampler`] resolves coordinates outside the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AddressMode {
+    /// Repeat the edge texels
+    #[default]
+    Clamp,
+
+    /// Tile the image (seamless textures)
+    Wrap,
+
+    /// Tile the image, flipping every other repetition
+    Mirror,
+}
+
+impl AddressMode {
+    /// Map a texel index to `0..size`
+    fn resolve(self, index: i64, size: i64) -> usize {
+        let resolved = match self {
+            AddressMode::Clamp => index.clamp(0, size - 1),
+            AddressMode::Wrap => index.rem_euclid(size),
+            AddressMode::Mirror => {
+                let period = index.rem_euclid(2 * size);
+                if period < size { period } else { 2 * size - 1 - period }
+            }
+        };
+        resolved as usize
+    }
+}
+
+/// Filtered reads of an image at normalized texture coordinates
+///
+/// Follows GPU conventions: `(0, 0)` is the top-left corner of the first
+/// texel, `(1, 1)` the bottom-right corner of the last one, and texel centers
+/// are at `(x + 0.5) / width`. Samples are normalized RGBA like
+/// [`Image::read_pixel_f32`]: integer components map to `0.0..=1.0`, BGR(A)
+/// is swapped, grayscale is replicated and missing alpha reads as 1.0.
+///
+/// # Examples
+///
+/// ```
+/// use galaxy_image::{AddressMode, ComponentType, Image, PixelFormat, Sampler};
+///
+/// let mut image = Image::new(2, 1, PixelFormat::R, ComponentType::U8);
+/// image.data_mut().copy_from_slice(&[0, 255]);
+/// let sampler = Sampler::new(&image).with_address_mode(AddressMode::Wrap);
+///
+/// // Halfway between the two texel centers
+/// assert!((sampler.sample_uv(0.5, 0.5)[0] - 0.5).abs() < 1e-6);
+/// // Wrapping blends the last texel with the first one
+/// assert!((sampler.sample_uv(1.0, 0.5)[0] - 0.5).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Sampler<'a> {
+    image: &'a Image,
+    filter: SamplerFilter,
+    address_u: AddressMode,
+    address_v: AddressMode,
+}
+
+impl<'a> Sampler<'a> {
+    /// Create a bilinear, clamping sampler over an image
+    pub fn new(image: &'a Image) -> Self {
+        Self {
+            image,
+            filter: SamplerFilter::default(),
+            address_u: AddressMode::default(),
+            address_v: AddressMode::default(),
+        }
+    }
+
+    /// Set the filter
+    pub fn with_filter(mut self, filter: SamplerFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the address mode of both axes
+    pub fn with_address_mode(mut self, mode: AddressMode) -> Self {
+        self.address_u = mode;
+        self.address_v = mode;
+        self
+    }
+
+    /// Set the address modes of the horizontal (`u`) and vertical (`v`) axes
+    pub fn with_address_modes(mut self, u: AddressMode, v: AddressMode) -> Self {
+        self.address_u = u;
+        self.address_v = v;
+        self
+    }
+
+    /// Get the sampled image
+    pub fn image(&self) -> &'a Image {
+        self.image
+    }
+
+    /// Sample at normalized texture coordinates
+    ///
+    /// Returns zeros for images without pixels.
+    ///
+    /// # Arguments
+    /// * `u` - Horizontal coordinate (`0.0` left edge, `1.0` right edge)
+    /// * `v` - Vertical coordinate (`0.0` top edge, `1.0` bottom edge)
+    pub fn sample_uv(&self, u: f32, v: f32) -> [f32; 4] {
+        self.sample_texel(u * self.image.width() as f32, v * self.image.height() as f32)
+    }
+
+    /// Sample at texel coordinates (`(x + 0.5, y + 0.5)` is the center of texel `(x, y)`)
+    ///
+    /// Returns zeros for images without pixels.
+    pub fn sample_texel(&self, x: f32, y: f32) -> [f32; 4] {
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return [0.0; 4];
+        }
+
+        match self.filter {
+            SamplerFilter::Nearest => self.fetch_wide(x.floor() as i64, y.floor() as i64),
+            SamplerFilter::Bilinear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                // Float to int casts saturate, so huge coordinates stay in range
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let (x1, y1) = (x0.saturating_add(1), y0.saturating_add(1));
+
+                let top = lerp(self.fetch_wide(x0, y0), self.fetch_wide(x1, y0), tx);
+                let bottom = lerp(self.fetch_wide(x0, y1), self.fetch_wide(x1, y1), tx);
+                lerp(top, bottom, ty)
+            }
+        }
+    }
+
+    /// Read one texel, resolving out-of-range indices with the address modes
+    ///
+    /// Useful for neighborhood lookups (dilation, curvature) across tiled edges.
+    /// Returns zeros (transparent black) for images without pixels.
+    pub fn fetch(&self, x: i32, y: i32) -> [f32; 4] {
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return [0.0; 4];
+        }
+        self.fetch_wide(x as i64, y as i64)
+    }
+
+    fn fetch_wide(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = self.address_u.resolve(x, self.image.width() as i64);
+        let y = self.address_v.resolve(y, self.image.height() as i64);
+        self.image.read_pixel_f32(x as u32, y as u32)
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|channel| a[channel] + (b[channel] - a[channel]) * t)
+}
+
+impl Image {
+    /// Create a bilinear, clamping [`Sampler`] over this image
+    pub fn sampler(&self) -> Sampler<'_> {
+        Sampler::new(self)
+    }
+}
@@ -0,0 +1,454 @@
+use crate::TonemapOperator;
+
+/// Policy for saving images to formats that cannot store their component type
+///
+/// Applies when saving F16/F32 or U16 images to formats limited to 8-bit
+/// samples (JPEG, BMP, and PNG or PNM for float images). The image is converted to U8.
+/// Alpha is always clamped to `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LdrConversion {
+    /// Refuse to save and return an error
+    #[default]
+    Error,
+
+    /// Clamp samples to `0.0..=1.0` (no transfer function)
+    Clamp,
+
+    /// Scale so the brightest color sample maps to 1.0, then clamp negatives
+    Normalize,
+
+    /// Tone map with [`SaveOptions::ldr_tonemap`] (Reinhard by default) followed
+    /// by sRGB encoding, for previews of linear HDR images
+    Tonemap,
+}
+
+/// Compression of TIFF strips
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TiffCompression {
+    /// Uncompressed strips, the fastest to read and write
+    None,
+
+    /// LZW, read by virtually every TIFF reader
+    #[default]
+    Lzw,
+
+    /// Deflate (zlib), usually smaller than LZW
+    Deflate,
+}
+
+/// Options specific to TIFF files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TiffSaveOptions {
+    /// Compression of strips (default: LZW)
+    ///
+    /// Integer samples are written with the horizontal differencing predictor
+    /// when compressed.
+    pub compression: TiffCompression,
+}
+
+/// Options specific to TGA files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TgaSaveOptions {
+    /// Compress with run-length encoding (default: true)
+    ///
+    /// Lossless and read by every TGA reader that follows the specification;
+    /// disable for legacy tools that only accept uncompressed files.
+    pub rle: bool,
+}
+
+impl Default for TgaSaveOptions {
+    fn default() -> Self {
+        Self { rle: true }
+    }
+}
+
+/// Options specific to PGM/PPM files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PnmSaveOptions {
+    /// Write ASCII (`P2`/`P3`) instead of binary (`P5`/`P6`) files (default: false)
+    ///
+    /// ASCII files are several times larger but can be read and diffed as text.
+    pub ascii: bool,
+}
+
+/// Bits per pixel of BMP files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BmpBitDepth {
+    /// BGR without alpha, read by every BMP reader
+    #[default]
+    Bits24,
+
+    /// BGRA with an alpha mask in a `BITMAPV4HEADER`; images without alpha are
+    /// written opaque
+    Bits32,
+}
+
+/// Options specific to BMP files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BmpSaveOptions {
+    /// Bits per pixel (default: 24, alpha is dropped)
+    pub bit_depth: BmpBitDepth,
+}
+
+/// Chroma subsampling of JPEG files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum JpegSubsampling {
+    /// 4:4:4 from quality 90, 4:2:0 below
+    #[default]
+    Auto,
+
+    /// Full-resolution chroma, no color bleeding on sharp edges (normal maps, UI)
+    Yuv444,
+
+    /// Chroma at half horizontal resolution
+    Yuv422,
+
+    /// Chroma at half horizontal and vertical resolution, the smallest files
+    Yuv420,
+}
+
+/// Options specific to JPEG files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JpegSaveOptions {
+    /// Quality from 1 to 100 (default: 90)
+    pub quality: u8,
+
+    /// Chroma subsampling of color images (default: `Auto`)
+    pub subsampling: JpegSubsampling,
+
+    /// Write a progressive JPEG that displays in increasing detail while loading (default: false)
+    pub progressive: bool,
+}
+
+impl Default for JpegSaveOptions {
+    fn default() -> Self {
+        Self {
+            quality: 90,
+            subsampling: JpegSubsampling::Auto,
+            progressive: false,
+        }
+    }
+}
+
+/// Deflate effort of PNG files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PngCompression {
+    /// Fastest encoding with larger files, e.g. for editor autosaves
+    Fast,
+
+    /// Balance of speed and size
+    #[default]
+    Default,
+
+    /// Smallest files and slowest encoding, e.g. for shipped assets
+    Best,
+}
+
+/// Row filter applied before PNG compression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PngFilter {
+    /// Rows are compressed as is, best for palette-like flat graphics
+    None,
+
+    /// Difference with the pixel to the left
+    #[default]
+    Sub,
+
+    /// Difference with the pixel above
+    Up,
+
+    /// Difference with the average of the left and upper pixels
+    Average,
+
+    /// Difference with the Paeth predictor of the left, upper and upper-left pixels
+    Paeth,
+
+    /// Best of the five filters for each row, usually the smallest and slowest
+    Adaptive,
+}
+
+/// Options specific to PNG files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PngSaveOptions {
+    /// Deflate effort (default: `Default`)
+    pub compression: PngCompression,
+
+    /// Row filter (default: `Sub`)
+    pub filter: PngFilter,
+
+    /// Write an Adam7 interlaced file that web browsers display progressively (default: false)
+    ///
+    /// Interlaced files are usually larger and slower to decode. Ignored for
+    /// 1-bit masks.
+    pub interlaced: bool,
+}
+
+/// Compression of EXR files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ExrCompression {
+    /// Uncompressed scan lines
+    None,
+
+    /// Run-length encoding in 64x64 tiles, fast and lossless
+    #[default]
+    Rle,
+
+    /// Deflate in blocks of 16 scan lines, lossless and small
+    Zip,
+
+    /// Deflate per scan line, lossless, faster random access than `Zip`
+    Zips,
+
+    /// Wavelet and Huffman coding in blocks of 32 scan lines, lossless, best for noisy images
+    Piz,
+
+    /// F32 samples rounded to 24 bits, then deflated (lossy for F32 only)
+    Pxr24,
+
+    /// F16 samples in fixed-size 4x4 blocks (lossy, F32 stored uncompressed)
+    B44,
+
+    /// B44 with flat blocks stored in 3 bytes
+    B44a,
+
+    /// Lossy DCT in blocks of 32 scan lines (DreamWorks); `level` sets the
+    /// quantization, 45 in OpenEXR tools, higher is smaller and lossier
+    Dwaa {
+        /// DWA compression level
+        level: u32,
+    },
+
+    /// Like `Dwaa` with blocks of 256 scan lines, smaller but slower to read partially
+    Dwab {
+        /// DWA compression level
+        level: u32,
+    },
+}
+
+/// Options specific to EXR files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ExrSaveOptions {
+    /// Compression method (default: RLE)
+    ///
+    /// DWA only compresses R, G, B and Y channels lossily; alpha is
+    /// run-length encoded.
+    pub compression: ExrCompression,
+}
+
+/// Options controlling how images are encoded
+///
+/// Settings of each format are grouped in a field named after it, which only
+/// that encoder reads, so one value can be shared by saves to different
+/// formats. Build it with struct update syntax or the `with_*` methods:
+///
+/// ```
+/// use galaxy_image::{ExrCompression, PngCompression, SaveOptions};
+///
+/// let options = SaveOptions::new()
+///     .with_jpeg_quality(85)
+///     .with_png_compression(PngCompression::Best)
+///     .with_exr_compression(ExrCompression::Piz);
+/// assert_eq!(options.jpeg.quality, 85);
+/// assert_eq!(options.png.compression, PngCompression::Best);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// Pin encoder settings so the same input always produces the same bytes
+    ///
+    /// Writes no timestamps or optional metadata, and writes EXR chunks in
+    /// increasing order even when compressed in parallel.
+    pub deterministic: bool,
+
+    /// Conversion applied when the format cannot store the image component type
+    pub ldr_conversion: LdrConversion,
+
+    /// Curve used by [`LdrConversion::Tonemap`] (default: Reinhard)
+    ///
+    /// Use [`Image::bake_ldr`](crate::Image::bake_ldr) first to also adjust exposure.
+    pub ldr_tonemap: TonemapOperator,
+
+    /// Write files through a temporary file that is renamed over the destination (default: true)
+    ///
+    /// Processes watching the destination never see a partially written file,
+    /// and a crash mid-write leaves the previous file intact. Only used when
+    /// saving to a file.
+    pub atomic: bool,
+
+    /// PNG compression, filter and interlacing
+    pub png: PngSaveOptions,
+
+    /// JPEG quality, chroma subsampling and progressive encoding
+    pub jpeg: JpegSaveOptions,
+
+    /// EXR compression (default: RLE)
+    pub exr: ExrSaveOptions,
+
+    /// TIFF strip compression (default: LZW)
+    pub tiff: TiffSaveOptions,
+
+    /// TGA run-length encoding (default: on)
+    pub tga: TgaSaveOptions,
+
+    /// BMP bits per pixel (default: 24)
+    pub bmp: BmpSaveOptions,
+
+    /// PGM/PPM ASCII or binary encoding (default: binary)
+    pub pnm: PnmSaveOptions,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            deterministic: false,
+            ldr_conversion: LdrConversion::Error,
+            ldr_tonemap: TonemapOperator::Reinhard,
+            atomic: true,
+            png: PngSaveOptions::default(),
+            jpeg: JpegSaveOptions::default(),
+            exr: ExrSaveOptions::default(),
+            tiff: TiffSaveOptions::default(),
+            tga: TgaSaveOptions::default(),
+            bmp: BmpSaveOptions::default(),
+            pnm: PnmSaveOptions::default(),
+        }
+    }
+}
+
+impl SaveOptions {
+    /// Create the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the JPEG quality (1-100)
+    pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+        self.jpeg.quality = quality;
+        self
+    }
+
+    /// Set the JPEG options
+    pub fn with_jpeg(mut self, jpeg: JpegSaveOptions) -> Self {
+        self.jpeg = jpeg;
+        self
+    }
+
+    /// Set the JPEG chroma subsampling
+    pub fn with_jpeg_subsampling(mut self, subsampling: JpegSubsampling) -> Self {
+        self.jpeg.subsampling = subsampling;
+        self
+    }
+
+    /// Write progressive JPEG files
+    pub fn with_jpeg_progressive(mut self, progressive: bool) -> Self {
+        self.jpeg.progressive = progressive;
+        self
+    }
+
+    /// Pin encoder settings for byte-identical outputs
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Set the conversion for formats that cannot store the component type
+    pub fn with_ldr_conversion(mut self, conversion: LdrConversion) -> Self {
+        self.ldr_conversion = conversion;
+        self
+    }
+
+    /// Set the curve used by [`LdrConversion::Tonemap`]
+    pub fn with_ldr_tonemap(mut self, operator: TonemapOperator) -> Self {
+        self.ldr_tonemap = operator;
+        self
+    }
+
+    /// Write files through a temporary file renamed over the destination
+    pub fn with_atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Set the PNG options
+    pub fn with_png(mut self, png: PngSaveOptions) -> Self {
+        self.png = png;
+        self
+    }
+
+    /// Set the PNG deflate effort
+    pub fn with_png_compression(mut self, compression: PngCompression) -> Self {
+        self.png.compression = compression;
+        self
+    }
+
+    /// Set the PNG row filter
+    pub fn with_png_filter(mut self, filter: PngFilter) -> Self {
+        self.png.filter = filter;
+        self
+    }
+
+    /// Write Adam7 interlaced PNG files
+    pub fn with_png_interlaced(mut self, interlaced: bool) -> Self {
+        self.png.interlaced = interlaced;
+        self
+    }
+
+    /// Set the EXR options
+    pub fn with_exr(mut self, exr: ExrSaveOptions) -> Self {
+        self.exr = exr;
+        self
+    }
+
+    /// Set the EXR compression
+    pub fn with_exr_compression(mut self, compression: ExrCompression) -> Self {
+        self.exr.compression = compression;
+        self
+    }
+
+    /// Set the TIFF options
+    pub fn with_tiff(mut self, tiff: TiffSaveOptions) -> Self {
+        self.tiff = tiff;
+        self
+    }
+
+    /// Set the compression of TIFF strips
+    pub fn with_tiff_compression(mut self, compression: TiffCompression) -> Self {
+        self.tiff.compression = compression;
+        self
+    }
+
+    /// Set the TGA options
+    pub fn with_tga(mut self, tga: TgaSaveOptions) -> Self {
+        self.tga = tga;
+        self
+    }
+
+    /// Compress TGA files with run-length encoding
+    pub fn with_tga_rle(mut self, rle: bool) -> Self {
+        self.tga.rle = rle;
+        self
+    }
+
+    /// Set the BMP options
+    pub fn with_bmp(mut self, bmp: BmpSaveOptions) -> Self {
+        self.bmp = bmp;
+        self
+    }
+
+    /// Set the BMP bits per pixel
+    pub fn with_bmp_bit_depth(mut self, bit_depth: BmpBitDepth) -> Self {
+        self.bmp.bit_depth = bit_depth;
+        self
+    }
+
+    /// Set the PGM/PPM options
+    pub fn with_pnm(mut self, pnm: PnmSaveOptions) -> Self {
+        self.pnm = pnm;
+        self
+    }
+
+    /// Write PGM/PPM files as ASCII
+    pub fn with_pnm_ascii(mut self, ascii: bool) -> Self {
+        self.pnm.ascii = ascii;
+        self
+    }
+}
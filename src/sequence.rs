@@ -0,0 +1,189 @@
+/// Numbered image sequences (flipbooks, render outputs)
+use crate::{GalaxyImage, Image, ImageError, ImageResult, LoadOptions};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::{fs, thread};
+
+/// Sequence pattern split around its frame number placeholder
+struct Pattern {
+    prefix: String,
+    /// Minimum digit count, zero-padded (0 for `%d`)
+    digits: usize,
+    suffix: String,
+}
+
+impl Pattern {
+    /// Parse a printf-style pattern with one `%d` or `%0Nd` placeholder (`%%` is a literal `%`)
+    fn parse(pattern: &str) -> ImageResult<Self> {
+        let invalid = |message: &str| ImageError::Other(format!("Invalid sequence pattern '{}': {}", pattern, message));
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let mut digits = None;
+        let mut chars = pattern.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let literal = if digits.is_some() { &mut suffix } else { &mut prefix };
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                literal.push('%');
+                continue;
+            }
+
+            let mut width = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                width.push(digit);
+            }
+            if chars.next() != Some('d') {
+                return Err(invalid("expected %d or %0Nd"));
+            }
+            if digits.is_some() {
+                return Err(invalid("more than one frame number"));
+            }
+            digits = Some(width.parse().unwrap_or(0));
+        }
+
+        let digits = digits.ok_or_else(|| invalid("no %d frame number"))?;
+        Ok(Self { prefix, digits, suffix })
+    }
+
+    fn path(&self, frame: u32) -> PathBuf {
+        PathBuf::from(format!("{}{:0width$}{}", self.prefix, frame, self.suffix, width = self.digits))
+    }
+}
+
+/// Load frames in parallel, in frame order
+fn load_frames(paths: &[PathBuf], options: &LoadOptions) -> Vec<ImageResult<Image>> {
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+    let chunk_size = paths.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| GalaxyImage::load_from_file_with_options(path, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| vec![Err(ImageError::Other("Sequence loading thread panicked".to_string()))])
+            })
+            .collect()
+    })
+}
+
+pub(crate) fn load_sequence(
+    pattern: &str,
+    frames: impl IntoIterator<Item = u32>,
+    options: &LoadOptions,
+) -> ImageResult<Vec<Image>> {
+    let pattern = Pattern::parse(pattern)?;
+    let frames: Vec<u32> = frames.into_iter().collect();
+    let paths: Vec<PathBuf> = frames.iter().map(|&frame| pattern.path(frame)).collect();
+
+    let mut images: Vec<Image> = Vec::with_capacity(frames.len());
+    for ((frame, path), result) in frames.iter().zip(&paths).zip(load_frames(&paths, options)) {
+        let image = result?;
+        // Every frame must match the first one
+        if let Some(first) = images.first() {
+            if (image.width(), image.height(), image.pixel_format(), image.component_type())
+                != (first.width(), first.height(), first.pixel_format(), first.component_type())
+            {
+                return Err(ImageError::Other(format!(
+                    "Frame {} ('{}') is {}x{} {:?} {:?}, expected {}x{} {:?} {:?} like frame {}",
+                    frame,
+                    path.display(),
+                    image.width(),
+                    image.height(),
+                    image.pixel_format(),
+                    image.component_type(),
+                    first.width(),
+                    first.height(),
+                    first.pixel_format(),
+                    first.component_type(),
+                    frames[0],
+                )));
+            }
+        }
+        images.push(image);
+    }
+    Ok(images)
+}
+
+/// Split a file name around its last run of digits
+fn split_number(name: &str) -> Option<(&str, &str, &str)> {
+    let end = name.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = name[..end].rfind(|c: char| !c.is_ascii_digit()).map_or(0, |index| index + 1);
+    Some((&name[..start], &name[start..end], &name[end..]))
+}
+
+pub(crate) fn detect_sequence(path: &Path) -> ImageResult<(String, RangeInclusive<u32>)> {
+    let not_a_sequence = || ImageError::Other(format!("'{}' is not a numbered sequence frame", path.display()));
+    let name = path.file_name().and_then(|name| name.to_str()).ok_or_else(not_a_sequence)?;
+    let (prefix, number, suffix) = split_number(name).ok_or_else(not_a_sequence)?;
+    let frame: u32 = number.parse().map_err(|_| not_a_sequence())?;
+
+    // Frame numbers present in the directory with the same prefix and suffix
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut numbers = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(candidate) = file_name.to_str() else {
+            continue;
+        };
+        if let Some(digits) = candidate.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(suffix)) {
+            if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                numbers.push(digits.to_string());
+            }
+        }
+    }
+
+    // Zero-padded if this frame or a frame of the same length starts with 0
+    let padded = numbers.iter().any(|digits| digits.len() == number.len() && digits.starts_with('0'));
+    let digits = if padded { number.len() } else { 0 };
+    let mut frames: Vec<u32> = numbers
+        .iter()
+        .filter_map(|text| {
+            let value: u32 = text.parse().ok()?;
+            (format!("{:0width$}", value, width = digits) == *text).then_some(value)
+        })
+        .collect();
+    frames.sort_unstable();
+
+    // Contiguous run of frames around the given one
+    let mut first = frame;
+    while first > 0 && frames.binary_search(&(first - 1)).is_ok() {
+        first -= 1;
+    }
+    let mut last = frame;
+    while last < u32::MAX && frames.binary_search(&(last + 1)).is_ok() {
+        last += 1;
+    }
+
+    let escape = |text: &str| text.replace('%', "%%");
+    let placeholder = if padded { format!("%0{}d", digits) } else { "%d".to_string() };
+    let pattern = format!("{}{}{}{}", escape(&directory_prefix(path)), escape(prefix), placeholder, escape(suffix));
+    Ok((pattern, first..=last))
+}
+
+/// Directory part of a path including its trailing separator, empty for bare file names
+fn directory_prefix(path: &Path) -> String {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join("").to_string_lossy().into_owned(),
+        _ => String::new(),
+    }
+}
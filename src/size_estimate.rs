@@ -0,0 +1,60 @@
+/// Fast approximation of encoded file sizes
+use crate::{BmpBitDepth, ComponentType, GalaxyImage, Image, ImageError, ImageFormat, ImageResult, LdrConversion, SaveOptions};
+
+/// Rows per sampled band, a multiple of JPEG MCU and EXR block heights
+const BAND_ROWS: usize = 32;
+
+/// Number of bands sampled across the image height
+const BANDS: usize = 8;
+
+/// Copy evenly spaced bands of rows into a smaller image
+fn sample_bands(image: &Image, bands: usize) -> Image {
+    let row_bytes = image.width() as usize * image.bytes_per_pixel();
+    let height = image.height() as usize;
+    let mut data = Vec::with_capacity(bands * BAND_ROWS * row_bytes);
+    for band in 0..bands {
+        let first = band * (height - BAND_ROWS) / (BANDS - 1);
+        data.extend_from_slice(&image.data()[first * row_bytes..(first + BAND_ROWS) * row_bytes]);
+    }
+    Image::from_raw(data, image.width(), (bands * BAND_ROWS) as u32, image.pixel_format(), image.component_type())
+}
+
+pub(crate) fn estimate_encoded_size(image: &Image, format: ImageFormat, options: &SaveOptions) -> ImageResult<usize> {
+    match format {
+        ImageFormat::Unknown => return Err(ImageError::UnsupportedFormat("Unknown format".to_string())),
+        ImageFormat::Bmp => {
+            if image.component_type() != ComponentType::U8 && options.ldr_conversion == LdrConversion::Error {
+                return Err(ImageError::UnsupportedFormat("BMP only supports U8 component type".to_string()));
+            }
+            if options.bmp.bit_depth == BmpBitDepth::Bits32 {
+                // 4-byte pixels after the 122-byte V4 headers
+                return Ok(122 + image.width() as usize * image.height() as usize * 4);
+            }
+            // 24-bit rows padded to 4 bytes after the 54-byte headers
+            let row_bytes = (image.width() as usize * 3).next_multiple_of(4);
+            return Ok(54 + row_bytes * image.height() as usize);
+        }
+        ImageFormat::Tga if !options.tga.rle => {
+            if image.component_type() != ComponentType::U8 && options.ldr_conversion == LdrConversion::Error {
+                return Err(ImageError::UnsupportedFormat("TGA only supports U8 component type".to_string()));
+            }
+            // 18-byte header, one byte per channel, 26-byte footer
+            let pixels = image.width() as usize * image.height() as usize;
+            return Ok(18 + pixels * image.pixel_format().channel_count() + 26);
+        }
+        _ => {}
+    }
+
+    // Small images are cheap enough to encode for an exact size
+    let height = image.height() as usize;
+    let sampled_rows = BANDS * BAND_ROWS;
+    if height <= sampled_rows * 2 {
+        return GalaxyImage::save_to_bytes_with_options(image, format, options).map(|bytes| bytes.len());
+    }
+
+    // Fit size = header + rows * bytes_per_row between one band and all sampled bands
+    let one_band = GalaxyImage::save_to_bytes_with_options(&sample_bands(image, 1), format, options)?.len();
+    let all_bands = GalaxyImage::save_to_bytes_with_options(&sample_bands(image, BANDS), format, options)?.len();
+    let bytes_per_row = all_bands.saturating_sub(one_band) as f64 / (sampled_rows - BAND_ROWS) as f64;
+    Ok(all_bands + (bytes_per_row * (height - sampled_rows) as f64).round() as usize)
+}
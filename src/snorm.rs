@@ -0,0 +1,78 @@
+/// Signed-normalized images and 0.5-biased unsigned encodings (normal maps)
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, PixelFormat};
+
+/// Remap every channel except RGBA/BGRA alpha into a new component type
+fn rebias(image: &Image, component_type: ComponentType, map: impl Fn(f32) -> f32) -> Image {
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    // RG images hold the X and Y of two-channel normal maps, both are remapped
+    let alpha_channel = matches!(pixel_format, PixelFormat::RGBA | PixelFormat::BGRA).then_some(3);
+
+    let mut output = Image::new(image.width(), image.height(), pixel_format, component_type);
+    let row_bytes = image.width() as usize * image.bytes_per_pixel();
+    let output_row_bytes = output.width() as usize * output.bytes_per_pixel();
+    if row_bytes == 0 {
+        return output;
+    }
+
+    let mut values = vec![0f32; image.width() as usize * channel_count];
+    for (row, output_row) in image.data().chunks_exact(row_bytes).zip(output.data_mut().chunks_exact_mut(output_row_bytes)) {
+        read_normalized_slice(row, image.component_type(), &mut values);
+        for pixel in values.chunks_exact_mut(channel_count) {
+            for (channel, value) in pixel.iter_mut().enumerate() {
+                if Some(channel) != alpha_channel {
+                    *value = map(*value);
+                }
+            }
+        }
+        write_normalized_slice(output_row, component_type, &values);
+    }
+    output
+}
+
+impl Image {
+    /// Decode a 0.5-biased unsigned encoding to signed-normalized values
+    ///
+    /// Maps `0.0..=1.0` to `-1.0..=1.0` (`2v - 1`), the inverse of
+    /// [`Image::snorm_to_unorm`]. U8 images become I8 and U16 images I16, float
+    /// images keep their type; signed images are returned unchanged. Alpha of
+    /// RGBA/BGRA images is kept, both channels of RG images are converted.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{ComponentType, GalaxyImage};
+    ///
+    /// // Tangent-space normal map painted as RGB8, prepared for a BC5 SNORM encoder
+    /// let normals = GalaxyImage::load_from_file("rock_normal.png")?;
+    /// let signed = normals.unorm_to_snorm();
+    /// assert_eq!(signed.component_type(), ComponentType::I8);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn unorm_to_snorm(&self) -> Image {
+        let component_type = match self.component_type() {
+            ComponentType::U8 => ComponentType::I8,
+            ComponentType::U16 => ComponentType::I16,
+            ComponentType::I8 | ComponentType::I16 => return self.clone(),
+            float => float,
+        };
+        rebias(self, component_type, |v| v * 2.0 - 1.0)
+    }
+
+    /// Encode signed-normalized values as 0.5-biased unsigned values
+    ///
+    /// Maps `-1.0..=1.0` to `0.0..=1.0` (`(v + 1) / 2`), the encoding of normal
+    /// maps stored in unsigned formats such as PNG. I8 images become U8 and I16
+    /// images U16, float images keep their type; unsigned images are returned
+    /// unchanged. Alpha of RGBA/BGRA images is kept, both channels of RG images
+    /// are converted.
+    pub fn snorm_to_unorm(&self) -> Image {
+        let component_type = match self.component_type() {
+            ComponentType::I8 => ComponentType::U8,
+            ComponentType::I16 => ComponentType::U16,
+            ComponentType::U8 | ComponentType::U16 => return self.clone(),
+            float => float,
+        };
+        rebias(self, component_type, |v| (v + 1.0) * 0.5)
+    }
+}
@@ -0,0 +1,59 @@
+/// Per-channel image statistics
+use crate::sample::read_normalized_slice;
+use crate::Image;
+
+/// Statistics of one image channel
+///
+/// Values are normalized: integer components are mapped to `0.0..=1.0`,
+/// float components are reported as stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelStats {
+    /// Minimum value
+    pub min: f32,
+
+    /// Maximum value
+    pub max: f32,
+
+    /// Mean value
+    pub mean: f32,
+}
+
+impl Image {
+    /// Compute minimum, maximum and mean of every channel
+    ///
+    /// Channels are returned in storage order (e.g. B, G, R for BGR images).
+    /// Returns an empty list for images without pixels.
+    pub fn channel_stats(&self) -> Vec<ChannelStats> {
+        let channel_count = self.pixel_format().channel_count();
+        let pixel_count = self.size_bytes() / self.bytes_per_pixel().max(1);
+        if pixel_count == 0 {
+            return Vec::new();
+        }
+
+        let mut min = vec![f32::INFINITY; channel_count];
+        let mut max = vec![f32::NEG_INFINITY; channel_count];
+        let mut sum = vec![0f64; channel_count];
+
+        // Convert one row at a time so F16 samples go through the batched path
+        let row_bytes = self.width() as usize * self.bytes_per_pixel();
+        let mut values = vec![0f32; self.width() as usize * channel_count];
+        for row in self.data().chunks_exact(row_bytes) {
+            read_normalized_slice(row, self.component_type(), &mut values);
+            for pixel in values.chunks_exact(channel_count) {
+                for (channel, &value) in pixel.iter().enumerate() {
+                    min[channel] = min[channel].min(value);
+                    max[channel] = max[channel].max(value);
+                    sum[channel] += value as f64;
+                }
+            }
+        }
+
+        (0..channel_count)
+            .map(|channel| ChannelStats {
+                min: min[channel],
+                max: max[channel],
+                mean: (sum[channel] / pixel_count as f64) as f32,
+            })
+            .collect()
+    }
+}
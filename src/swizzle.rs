@@ -0,0 +1,163 @@
+/// Channel swizzling
+use crate::sample::write_normalized;
+use crate::{AlphaMode, Image, ImageError, ImageResult, PixelFormat};
+
+/// Source of one output channel of [`Image::swizzle_channels`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwizzleSource {
+    /// Red channel of the source (the gray value for R and RG images)
+    R,
+
+    /// Green channel of the source (the gray value for R and RG images)
+    G,
+
+    /// Blue channel of the source (the gray value for R and RG images)
+    B,
+
+    /// Alpha channel of the source (opaque for formats without alpha)
+    A,
+
+    /// Constant zero
+    Zero,
+
+    /// Constant one (the maximum of integer types)
+    One,
+}
+
+impl SwizzleSource {
+    /// Parse one pattern character: `r`, `g`, `b`, `a`, `0` or `1`, in either case
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'r' => Some(SwizzleSource::R),
+            'g' => Some(SwizzleSource::G),
+            'b' => Some(SwizzleSource::B),
+            'a' => Some(SwizzleSource::A),
+            '0' => Some(SwizzleSource::Zero),
+            '1' => Some(SwizzleSource::One),
+            _ => None,
+        }
+    }
+
+    /// Storage index of the channel in `pixel_format`, `None` for constants
+    fn channel(self, pixel_format: PixelFormat) -> Option<usize> {
+        let [r, g, b, a] = match pixel_format {
+            PixelFormat::R => [Some(0), Some(0), Some(0), None],
+            PixelFormat::RG => [Some(0), Some(0), Some(0), Some(1)],
+            PixelFormat::RGB => [Some(0), Some(1), Some(2), None],
+            PixelFormat::RGBA => [Some(0), Some(1), Some(2), Some(3)],
+            PixelFormat::BGR => [Some(2), Some(1), Some(0), None],
+            PixelFormat::BGRA => [Some(2), Some(1), Some(0), Some(3)],
+        };
+        match self {
+            SwizzleSource::R => r,
+            SwizzleSource::G => g,
+            SwizzleSource::B => b,
+            SwizzleSource::A => a,
+            SwizzleSource::Zero | SwizzleSource::One => None,
+        }
+    }
+}
+
+impl Image {
+    /// Reorder, duplicate or drop channels with a pattern such as `"bgra"` or `"rrr1"`
+    ///
+    /// Each character picks the source of one output channel, see
+    /// [`SwizzleSource::from_char`]. Returns an error if the pattern is not 1
+    /// to 4 valid characters. See [`Image::swizzle_channels`] for the output
+    /// layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let image = Image::from_raw(vec![10, 20, 30], 1, 1, PixelFormat::RGB, ComponentType::U8);
+    /// assert_eq!(image.swizzle("rgb1")?.data(), &[10, 20, 30, 255]);
+    /// assert_eq!(image.swizzle("bgr")?.data(), &[30, 20, 10]);
+    /// assert_eq!(image.swizzle("g")?.data(), &[20]);
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn swizzle(&self, pattern: &str) -> ImageResult<Image> {
+        let sources = pattern
+            .chars()
+            .map(SwizzleSource::from_char)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| ImageError::InvalidPixelFormat(format!(
+                "Invalid swizzle pattern {:?}, expected characters r, g, b, a, 0 or 1",
+                pattern
+            )))?;
+        self.swizzle_channels(&sources)
+    }
+
+    /// Build an image whose channels are taken from `sources`, in one pass
+    ///
+    /// One to four sources give an R, RG, RGB or RGBA image with the component
+    /// type of the source; channels are written in the order given, so
+    /// `[B, G, R, A]` stores BGRA bytes in an image tagged RGBA, as GPU uploads
+    /// expect. Sources read the source in RGBA terms whatever its storage
+    /// order. The alpha mode tag is kept if the output has alpha. Returns an
+    /// error for fewer than 1 or more than 4 sources.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    /// use galaxy_image::SwizzleSource::{A, R};
+    ///
+    /// let mask = GalaxyImage::load_from_file("mask.png")?;
+    /// // Replicate red into RGB and keep alpha (RGBA -> RRRA)
+    /// let preview = mask.swizzle_channels(&[R, R, R, A])?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn swizzle_channels(&self, sources: &[SwizzleSource]) -> ImageResult<Image> {
+        let pixel_format = match sources.len() {
+            1 => PixelFormat::R,
+            2 => PixelFormat::RG,
+            3 => PixelFormat::RGB,
+            4 => PixelFormat::RGBA,
+            count => {
+                return Err(ImageError::InvalidPixelFormat(format!(
+                    "Cannot swizzle into {} channels, expected 1 to 4",
+                    count
+                )))
+            }
+        };
+
+        let component_type = self.component_type();
+        let size = component_type.size_bytes();
+        let mut zero = [0u8; 4];
+        let mut one = [0u8; 4];
+        write_normalized(&mut zero, component_type, 0.0);
+        write_normalized(&mut one, component_type, 1.0);
+        // Per output channel: a source channel, or constant bytes
+        let plan: Vec<Result<usize, &[u8]>> = sources
+            .iter()
+            .map(|source| match (source.channel(self.pixel_format()), source) {
+                (Some(channel), _) => Ok(channel * size),
+                (None, SwizzleSource::Zero) => Err(&zero[..size]),
+                (None, _) => Err(&one[..size]),
+            })
+            .collect();
+
+        let mut output = Image::new(self.width(), self.height(), pixel_format, component_type);
+        let output_pixel_bytes = output.bytes_per_pixel();
+        let pixels = self.data().chunks_exact(self.bytes_per_pixel());
+        for (target, pixel) in output.data_mut().chunks_exact_mut(output_pixel_bytes).zip(pixels) {
+            for (sample, step) in target.chunks_exact_mut(size).zip(&plan) {
+                match step {
+                    Ok(offset) => sample.copy_from_slice(&pixel[*offset..*offset + size]),
+                    Err(constant) => sample.copy_from_slice(constant),
+                }
+            }
+        }
+
+        if pixel_format.has_alpha() {
+            output.set_alpha_mode(self.alpha_mode());
+        } else {
+            output.set_alpha_mode(AlphaMode::Straight);
+        }
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().converted(self.pixel_format(), pixel_format));
+        Ok(output)
+    }
+}
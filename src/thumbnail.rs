@@ -0,0 +1,50 @@
+/// Fast thumbnail generation
+use crate::loaders::{load_dds_level_for_size, load_exr_level_for_size, load_jpeg_scaled};
+use crate::instrument;
+use crate::{GalaxyImage, Image, ImageFormat, ImageResult, ResizeFilter};
+
+/// Compute thumbnail dimensions fitting in `max_dim` while keeping the aspect ratio
+pub(crate) fn thumbnail_size(width: u32, height: u32, max_dim: u32) -> (u32, u32) {
+    let max_dim = max_dim.max(1);
+    let largest = width.max(height);
+    if largest <= max_dim {
+        return (width, height);
+    }
+    let scale = max_dim as f64 / largest as f64;
+    let w = ((width as f64 * scale).round() as u32).clamp(1, max_dim);
+    let h = ((height as f64 * scale).round() as u32).clamp(1, max_dim);
+    (w, h)
+}
+
+pub(crate) fn thumbnail_from_bytes(
+    bytes: &[u8],
+    format: ImageFormat,
+    max_dim: u32,
+) -> ImageResult<Image> {
+    // Probe the header first to skip work for images that are already small
+    let info = GalaxyImage::probe_from_bytes(bytes, format)?;
+    let (width, height) = thumbnail_size(info.width, info.height, max_dim);
+
+    let image = if (width, height) == (info.width, info.height) {
+        GalaxyImage::load_from_bytes(bytes, format)?
+    } else {
+        match format {
+            ImageFormat::Jpeg => {
+                instrument::load(format, bytes.len(), || load_jpeg_scaled(bytes, width, height))?
+            }
+            ImageFormat::Exr => {
+                instrument::load(format, bytes.len(), || load_exr_level_for_size(bytes, width.max(height)))?
+            }
+            ImageFormat::Dds => {
+                instrument::load(format, bytes.len(), || load_dds_level_for_size(bytes, width.max(height)))?
+            }
+            _ => GalaxyImage::load_from_bytes(bytes, format)?,
+        }
+    };
+
+    if (image.width(), image.height()) == (width, height) {
+        Ok(image)
+    } else {
+        Ok(image.resize(width, height, ResizeFilter::Area))
+    }
+}
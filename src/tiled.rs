@@ -0,0 +1,304 @@
+/// Tiled pixel storage for cache-friendly transforms of large images
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+
+/// Width and height of one tile in pixels
+pub const TILE_SIZE: u32 = 64;
+
+/// Image stored as square tiles of [`TILE_SIZE`] pixels
+///
+/// Tiles are stored in row-major tile order, each tile holding its pixels
+/// row-major. Tiles on the right and bottom edges are padded to full size, so
+/// every tile has the same byte length. Rotations and transposes work tile by
+/// tile and touch only two tiles at a time, which keeps them in cache for very
+/// large images where row-major traversal would stride through memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    pixel_format: PixelFormat,
+    component_type: ComponentType,
+}
+
+impl TiledImage {
+    /// Create a zero-filled tiled image
+    pub fn new(
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        component_type: ComponentType,
+    ) -> Self {
+        let bytes_per_pixel = pixel_format.channel_count() * component_type.size_bytes();
+        let tile_count = width.div_ceil(TILE_SIZE) as usize * height.div_ceil(TILE_SIZE) as usize;
+        let tile_bytes = (TILE_SIZE * TILE_SIZE) as usize * bytes_per_pixel;
+
+        Self {
+            data: vec![0; tile_count * tile_bytes],
+            width,
+            height,
+            pixel_format,
+            component_type,
+        }
+    }
+
+    /// Get image width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get image height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get pixel format
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Get component type
+    pub fn component_type(&self) -> ComponentType {
+        self.component_type
+    }
+
+    /// Number of tile columns
+    pub fn tiles_x(&self) -> u32 {
+        self.width.div_ceil(TILE_SIZE)
+    }
+
+    /// Number of tile rows
+    pub fn tiles_y(&self) -> u32 {
+        self.height.div_ceil(TILE_SIZE)
+    }
+
+    /// Get the pixels of one tile, including edge padding
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tile coordinates are outside the tile grid.
+    pub fn tile(&self, tile_x: u32, tile_y: u32) -> &[u8] {
+        assert!(
+            tile_x < self.tiles_x() && tile_y < self.tiles_y(),
+            "tile ({}, {}) out of bounds", tile_x, tile_y
+        );
+        let tile_bytes = self.tile_bytes();
+        let start = (tile_y * self.tiles_x() + tile_x) as usize * tile_bytes;
+        &self.data[start..start + tile_bytes]
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.pixel_format.channel_count() * self.component_type.size_bytes()
+    }
+
+    fn tile_bytes(&self) -> usize {
+        (TILE_SIZE * TILE_SIZE) as usize * self.bytes_per_pixel()
+    }
+
+    /// Byte offset of pixel (x, y) in the tile buffer
+    fn offset(&self, x: u32, y: u32) -> usize {
+        let tile = (y / TILE_SIZE) * self.tiles_x() + x / TILE_SIZE;
+        let within = (y % TILE_SIZE) * TILE_SIZE + x % TILE_SIZE;
+        (tile as usize * (TILE_SIZE * TILE_SIZE) as usize + within as usize) * self.bytes_per_pixel()
+    }
+
+    /// Convert back to a row-major [`Image`]
+    pub fn to_image(&self) -> Image {
+        let mut image = Image::new(self.width, self.height, self.pixel_format, self.component_type);
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = self.width as usize * bytes_per_pixel;
+        let output = image.data_mut();
+
+        for y in 0..self.height {
+            for tile_x in 0..self.tiles_x() {
+                let x = tile_x * TILE_SIZE;
+                let run = (self.width - x).min(TILE_SIZE) as usize * bytes_per_pixel;
+                let src = self.offset(x, y);
+                let dst = y as usize * row_bytes + x as usize * bytes_per_pixel;
+                output[dst..dst + run].copy_from_slice(&self.data[src..src + run]);
+            }
+        }
+        image
+    }
+
+    /// Build a new tiled image where each pixel (x, y) is read from `source(x, y)`
+    ///
+    /// Walks the destination tile by tile so both sides stay cache-resident.
+    fn remap(&self, width: u32, height: u32, source: impl Fn(u32, u32) -> (u32, u32)) -> TiledImage {
+        let mut output = TiledImage::new(width, height, self.pixel_format, self.component_type);
+
+        // Fixed-size pixel copies avoid a memcpy call per pixel
+        match self.bytes_per_pixel() {
+            1 => self.remap_into::<1>(&mut output, &source),
+            2 => self.remap_into::<2>(&mut output, &source),
+            3 => self.remap_into::<3>(&mut output, &source),
+            4 => self.remap_into::<4>(&mut output, &source),
+            6 => self.remap_into::<6>(&mut output, &source),
+            8 => self.remap_into::<8>(&mut output, &source),
+            12 => self.remap_into::<12>(&mut output, &source),
+            _ => self.remap_into::<16>(&mut output, &source),
+        }
+        output
+    }
+
+    fn remap_into<const N: usize>(&self, output: &mut TiledImage, source: &impl Fn(u32, u32) -> (u32, u32)) {
+        let (width, height) = (output.width, output.height);
+
+        for tile_y in 0..output.tiles_y() {
+            for tile_x in 0..output.tiles_x() {
+                let x_end = ((tile_x + 1) * TILE_SIZE).min(width);
+                let y_end = ((tile_y + 1) * TILE_SIZE).min(height);
+                for y in tile_y * TILE_SIZE..y_end {
+                    for x in tile_x * TILE_SIZE..x_end {
+                        let (sx, sy) = source(x, y);
+                        let src = self.offset(sx, sy);
+                        let dst = output.offset(x, y);
+                        let pixel: [u8; N] = self.data[src..src + N].try_into().unwrap();
+                        output.data[dst..dst + N].copy_from_slice(&pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> TiledImage {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotate 90 degrees clockwise
+    pub fn rotate_90(&self) -> TiledImage {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Rotate 180 degrees
+    pub fn rotate_180(&self) -> TiledImage {
+        let (width, height) = (self.width, self.height);
+        self.remap(width, height, |x, y| (width - 1 - x, height - 1 - y))
+    }
+
+    /// Rotate 90 degrees counter-clockwise
+    pub fn rotate_270(&self) -> TiledImage {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    fn check_region(&self, x: u32, y: u32, width: u32, height: u32) -> ImageResult<()> {
+        let fits = x.checked_add(width).is_some_and(|end| end <= self.width)
+            && y.checked_add(height).is_some_and(|end| end <= self.height);
+        if !fits {
+            return Err(ImageError::Other(format!(
+                "region {}x{} at ({}, {}) out of bounds for {}x{} image",
+                width, height, x, y, self.width, self.height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Copy a rectangle out as a row-major [`Image`]
+    ///
+    /// Returns an error if the rectangle does not fit in the image.
+    pub fn region(&self, x: u32, y: u32, width: u32, height: u32) -> ImageResult<Image> {
+        self.check_region(x, y, width, height)?;
+
+        let mut image = Image::new(width, height, self.pixel_format, self.component_type);
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = width as usize * bytes_per_pixel;
+        let output = image.data_mut();
+
+        for row in 0..height {
+            let mut column = 0;
+            while column < width {
+                // Copy the run of pixels up to the next tile boundary
+                let sx = x + column;
+                let run = (TILE_SIZE - sx % TILE_SIZE).min(width - column);
+                let src = self.offset(sx, y + row);
+                let dst = row as usize * row_bytes + column as usize * bytes_per_pixel;
+                let len = run as usize * bytes_per_pixel;
+                output[dst..dst + len].copy_from_slice(&self.data[src..src + len]);
+                column += run;
+            }
+        }
+        Ok(image)
+    }
+
+    /// Write a row-major [`Image`] into a rectangle starting at (x, y)
+    ///
+    /// Returns an error if the formats differ or the image does not fit.
+    pub fn write_region(&mut self, x: u32, y: u32, image: &Image) -> ImageResult<()> {
+        if image.pixel_format() != self.pixel_format || image.component_type() != self.component_type {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "cannot write {:?} {:?} into {:?} {:?} tiled image",
+                image.pixel_format(), image.component_type(), self.pixel_format, self.component_type
+            )));
+        }
+        self.check_region(x, y, image.width(), image.height())?;
+
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = image.width() as usize * bytes_per_pixel;
+        let input = image.data();
+
+        for row in 0..image.height() {
+            let mut column = 0;
+            while column < image.width() {
+                let dx = x + column;
+                let run = (TILE_SIZE - dx % TILE_SIZE).min(image.width() - column);
+                let dst = self.offset(dx, y + row);
+                let src = row as usize * row_bytes + column as usize * bytes_per_pixel;
+                let len = run as usize * bytes_per_pixel;
+                self.data[dst..dst + len].copy_from_slice(&input[src..src + len]);
+                column += run;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&Image> for TiledImage {
+    fn from(image: &Image) -> Self {
+        image.to_tiled()
+    }
+}
+
+impl From<&TiledImage> for Image {
+    fn from(tiled: &TiledImage) -> Self {
+        tiled.to_image()
+    }
+}
+
+impl Image {
+    /// Convert to tiled storage
+    ///
+    /// Worth it when several rotations, transposes or region copies are applied
+    /// to a very large image; single operations on [`Image`] convert internally.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let scan = GalaxyImage::load_from_file("scan_16k.png")?;
+    /// let tiled = scan.to_tiled();
+    /// let rotated = tiled.rotate_90();
+    /// let detail = rotated.region(1024, 2048, 512, 512)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn to_tiled(&self) -> TiledImage {
+        let mut tiled = TiledImage::new(self.width(), self.height(), self.pixel_format(), self.component_type());
+        tiled
+            .write_region(0, 0, self)
+            .expect("image always fits a tiled image of its own size");
+        tiled
+    }
+
+    /// Swap rows and columns
+    ///
+    /// Goes through tiled storage so large images are transposed without
+    /// striding through memory.
+    pub fn transpose(&self) -> Image {
+        let mut output = self.to_tiled().transpose().to_image();
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().clone());
+        output
+    }
+}
@@ -0,0 +1,138 @@
+/// Roughness mip generation adjusted for normal variance (Toksvig)
+use crate::color::ColorSpace;
+use crate::mipmap::{mip_count, next_mip};
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat, ResizeFilter};
+
+/// Shortest averaged normal considered, so fully cancelling normals stay finite
+const MIN_NORMAL_LENGTH: f32 = 1e-4;
+
+/// Decode a normal map into an F32 RGB image of unit XYZ vectors
+///
+/// Channels are decoded from `0.0..=1.0` with `2v - 1`. Two-channel maps store
+/// X and Y (BC5 style) and Z is reconstructed.
+fn decode_normals(normal_map: &Image) -> ImageResult<Image> {
+    let pixel_format = normal_map.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let (x, y, z) = match pixel_format {
+        PixelFormat::R => {
+            return Err(ImageError::InvalidPixelFormat(
+                "normal maps need at least two channels".to_string(),
+            ))
+        }
+        PixelFormat::RG => (0, 1, None),
+        PixelFormat::RGB | PixelFormat::RGBA => (0, 1, Some(2)),
+        PixelFormat::BGR | PixelFormat::BGRA => (2, 1, Some(0)),
+    };
+
+    let mut values = vec![0f32; normal_map.size_bytes() / normal_map.component_type().size_bytes()];
+    read_normalized_slice(normal_map.data(), normal_map.component_type(), &mut values);
+    let vectors: Vec<f32> = values
+        .chunks_exact(channel_count)
+        .flat_map(|pixel| {
+            let nx = pixel[x] * 2.0 - 1.0;
+            let ny = pixel[y] * 2.0 - 1.0;
+            let nz = match z {
+                Some(z) => pixel[z] * 2.0 - 1.0,
+                None => (1.0 - nx * nx - ny * ny).max(0.0).sqrt(),
+            };
+            let length = (nx * nx + ny * ny + nz * nz).sqrt();
+            if length > 0.0 {
+                [nx / length, ny / length, nz / length]
+            } else {
+                [0.0, 0.0, 1.0]
+            }
+        })
+        .collect();
+
+    let mut decoded = Image::new(normal_map.width(), normal_map.height(), PixelFormat::RGB, ComponentType::F32);
+    write_normalized_slice(decoded.data_mut(), ComponentType::F32, &vectors);
+    Ok(decoded)
+}
+
+/// Widen the roughness of a level by the variance of its averaged normals
+fn apply_normal_variance(level: &mut Image, normals: &Image, channel: usize) {
+    let channel_count = level.pixel_format().channel_count();
+    let component_type = level.component_type();
+    let mut values = vec![0f32; level.size_bytes() / component_type.size_bytes()];
+    read_normalized_slice(level.data(), component_type, &mut values);
+    let mut vectors = vec![0f32; normals.size_bytes() / 4];
+    read_normalized_slice(normals.data(), ComponentType::F32, &mut vectors);
+
+    for (pixel, normal) in values.chunks_exact_mut(channel_count).zip(vectors.chunks_exact(3)) {
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2])
+            .sqrt()
+            .clamp(MIN_NORMAL_LENGTH, 1.0);
+        // Toksvig variance of the normal distribution, added to the GGX alpha squared
+        let variance = (1.0 - length) / length;
+        let alpha = pixel[channel].clamp(0.0, 1.0).powi(2);
+        let alpha_squared = (alpha * alpha + 2.0 * variance).min(1.0);
+        pixel[channel] = alpha_squared.sqrt().sqrt();
+    }
+
+    write_normalized_slice(level.data_mut(), component_type, &values);
+}
+
+impl Image {
+    /// Generate a roughness mip chain that accounts for normal map detail
+    ///
+    /// Regular mips average bumpy normals into a smooth, shiny surface, which
+    /// makes distant highlights sparkle and alias. The Toksvig method measures
+    /// how much the normals of each mip texel disagree (the shorter their
+    /// average, the wider the distribution) and raises roughness accordingly,
+    /// so specular response stays stable with distance.
+    ///
+    /// The image holds perceptual roughness (GGX alpha = roughness²) in
+    /// `channel`; other channels (e.g. occlusion and metalness of a packed ORM
+    /// texture) are mipped with a plain box filter. Normal map channels are
+    /// decoded with `2v - 1`; two-channel maps store X and Y. The normal map is
+    /// resampled to the roughness map size if they differ.
+    ///
+    /// Returns the full chain down to 1x1, base level first (unchanged), or an
+    /// error if `channel` is out of range or the normal map has a single channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `normal_map` - Tangent-space normal map of the same surface
+    /// * `channel` - Storage channel holding roughness
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let normals = GalaxyImage::load_from_file("rock_normal.png")?;
+    /// let orm = GalaxyImage::load_from_file("rock_orm.png")?;
+    /// // glTF packs roughness in the green channel
+    /// let mips = orm.toksvig_roughness_mips(&normals, 1)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn toksvig_roughness_mips(&self, normal_map: &Image, channel: usize) -> ImageResult<Vec<Image>> {
+        if channel >= self.pixel_format().channel_count() {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "channel {} out of range for {:?}",
+                channel, self.pixel_format()
+            )));
+        }
+
+        let mut normals = decode_normals(normal_map)?;
+        if (normals.width(), normals.height()) != (self.width(), self.height()) {
+            normals = normals.resize(self.width(), self.height(), ResizeFilter::Area);
+        }
+
+        let level_count = mip_count(self.width(), self.height());
+        let mut levels = Vec::with_capacity(level_count);
+        levels.push(self.clone());
+
+        // Each adjusted level comes from the plain chain, so variance is not counted twice
+        let mut plain = self.clone();
+        for _ in 1..level_count {
+            plain = next_mip(&plain, ColorSpace::Linear);
+            normals = next_mip(&normals, ColorSpace::Linear);
+            let mut level = plain.clone();
+            apply_normal_variance(&mut level, &normals, channel);
+            levels.push(level);
+        }
+        Ok(levels)
+    }
+}
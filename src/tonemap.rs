@@ -0,0 +1,308 @@
+/// Tone mapping between HDR and LDR: baking to 8 bits and inverse expansion
+use crate::color::{linear_to_srgb, ColorSpace};
+use crate::sample::{read_normalized_slice, write_normalized_slice};
+use crate::{ComponentType, Image, PixelFormat};
+
+/// Tone mapping curve applied to linear color values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TonemapOperator {
+    /// Clamp to `0.0..=1.0` (no compression of highlights)
+    Clamp,
+
+    /// Reinhard (`x / (1 + x)`)
+    #[default]
+    Reinhard,
+
+    /// ACES filmic approximation (Narkowicz 2015), with a contrasty toe
+    AcesFilmic,
+
+    /// Exponential exposure curve (`1 - e^-x`)
+    ///
+    /// Brighter midtones than Reinhard with a softer shoulder: 1.0 maps to 0.63
+    /// and highlights approach white without clipping. Pair it with an exposure
+    /// adjustment to pick the key of the image.
+    Exposure,
+}
+
+impl TonemapOperator {
+    /// Map a linear value to `0.0..=1.0`
+    pub(crate) fn apply(&self, value: f32) -> f32 {
+        let v = value.max(0.0);
+        match self {
+            TonemapOperator::Clamp => v.min(1.0),
+            TonemapOperator::Reinhard => v / (1.0 + v),
+            TonemapOperator::AcesFilmic => {
+                ((v * (2.51 * v + 0.03)) / (v * (2.43 * v + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }
+            TonemapOperator::Exposure => 1.0 - (-v).exp(),
+        }
+    }
+}
+
+/// Transfer function applied after tone mapping
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LdrEncoding {
+    /// sRGB transfer function (for color textures and display)
+    #[default]
+    Srgb,
+
+    /// Pure power-law gamma (`v^(1/gamma)`)
+    Gamma(f32),
+
+    /// No transfer function (for data stored in 8 bits)
+    Linear,
+}
+
+impl LdrEncoding {
+    fn encode(&self, value: f32) -> f32 {
+        match self {
+            LdrEncoding::Srgb => linear_to_srgb(value),
+            LdrEncoding::Gamma(gamma) => value.powf(1.0 / gamma.max(f32::EPSILON)),
+            LdrEncoding::Linear => value,
+        }
+    }
+}
+
+/// Options of [`Image::bake_ldr`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BakeOptions {
+    /// Exposure adjustment in stops, applied before tone mapping (default: 0.0)
+    pub exposure: f32,
+
+    /// Tone mapping curve (default: Reinhard)
+    pub tonemap: TonemapOperator,
+
+    /// Transfer encoding (default: sRGB)
+    pub encoding: LdrEncoding,
+
+    /// Add triangular noise of one 8-bit step before quantization to avoid
+    /// banding in gradients (default: true)
+    ///
+    /// The noise is derived from pixel coordinates, so output is reproducible.
+    pub dither: bool,
+}
+
+impl Default for BakeOptions {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tonemap: TonemapOperator::Reinhard,
+            encoding: LdrEncoding::Srgb,
+            dither: true,
+        }
+    }
+}
+
+/// Uniform noise in `0.0..1.0` from sample coordinates
+fn hash_noise(x: u32, y: u32, channel: u32) -> f32 {
+    let mut h = x.wrapping_mul(0x8DA6_B343) ^ y.wrapping_mul(0xD816_3841) ^ channel.wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Triangular noise in `-1.0..1.0`
+fn triangular_noise(x: u32, y: u32, channel: u32) -> f32 {
+    hash_noise(x, y, channel) + hash_noise(x, y, channel + 8) - 1.0
+}
+
+/// Bake an image to U8
+pub(crate) fn bake_ldr(image: &Image, options: &BakeOptions) -> Image {
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let alpha_channel = pixel_format.has_alpha().then(|| channel_count - 1);
+    let exposure_scale = options.exposure.exp2();
+
+    let width = image.width() as usize;
+    let row_bytes = width * image.bytes_per_pixel();
+    let mut output = Image::new(image.width(), image.height(), pixel_format, ComponentType::U8);
+    if row_bytes == 0 {
+        return output;
+    }
+
+    let mut values = vec![0f32; width * channel_count];
+    let output_row_bytes = width * channel_count;
+    let output_data = output.data_mut();
+    for (y, row) in image.data().chunks_exact(row_bytes).enumerate() {
+        read_normalized_slice(row, image.component_type(), &mut values);
+        let output_row = &mut output_data[y * output_row_bytes..(y + 1) * output_row_bytes];
+
+        for (index, (value, out)) in values.iter().zip(output_row.iter_mut()).enumerate() {
+            let channel = index % channel_count;
+            let v = if value.is_finite() { *value } else { 0.0 };
+
+            // Alpha is only clamped
+            let encoded = if Some(channel) == alpha_channel {
+                v.clamp(0.0, 1.0)
+            } else {
+                options.encoding.encode(options.tonemap.apply(v * exposure_scale))
+            };
+
+            let mut level = encoded * 255.0;
+            if options.dither && Some(channel) != alpha_channel {
+                level += triangular_noise((index / channel_count) as u32, y as u32, channel as u32);
+            }
+            *out = level.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    output
+}
+
+/// Curve used by [`Image::expand_to_hdr`] to recover highlights
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HdrExpansion {
+    /// Inverse of the extended Reinhard curve with the peak as white point
+    ///
+    /// Expands the whole range smoothly; dark values stay nearly unchanged.
+    InverseReinhard,
+
+    /// Leave values below `threshold` unchanged and boost brighter values
+    /// quadratically so that 1.0 maps to the peak
+    HighlightBoost {
+        /// Linear luminance where boosting starts (`0.0..1.0`)
+        threshold: f32,
+    },
+}
+
+/// Options of [`Image::expand_to_hdr`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpandOptions {
+    /// Expansion curve (default: InverseReinhard)
+    pub method: HdrExpansion,
+
+    /// Linear luminance that pure white maps to (default: 16.0)
+    pub peak: f32,
+
+    /// Encoding of the input values (default: sRGB, decoded before expansion)
+    pub input_color_space: ColorSpace,
+}
+
+impl Default for ExpandOptions {
+    fn default() -> Self {
+        Self {
+            method: HdrExpansion::InverseReinhard,
+            peak: 16.0,
+            input_color_space: ColorSpace::Srgb,
+        }
+    }
+}
+
+impl HdrExpansion {
+    /// Map a linear luminance in `0.0..=1.0` to `0.0..=peak`
+    fn expand(&self, luminance: f32, peak: f32) -> f32 {
+        let l = luminance.clamp(0.0, 1.0);
+        match *self {
+            HdrExpansion::InverseReinhard => {
+                // Solve l = L (1 + L / W^2) / (1 + L) for L
+                let w2 = peak * peak;
+                let b = 1.0 - l;
+                (-b + (b * b + 4.0 * l / w2).sqrt()) * w2 / 2.0
+            }
+            HdrExpansion::HighlightBoost { threshold } => {
+                let threshold = threshold.clamp(0.0, 0.999);
+                if l <= threshold {
+                    l
+                } else {
+                    let t = (l - threshold) / (1.0 - threshold);
+                    l * (1.0 + t * t * (peak - 1.0))
+                }
+            }
+        }
+    }
+}
+
+/// Expand an LDR image to F16 linear HDR
+pub(crate) fn expand_to_hdr(image: &Image, options: &ExpandOptions) -> Image {
+    let pixel_format = image.pixel_format();
+    let channel_count = pixel_format.channel_count();
+    let peak = options.peak.max(1.0);
+
+    let mut values = vec![0f32; image.size_bytes() / image.component_type().size_bytes()];
+    read_normalized_slice(image.data(), image.component_type(), &mut values);
+
+    // Rec.709 luma weights in storage order
+    let weights: &[f32] = match pixel_format {
+        PixelFormat::R | PixelFormat::RG => &[1.0],
+        PixelFormat::RGB | PixelFormat::RGBA => &[0.2126, 0.7152, 0.0722],
+        PixelFormat::BGR | PixelFormat::BGRA => &[0.0722, 0.7152, 0.2126],
+    };
+
+    for pixel in values.chunks_exact_mut(channel_count) {
+        let color = &mut pixel[..weights.len()];
+        for value in color.iter_mut() {
+            let v = if value.is_finite() { value.clamp(0.0, 1.0) } else { 0.0 };
+            *value = options.input_color_space.decode(v);
+        }
+
+        // Scale by luminance so hues are preserved
+        let luminance: f32 = color.iter().zip(weights).map(|(v, w)| v * w).sum();
+        if luminance > 0.0 {
+            let scale = options.method.expand(luminance, peak) / luminance;
+            color.iter_mut().for_each(|value| *value *= scale);
+        }
+    }
+
+    let mut output = Image::new(image.width(), image.height(), pixel_format, ComponentType::F16);
+    write_normalized_slice(output.data_mut(), ComponentType::F16, &values);
+    output
+}
+
+impl Image {
+    /// Bake the image to U8 with exposure, tone mapping, encoding and dithering
+    ///
+    /// Runs the usual HDR export chain in the right order: exposure scaling in
+    /// linear light, tone mapping, transfer encoding, then dithered quantization
+    /// to 8 bits. Alpha is clamped and never tone mapped or dithered. Non-finite
+    /// values map to 0. The pixel format is kept.
+    ///
+    /// # Arguments
+    /// * `options` - Exposure, tone mapping curve, encoding and dithering
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{BakeOptions, GalaxyImage, ImageFormat, TonemapOperator};
+    ///
+    /// let hdr = GalaxyImage::load_from_file("sky.exr")?;
+    /// let ldr = hdr.bake_ldr(&BakeOptions {
+    ///     exposure: -1.0,
+    ///     tonemap: TonemapOperator::AcesFilmic,
+    ///     ..Default::default()
+    /// });
+    /// GalaxyImage::save_to_file(&ldr, "sky.png", ImageFormat::Png)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn bake_ldr(&self, options: &BakeOptions) -> Image {
+        bake_ldr(self, options)
+    }
+
+    /// Expand an LDR image to a linear F16 HDR image (inverse tone mapping)
+    ///
+    /// Decodes the input transfer function, then expands luminance so that pure
+    /// white reaches `peak` while hues are preserved. Useful to promote legacy
+    /// LDR skyboxes to environment maps with a sensible dynamic range. Alpha is
+    /// kept unchanged.
+    ///
+    /// # Arguments
+    /// * `options` - Expansion curve, peak luminance and input encoding
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use galaxy_image::{ExpandOptions, GalaxyImage, HdrExpansion, ImageFormat};
+    ///
+    /// let sky = GalaxyImage::load_from_file("legacy_sky.jpg")?;
+    /// let hdr = sky.expand_to_hdr(&ExpandOptions {
+    ///     method: HdrExpansion::HighlightBoost { threshold: 0.8 },
+    ///     peak: 32.0,
+    ///     ..Default::default()
+    /// });
+    /// GalaxyImage::save_to_file(&hdr, "sky.exr", ImageFormat::Exr)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn expand_to_hdr(&self, options: &ExpandOptions) -> Image {
+        expand_to_hdr(self, options)
+    }
+}
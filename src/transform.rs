@@ -0,0 +1,106 @@
+/// Crop, flip and rotate
+use crate::roi::Rect;
+use crate::{Image, ImageResult};
+
+impl Image {
+    /// Copy a rectangle out as a new image
+    ///
+    /// Works with every pixel format and component type; the alpha mode, color
+    /// space and metadata are kept. Returns an error if the rectangle does not fit in the image.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Left column of the rectangle
+    /// * `y` - Top row of the rectangle
+    /// * `width` - Width of the rectangle in pixels
+    /// * `height` - Height of the rectangle in pixels
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use galaxy_image::GalaxyImage;
+    ///
+    /// let sheet = GalaxyImage::load_from_file("sprites.png")?;
+    /// // Third 32x32 sprite of the second row
+    /// let sprite = sheet.crop(64, 32, 32, 32)?;
+    /// # Ok::<(), galaxy_image::ImageError>(())
+    /// ```
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> ImageResult<Image> {
+        let mut output = self.copy_rect(Rect::new(x, y, width, height))?;
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().clone());
+        Ok(output)
+    }
+
+    /// Mirror the image left to right
+    pub fn flip_horizontal(&self) -> Image {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let row_bytes = self.width() as usize * bytes_per_pixel;
+        let mut output = self.clone();
+        if row_bytes == 0 {
+            return output;
+        }
+
+        for (row, source_row) in output.data_mut().chunks_exact_mut(row_bytes).zip(self.data().chunks_exact(row_bytes)) {
+            let source_pixels = source_row.chunks_exact(bytes_per_pixel).rev();
+            for (target, source) in row.chunks_exact_mut(bytes_per_pixel).zip(source_pixels) {
+                target.copy_from_slice(source);
+            }
+        }
+        output
+    }
+
+    /// Mirror the image top to bottom
+    pub fn flip_vertical(&self) -> Image {
+        let mut output = self.clone();
+        output.flip_rows();
+        output
+    }
+
+    /// Rotate 90 degrees clockwise
+    ///
+    /// Swaps width and height. Goes through tiled storage like
+    /// [`Image::transpose`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use galaxy_image::{ComponentType, Image, PixelFormat};
+    ///
+    /// let image = Image::from_raw(vec![1, 2, 3, 4, 5, 6], 3, 2, PixelFormat::R, ComponentType::U8);
+    /// let rotated = image.rotate_90();
+    /// assert_eq!((rotated.width(), rotated.height()), (2, 3));
+    /// assert_eq!(rotated.data(), &[4, 1, 5, 2, 6, 3]);
+    /// ```
+    pub fn rotate_90(&self) -> Image {
+        let mut output = self.to_tiled().rotate_90().to_image();
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().clone());
+        output
+    }
+
+    /// Rotate 180 degrees
+    pub fn rotate_180(&self) -> Image {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let mut output = self.clone();
+        let source_pixels = self.data().chunks_exact(bytes_per_pixel).rev();
+        for (target, source) in output.data_mut().chunks_exact_mut(bytes_per_pixel).zip(source_pixels) {
+            target.copy_from_slice(source);
+        }
+        output
+    }
+
+    /// Rotate 90 degrees counter-clockwise
+    ///
+    /// Swaps width and height. Goes through tiled storage like
+    /// [`Image::transpose`].
+    pub fn rotate_270(&self) -> Image {
+        let mut output = self.to_tiled().rotate_270().to_image();
+        output.set_alpha_mode(self.alpha_mode());
+        output.set_color_space(self.color_space());
+        output.set_metadata(self.metadata().clone());
+        output
+    }
+}
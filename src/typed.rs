@@ -0,0 +1,289 @@
+/// Images with compile-time component type and channel count
+use crate::{ComponentType, Image, ImageError, ImageResult, PixelFormat};
+use half::f16;
+use std::ops::{Index, IndexMut};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Component type usable in a [`TypedImage`]
+///
+/// Implemented for `u8`, `u16`, `half::f16`, `f32`, and `i8` and `i16` for
+/// signed-normalized images.
+pub trait Component: Copy + Default + PartialEq + std::fmt::Debug + bytemuck::Pod + sealed::Sealed + 'static {
+    /// Matching component type
+    const COMPONENT_TYPE: ComponentType;
+
+    /// Value of a fully opaque alpha (normalized 1.0)
+    const OPAQUE: Self;
+
+    /// Decode one component from little-endian bytes
+    fn from_le(bytes: &[u8]) -> Self;
+
+    /// Encode one component as little-endian bytes
+    fn write_le(self, bytes: &mut [u8]);
+}
+
+macro_rules! components {
+    ($($component:ty => $component_type:ident, $size:literal, $opaque:expr;)*) => {$(
+        impl sealed::Sealed for $component {}
+        impl Component for $component {
+            const COMPONENT_TYPE: ComponentType = ComponentType::$component_type;
+            const OPAQUE: Self = $opaque;
+
+            fn from_le(bytes: &[u8]) -> Self {
+                let array: [u8; $size] = bytes[..$size].try_into().unwrap();
+                <$component>::from_le_bytes(array)
+            }
+
+            fn write_le(self, bytes: &mut [u8]) {
+                bytes[..$size].copy_from_slice(&self.to_le_bytes());
+            }
+        }
+    )*};
+}
+
+components! {
+    u8 => U8, 1, u8::MAX;
+    u16 => U16, 2, u16::MAX;
+    f16 => F16, 2, f16::ONE;
+    f32 => F32, 4, 1.0;
+    i8 => I8, 1, i8::MAX;
+    i16 => I16, 2, i16::MAX;
+}
+
+/// Grayscale 8-bit image
+pub type GrayImage8 = TypedImage<u8, 1>;
+
+/// RGB 8-bit image
+pub type RgbImage8 = TypedImage<u8, 3>;
+
+/// RGBA 8-bit image
+pub type RgbaImage8 = TypedImage<u8, 4>;
+
+/// Grayscale 16-bit image (heightmaps)
+pub type GrayImage16 = TypedImage<u16, 1>;
+
+/// RGBA 16-bit image
+pub type RgbaImage16 = TypedImage<u16, 4>;
+
+/// RGBA half-float image
+pub type RgbaImageF16 = TypedImage<f16, 4>;
+
+/// RGBA float image
+pub type RgbaImageF32 = TypedImage<f32, 4>;
+
+/// Image whose component type `T` and channel count `C` are known at compile time
+///
+/// Channels are in R, RG, RGB or RGBA order for `C` = 1 to 4. Pixels are
+/// indexed by `(x, y)` and stored row-major as `[T; C]`, so engine code
+/// working with known formats needs no runtime matching or byte offsets.
+///
+/// # Examples
+///
+/// ```
+/// use galaxy_image::{Image, RgbaImage8};
+///
+/// let mut sprite = RgbaImage8::new(4, 4);
+/// sprite[(1, 2)] = [255, 0, 0, 255];
+///
+/// let image: Image = sprite.to_image();
+/// let back = RgbaImage8::try_from(&image).unwrap();
+/// assert_eq!(back[(1, 2)], [255, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedImage<T: Component, const C: usize> {
+    width: u32,
+    height: u32,
+    pixels: Vec<[T; C]>,
+}
+
+impl<T: Component, const C: usize> TypedImage<T, C> {
+    /// Pixel format matching the channel count
+    pub const PIXEL_FORMAT: PixelFormat = match C {
+        1 => PixelFormat::R,
+        2 => PixelFormat::RG,
+        3 => PixelFormat::RGB,
+        4 => PixelFormat::RGBA,
+        _ => panic!("typed images have 1 to 4 channels"),
+    };
+
+    /// Create an image with every component set to zero
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::filled(width, height, [T::default(); C])
+    }
+
+    /// Create an image with every pixel set to `pixel`
+    pub fn filled(width: u32, height: u32, pixel: [T; C]) -> Self {
+        // Evaluating the constant rejects unsupported channel counts at compile time
+        let _ = Self::PIXEL_FORMAT;
+        Self { width, height, pixels: vec![pixel; width as usize * height as usize] }
+    }
+
+    /// Create an image from row-major pixels
+    ///
+    /// Returns an error if the pixel count does not match the dimensions.
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<[T; C]>) -> ImageResult<Self> {
+        let _ = Self::PIXEL_FORMAT;
+        if pixels.len() != width as usize * height as usize {
+            return Err(ImageError::Other(format!(
+                "{} pixels do not match a {}x{} image",
+                pixels.len(), width, height
+            )));
+        }
+        Ok(Self { width, height, pixels })
+    }
+
+    /// Get image width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get image height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get all pixels, row-major
+    pub fn pixels(&self) -> &[[T; C]] {
+        &self.pixels
+    }
+
+    /// Get all pixels mutably, row-major
+    pub fn pixels_mut(&mut self) -> &mut [[T; C]] {
+        &mut self.pixels
+    }
+
+    /// Get the pixels as owned Vec
+    pub fn into_pixels(self) -> Vec<[T; C]> {
+        self.pixels
+    }
+
+    /// Get a pixel, or `None` outside the image
+    pub fn get(&self, x: u32, y: u32) -> Option<&[T; C]> {
+        (x < self.width && y < self.height).then(|| &self.pixels[self.index(x, y)])
+    }
+
+    /// Get a pixel mutably, or `None` outside the image
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut [T; C]> {
+        let index = self.index(x, y);
+        (x < self.width && y < self.height).then(move || &mut self.pixels[index])
+    }
+
+    /// Get one row of pixels
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside the image.
+    pub fn row(&self, y: u32) -> &[[T; C]] {
+        assert!(y < self.height, "row {} out of bounds for height {}", y, self.height);
+        let width = self.width as usize;
+        &self.pixels[y as usize * width..(y as usize + 1) * width]
+    }
+
+    /// Get one row of pixels mutably
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside the image.
+    pub fn row_mut(&mut self, y: u32) -> &mut [[T; C]] {
+        assert!(y < self.height, "row {} out of bounds for height {}", y, self.height);
+        let width = self.width as usize;
+        &mut self.pixels[y as usize * width..(y as usize + 1) * width]
+    }
+
+    /// Iterate over the rows of pixels
+    pub fn rows(&self) -> impl Iterator<Item = &[[T; C]]> {
+        self.pixels.chunks_exact(self.width.max(1) as usize)
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Convert to a dynamic [`Image`]
+    pub fn to_image(&self) -> Image {
+        let size = T::COMPONENT_TYPE.size_bytes();
+        let mut image = Image::new(self.width, self.height, Self::PIXEL_FORMAT, T::COMPONENT_TYPE);
+
+        let components = self.pixels.iter().flatten();
+        for (bytes, &component) in image.data_mut().chunks_exact_mut(size).zip(components) {
+            component.write_le(bytes);
+        }
+        image
+    }
+}
+
+impl<T: Component, const C: usize> Index<(u32, u32)> for TypedImage<T, C> {
+    type Output = [T; C];
+
+    fn index(&self, (x, y): (u32, u32)) -> &[T; C] {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!("pixel ({}, {}) out of bounds for {}x{} image", x, y, self.width, self.height)
+        })
+    }
+}
+
+impl<T: Component, const C: usize> IndexMut<(u32, u32)> for TypedImage<T, C> {
+    fn index_mut(&mut self, (x, y): (u32, u32)) -> &mut [T; C] {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| {
+            panic!("pixel ({}, {}) out of bounds for {}x{} image", x, y, width, height)
+        })
+    }
+}
+
+impl<T: Component, const C: usize> TryFrom<&Image> for TypedImage<T, C> {
+    type Error = ImageError;
+
+    /// Copy a dynamic image into a typed image
+    ///
+    /// BGR and BGRA images are reordered to RGB and RGBA. Returns an error if the
+    /// component type or channel count does not match.
+    fn try_from(image: &Image) -> ImageResult<Self> {
+        let pixel_format = image.pixel_format();
+        if image.component_type() != T::COMPONENT_TYPE || pixel_format.channel_count() != C {
+            return Err(ImageError::InvalidPixelFormat(format!(
+                "{:?} {:?} image does not match a typed image of {} {:?} channels",
+                pixel_format, image.component_type(), C, T::COMPONENT_TYPE
+            )));
+        }
+
+        let size = T::COMPONENT_TYPE.size_bytes();
+        let swap_red_blue = matches!(pixel_format, PixelFormat::BGR | PixelFormat::BGRA);
+        let pixels = image
+            .data()
+            .chunks_exact(C * size)
+            .map(|bytes| {
+                let mut pixel: [T; C] = std::array::from_fn(|c| T::from_le(&bytes[c * size..]));
+                if swap_red_blue {
+                    pixel.swap(0, 2);
+                }
+                pixel
+            })
+            .collect();
+
+        Ok(Self { width: image.width(), height: image.height(), pixels })
+    }
+}
+
+impl<T: Component, const C: usize> TryFrom<Image> for TypedImage<T, C> {
+    type Error = ImageError;
+
+    fn try_from(image: Image) -> ImageResult<Self> {
+        TypedImage::try_from(&image)
+    }
+}
+
+impl<T: Component, const C: usize> From<&TypedImage<T, C>> for Image {
+    fn from(image: &TypedImage<T, C>) -> Self {
+        image.to_image()
+    }
+}
+
+impl<T: Component, const C: usize> From<TypedImage<T, C>> for Image {
+    fn from(image: TypedImage<T, C>) -> Self {
+        image.to_image()
+    }
+}
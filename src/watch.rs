@@ -0,0 +1,148 @@
+/// File watching helper for hot-reloading images
+use crate::{GalaxyImage, Image, ImageResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Options controlling how a watched file is polled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Interval between two file metadata checks
+    pub poll_interval: Duration,
+
+    /// Time the file must stay unchanged before it is reloaded
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Handle to a running file watcher
+///
+/// The watcher stops when this handle is dropped.
+pub struct ImageWatcher {
+    path: PathBuf,
+    stop: Option<Sender<()>>,
+    poller: Option<JoinHandle<()>>,
+}
+
+impl ImageWatcher {
+    /// Get the watched file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stop watching the file
+    pub fn stop(self) {
+        // Dropping does the work
+    }
+}
+
+impl Drop for ImageWatcher {
+    fn drop(&mut self) {
+        // Disconnecting the stop channel wakes the poller immediately
+        self.stop.take();
+        if let Some(poller) = self.poller.take() {
+            let _ = poller.join();
+        }
+    }
+}
+
+/// File state used for change detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+pub(crate) fn spawn_watcher<F>(
+    path: &Path,
+    options: WatchOptions,
+    mut callback: F,
+) -> ImageResult<ImageWatcher>
+where
+    F: FnMut(ImageResult<Image>) + Send + 'static,
+{
+    let path = path.to_path_buf();
+    let mut last_stamp = fs::metadata(&path).map(|metadata| FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })?;
+
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let (reload_tx, reload_rx) = mpsc::channel::<()>();
+
+    // Decoder thread: decodes off the notification thread and runs the callback
+    let decode_path = path.clone();
+    thread::Builder::new()
+        .name("galaxy_image-watch-decode".to_string())
+        .spawn(move || {
+            let mut last_checksum: Option<u64> = None;
+            while reload_rx.recv().is_ok() {
+                // Coalesce notifications queued while the previous decode was running
+                while reload_rx.try_recv().is_ok() {}
+
+                let result = GalaxyImage::load_from_file(&decode_path);
+                if let Ok(image) = &result {
+                    // Skip files that were touched without changing their pixels
+                    let checksum = image.checksum();
+                    if last_checksum == Some(checksum) {
+                        continue;
+                    }
+                    last_checksum = Some(checksum);
+                }
+                callback(result);
+            }
+        })?;
+
+    // Poller thread: detects changes and waits until the file is stable
+    let poll_path = path.clone();
+    let poller = thread::Builder::new()
+        .name("galaxy_image-watch-poll".to_string())
+        .spawn(move || {
+            let mut pending_since: Option<Instant> = None;
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(options.poll_interval) {
+                // A missing file (e.g. during an atomic rename) is not a change yet
+                let Some(stamp) = file_stamp(&poll_path) else {
+                    continue;
+                };
+
+                if stamp != last_stamp {
+                    last_stamp = stamp;
+                    pending_since = Some(Instant::now());
+                    continue;
+                }
+
+                if let Some(since) = pending_since {
+                    if since.elapsed() >= options.debounce {
+                        pending_since = None;
+                        if reload_tx.send(()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        })?;
+
+    Ok(ImageWatcher {
+        path,
+        stop: Some(stop_tx),
+        poller: Some(poller),
+    })
+}